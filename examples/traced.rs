@@ -0,0 +1,42 @@
+//Import library modules
+use elevator_optimization::building::Building;
+use elevator_optimization::controller::RandomController;
+use elevator_optimization::error::ElevatorError;
+use elevator_optimization::profile::Profiler;
+use elevator_optimization::rng::RngStream;
+use elevator_optimization::simulation::{SimulationConfig, step};
+
+//Import libraries
+use tracing_subscriber::fmt::format::FmtSpan;
+
+//Constant representing the number of time steps traced
+const TIME_STEPS: i32 = 5_i32;
+
+/** main function
+ *
+ * Simulate a few time steps of a small building with the tracing
+ * feature enabled, printing each span as it closes along with its
+ * duration, and each event as it is emitted.  Run with
+ *
+ * cargo run --example traced --features tracing
+ */
+fn main() -> Result<(), ElevatorError> {
+    //Print every span and event, including the debug events such as crowding refusals
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    //Initialize the building and the controller, then step the simulation
+    let seed: u64 = 42_u64;
+    let mut config: SimulationConfig = SimulationConfig::from(4_usize, 2_usize, 0.5_f64);
+    config.capacity = Some(2_usize);
+    config.min_crowding_tolerance = 0.5_f64;
+    let building: Building = config.build(seed)?;
+    let mut controller = RandomController::from(building, RngStream::Controller(0_usize).get_rng(seed));
+    let mut profiler: Profiler = Profiler::from(false);
+    for i in 0..TIME_STEPS {
+        step(&mut controller, i, &mut profiler)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+//Constant representing the largest factor any profile scales the expected arrivals by
+pub const MAX_MULTIPLIER: f64 = 3_f64;
+
+/** ArrivalProfile enum
+ *
+ * A built-in schedule scaling the expected number of people arriving
+ * over the course of a run, by the fraction of its time steps elapsed.
+ * - Constant: The expected arrivals throughout
+ * - MorningPeak: Three times the expected arrivals for the first 20% of the run, as people arrive for work
+ * - Lunch: Two and a half times the expected arrivals from 40% to 55% of the run, as people return from lunch
+ * - EveningExit: A quarter of the expected arrivals for the last 30% of the run, as the building empties
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum ArrivalProfile {
+    #[default]
+    Constant,
+    MorningPeak,
+    Lunch,
+    EveningExit
+}
+
+/** ArrivalProfile type implementation
+ *
+ * The following functions are implemented for the ArrivalProfile type,
+ * and are callable via
+ *
+ * //Example
+ * let is_constant: bool = ArrivalProfile::Lunch.is_constant();
+ * let my_multiplier: f64 = ArrivalProfile::MorningPeak.get_multiplier(10_u64, 1000_u64);
+ */
+impl ArrivalProfile {
+    /** is_constant function
+     *
+     * Return whether the expected arrivals are never scaled.
+     */
+    pub fn is_constant(&self) -> bool {
+        *self == ArrivalProfile::Constant
+    }
+
+    /** get_name function
+     *
+     * Return the name of the profile, as given on the command line.
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ArrivalProfile::Constant => "constant",
+            ArrivalProfile::MorningPeak => "morning-peak",
+            ArrivalProfile::Lunch => "lunch",
+            ArrivalProfile::EveningExit => "evening-exit"
+        }
+    }
+
+    /** get_multiplier function
+     *
+     * Return the factor the expected arrivals are scaled by on the
+     * given time step of a run of the given number of time steps.  If
+     * the run has no time steps, then the arrivals are not scaled.
+     */
+    pub fn get_multiplier(&self, time_step: u64, time_steps: u64) -> f64 {
+        if time_steps == 0_u64 {
+            return 1_f64;
+        }
+        let elapsed: f64 = time_step as f64 / time_steps as f64;
+        match self {
+            ArrivalProfile::Constant => 1_f64,
+            ArrivalProfile::MorningPeak => if elapsed < 0.2_f64 { MAX_MULTIPLIER } else { 1_f64 },
+            ArrivalProfile::Lunch => if (0.4_f64..0.55_f64).contains(&elapsed) { 2.5_f64 } else { 1_f64 },
+            ArrivalProfile::EveningExit => if elapsed >= 0.7_f64 { 0.25_f64 } else { 1_f64 }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//Import source modules
+use crate::building::Building;
+use crate::units::Ticks;
+use crate::zone::ZoneMap;
+
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+/** Bank struct schema
+ *
+ * A Bank has the following properties
+ * - name (String): The name the bank is reported by
+ * - elevators (Vec<usize>): The indices of the elevators in the bank
+ * - floors (Vec<usize>): The floors served by every elevator in the bank
+ *
+ * Every elevator belongs to exactly one bank, and only answers calls
+ * and carries people between the floors of its bank.  Banks may share
+ * floors, such as the lobby.
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Bank {
+    pub name: String,
+    pub elevators: Vec<usize>,
+    pub floors: Vec<usize>
+}
+
+/** BankSummary struct schema
+ *
+ * A BankSummary has the following properties
+ * - name (String): The name of the bank
+ * - num_delivered (usize): The number of people the bank's elevators carried to their destinations
+ * - avg_wait_time (f64): The average wait time of those people
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BankSummary {
+    pub name: String,
+    pub num_delivered: usize,
+    pub avg_wait_time: f64
+}
+
+/** Bank type implementation
+ *
+ * The following functions are implemented for the Bank type, and
+ * are callable via
+ *
+ * //Example
+ * let my_bank: Bank = Bank::from("low-rise", vec![0_usize, 1_usize], (0_usize..11_usize).collect());
+ * let my_summary: BankSummary = my_bank.get_summary(&my_building);
+ */
+impl Bank {
+    /** Bank constructor function
+     *
+     * Initialize a bank given its name, its elevators and the floors
+     * they serve.
+     */
+    pub fn from(name: &str, elevators: Vec<usize>, floors: Vec<usize>) -> Bank {
+        Bank {
+            name: String::from(name),
+            elevators,
+            floors
+        }
+    }
+
+    /** get_summary function
+     *
+     * Return the number of people the bank's elevators have carried to
+     * their destinations in the given building, and their average wait.
+     */
+    pub fn get_summary(&self, building: &Building) -> BankSummary {
+        let mut num_delivered: usize = 0_usize;
+        let mut total_wait_time: Ticks = Ticks(0_u64);
+        for elevator in self.elevators.iter().filter_map(|i| building.elevators.get(*i)) {
+            num_delivered += elevator.num_delivered;
+            total_wait_time = total_wait_time.saturating_add(elevator.total_wait_time);
+        }
+        BankSummary {
+            name: self.name.clone(),
+            num_delivered,
+            avg_wait_time: if num_delivered == 0_usize { 0_f64 } else { total_wait_time.as_f64() / num_delivered as f64 }
+        }
+    }
+}
+
+/** validate_banks function
+ *
+ * Check that the banks have distinct names, that every elevator of a
+ * building with the given floors and elevators is in exactly one bank,
+ * and that every floor is served by some bank.  No banks at all leave
+ * every elevator serving every floor.
+ */
+pub fn validate_banks(banks: &[Bank], num_floors: usize, num_elevators: usize) -> Result<(), String> {
+    if banks.is_empty() {
+        return Ok(());
+    }
+    let mut bank_of: Vec<Option<&str>> = vec![None; num_elevators];
+    for (i, bank) in banks.iter().enumerate() {
+        if banks[..i].iter().any(|x| x.name == bank.name) {
+            return Err(format!("bank {} is given more than once", bank.name));
+        }
+        for elevator in bank.elevators.iter() {
+            match bank_of.get_mut(*elevator) {
+                Some(Some(other)) => return Err(format!(
+                    "elevator {} is in both bank {} and bank {}", elevator, other, bank.name
+                )),
+                Some(x) => *x = Some(&bank.name),
+                None => return Err(format!(
+                    "bank {} has elevator {}, out of range for a building with {} elevators", bank.name, elevator, num_elevators
+                ))
+            }
+        }
+    }
+    if let Some(elevator) = bank_of.iter().position(|x| x.is_none()) {
+        return Err(format!("elevator {} is not in any bank", elevator));
+    }
+    get_bank_zones(banks, num_elevators).validate(num_floors, num_elevators)
+}
+
+/** get_bank_zones function
+ *
+ * Return the zone map in which each elevator serves the floors of its
+ * bank, given validated banks for the given number of elevators.
+ */
+pub fn get_bank_zones(banks: &[Bank], num_elevators: usize) -> ZoneMap {
+    let zones: Vec<Vec<usize>> = (0_usize..num_elevators).map(|i| {
+        match banks.iter().find(|bank| bank.elevators.contains(&i)) {
+            Some(bank) => bank.floors.clone(),
+            None => Vec::new()
+        }
+    }).collect();
+    ZoneMap::from(zones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elevator::Elevator;
+    use crate::controller::{ElevatorController, ControllerKind};
+    use crate::simulation::{SimulationConfig, build_controller, step};
+    use crate::params::ControllerParams;
+    use crate::profile::Profiler;
+
+    //Get the config of a building of 10 floors with a low-rise and a high-rise bank of 2 elevators each
+    fn get_banked_config() -> SimulationConfig {
+        let mut config: SimulationConfig = SimulationConfig::from(10_usize, 4_usize, 0.6_f64);
+        config.banks = vec![
+            Bank::from("low-rise", vec![0_usize, 1_usize], (0_usize..5_usize).collect()),
+            Bank::from("high-rise", vec![2_usize, 3_usize], std::iter::once(0_usize).chain(5_usize..10_usize).collect())
+        ];
+        config
+    }
+
+    //Check that every elevator only stops on floors of its bank, carrying people to floors of its bank
+    fn check_banks(building: &Building, context: &str) {
+        for bank in building.banks.iter() {
+            for i in bank.elevators.iter() {
+                let elevator: &Elevator = &building.elevators[*i];
+                assert!(
+                    !elevator.stopped || bank.floors.contains(&elevator.floor_on),
+                    "{}: elevator {} stopped on floor {}", context, i, elevator.floor_on
+                );
+                for pers in elevator.people.iter() {
+                    assert!(bank.floors.contains(&pers.floor_to), "{}: elevator {} carries {} to {}", context, i, pers.id, pers.floor_to);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn banked_elevators_stay_in_their_banks() {
+        let config: SimulationConfig = get_banked_config();
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, ControllerKind::Nearest, &ControllerParams::default(), 2_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //Run with arrivals, then without until nobody is waiting or riding
+        for i in 0_i32..300_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            check_banks(controller.get_building(), &format!("step {}", i));
+        }
+        controller.get_building_mut().set_expected_arrivals(0_f64).unwrap();
+        let mut drained: bool = false;
+        for i in 300_i32..600_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+            check_banks(building, &format!("step {}", i));
+            let num_waiting: usize = (0_usize..10_usize).map(|x| building.get_num_people_waiting_on_floor(x)).sum();
+            if num_waiting == 0_usize && building.elevators.iter().all(|x| x.people.is_empty()) {
+                drained = true;
+                break;
+            }
+        }
+        assert!(drained, "some passengers were never delivered");
+
+        //Both banks delivered people, and their summaries add up to the building's deliveries
+        let building: &Building = controller.get_building();
+        let summaries: Vec<BankSummary> = building.get_bank_summaries();
+        assert_eq!(summaries.iter().map(|x| x.name.as_str()).collect::<Vec<&str>>(), vec!["low-rise", "high-rise"]);
+        assert!(summaries.iter().all(|x| x.num_delivered > 0_usize && x.avg_wait_time > 0_f64), "{:?}", summaries);
+        assert_eq!(summaries.iter().map(|x| x.num_delivered).sum::<usize>(), building.get_num_delivered());
+    }
+
+    #[test]
+    fn validate_banks_names_the_problem() {
+        let low: Bank = Bank::from("low", vec![0_usize], vec![0_usize, 1_usize]);
+        let high: Bank = Bank::from("high", vec![1_usize], vec![0_usize, 2_usize]);
+        assert_eq!(validate_banks(&[], 3_usize, 2_usize), Ok(()));
+        assert_eq!(validate_banks(&[low.clone(), high.clone()], 3_usize, 2_usize), Ok(()));
+        let cases: [(Vec<Bank>, &str); 4] = [
+            (vec![low.clone(), Bank { name: String::from("low"), ..high.clone() }], "bank low is given more than once"),
+            (vec![low.clone(), Bank { elevators: vec![0_usize, 1_usize], ..high.clone() }], "elevator 0 is in both bank low and bank high"),
+            (vec![low.clone(), Bank { elevators: vec![1_usize, 2_usize], ..high.clone() }], "bank high has elevator 2, out of range for a building with 2 elevators"),
+            (vec![low.clone()], "elevator 1 is not in any bank")
+        ];
+        for (banks, expected) in cases {
+            assert_eq!(validate_banks(&banks, 3_usize, 2_usize), Err(String::from(expected)));
+        }
+    }
+}
@@ -1,39 +1,176 @@
 //Import external/standard modules
 use rand::Rng;
-use rand::distributions::Distribution;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::distributions::{Distribution, Uniform, Bernoulli, WeightedIndex};
 use statrs::distribution::Poisson;
-use crossterm::style::Stylize;
+use serde::{Serialize, Deserialize};
 
 //Import source modules
 use crate::person::Person;
+use crate::error::ElevatorError;
 use crate::people::People;
 use crate::floor::Floor;
 use crate::floors::Floors;
-use crate::elevator::Elevator;
+use crate::elevator::{Elevator, ElevatorProfile, ElevatorHome};
 use crate::elevators::Elevators;
+use crate::render::{View, render_building};
+use crate::event::SimEvent;
+use crate::cache::BuildingCache;
+use crate::rng::RngStream;
+use crate::invariant::InvariantViolation;
+use crate::shuttle::ShuttleAssignment;
+use crate::zone::ZoneMap;
+use crate::demand::{DemandEstimate, DEMAND_SMOOTHING};
+use crate::controller::ElevatorCommand;
+use crate::bank::{Bank, BankSummary, get_bank_zones};
+use crate::maintenance::MaintenanceWindow;
+use crate::stats::StreamingStats;
+use crate::energy::OverloadPenalty;
+use crate::destination::DestinationModel;
+use crate::meeting::{MeetingModel, Meeting};
+use crate::arrival::{ArrivalProfile, MAX_MULTIPLIER};
+use crate::layout::LayoutFile;
+use crate::units::{Energy, Ticks};
 
 //Constant representing the probability a person leaves the building during a time step
-const P_OUT: f64 = 0.05_f64;
+pub const P_OUT: f64 = 0.05_f64;
+
+//Constant representing the default number of time steps a person slow to board takes to get on or off
+pub const SLOW_BOARDING_TICKS: usize = 3_usize;
+
+/** ExchangeOrder enum
+ *
+ * The order the elevators take turns exchanging people in each time
+ * step, which decides which elevator stopped on a floor gets the first
+ * pick of the people waiting there.
+ * - Fixed: In the order of their indices, favoring the lowest
+ * - RoundRobin: In the order of their indices, starting from the next elevator each time step
+ * - Shuffled: In an order drawn each time step from a stream of its own
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum ExchangeOrder {
+    Fixed,
+    #[default]
+    RoundRobin,
+    Shuffled
+}
 
 /** Building struct schema
  *
  * A Building has the following properties
  * - elevators (Vec<Elevator>): A vector of elevators for transporting people between floors
  * - floors (Vec<Floor>): A vector of floors composing the building
- * - avg_energy (f64): Average energy expendature by the building's elevator over time
- * - avg_wait_time (f64): Average wait time throughout the building per person waiting
- * - wait_time_denom (usize): The number of people whose wait time has been aggregated into the average
+ * - energy (StreamingStats): The energy expendature by the building's elevators per measured time step
+ * - wait_times (StreamingStats): The wait times of the people who have reached their destination, from arriving to getting off
+ * - floor_wait_times (StreamingStats): The time those people spent waiting on a floor, before getting on an elevator
+ * - total_ride_time (Ticks): The total time spent on elevators by those who have reached their destination
+ * - max_ride_time (Ticks): The longest time spent on an elevator by anyone who has reached their destination
+ * - current_step (u64): The number of time steps the building has been simulated for
+ * - measured_steps (u64): The number of time steps the metrics cover, since they were last reset
  * - p_in (f64): The lambda value for the arrival probability distribution
- * - dst_in (Poisson): The arrival probability distribution
+ * - dst_in (Option<Poisson>): The arrival probability distribution, if anyone arrives
+ * - arrival_profile (ArrivalProfile): The schedule scaling the expected arrivals over the run
+ * - profile_steps (u64): The number of time steps the arrival profile is spread over
+ * - arrival_multiplier (f64): The factor the arrival probability distribution is currently scaled by
+ * - num_arrivals (usize): The number of people who arrived, since the metrics were last reset
+ * - p_out (f64): The probability each person generated leaves each time step, P_OUT by default
+ * - dst_out (Bernoulli): The distribution of leaving given to each person generated, with probability p_out
+ * - events (Vec<SimEvent>): The events which occurred since the events were last cleared
+ * - next_person_id (usize): The id assigned to the next person who arrives
+ * - num_departed (usize): The number of people who have left the building
+ * - max_population (Option<usize>): The most people allowed in the building at once, if limited
+ * - rejected_arrivals (usize): The number of people turned away on arriving because the building was full
+ * - missed_stops (usize): The number of times an elevator left a floor some of its passengers were headed to
+ * - strict_stops (bool): Whether an elevator about to leave a floor some of its passengers are headed to is stopped there instead
+ * - cache (BuildingCache): The waiting and destination floors, computed once per time step
+ * - arrival_rng (StdRng): The RNG for the number of people arriving each time step
+ * - destination_rng (StdRng): The RNG for the destination floors of the people arriving
+ * - behavior_rng (StdRng): The RNG for the decisions of the people in the building
+ * - rerouting_rng (StdRng): The RNG for the destination changes of the people on the elevators
+ * - crowding_rng (StdRng): The RNG for the crowding tolerances of the people arriving
+ * - min_crowding_tolerance (f64): The lowest crowding tolerance drawn for the people arriving, 1 if nobody refuses a cab with room
+ * - mobility_rng (StdRng): The RNG for whether the people arriving are slow to board
+ * - p_slow_boarding (f64): The probability a person arriving is slow to get on and off elevators
+ * - slow_boarding_ticks (usize): The number of time steps a person slow to board takes to get on or off
+ * - p_change_dest (f64): The probability each person on an elevator changes their destination each time step
+ * - shuttle (Option<ShuttleAssignment>): The elevator pinned as the lobby shuttle, if any
+ * - zones (Option<ZoneMap>): The floors served by each elevator, if they do not all serve every floor
+ * - demand (DemandEstimate): The estimated arrivals and hall calls per floor, following the current traffic
+ * - no_reversal (bool): Whether an occupied elevator is kept from moving away from all its passengers' destinations
+ * - banks (Vec<Bank>): The banks the elevators are grouped into, if any
+ * - freight_serves_passengers (bool): Whether freight elevators always answer calls and carry people
+ * - freight_surge_waiting (Option<usize>): The number of people waiting above which freight elevators are opted in
+ * - freight_opted_in (bool): Whether freight elevators answer calls and carry people until the cache is next updated
+ * - maintenance (Vec<MaintenanceWindow>): The recurring windows in which elevators are out of service
+ * - exchange_order (ExchangeOrder): The order the elevators exchange people in each time step
+ * - exchange_rng (StdRng): The RNG for the order the elevators exchange people in, if shuffled
+ * - entry_floors (Vec<(usize, f64)>): The floors people arrive on, each with the weight it is drawn with
+ * - entry_dist (Option<WeightedIndex<f64>>): The distribution of the entry floors, if there are several
+ * - entry_rng (StdRng): The RNG for the entry floor of the people arriving
+ * - destination_model (DestinationModel): The way the destination floors of the people arriving are drawn
+ * - destination_dists (Vec<Option<WeightedIndex<f64>>>): The distribution of the destinations of the people arriving on each entry floor, None if uniform
+ * - meetings (Option<MeetingModel>): The meetings layered over the destination model, if any
+ * - meeting (Option<Meeting>): The meeting under way, if any
+ * - meeting_rng (StdRng): The RNG for the meetings and who goes to and leaves them
  */
 pub struct Building {
     pub elevators: Vec<Elevator>,
     pub floors: Vec<Floor>,
-    pub avg_energy: f64,
-    pub avg_wait_time: f64,
-    wait_time_denom: usize,
+    pub energy: StreamingStats,
+    pub wait_times: StreamingStats,
+    pub floor_wait_times: StreamingStats,
+    pub total_ride_time: Ticks,
+    pub max_ride_time: Ticks,
+    pub current_step: u64,
+    pub measured_steps: u64,
     p_in: f64,
-    dst_in: Poisson
+    dst_in: Option<Poisson>,
+    pub arrival_profile: ArrivalProfile,
+    profile_steps: u64,
+    arrival_multiplier: f64,
+    pub num_arrivals: usize,
+    p_out: f64,
+    dst_out: Bernoulli,
+    pub events: Vec<SimEvent>,
+    next_person_id: usize,
+    num_departed: usize,
+    pub max_population: Option<usize>,
+    pub rejected_arrivals: usize,
+    pub missed_stops: usize,
+    pub strict_stops: bool,
+    pub cache: BuildingCache,
+    arrival_rng: StdRng,
+    destination_rng: StdRng,
+    behavior_rng: StdRng,
+    rerouting_rng: StdRng,
+    crowding_rng: StdRng,
+    pub min_crowding_tolerance: f64,
+    mobility_rng: StdRng,
+    pub p_slow_boarding: f64,
+    pub slow_boarding_ticks: usize,
+    pub p_change_dest: f64,
+    pub shuttle: Option<ShuttleAssignment>,
+    pub zones: Option<ZoneMap>,
+    pub demand: DemandEstimate,
+    pub no_reversal: bool,
+    pub banks: Vec<Bank>,
+    pub freight_serves_passengers: bool,
+    pub freight_surge_waiting: Option<usize>,
+    freight_opted_in: bool,
+    pub maintenance: Vec<MaintenanceWindow>,
+    pub exchange_order: ExchangeOrder,
+    exchange_rng: StdRng,
+    pub entry_floors: Vec<(usize, f64)>,
+    entry_dist: Option<WeightedIndex<f64>>,
+    entry_rng: StdRng,
+    pub destination_model: DestinationModel,
+    destination_dists: Vec<Option<WeightedIndex<f64>>>,
+    pub meetings: Option<MeetingModel>,
+    pub meeting: Option<Meeting>,
+    meeting_rng: StdRng
 }
 
 /** Building type implementation
@@ -42,18 +179,22 @@ pub struct Building {
  * and are callable via
  *
  * //Example
- * let my_building: Building = Building::new(...);
+ * let my_building: Building = Building::from(...)?;
  * let are_people_waiting: bool = my_building.are_people_waiting_on_floor(0_usize);
  */
 impl Building {
     /** Building constructor function
      *
      * Construct a building given the number of floors
-     * it should have, its arrival probability, and its
-     * Elevator parameters
+     * it should have, its arrival probability, its
+     * Elevator parameters, and the seed its RNG streams
+     * are derived from.  An arrival probability of 0
+     * means nobody arrives.  If the arrival probability is
+     * not in [0, inf), even once scaled by an arrival profile,
+     * then an error is returned.
      */
     pub fn from(num_floors: usize, num_elevators: usize, p_in: f64, energy_up: f64,
-                energy_down: f64, energy_coef: f64) -> Building {
+                energy_down: f64, energy_coef: f64, seed: u64) -> Result<Building, ElevatorError> {
         //Initialize the Floors
         let floors: Vec<Floor> = {
             let mut tmp_floors: Vec<Floor> = Vec::new();
@@ -69,61 +210,614 @@ impl Building {
             let mut tmp_elevators: Vec<Elevator> = Vec::new();
             for _ in 0_usize..num_elevators {
                 let tmp_elevator: Elevator = Elevator::from(
-                    energy_up, energy_down, energy_coef
+                    ElevatorProfile::Passenger, energy_up, energy_down, energy_coef
                 );
                 tmp_elevators.push(tmp_elevator);
             }
             tmp_elevators
         };
     
-        //Initialize the arrival probability distribution
-        let dst_in = Poisson::new(p_in).unwrap();
+        //Initialize the arrival probability distribution, which has no lambda if nobody arrives
+        check_arrival_rate(p_in)?;
+        let dst_in: Option<Poisson> = get_arrival_distribution(p_in)?;
+
+        //Initialize the distribution of leaving given to each person generated
+        let dst_out: Bernoulli = Bernoulli::new(P_OUT)
+            .map_err(|_| ElevatorError::Distribution(format!("probability of leaving {} is not in [0, 1]", P_OUT)))?;
     
         //Initialize and return the Building
-        Building {
-            floors: floors,
-            elevators: elevators,
-            avg_energy: 0_f64,
-            avg_wait_time: 0_f64,
-            wait_time_denom: 0_usize,
-            p_in: p_in,
-            dst_in: dst_in
+        Ok(Building {
+            floors,
+            elevators,
+            energy: StreamingStats::new(),
+            wait_times: StreamingStats::new(),
+            floor_wait_times: StreamingStats::new(),
+            total_ride_time: Ticks(0_u64),
+            max_ride_time: Ticks(0_u64),
+            current_step: 0_u64,
+            measured_steps: 0_u64,
+            p_in,
+            dst_in,
+            arrival_profile: ArrivalProfile::Constant,
+            profile_steps: 0_u64,
+            arrival_multiplier: 1_f64,
+            num_arrivals: 0_usize,
+            p_out: P_OUT,
+            dst_out,
+            events: Vec::new(),
+            next_person_id: 0_usize,
+            num_departed: 0_usize,
+            max_population: None,
+            rejected_arrivals: 0_usize,
+            missed_stops: 0_usize,
+            strict_stops: false,
+            cache: BuildingCache::from(num_floors),
+            arrival_rng: RngStream::Arrivals.get_rng(seed),
+            destination_rng: RngStream::Destinations.get_rng(seed),
+            behavior_rng: RngStream::Behavior.get_rng(seed),
+            rerouting_rng: RngStream::Rerouting.get_rng(seed),
+            crowding_rng: RngStream::Crowding.get_rng(seed),
+            min_crowding_tolerance: 1_f64,
+            mobility_rng: RngStream::Mobility.get_rng(seed),
+            p_slow_boarding: 0_f64,
+            slow_boarding_ticks: SLOW_BOARDING_TICKS,
+            p_change_dest: 0_f64,
+            shuttle: None,
+            zones: None,
+            demand: DemandEstimate::from(num_floors, p_in, DEMAND_SMOOTHING),
+            no_reversal: false,
+            banks: Vec::new(),
+            freight_serves_passengers: false,
+            freight_surge_waiting: None,
+            freight_opted_in: false,
+            maintenance: Vec::new(),
+            exchange_order: ExchangeOrder::default(),
+            exchange_rng: RngStream::Exchange.get_rng(seed),
+            entry_floors: vec![(0_usize, 1_f64)],
+            entry_dist: None,
+            entry_rng: RngStream::Entry.get_rng(seed),
+            destination_model: DestinationModel::Uniform,
+            destination_dists: vec![None],
+            meetings: None,
+            meeting: None,
+            meeting_rng: RngStream::Meetings.get_rng(seed)
+        })
+    }
+
+    /** Building constructor function
+     *
+     * Construct a building in the exact state described by the layout
+     * file at the given path, with its config, elevators and occupants.
+     * If the layout cannot be read or is inconsistent, then an error is
+     * returned before anything is built.
+     */
+    pub fn from_layout(path: &str) -> Result<Building, ElevatorError> {
+        let layout: LayoutFile = LayoutFile::load(path)?;
+        layout.validate()?;
+        Ok(layout.build()?)
+    }
+
+    /** set_expected_arrivals function
+     *
+     * Set the expected number of people arriving each time step, before
+     * it is scaled by the arrival profile.  If it is 0, then nobody
+     * arrives.  If it is not in [0, inf), even once scaled by an arrival
+     * profile, then an error is returned and it is left unchanged.
+     */
+    pub fn set_expected_arrivals(&mut self, p_in: f64) -> Result<(), ElevatorError> {
+        check_arrival_rate(p_in)?;
+        self.dst_in = get_arrival_distribution(p_in * self.arrival_multiplier)?;
+        self.p_in = p_in;
+        Ok(())
+    }
+
+    /** set_arrival_profile function
+     *
+     * Set the schedule scaling the expected arrivals, spread over a run
+     * of the given number of time steps, and scale the arrival
+     * probability distribution for the current time step.
+     */
+    pub fn set_arrival_profile(&mut self, arrival_profile: ArrivalProfile, time_steps: u64) -> Result<(), ElevatorError> {
+        self.arrival_profile = arrival_profile;
+        self.profile_steps = time_steps;
+        self.update_arrival_rate()
+    }
+
+    /** update_arrival_rate function
+     *
+     * Rebuild the arrival probability distribution if the arrival
+     * profile scales the expected arrivals by a different factor on the
+     * current time step than on the last.  If the scaled expected
+     * arrivals are not in [0, inf), then an error is returned and the
+     * distribution is left unchanged.
+     */
+    fn update_arrival_rate(&mut self) -> Result<(), ElevatorError> {
+        let multiplier: f64 = self.arrival_profile.get_multiplier(self.current_step, self.profile_steps);
+        if multiplier != self.arrival_multiplier {
+            self.dst_in = get_arrival_distribution(self.p_in * multiplier)?;
+            self.arrival_multiplier = multiplier;
+        }
+        Ok(())
+    }
+
+    /** set_p_out function
+     *
+     * Set the probability each person arriving from now on leaves each
+     * time step.  If it is not above 0 and at most 1, then an error is
+     * returned and the probability is left unchanged.
+     */
+    pub fn set_p_out(&mut self, p_out: f64) -> Result<(), ElevatorError> {
+        if !(p_out > 0_f64 && p_out <= 1_f64) {
+            return Err(ElevatorError::Distribution(format!("probability of leaving {} is not in (0, 1]", p_out)));
+        }
+        self.dst_out = Bernoulli::new(p_out)
+            .map_err(|_| ElevatorError::Distribution(format!("probability of leaving {} is not in (0, 1]", p_out)))?;
+        self.p_out = p_out;
+        Ok(())
+    }
+
+    /** set_entry_floors function
+     *
+     * Set the floors people arrive on, each with the weight it is drawn
+     * with, restarting the estimated arrivals from them.  If no entry
+     * floors are given, then everyone arrives on the first floor.  If
+     * the entry floors are invalid, then an error is returned.
+     */
+    pub fn set_entry_floors(&mut self, entry_floors: Vec<(usize, f64)>) -> Result<(), ElevatorError> {
+        validate_entry_floors(&entry_floors, self.floors.len()).map_err(ElevatorError::Config)?;
+        self.entry_floors = if entry_floors.is_empty() { vec![(0_usize, 1_f64)] } else { entry_floors };
+        self.entry_dist = if self.entry_floors.len() > 1_usize {
+            let dist: WeightedIndex<f64> = WeightedIndex::new(self.entry_floors.iter().map(|(_, weight)| *weight))
+                .map_err(|e| ElevatorError::Config(format!("entry floor weights: {}", e)))?;
+            Some(dist)
+        } else {
+            None
+        };
+        self.demand.set_entry_floors(self.p_in, &self.entry_floors);
+        self.update_destination_dists();
+        Ok(())
+    }
+
+    /** set_destination_model function
+     *
+     * Set the way the destination floors of the people arriving are
+     * drawn.  If the model is invalid for the building, then an error
+     * is returned.
+     */
+    pub fn set_destination_model(&mut self, destination_model: DestinationModel) -> Result<(), ElevatorError> {
+        destination_model.validate(self.floors.len()).map_err(ElevatorError::Config)?;
+        self.destination_model = destination_model;
+        self.update_destination_dists();
+        Ok(())
+    }
+
+    /** update_destination_dists function
+     *
+     * Construct the distribution of the destinations of the people
+     * arriving on each entry floor from the destination model.
+     */
+    fn update_destination_dists(&mut self) {
+        let num_floors: usize = self.floors.len();
+        self.destination_dists = self.entry_floors.iter()
+            .map(|(floor, _)| self.destination_model.get_dist(num_floors, *floor))
+            .collect();
+    }
+
+    /** is_entry_floor function
+     *
+     * Return whether people arrive on the floor with the given index.
+     */
+    pub fn is_entry_floor(&self, floor_index: usize) -> bool {
+        self.entry_floors.iter().any(|(floor, _)| *floor == floor_index)
+    }
+
+    /** set_shuttle function
+     *
+     * Pin an elevator as the lobby shuttle, replacing any previous
+     * shuttle, or release the shuttle if None is given.  Controllers
+     * which support a shuttle read it when updating the elevators.
+     */
+    pub fn set_shuttle(&mut self, shuttle: Option<ShuttleAssignment>) {
+        self.shuttle = shuttle;
+    }
+
+    /** set_zones function
+     *
+     * Restrict each elevator to the floors of its zone, or let every
+     * elevator serve every floor if None is given.  People only board
+     * an elevator which serves their destination floor.
+     */
+    pub fn set_zones(&mut self, zones: Option<ZoneMap>) {
+        self.zones = zones;
+    }
+
+    /** set_demand_smoothing function
+     *
+     * Set the weight of the latest time step in the estimates of the
+     * arrivals and hall calls per floor.
+     */
+    pub fn set_demand_smoothing(&mut self, smoothing: f64) {
+        self.demand.smoothing = smoothing;
+    }
+
+    /** set_banks function
+     *
+     * Group the elevators into the given validated banks, restricting
+     * each elevator to the floors of its bank.  If no banks are given,
+     * then the zones are left as they are.
+     */
+    pub fn set_banks(&mut self, banks: Vec<Bank>) {
+        if !banks.is_empty() {
+            self.set_zones(Some(get_bank_zones(&banks, self.elevators.len())));
+        }
+        self.banks = banks;
+    }
+
+    /** get_bank_summaries function
+     *
+     * Return the people delivered and their average wait time for each
+     * bank, which is empty if the elevators are not grouped into banks.
+     */
+    pub fn get_bank_summaries(&self) -> Vec<BankSummary> {
+        self.banks.iter().map(|bank| bank.get_summary(self)).collect()
+    }
+
+    /** set_capacity function
+     *
+     * Set the number of passengers each passenger elevator may carry,
+     * which each elevator's profile scales, or let them carry anyone
+     * waiting if None is given.
+     */
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        for elevator in self.elevators.iter_mut() {
+            elevator.capacity = capacity.map(|x| elevator.profile.get_capacity(x));
+        }
+    }
+
+    /** set_overload function
+     *
+     * Set the penalty every elevator spends carrying more people than
+     * its nominal load, or remove it if None is given.
+     */
+    pub fn set_overload(&mut self, overload: Option<OverloadPenalty>) {
+        for elevator in self.elevators.iter_mut() {
+            elevator.overload = overload;
+        }
+    }
+
+    /** set_profiles function
+     *
+     * Set the profile of each elevator, in order.  Elevators without a
+     * profile given are left as they are.  The capacity should be set
+     * after the profiles, since it depends on them.
+     */
+    pub fn set_profiles(&mut self, profiles: &[ElevatorProfile]) {
+        for (elevator, profile) in self.elevators.iter_mut().zip(profiles.iter()) {
+            elevator.profile = *profile;
+        }
+    }
+
+    /** set_homes function
+     *
+     * Give each of the given elevators a home floor to return to after
+     * idling.  Elevators without a home given are left as they are.
+     */
+    pub fn set_homes(&mut self, homes: &[ElevatorHome]) {
+        for home in homes.iter() {
+            if let Some(elevator) = self.elevators.get_mut(home.elevator) {
+                elevator.home_floor = Some(home.floor);
+                elevator.home_after_idle = home.after_idle;
+            }
+        }
+    }
+
+    /** opt_in_freight function
+     *
+     * Let the freight elevators answer calls and carry people until the
+     * cache is next updated, covering the controller's decisions and
+     * the following exchange, such as when a controller adapts to a
+     * surge in demand.
+     */
+    pub fn opt_in_freight(&mut self) {
+        self.freight_opted_in = true;
+    }
+
+    /** may_serve_passengers function
+     *
+     * Return whether the elevator at the given index may answer calls
+     * and pick people up this time step.  Passenger elevators always
+     * may, while freight elevators may only if the building lets them
+     * serve passengers or they were opted in.  No elevator may while
+     * out of service or let its passengers off ahead of maintenance.
+     */
+    pub fn may_serve_passengers(&self, elevator_index: usize) -> bool {
+        let elevator: &Elevator = &self.elevators[elevator_index];
+        if !elevator.in_service || elevator.draining {
+            return false;
+        }
+        elevator.profile == ElevatorProfile::Passenger
+            || self.freight_serves_passengers
+            || self.freight_opted_in
+    }
+
+    /** update_maintenance function
+     *
+     * Take elevators in and out of service at the boundaries of their
+     * maintenance windows for the current time step, and count their
+     * downtime.  An elevator stops taking on people once the next
+     * window starts within the time steps it needs to let its
+     * passengers off, and anyone still aboard when the window starts is
+     * let off onto the floor it is on to wait for another elevator.
+     */
+    pub fn update_maintenance(&mut self) {
+        let time_step: usize = self.current_step as usize;
+        let num_floors: usize = self.floors.len();
+        for window in self.maintenance.iter() {
+            let elevator: &mut Elevator = &mut self.elevators[window.elevator];
+            let floor_index: usize = elevator.floor_on;
+
+            //During the window, take the elevator out of service on its first time step, then count the downtime
+            if window.is_active(time_step) {
+                if elevator.in_service {
+                    let mut people_discharged: Vec<Person> = std::mem::take(&mut elevator.people);
+                    for pers in people_discharged.iter_mut() {
+                        pers.discharge();
+                    }
+                    elevator.in_service = false;
+                    elevator.draining = false;
+                    elevator.homing = false;
+                    elevator.idle_steps = 0_usize;
+                    elevator.floor_to = None;
+                    elevator.stopped = true;
+                    elevator.move_ticks = 0_usize;
+                    elevator.dwell_steps = 0_usize;
+                    sim_event!(info, elevator = window.elevator, floor = floor_index, "elevator out of service");
+                    self.events.push(SimEvent::MaintenanceStarted {
+                        elevator: window.elevator,
+                        floor: floor_index,
+                        num_discharged: people_discharged.len()
+                    });
+                    self.floors[floor_index].extend(people_discharged);
+                    self.cache.invalidate();
+                }
+                elevator.downtime += 1_usize;
+                continue;
+            }
+
+            //After the window, put the elevator back in service
+            if !elevator.in_service {
+                elevator.in_service = true;
+                sim_event!(info, elevator = window.elevator, floor = floor_index, "elevator back in service");
+                self.events.push(SimEvent::MaintenanceEnded { elevator: window.elevator, floor: floor_index });
+            }
+
+            //Ahead of the window, stop taking on people once they could not all be let off in time
+            let steps_until_start: usize = window.get_steps_until_start(time_step);
+            if !elevator.draining && steps_until_start <= elevator.get_discharge_steps(num_floors) {
+                elevator.draining = true;
+                self.events.push(SimEvent::MaintenanceNotice {
+                    elevator: window.elevator,
+                    floor: floor_index,
+                    steps_until_start
+                });
+            }
+        }
+    }
+
+    /** set_no_reversal function
+     *
+     * Set whether an occupied elevator whose passengers are all headed
+     * the same way is kept from moving the other way.
+     */
+    pub fn set_no_reversal(&mut self, no_reversal: bool) {
+        self.no_reversal = no_reversal;
+    }
+
+    /** get_required_direction function
+     *
+     * Return the direction the elevator at the given index must move
+     * in if it moves at all, Up if its passengers are all headed above
+     * it and Down if they are all headed below it.  Return None if the
+     * policy is off, the elevator is empty or its passengers disagree.
+     */
+    pub fn get_required_direction(&self, elevator_index: usize) -> Option<ElevatorCommand> {
+        if !self.no_reversal {
+            return None;
+        }
+        let elevator: &Elevator = &self.elevators[elevator_index];
+        let mut dests = elevator.people.iter()
+            .map(|pers| pers.floor_to)
+            .filter(|floor_to| *floor_to != elevator.floor_on)
+            .peekable();
+        let going_up: bool = *dests.peek()? > elevator.floor_on;
+        if dests.all(|floor_to| (floor_to > elevator.floor_on) == going_up) {
+            Some(if going_up { ElevatorCommand::Up } else { ElevatorCommand::Down })
+        } else {
+            None
+        }
+    }
+
+    /** apply_commands function
+     *
+     * Move or stop each elevator as commanded and update its floor.
+     * Elevators out of service or holding their doors open for people
+     * getting on and off stay stopped whatever they are told.
+     * If the no-reversal policy is on, then a command to move an
+     * occupied elevator away from all its passengers' destinations is
+     * turned around, and an event is logged.  An elevator leaving a
+     * floor some of its passengers are headed to misses the stop, see
+     * check_missed_stop.
+     */
+    pub fn apply_commands(&mut self, commands: &[ElevatorCommand]) {
+        for (i, command) in commands.iter().enumerate() {
+            //Keep an elevator out of service or holding its doors for people getting on and off stopped
+            if self.elevators[i].dwell_steps > 0_usize {
+                self.elevators[i].dwell_steps -= 1_usize;
+                self.elevators[i].stopped = true;
+                self.elevators[i].update_floor();
+                continue;
+            }
+            if !self.elevators[i].in_service {
+                self.elevators[i].stopped = true;
+                self.elevators[i].update_floor();
+                continue;
+            }
+
+            //Send the elevator home if it has idled long enough, or keep it going home
+            let command: ElevatorCommand = self.get_homing_command(i, *command);
+
+            //Turn around any command moving away from every passenger's destination
+            let command: ElevatorCommand = match self.get_required_direction(i) {
+                Some(required) if command != ElevatorCommand::Stop && command != required => {
+                    self.events.push(SimEvent::ReversalOverridden {
+                        elevator: i,
+                        floor: self.elevators[i].floor_on,
+                        moving_up: required == ElevatorCommand::Up
+                    });
+                    required
+                },
+                _ => command
+            };
+
+            //Count leaving a floor some passengers are headed to as a missed stop, stopping instead if strict
+            let command: ElevatorCommand = self.check_missed_stop(i, command);
+
+            //Update the elevator direction and floor
+            let elevator: &mut Elevator = &mut self.elevators[i];
+            match command {
+                ElevatorCommand::Up => {
+                    elevator.stopped = false;
+                    elevator.moving_up = true;
+                },
+                ElevatorCommand::Down => {
+                    elevator.stopped = false;
+                    elevator.moving_up = false;
+                },
+                ElevatorCommand::Stop => elevator.stopped = true
+            }
+            let _new_floor_index = elevator.update_floor();
+        }
+    }
+
+    /** check_missed_stop function
+     *
+     * Return the command the elevator at the given index follows in
+     * place of the given command, after checking whether it would leave
+     * a floor some of its passengers are headed to without stopping, as
+     * they only get off a stopped elevator.  If so, then the stop is
+     * counted as missed and an event is logged, and if stops are
+     * strict, then the elevator stops there instead.  An elevator part
+     * way to the next floor has already left.
+     */
+    fn check_missed_stop(&mut self, elevator_index: usize, command: ElevatorCommand) -> ElevatorCommand {
+        let elevator: &Elevator = &self.elevators[elevator_index];
+        if command == ElevatorCommand::Stop || elevator.move_ticks > 0_usize {
+            return command;
+        }
+        let num_passengers: usize = elevator.people.iter().filter(|pers| pers.floor_to == elevator.floor_on).count();
+        if num_passengers == 0_usize {
+            return command;
+        }
+        self.missed_stops += 1_usize;
+        self.events.push(SimEvent::StopMissed {
+            elevator: elevator_index,
+            floor: elevator.floor_on,
+            num_passengers,
+            forced: self.strict_stops
+        });
+        if self.strict_stops {
+            ElevatorCommand::Stop
+        } else {
+            command
         }
     }
 
+    /** get_homing_command function
+     *
+     * Return the command the elevator at the given index follows in
+     * place of the given command from its controller.  An empty elevator
+     * with a home floor which has been told to stop for its idle timeout
+     * sets off home, and an event is logged.  On the way, commands to
+     * stop or keep going leave it heading home, while a command to move
+     * the other way or anyone boarding cancels the trip, and the command
+     * is followed instead.  The trip ends once the elevator is home.
+     */
+    fn get_homing_command(&mut self, elevator_index: usize, command: ElevatorCommand) -> ElevatorCommand {
+        let elevator: &mut Elevator = &mut self.elevators[elevator_index];
+        let home_floor: usize = match elevator.home_floor {
+            Some(x) => x,
+            None => return command
+        };
+
+        //If going home, then keep going unless home, boarded, or told to move the other way
+        if elevator.homing {
+            let homing_command: ElevatorCommand = if home_floor > elevator.floor_on {
+                ElevatorCommand::Up
+            } else if home_floor < elevator.floor_on {
+                ElevatorCommand::Down
+            } else {
+                ElevatorCommand::Stop
+            };
+            let is_cancelled: bool = homing_command == ElevatorCommand::Stop
+                || !elevator.people.is_empty()
+                || (command != ElevatorCommand::Stop && command != homing_command);
+            if !is_cancelled {
+                return homing_command;
+            }
+            elevator.homing = false;
+            elevator.idle_steps = 0_usize;
+            return command;
+        }
+
+        //Count the time steps told to stop while empty away from home, and set off home once timed out
+        if command != ElevatorCommand::Stop || !elevator.people.is_empty() || elevator.floor_on == home_floor {
+            elevator.idle_steps = 0_usize;
+            return command;
+        }
+        elevator.idle_steps += 1_usize;
+        if elevator.idle_steps < elevator.home_after_idle {
+            return command;
+        }
+        elevator.idle_steps = 0_usize;
+        elevator.homing = true;
+        elevator.floor_to = Some(home_floor);
+        self.events.push(SimEvent::HomingStarted {
+            elevator: elevator_index,
+            floor: self.elevators[elevator_index].floor_on,
+            home_floor
+        });
+        if home_floor > self.elevators[elevator_index].floor_on { ElevatorCommand::Up } else { ElevatorCommand::Down }
+    }
+
     /** update_dest_probabilities function
      *
      * Loop through each floor and calculate the probability that
      * that floor becomes a waiting floor next time step.  Then set
-     * the dest_prob attribute for each floor with the value.
+     * the dest_prob attribute for each floor with the value.  The
+     * entry floors' probabilities follow the estimated arrivals on them
+     * rather than the rate the building was built with.
      */
     pub fn update_dest_probabilities(&mut self) {
-        //Get the number of floors in the building
-        let num_floors: usize = self.floors.len() as usize;
-
-        //Get the destination floors across each elevator
-        let dest_floors: Vec<usize> = self.elevators.get_dest_floors();
-
         //Loop through the floors
         for (i, floor) in self.floors.iter_mut().enumerate() {
             //Initialize an f64 for this floor's probability
-            let dest_probability: f64 = if i == 0 {
-                //If this is the first floor, then calculate the prob
-                //based on arrival probability only
+            let dest_probability: f64 = if self.entry_floors.iter().any(|(floor, _)| *floor == i) {
+                //If people arrive on this floor, then calculate the prob
+                //based on the estimated arrivals only
                 let people_waiting: f64 = {
-                    let waiting: f64 = if floor.are_people_waiting() { 1_f64 } else { 0_f64 };
-                    let going: f64 = if dest_floors.contains(&i) { 1_f64 } else { 0_f64 };
+                    let waiting: f64 = if self.cache.waiting_floors[i] { 1_f64 } else { 0_f64 };
+                    let going: f64 = if self.cache.dest_floor_set.contains(&i) { 1_f64 } else { 0_f64 };
                     if waiting > going { waiting } else { going }
                 };
-                let p_in: f64 = self.p_in * ((num_floors as f64 - 1_f64)/(num_floors as f64));
+                let p_in: f64 = self.demand.arrivals[i];
                 if people_waiting > p_in { people_waiting } else { p_in }
             } else {
-                //If this is not the first floor, then calculate the
+                //If nobody arrives on this floor, then calculate the
                 //prob based on the elevator's people and the floor's
                 //people and append it to the list
                 let people_waiting: f64 = {
-                    let waiting: f64 = if floor.are_people_waiting() { 1_f64 } else { 0_f64 };
-                    let going: f64 = if dest_floors.contains(&i) { 1_f64 } else { 0_f64 };
+                    let waiting: f64 = if self.cache.waiting_floors[i] { 1_f64 } else { 0_f64 };
+                    let going: f64 = if self.cache.dest_floor_set.contains(&i) { 1_f64 } else { 0_f64 };
                     if waiting > going { waiting } else { going }
                 };
                 let p_out: f64 = floor.get_p_out();
@@ -133,167 +827,762 @@ impl Building {
         }
     }
 
-    /** gen_people_arriving function
+    /** gen_people function
      *
-     * Given an RNG, generate new people based on the arrival
-     * probability distribution.  Add the new people to the first
-     * floor.
+     * Generate the people arriving and leaving during a time step,
+     * drawing from the building's own RNG streams.  If the arrival
+     * probability distribution cannot be scaled for the time step, then
+     * an error is returned before anyone arrives or leaves.
      */
-    pub fn gen_people_arriving(&mut self, mut rng: &mut impl Rng) {
-        //Initialize a vector of Persons
-        let mut arrivals: Vec<Person> = Vec::new();
+    pub fn gen_people(&mut self) -> Result<(), ElevatorError> {
+        //Call or let out a meeting, then generate the people arriving
+        self.gen_meeting();
+        self.gen_people_arriving()?;
 
-        //Loop until no new arrivals occur, for each arrival append a new person
-        for _ in 0_i32..self.dst_in.sample(&mut rng) as i32 {
-            let new_person: Person = Person::from(P_OUT, self.floors.len(), &mut rng);
-            arrivals.push(new_person);
-        }
+        //Generate the people leaving on each floor from the stream of their decisions, which is lent out and put back
+        let mut behavior_rng: StdRng = self.behavior_rng.clone();
+        self.gen_people_leaving(&mut behavior_rng);
+        self.behavior_rng = behavior_rng;
 
-        //Extend the first floor with the new arrivals
-        self.floors[0].extend(arrivals);
+        //Generate the destination changes of the people on the elevators
+        self.gen_destination_changes();
+        Ok(())
     }
 
-    /** exchange_people_on_elevator function
+    /** gen_meeting function
      *
-     * This function flushes the floor of its people waiting for the
-     * elevator, and flushes the elevator of its people waiting to get
-     * off.  It extends the floor with the people who got off, and the
-     * elevator with the people who got on.  It also aggregates the
-     * averages 
+     * If meetings are layered over the destination model, then either
+     * count down the meeting under way, or call a meeting.  Once a
+     * meeting ends, the people idle on its floor each leave with the
+     * model's fraction, and their decisions to leave are recorded.
      */
-    pub fn exchange_people_on_elevator(&mut self) {
-        for elevator in self.elevators.iter_mut() {
-            //If the elevator is not stopped then continue
-            if !elevator.stopped {
-                continue;
+    pub fn gen_meeting(&mut self) {
+        let meetings: MeetingModel = match self.meetings {
+            Some(x) => x,
+            None => return
+        };
+        match self.meeting.as_mut() {
+            Some(meeting) if meeting.steps_left > 1_usize => meeting.steps_left -= 1_usize,
+            Some(meeting) => {
+                //Let the meeting out, sending the fraction of those on its floor down
+                let floor_index: usize = meeting.floor;
+                self.meeting = None;
+                self.cache.invalidate();
+                let leaving_ids: Vec<usize> = self.floors[floor_index]
+                    .gen_people_leaving_with(&mut self.meeting_rng, meetings.fraction);
+                self.events.push(SimEvent::MeetingEnded { floor: floor_index, num_leaving: leaving_ids.len() });
+                for person_id in leaving_ids {
+                    self.events.push(SimEvent::PersonLeaving { person_id, floor: floor_index });
+                    self.demand.record_hall_call(floor_index);
+                }
+            },
+            None => {
+                self.meeting = meetings.gen_meeting(&mut self.meeting_rng, self.floors.len(), &self.entry_floors);
+                if let Some(meeting) = self.meeting {
+                    self.events.push(SimEvent::MeetingStarted { floor: meeting.floor });
+                }
             }
+        }
+    }
 
-            //Get the elevator's floor index
-            let floor_index: usize = elevator.floor_on;
-
-            //Move people off the floor and off the elevator
-            let people_leaving_floor: Vec<Person> = self.floors[floor_index].flush_people_entering_elevator();
-            let mut people_leaving_elevator: Vec<Person> = elevator.flush_people_leaving_elevator();
-
-            //Aggregate the wait times of the people leaving the elevator into the average and reset
-            let wait_times: usize = people_leaving_elevator.get_aggregate_wait_time();
-            let num_people: usize = people_leaving_elevator.get_num_people();
-            self.avg_wait_time = {
-                let tmp_num: f64 = wait_times as f64 + (self.avg_wait_time * self.wait_time_denom as f64);
-                let tmp_denom: f64 = num_people as f64 + self.wait_time_denom as f64;
-                if tmp_denom == 0_f64 {
-                    0_f64 //If the denominator is 0, return 0 to avoid NaNs
-                } else {
-                    tmp_num / tmp_denom
+    /** gen_destination_changes function
+     *
+     * Decide for each person on an elevator whether they change their
+     * destination, such as having pressed the wrong button, and if so
+     * draw a new destination from the building's destination model,
+     * excluding the floor they are on.  People leaving the building
+     * keep heading for the lobby.
+     */
+    pub fn gen_destination_changes(&mut self) {
+        let num_floors: usize = self.floors.len();
+        if self.p_change_dest <= 0_f64 || num_floors < 2_usize {
+            return;
+        }
+        let mut changes: Vec<(usize, usize)> = Vec::new();
+        for elevator in self.elevators.iter() {
+            for pers in elevator.people.iter().filter(|pers| !pers.is_leaving) {
+                if !self.rerouting_rng.gen_bool(self.p_change_dest) {
+                    continue;
                 }
-            };
-            self.wait_time_denom += num_people;
-            people_leaving_elevator.reset_wait_times();
-
-            //Extend the current floor and elevator with the people getting on and off
-            elevator.extend(people_leaving_floor);
-            self.floors[floor_index].extend(people_leaving_elevator);
+                let floor_to: usize = match self.destination_model.get_dist(num_floors, pers.floor_on) {
+                    Some(destination_dist) => destination_dist.sample(&mut self.rerouting_rng),
+                    None => {
+                        let floor_to: usize = self.rerouting_rng.gen_range(0_usize..(num_floors - 1_usize));
+                        if floor_to >= pers.floor_on { floor_to + 1_usize } else { floor_to }
+                    }
+                };
+                changes.push((pers.id, floor_to));
+            }
         }
+        self.change_destinations(&changes);
     }
 
-    /** update_average_energy function
+    /** change_destinations function
      *
-     * Update the average energy expendature of the elevator given the
-     * current time step.
+     * Change the destination of each person with the given id on an
+     * elevator to the given floor, such as when replaying a recorded
+     * change, and record an event.  If the elevator was headed for the
+     * old destination and nobody else needs to stop there, then it is
+     * headed for its nearest remaining destination instead.
      */
-    pub fn update_average_energy(&mut self, time_step: i32, energy_spent: f64) {
-        self.avg_energy = {
-            let tmp_num: f64 = (self.avg_energy * time_step as f64) + energy_spent;
-            let tmp_denom: f64 = (time_step + 1_i32) as f64;
-            tmp_num / tmp_denom
-        };
+    pub fn change_destinations(&mut self, changes: &[(usize, usize)]) {
+        if !changes.is_empty() {
+            self.cache.invalidate();
+        }
+        for (person_id, floor_to) in changes.iter() {
+            for (i, elevator) in self.elevators.iter_mut().enumerate() {
+                let pers: &mut Person = match elevator.people.iter_mut().find(|pers| pers.id == *person_id) {
+                    Some(x) => x,
+                    None => continue
+                };
+                let old_floor_to: usize = pers.floor_to;
+                pers.change_destination(*floor_to);
+                self.events.push(SimEvent::DestinationChanged {
+                    person_id: *person_id,
+                    elevator: i,
+                    old_floor_to,
+                    floor_to: *floor_to
+                });
+
+                //Drop the old stop if nobody on the elevator or waiting on the floor needs it
+                let is_stop_needed: bool = elevator.people.are_people_going_to_floor(old_floor_to)
+                    || self.floors[old_floor_to].get_num_people_waiting() > 0_usize;
+                if elevator.floor_to == Some(old_floor_to) && !is_stop_needed {
+                    elevator.floor_to = elevator.get_nearest_dest_floor().map(|(x, _)| x);
+                }
+                break;
+            }
+        }
     }
-}
 
-//Display trait implementation for a building
-impl std::fmt::Display for Building {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut building_status: String = String::new();
-        let elevator_space: String = String::from("   \t ");
-        for (i, floor) in self.floors.iter().enumerate() {
-            //Initialize strings representing this floor
-            let mut floor_roof: String = String::from("----\t||---\t||");
-            let mut floor_body: String = format!("{:.2}\t||{}\t||", floor.dest_prob, floor.get_num_people());
+    /** gen_people_arriving function
+     *
+     * Generate new people based on the arrival probability
+     * distribution, each entering on a floor drawn by weight from the
+     * entry floors, with destinations other than it drawn following the
+     * destination model from their own stream.  While a meeting is
+     * under way, the model's fraction of those arriving on other floors
+     * head for the meeting instead, drawn from the meetings' stream.
+     * If people may refuse crowded elevators, then their crowding
+     * tolerances are drawn uniformly from the lowest tolerance to 1
+     * from a stream of their own too, as is whether they are slow to
+     * board.  If the building has a population limit, then those
+     * arriving beyond it are turned away and counted, after every
+     * arrival has been drawn.  Assign each new person an id and add the
+     * new people to their entry floors.  If the arrival probability
+     * distribution cannot be scaled for the time step, then an error is
+     * returned and nobody arrives.
+     */
+    pub fn gen_people_arriving(&mut self) -> Result<(), ElevatorError> {
+        //Initialize a vector of Persons, and scale the arrivals for this time step by the profile
+        let mut arrivals: Vec<Person> = Vec::new();
+        self.update_arrival_rate()?;
 
-            //If this floor has people waiting, then color it yellow
-            if floor.are_people_waiting() {
-                floor_roof = floor_roof.yellow().to_string();
-                floor_body = floor_body.yellow().to_string();
+        //Loop until no new arrivals occur, for each arrival append a new person
+        let num_arrivals: i32 = match &self.dst_in {
+            Some(dst_in) => dst_in.sample(&mut self.arrival_rng) as i32,
+            None => 0_i32
+        };
+        let meeting_fraction: f64 = self.meetings.map_or(0_f64, |x| x.fraction);
+        for _ in 0_i32..num_arrivals {
+            let entry_index: usize = match &self.entry_dist {
+                Some(entry_dist) => entry_dist.sample(&mut self.entry_rng),
+                None => 0_usize
+            };
+            let floor_on: usize = self.entry_floors[entry_index].0;
+            let floor_to: usize = match &self.destination_dists[entry_index] {
+                Some(destination_dist) => destination_dist.sample(&mut self.destination_rng),
+                None if self.floors.len() < 2_usize => floor_on,
+                None => {
+                    let floor_to: usize = Uniform::new(0_usize, self.floors.len() - 1_usize).sample(&mut self.destination_rng);
+                    if floor_to >= floor_on { floor_to + 1_usize } else { floor_to }
+                }
+            };
+            let floor_to: usize = match self.meeting {
+                Some(meeting) if meeting.floor != floor_on && self.meeting_rng.gen_bool(meeting_fraction) => meeting.floor,
+                _ => floor_to
+            };
+            let mut new_person: Person = Person::from_dist(self.p_out, self.dst_out, floor_on, floor_to);
+            if self.min_crowding_tolerance < 1_f64 {
+                new_person.crowding_tolerance = self.crowding_rng.gen_range(self.min_crowding_tolerance..=1_f64);
+            }
+            if self.p_slow_boarding > 0_f64 && self.mobility_rng.gen_bool(self.p_slow_boarding) {
+                new_person.boarding_ticks = self.slow_boarding_ticks;
             }
+            arrivals.push(new_person);
+        }
 
-            //Loop through the elevators to check if any are on this floor
-            let mut last_elevator_on_floor: usize = 0_usize;
-            for (j, elevator) in self.elevators.iter().enumerate() {
-                if elevator.floor_on != i as usize {
-                    continue;
+        //Turn away the arrivals beyond the population limit, if any
+        if let Some(max_population) = self.max_population {
+            let room: usize = max_population.saturating_sub(self.get_population());
+            if arrivals.len() > room {
+                for pers in arrivals.drain(room..) {
+                    self.events.push(SimEvent::ArrivalRejected { floor: pers.floor_on, floor_to: pers.floor_to });
+                    self.rejected_arrivals += 1_usize;
                 }
-
-                //If the elevator is on this floor, then display it i spaces away from the building
-                let elevator_roof: String = format!("{}{}", str::repeat(&elevator_space, j - last_elevator_on_floor as usize), String::from("|-\t|"));
-                let elevator_body: String = format!("{}|{}\t|", str::repeat(&elevator_space, j - last_elevator_on_floor as usize), elevator.get_num_people());
-
-                //Append the elevator to the floor strings
-                floor_roof.push_str(&elevator_roof);
-                floor_body.push_str(&elevator_body);
-
-                //Increment the counter for num elevators on this floor
-                last_elevator_on_floor = j + 1_usize;
             }
-
-            //Add the floor to the building status
-            building_status = [floor_roof, floor_body, building_status].join("\n");
         }
-        //Add the average energy and wait times throughout the building
-        let wait_time_str: String = format!("Average wait time:\t{:.2}", self.avg_wait_time);
-        let energy_str: String = format!("Average energy spent:\t{:.2}", self.avg_energy);
-        building_status = [building_status, wait_time_str, energy_str].join("\n");
 
-        //Format the string and return
-        f.write_str(&building_status)
+        //Add the new arrivals to the building
+        self.add_arrivals(arrivals);
+        Ok(())
     }
-}
 
-//Floors trait implementation for a building
-impl Floors for Building {
-    /** are_people_waiting_on_floor function
+    /** add_arrivals function
      *
-     * Call the floor vec implementation of the function and return
-     * the result.
+     * Assign each arriving person an id, record their arrival, and
+     * add them to the floor they arrive on.
      */
-    fn are_people_waiting_on_floor(&self, floor_index: usize) -> bool {
-        self.floors.are_people_waiting_on_floor(floor_index)
+    pub fn add_arrivals(&mut self, mut arrivals: Vec<Person>) {
+        for new_person in arrivals.iter_mut() {
+            new_person.id = self.next_person_id;
+            self.next_person_id += 1_usize;
+            self.events.push(SimEvent::PersonArrived {
+                person_id: new_person.id,
+                floor: new_person.floor_on,
+                floor_to: new_person.floor_to,
+                p_out: new_person.p_out,
+                crowding_tolerance: new_person.crowding_tolerance,
+                boarding_ticks: new_person.boarding_ticks
+            });
+            self.demand.record_arrival(new_person.floor_on);
+            self.num_arrivals += 1_usize;
+        }
+
+        //Extend the floors the new arrivals are on with them
+        if !arrivals.is_empty() {
+            self.cache.invalidate();
+        }
+        for new_person in arrivals {
+            let floor_on: usize = new_person.floor_on;
+            self.floors[floor_on].extend(std::iter::once(new_person));
+        }
     }
 
-    /** get_nearest_wait_floor function
+    /** place_person function
      *
-     * Call the floor vec implementation of the function and return
-     * the result.
+     * Place a person already in the building, such as one described by
+     * a layout, on the elevator at the given index if any, or else on
+     * the floor they are on, without recording an arrival.  The people
+     * arriving later are given ids beyond theirs, and the ids of nobody
+     * placed are taken to be of people who have left, so that the
+     * population stays the number of people in the building.  Each
+     * person placed must have an id no one else has.
      */
-    fn get_nearest_wait_floor(&self, floor_on: usize) -> (usize, usize) {
-        self.floors.get_nearest_wait_floor(floor_on)
+    pub fn place_person(&mut self, pers: Person, elevator_index: Option<usize>) {
+        if pers.id >= self.next_person_id {
+            self.num_departed += pers.id - self.next_person_id;
+            self.next_person_id = pers.id + 1_usize;
+        } else {
+            self.num_departed -= 1_usize;
+        }
+        self.cache.invalidate();
+        match elevator_index {
+            Some(i) => self.elevators[i].people.push(pers),
+            None => {
+                let floor_on: usize = pers.floor_on;
+                self.floors[floor_on].extend(std::iter::once(pers));
+            }
+        }
     }
 
-    /** get_dest_probabilities function
+    /** set_people_leaving function
      *
-     * Call the floor vec implementation of the function and return
-     * the result.
+     * Mark the people with the given ids as leaving, wherever they are
+     * not waiting for an elevator, and record their decision.
      */
-    fn get_dest_probabilities(&self) -> Vec<f64> {
-        self.floors.get_dest_probabilities()
+    pub fn set_people_leaving(&mut self, person_ids: &[usize]) {
+        self.cache.invalidate();
+        for (i, floor) in self.floors.iter_mut().enumerate() {
+            for person_id in floor.set_people_leaving(person_ids) {
+                self.events.push(SimEvent::PersonLeaving { person_id, floor: i });
+                if i > 0_usize {
+                    self.demand.record_hall_call(i);
+                }
+            }
+        }
     }
 
-    /** gen_people_leaving function
+    /** check_invariants function
      *
-     * Call the floor vec implementation of the function and return
-     * the result.
+     * Check that the state of the building is consistent after a time
+     * step.  Every elevator and destination must be within the
+     * building, every person must be on the floor they are in or on
+     * their elevator's floor, no passenger may stay on at their
+     * destination once the elevator has stopped there for a full time
+     * step, and everyone who arrived and has not left must still be
+     * in the building.  Return the first violation found, if any.
      */
-    fn gen_people_leaving(&mut self, rng: &mut impl Rng) {
-        self.floors.gen_people_leaving(rng)
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let num_floors: usize = self.floors.len();
+        let mut population: usize = 0_usize;
+
+        //Check the people on each floor
+        for (i, floor) in self.floors.iter().enumerate() {
+            for pers in floor.get_people().iter() {
+                if pers.floor_on != i {
+                    return Err(InvariantViolation::PersonOffFloor {
+                        person_id: pers.id, floor: i, floor_on: pers.floor_on
+                    });
+                }
+                if pers.floor_to >= num_floors {
+                    return Err(InvariantViolation::PersonTargetOutOfBounds {
+                        person_id: pers.id, floor_to: pers.floor_to, num_floors
+                    });
+                }
+                if !pers.is_state_consistent(false) {
+                    return Err(InvariantViolation::PersonStateMismatch {
+                        person_id: pers.id, state: pers.state, on_elevator: false, floor_on: pers.floor_on, floor_to: pers.floor_to
+                    });
+                }
+            }
+            population += floor.get_people().len();
+        }
+
+        //Check each elevator and the people on it
+        for (i, elevator) in self.elevators.iter().enumerate() {
+            if elevator.floor_on >= num_floors {
+                return Err(InvariantViolation::ElevatorOutOfBounds {
+                    elevator: i, floor_on: elevator.floor_on, num_floors
+                });
+            }
+            if let Some(floor_to) = elevator.floor_to {
+                if floor_to >= num_floors {
+                    return Err(InvariantViolation::ElevatorTargetOutOfBounds {
+                        elevator: i, floor_to, num_floors
+                    });
+                }
+            }
+            for pers in elevator.people.iter() {
+                if !pers.is_state_consistent(true) {
+                    return Err(InvariantViolation::PersonStateMismatch {
+                        person_id: pers.id, state: pers.state, on_elevator: true, floor_on: pers.floor_on, floor_to: pers.floor_to
+                    });
+                }
+                if pers.floor_on != elevator.floor_on {
+                    return Err(InvariantViolation::PersonOffElevator {
+                        person_id: pers.id, elevator: i, elevator_floor: elevator.floor_on, floor_on: pers.floor_on
+                    });
+                }
+                if pers.floor_to >= num_floors {
+                    return Err(InvariantViolation::PersonTargetOutOfBounds {
+                        person_id: pers.id, floor_to: pers.floor_to, num_floors
+                    });
+                }
+                //An elevator stopped for two time steps was stopped when people last got off
+                if elevator.stopped && elevator.stopped_steps > 1_usize && pers.floor_to == elevator.floor_on {
+                    return Err(InvariantViolation::PassengerNotAlighted {
+                        person_id: pers.id, elevator: i, floor: elevator.floor_on
+                    });
+                }
+            }
+            population += elevator.people.len();
+        }
+
+        //Check that nobody appeared or vanished
+        let expected_population: usize = self.next_person_id - self.num_departed;
+        if population != expected_population {
+            return Err(InvariantViolation::PopulationMismatch {
+                expected: expected_population, found: population
+            });
+        }
+        Ok(())
+    }
+
+    /** exchange_people_on_elevator function
+     *
+     * This function flushes the floor of its people waiting for the
+     * elevator, and flushes the elevator of its people waiting to get
+     * off.  It extends the floor with the people who got off, and the
+     * elevator with the people who got on.  It also aggregates the
+     * averages and records an event for each person getting on or off.
+     * The elevators take turns in the building's exchange order, and if
+     * several which may take on people are stopped on the same floor,
+     * then those waiting are split among them, see get_boarding_share.
+     */
+    pub fn exchange_people_on_elevator(&mut self) {
+        self.cache.invalidate();
+        let may_board: Vec<bool> = (0_usize..self.elevators.len()).map(|i| self.may_serve_passengers(i)).collect();
+        let boarding_rooms: Vec<Option<(usize, usize)>> = (0_usize..self.elevators.len())
+            .map(|i| self.get_boarding_room(i, may_board[i]))
+            .collect();
+        let order: Vec<usize> = self.get_exchange_order();
+        let mut emptied_floors: Vec<(usize, usize)> = Vec::new();
+        for (position, i) in order.iter().copied().enumerate() {
+            let elevator: &mut Elevator = &mut self.elevators[i];
+
+            //If the elevator is not stopped then continue
+            if !elevator.stopped {
+                continue;
+            }
+
+            //Get the elevator's floor index
+            let floor_index: usize = elevator.floor_on;
+
+            //Move people off the elevator, then off the floor, only boarding those whose destination
+            //it serves while it has room, up to its share if other elevators are stopped on the floor,
+            //and nobody on a floor it does not serve or onto a freight elevator which may not carry people
+            let mut people_leaving_elevator: Vec<Person> = elevator.flush_people_leaving_elevator();
+            let share: Option<usize> = get_boarding_share(
+                position, &order, &boarding_rooms, self.floors[floor_index].get_num_people_waiting()
+            );
+            let people_leaving_floor: Vec<Person> = match (&self.zones, elevator.capacity, share) {
+                _ if !may_board[i] => Vec::new(),
+                (None, None, None) => self.floors[floor_index].flush_people_entering_elevator(),
+                (Some(zones), _, _) if !zones.serves(i, floor_index) => Vec::new(),
+                (zones, capacity, share) => self.floors[floor_index].flush_people_entering_elevator_to(
+                    |x| zones.as_ref().is_none_or(|z| z.serves(i, x)), capacity, elevator.people.len(), share
+                )
+            };
+
+            //Fold the wait times of the people leaving the elevator into the stats and reset
+            let num_people: usize = people_leaving_elevator.get_num_people();
+            for pers in people_leaving_elevator.iter() {
+                self.wait_times.push(pers.wait_time.0);
+                self.floor_wait_times.push(pers.wait_time.0.saturating_sub(pers.ride_time.0));
+            }
+            elevator.num_delivered += num_people;
+            elevator.total_wait_time = elevator.total_wait_time.saturating_add(people_leaving_elevator.get_aggregate_wait_time());
+            self.total_ride_time = self.total_ride_time.saturating_add(people_leaving_elevator.iter().map(|pers| pers.ride_time).sum());
+            self.max_ride_time = people_leaving_elevator.iter()
+                .map(|pers| pers.ride_time)
+                .fold(self.max_ride_time, Ticks::max);
+            people_leaving_elevator.reset_wait_times();
+
+            //Hold the elevator's doors open for as long as the slowest person getting on or off takes
+            elevator.hold_doors(people_leaving_elevator.iter().chain(people_leaving_floor.iter()).map(|pers| pers.boarding_ticks));
+            elevator.update_door_state(people_leaving_floor.len() + num_people);
+
+            //Record the people getting on and off
+            for pers in people_leaving_elevator.iter() {
+                self.events.push(SimEvent::PersonAlighted { person_id: pers.id, elevator: i, floor: floor_index });
+            }
+            if num_people > 0_usize {
+                self.events.push(SimEvent::ElevatorStopped { elevator: i, floor: floor_index, num_alighted: num_people });
+            }
+            for pers in people_leaving_floor.iter() {
+                self.events.push(SimEvent::PersonBoarded {
+                    person_id: pers.id,
+                    elevator: i,
+                    floor: floor_index,
+                    wait_time: pers.wait_time
+                });
+            }
+
+            //Count the people getting on and off at the floor, then extend the current floor and
+            //elevator with them
+            let num_boarded: usize = people_leaving_floor.len();
+            self.floors[floor_index].num_boarded += num_boarded;
+            self.floors[floor_index].num_alighted += num_people;
+            elevator.extend(people_leaving_floor);
+            self.floors[floor_index].extend(people_leaving_elevator);
+
+            //If the elevator took everyone waiting on the floor, then the floor's call is satisfied
+            if num_boarded > 0_usize && self.floors[floor_index].get_num_people_waiting() == 0_usize {
+                emptied_floors.push((floor_index, i));
+            }
+        }
+
+        //Cancel the trips of other elevators to the floors whose calls were satisfied
+        self.cancel_stale_calls(&emptied_floors);
+    }
+
+    /** get_exchange_order function
+     *
+     * Return the indices of the elevators in the order they exchange
+     * people in during the current time step, drawing it if shuffled.
+     */
+    fn get_exchange_order(&mut self) -> Vec<usize> {
+        let num_elevators: usize = self.elevators.len();
+        match self.exchange_order {
+            ExchangeOrder::Fixed => (0_usize..num_elevators).collect(),
+            ExchangeOrder::RoundRobin if num_elevators > 0_usize => {
+                let start: usize = (self.current_step % num_elevators as u64) as usize;
+                (start..num_elevators).chain(0_usize..start).collect()
+            },
+            ExchangeOrder::RoundRobin => Vec::new(),
+            ExchangeOrder::Shuffled => {
+                let mut order: Vec<usize> = (0_usize..num_elevators).collect();
+                order.shuffle(&mut self.exchange_rng);
+                order
+            }
+        }
+    }
+
+    /** get_boarding_room function
+     *
+     * Return the floor the elevator with the given index is stopped on,
+     * along with the room it has for people boarding there once its
+     * passengers for the floor get off, or None if it may not take on
+     * anyone, such as while moving or on a floor outside its zone.  The
+     * room of an elevator without a capacity is unlimited.
+     */
+    fn get_boarding_room(&self, elevator_index: usize, may_board: bool) -> Option<(usize, usize)> {
+        let elevator: &Elevator = &self.elevators[elevator_index];
+        if !elevator.stopped || !may_board || self.zones.as_ref().is_some_and(|z| !z.serves(elevator_index, elevator.floor_on)) {
+            return None;
+        }
+        let room: usize = match elevator.capacity {
+            Some(x) => x.saturating_sub(elevator.people.iter().filter(|pers| pers.is_travelling()).count()),
+            None => usize::MAX
+        };
+        Some((elevator.floor_on, room))
+    }
+
+    /** cancel_stale_calls function
+     *
+     * Given the floors emptied during the exchange, each along with the
+     * elevator which emptied it, drop the floor as the target of any
+     * other moving elevator headed there for the call, unless one of
+     * its passengers is getting off there, and record an event.  The
+     * elevator is headed for its nearest remaining destination instead,
+     * if any, so that its controller may decide where it goes next in
+     * the same time step.  Homing, out of service and shuttle elevators
+     * keep their targets, since they are not headed there for the call.
+     */
+    fn cancel_stale_calls(&mut self, emptied_floors: &[(usize, usize)]) {
+        for (floor_index, emptied_by) in emptied_floors.iter() {
+            for (i, elevator) in self.elevators.iter_mut().enumerate() {
+                let is_stale: bool = i != *emptied_by
+                    && !elevator.stopped
+                    && elevator.floor_to == Some(*floor_index)
+                    && elevator.floor_on != *floor_index
+                    && elevator.in_service
+                    && !elevator.homing
+                    && self.shuttle.as_ref().is_none_or(|x| x.elevator != i)
+                    && !elevator.people.are_people_going_to_floor(*floor_index);
+                if !is_stale {
+                    continue;
+                }
+                elevator.floor_to = elevator.get_nearest_dest_floor().map(|(x, _)| x);
+                self.events.push(SimEvent::CallCancelled { elevator: i, floor: *floor_index, emptied_by: *emptied_by });
+                sim_event!(debug, elevator = i, floor = *floor_index, emptied_by = *emptied_by, "call cancelled");
+            }
+        }
+    }
+
+    /** update_cache function
+     *
+     * Recompute the building's cache of waiting and destination floors.
+     * This is called once per time step after people are exchanged on
+     * the elevators, so that the controller, the dest probabilities and
+     * the render all share a single scan of the building's people.
+     * Freight elevators are also opted in if more people are waiting
+     * than the surge threshold, if any.
+     */
+    pub fn update_cache(&mut self) {
+        self.cache.update(&self.floors, &self.elevators);
+        self.freight_opted_in = match self.freight_surge_waiting {
+            Some(x) => self.floors.iter().map(|floor| floor.get_num_people_waiting()).sum::<usize>() > x,
+            None => false
+        };
+    }
+
+    /** render_into function
+     *
+     * Render every floor of the building in full into the given buffer,
+     * which may be reused across frames to avoid reallocating.
+     */
+    pub fn render_into(&self, buf: &mut impl std::fmt::Write) -> std::fmt::Result {
+        let view: View = View::full(self.floors.len());
+        render_building(buf, self, &view)
+    }
+
+    /** update_average_energy function
+     *
+     * Fold the energy spent by the elevators during the current time
+     * step into the average over the measured time steps.
+     */
+    pub fn update_average_energy(&mut self, energy_spent: Energy) {
+        self.energy.push_f64(energy_spent.0);
+    }
+
+    /** get_energy_spent function
+     *
+     * Return the energy metered by the elevators during the current
+     * time step, as they moved.
+     */
+    pub fn get_energy_spent(&self) -> Energy {
+        self.elevators.get_energy_spent()
+    }
+
+    /** get_num_delivered function
+     *
+     * Return the number of people who have reached their destination
+     * on an elevator, whose wait times the average covers.
+     */
+    pub fn get_num_delivered(&self) -> usize {
+        self.wait_times.get_count() as usize
+    }
+
+    /** get_num_people_waiting_on_floor function
+     *
+     * Return the number of people waiting on the given floor, from the
+     * cache unless it is stale.
+     */
+    pub fn get_num_people_waiting_on_floor(&self, floor_index: usize) -> usize {
+        if self.cache.is_stale() {
+            return self.floors[floor_index].get_num_people_waiting();
+        }
+        self.cache.waiting_counts[floor_index]
+    }
+
+    /** get_population function
+     *
+     * Return the number of people in the building, on its floors and
+     * elevators.
+     */
+    pub fn get_population(&self) -> usize {
+        self.next_person_id - self.num_departed
+    }
+
+    /** tick function
+     *
+     * End the current time step, given the energy spent by the
+     * elevators during it.  The energy is folded into the average, and
+     * the time step is counted toward the run and the metrics.
+     */
+    pub fn tick(&mut self, energy_spent: Energy) {
+        self.update_average_energy(energy_spent);
+        for elevator in self.elevators.iter_mut() {
+            elevator.energy_meter.end_step();
+        }
+        self.measured_steps += 1_u64;
+        self.current_step += 1_u64;
+    }
+
+    /** get_average_ride_time function
+     *
+     * Return the average time spent on an elevator by those who have
+     * reached their destination, 0 if nobody has.
+     */
+    pub fn get_average_ride_time(&self) -> f64 {
+        match self.get_num_delivered() {
+            0_usize => 0_f64,
+            num_delivered => self.total_ride_time.as_f64() / num_delivered as f64
+        }
+    }
+
+    /** summary function
+     *
+     * Return a one-line summary of the run so far, with the number of
+     * time steps simulated, the average wait time and energy spent,
+     * the number of people who reached their destination, and the
+     * number who arrived under the arrival profile.
+     */
+    pub fn summary(&self) -> String {
+        format!(
+            "{} steps, avg wait {:.2}, avg energy {:.2}, {} people served, {} arrived ({} profile)",
+            self.current_step, self.wait_times.get_mean(), self.energy.get_mean(), self.get_num_delivered(),
+            self.num_arrivals, self.arrival_profile.get_name()
+        )
+    }
+
+    /** reset_metrics function
+     *
+     * Forget the wait and ride times and energy measured so far, including
+     * those of each elevator and floor, so that the metrics only cover the time steps
+     * from the next one on, such as after a warm-up.  The people in the
+     * building and their wait times so far are kept.
+     */
+    pub fn reset_metrics(&mut self) {
+        self.energy = StreamingStats::new();
+        self.wait_times = StreamingStats::new();
+        self.floor_wait_times = StreamingStats::new();
+        self.total_ride_time = Ticks(0_u64);
+        self.max_ride_time = Ticks(0_u64);
+        self.measured_steps = 0_u64;
+        self.num_arrivals = 0_usize;
+        self.rejected_arrivals = 0_usize;
+        self.missed_stops = 0_usize;
+        for elevator in self.elevators.iter_mut() {
+            elevator.num_delivered = 0_usize;
+            elevator.total_wait_time = Ticks(0_u64);
+            elevator.energy_meter.reset();
+        }
+        for floor in self.floors.iter_mut() {
+            floor.num_boarded = 0_usize;
+            floor.num_alighted = 0_usize;
+        }
+    }
+}
+
+//Display trait implementation for a building
+impl std::fmt::Display for Building {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render_into(f)
+    }
+}
+
+//Floors trait implementation for a building
+impl Floors for Building {
+    /** are_people_waiting_on_floor function
+     *
+     * Read the cache, or call the floor vec implementation of the
+     * function if the cache is stale, and return the result.
+     */
+    fn are_people_waiting_on_floor(&self, floor_index: usize) -> bool {
+        if self.cache.is_stale() {
+            return self.floors.are_people_waiting_on_floor(floor_index);
+        }
+        self.cache.waiting_floors[floor_index]
+    }
+
+    /** get_nearest_wait_floor function
+     *
+     * Read the cache, or call the floor vec implementation of the
+     * function if the cache is stale, and return the result.
+     */
+    fn get_nearest_wait_floor(&self, floor_on: usize) -> Option<(usize, usize)> {
+        if self.cache.is_stale() {
+            return self.floors.get_nearest_wait_floor(floor_on);
+        }
+        self.cache.get_nearest_wait_floor(floor_on)
+    }
+
+    /** get_longest_waiting_floor function
+     *
+     * Call the floor vec implementation of the function and return
+     * the result.
+     */
+    fn get_longest_waiting_floor(&self, claimed: &[bool]) -> Option<(usize, Ticks)> {
+        self.floors.get_longest_waiting_floor(claimed)
+    }
+
+    /** get_dest_probabilities function
+     *
+     * Call the floor vec implementation of the function and return
+     * the result.
+     */
+    fn get_dest_probabilities(&self) -> Vec<f64> {
+        self.floors.get_dest_probabilities()
+    }
+
+    /** get_called_destinations function
+     *
+     * Call the floor vec implementation of the function and return
+     * the result.
+     */
+    fn get_called_destinations(&self) -> Vec<Vec<usize>> {
+        self.floors.get_called_destinations()
+    }
+
+    /** gen_people_leaving function
+     *
+     * Generate the people leaving on each floor and record their
+     * decision to leave, along with the hall call of each person who
+     * must now wait for an elevator down to the first floor.
+     */
+    fn gen_people_leaving(&mut self, mut rng: &mut impl Rng) {
+        self.cache.invalidate();
+        for (i, floor) in self.floors.iter_mut().enumerate() {
+            for person_id in floor.gen_people_leaving(&mut rng) {
+                self.events.push(SimEvent::PersonLeaving { person_id, floor: i });
+                if i > 0_usize {
+                    self.demand.record_hall_call(i);
+                }
+            }
+        }
     }
 
     /** flush_first_floor function
@@ -301,8 +1590,13 @@ impl Floors for Building {
      * Call the floor vec implementation of the function and return
      * the result.
      */
-    fn flush_first_floor(&mut self) {
-        self.floors.flush_first_floor();
+    fn flush_first_floor(&mut self) -> usize {
+        let num_departed: usize = self.floors.flush_first_floor();
+        self.num_departed += num_departed;
+        if num_departed > 0_usize {
+            self.cache.invalidate();
+        }
+        num_departed
     }
 
     /** increment_wait_times function
@@ -314,4 +1608,1094 @@ impl Floors for Building {
         self.elevators.increment_wait_times();
         self.floors.increment_wait_times();
     }
-}
\ No newline at end of file
+}
+
+/** get_boarding_share function
+ *
+ * Return the most people the elevator at the given position of the
+ * exchange order may take on from the given number waiting on its
+ * floor, given the floor and room of each elevator which may take on
+ * people, by index, or None if no other elevator on the floor is yet to
+ * take on people, so that the last one takes whoever is left.
+ * Elevators on the same floor take on people in the exchange order,
+ * each taking an even share of those still waiting, rounded up, or
+ * more if the elevators after it have too little room between them
+ * for the rest, so that nobody is left behind while any of them has
+ * room.
+ */
+pub fn get_boarding_share(position: usize, order: &[usize], boarding_rooms: &[Option<(usize, usize)>],
+                          num_waiting: usize) -> Option<usize> {
+    //Get the room of the elevators on the same floor which take on people after this one
+    let floor_index: usize = boarding_rooms[order[position]]?.0;
+    let rooms_after: Vec<usize> = order[(position + 1_usize)..].iter()
+        .filter_map(|i| boarding_rooms[*i].filter(|(floor, _)| *floor == floor_index).map(|(_, room)| room))
+        .collect();
+    if rooms_after.is_empty() {
+        return None;
+    }
+
+    //Take an even share, or whoever the rest have no room for
+    let even_share: usize = num_waiting.div_ceil(rooms_after.len() + 1_usize);
+    let room_after: usize = rooms_after.iter().fold(0_usize, |acc, x| acc.saturating_add(*x));
+    Some(even_share.max(num_waiting.saturating_sub(room_after)))
+}
+
+/** get_arrival_distribution function
+ *
+ * Return the distribution of the number of people arriving each time
+ * step given the expected number, or None if it is 0 and so nobody
+ * arrives.  If the expected number is not in [0, inf), then an error
+ * is returned.
+ */
+pub fn get_arrival_distribution(p_in: f64) -> Result<Option<Poisson>, ElevatorError> {
+    if !(p_in.is_finite() && p_in >= 0_f64) {
+        return Err(ElevatorError::Distribution(format!("expected arrivals {} is not in [0, inf)", p_in)));
+    }
+    if p_in == 0_f64 {
+        return Ok(None);
+    }
+    Poisson::new(p_in)
+        .map(Some)
+        .map_err(|e| ElevatorError::Distribution(format!("expected arrivals {}: {}", p_in, e)))
+}
+
+/** check_arrival_rate function
+ *
+ * Check that the given expected arrivals give an arrival probability
+ * distribution however an arrival profile scales them, so that scaling
+ * them on each time step cannot fail.
+ */
+fn check_arrival_rate(p_in: f64) -> Result<(), ElevatorError> {
+    get_arrival_distribution(p_in)?;
+    get_arrival_distribution(p_in * MAX_MULTIPLIER).map_err(|_| ElevatorError::Distribution(format!(
+        "expected arrivals {} overflow when scaled by an arrival profile", p_in
+    )))?;
+    Ok(())
+}
+
+/** validate_entry_floors function
+ *
+ * Check that each entry floor is a distinct floor of a building with
+ * the given number of floors, with a finite nonnegative weight, and
+ * that the weights are not all 0 and have a finite sum.  No entry
+ * floors at all leave everyone arriving on the first floor.
+ */
+pub fn validate_entry_floors(entry_floors: &[(usize, f64)], num_floors: usize) -> Result<(), String> {
+    for (i, (floor, weight)) in entry_floors.iter().enumerate() {
+        if *floor >= num_floors {
+            return Err(format!("entry floor {} is out of range for a building with {} floors", floor, num_floors));
+        }
+        if entry_floors[..i].iter().any(|(x, _)| x == floor) {
+            return Err(format!("entry floor {} is given more than once", floor));
+        }
+        if !(weight.is_finite() && *weight >= 0_f64) {
+            return Err(format!("entry floor {} weight {} must be in [0, inf)", floor, weight));
+        }
+    }
+    if !entry_floors.is_empty() && entry_floors.iter().all(|(_, weight)| *weight == 0_f64) {
+        return Err(String::from("the entry floor weights must not all be 0"));
+    }
+    let total_weight: f64 = entry_floors.iter().map(|(_, weight)| *weight).sum();
+    if !total_weight.is_finite() {
+        return Err(format!("the entry floor weights sum to {}, which is not finite", total_weight));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{get_empty_building, get_person};
+    use crate::person::PersonState;
+    use crate::controller::{ElevatorController, ControllerKind, NearestController, CommandController};
+    use crate::simulation::{SimulationConfig, build_controller, step, step_with};
+    use crate::params::ControllerParams;
+    use crate::profile::Profiler;
+
+    #[test]
+    fn consistent_building_has_no_violation() {
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.place_person(get_person(0_usize, 2_usize, 0_usize), None);
+        building.place_person(get_person(1_usize, 0_usize, 3_usize), None);
+        assert_eq!(building.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn corrupt_elevators_are_reported() {
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.elevators[0].floor_on = 4_usize;
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::ElevatorOutOfBounds {
+            elevator: 0_usize, floor_on: 4_usize, num_floors: 4_usize
+        }));
+
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.elevators[0].floor_to = Some(7_usize);
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::ElevatorTargetOutOfBounds {
+            elevator: 0_usize, floor_to: 7_usize, num_floors: 4_usize
+        }));
+    }
+
+    #[test]
+    fn corrupt_people_on_floors_are_reported() {
+        //Someone on a floor other than the one they think they are on
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.place_person(get_person(0_usize, 2_usize, 0_usize), None);
+        building.floors[2].extend(std::iter::once(get_person(1_usize, 3_usize, 0_usize)));
+        building.next_person_id = 2_usize;
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PersonOffFloor {
+            person_id: 1_usize, floor: 2_usize, floor_on: 3_usize
+        }));
+
+        //Someone headed beyond the top floor
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.place_person(get_person(0_usize, 1_usize, 9_usize), None);
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PersonTargetOutOfBounds {
+            person_id: 0_usize, floor_to: 9_usize, num_floors: 4_usize
+        }));
+
+        //Someone riding on a floor
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        let mut pers: Person = get_person(0_usize, 1_usize, 3_usize);
+        pers.state = PersonState::Riding;
+        building.place_person(pers, None);
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PersonStateMismatch {
+            person_id: 0_usize, state: PersonState::Riding, on_elevator: false, floor_on: 1_usize, floor_to: 3_usize
+        }));
+    }
+
+    #[test]
+    fn corrupt_passengers_are_reported() {
+        //A passenger waiting rather than riding
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.place_person(get_person(0_usize, 0_usize, 3_usize), Some(0_usize));
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PersonStateMismatch {
+            person_id: 0_usize, state: PersonState::WaitingUp, on_elevator: true, floor_on: 0_usize, floor_to: 3_usize
+        }));
+
+        //A passenger on a floor other than their elevator's
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        let mut pers: Person = get_person(0_usize, 2_usize, 3_usize);
+        pers.state = PersonState::Riding;
+        building.place_person(pers, Some(0_usize));
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PersonOffElevator {
+            person_id: 0_usize, elevator: 0_usize, elevator_floor: 0_usize, floor_on: 2_usize
+        }));
+
+        //A passenger who stayed on at their destination while the elevator was stopped
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        let mut pers: Person = get_person(0_usize, 0_usize, 0_usize);
+        pers.state = PersonState::Riding;
+        building.place_person(pers, Some(0_usize));
+        building.elevators[0].stopped = true;
+        building.elevators[0].stopped_steps = 2_usize;
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PassengerNotAlighted {
+            person_id: 0_usize, elevator: 0_usize, floor: 0_usize
+        }));
+    }
+
+    #[test]
+    fn vanished_people_are_reported() {
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.place_person(get_person(0_usize, 0_usize, 2_usize), None);
+        building.place_person(get_person(1_usize, 0_usize, 3_usize), None);
+        building.next_person_id = 3_usize;
+        assert_eq!(building.check_invariants(), Err(InvariantViolation::PopulationMismatch {
+            expected: 3_usize, found: 2_usize
+        }));
+    }
+
+    //Check that the cached answers of the building match those computed from its floors
+    fn check_cache(building: &Building, context: &str) {
+        for i in 0_usize..building.floors.len() {
+            assert_eq!(
+                building.are_people_waiting_on_floor(i), building.floors.are_people_waiting_on_floor(i),
+                "waiting on floor {} after {}", i, context
+            );
+            assert_eq!(
+                building.get_nearest_wait_floor(i), building.floors.get_nearest_wait_floor(i),
+                "nearest wait floor to {} after {}", i, context
+            );
+            assert_eq!(
+                building.get_num_people_waiting_on_floor(i), building.floors[i].get_num_people_waiting(),
+                "number waiting on floor {} after {}", i, context
+            );
+        }
+    }
+
+    #[test]
+    fn cache_matches_floors_after_every_mutation() {
+        let mut controller: NearestController = NearestController::from(
+            Building::from(8_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 3_u64).unwrap()
+        );
+        let mut rng: StdRng = RngStream::Controller(7_usize).get_rng(0_u64);
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..2000_i32 {
+            let building: &mut Building = controller.get_building_mut();
+            let num_floors: usize = building.floors.len();
+            let context: &str = match rng.gen_range(0_usize..8_usize) {
+                0_usize => {
+                    let arrivals: Vec<Person> = (0_usize..rng.gen_range(1_usize..4_usize)).map(|_| {
+                        Person::from_dest(0.05_f64, rng.gen_range(0_usize..num_floors), rng.gen_range(0_usize..num_floors)).unwrap()
+                    }).collect();
+                    building.add_arrivals(arrivals);
+                    "add_arrivals"
+                },
+                1_usize => {
+                    let mut pers: Person = Person::from_dest(0.05_f64, rng.gen_range(0_usize..num_floors), 0_usize).unwrap();
+                    pers.id = building.next_person_id;
+                    building.place_person(pers, None);
+                    "place_person"
+                },
+                2_usize => {
+                    let person_ids: Vec<usize> = (0_usize..3_usize).map(|_| rng.gen_range(0_usize..building.next_person_id.max(1_usize))).collect();
+                    building.set_people_leaving(&person_ids);
+                    "set_people_leaving"
+                },
+                3_usize => {
+                    building.exchange_people_on_elevator();
+                    "exchange_people_on_elevator"
+                },
+                4_usize => {
+                    building.gen_people().unwrap();
+                    "gen_people"
+                },
+                5_usize => {
+                    building.flush_first_floor();
+                    "flush_first_floor"
+                },
+                6_usize => {
+                    let changes: Vec<(usize, usize)> = building.elevators.iter()
+                        .flat_map(|elevator| elevator.people.iter().map(|pers| pers.id))
+                        .map(|person_id| (person_id, rng.gen_range(0_usize..num_floors)))
+                        .collect();
+                    building.change_destinations(&changes);
+                    "change_destinations"
+                },
+                _ => {
+                    step(&mut controller, i, &mut profiler).unwrap();
+                    "step"
+                }
+            };
+            check_cache(controller.get_building(), context);
+        }
+    }
+
+    #[test]
+    fn capacity_one_takes_a_stop_per_person() {
+        //Three people wait on the ground floor for an elevator which carries one at a time
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.set_capacity(Some(1_usize));
+        for id in 0_usize..3_usize {
+            building.place_person(get_person(id, 0_usize, 3_usize), None);
+        }
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //Count the stops on the ground floor at which someone boarded, checking the elevator left in between
+        let mut num_waiting: usize = 3_usize;
+        let mut num_stops: usize = 0_usize;
+        let mut left_since_stop: bool = true;
+        for i in 0_i32..100_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+            assert!(building.elevators[0].people.len() <= 1_usize, "over capacity at step {}", i);
+            left_since_stop |= building.elevators[0].floor_on != 0_usize;
+            let num_waiting_now: usize = building.get_num_people_waiting_on_floor(0_usize);
+            if num_waiting_now < num_waiting {
+                assert_eq!(num_waiting - num_waiting_now, 1_usize, "boarded together at step {}", i);
+                assert!(left_since_stop, "boarded twice in one stop at step {}", i);
+                num_stops += 1_usize;
+                left_since_stop = false;
+                num_waiting = num_waiting_now;
+            }
+
+            //The people left behind keep waiting
+            assert!(building.floors[0].get_people().iter().all(|pers| pers.wait_time.0 > 0_u64), "wait time at step {}", i);
+        }
+        assert_eq!(num_stops, 3_usize);
+        assert_eq!(controller.get_building().floors[3].get_num_people(), 3_usize);
+    }
+
+    #[test]
+    fn crowding_refusers_wait_for_the_next_cab() {
+        //A cab of capacity 10 with 6 aboard stops on the ground floor, where four people of mixed tolerances wait
+        let mut building: Building = get_empty_building(4_usize, 2_usize);
+        building.set_capacity(Some(10_usize));
+        for id in 0_usize..6_usize {
+            let mut pers: Person = get_person(id, 0_usize, 3_usize);
+            pers.state = PersonState::Riding;
+            building.place_person(pers, Some(0_usize));
+        }
+        let tolerances: [f64; 4] = [0.5_f64, 1_f64, 0.6_f64, 0.9_f64];
+        for (i, tolerance) in tolerances.iter().enumerate() {
+            let mut pers: Person = get_person(6_usize + i, 0_usize, 2_usize);
+            pers.crowding_tolerance = *tolerance;
+            building.place_person(pers, None);
+        }
+        building.elevators[0].stopped = true;
+        building.elevators[1].floor_on = 3_usize;
+        building.elevators[1].stopped = false;
+
+        //Only those who tolerate the load once aboard get on, without the refusers holding up those behind them
+        building.exchange_people_on_elevator();
+        let boarded: Vec<usize> = building.elevators[0].people.iter().skip(6_usize).map(|pers| pers.id).collect();
+        assert_eq!(boarded, vec![7_usize, 9_usize]);
+        let waiting: Vec<usize> = building.floors[0].get_people().iter().filter(|pers| pers.is_waiting()).map(|pers| pers.id).collect();
+        assert_eq!(waiting, vec![6_usize, 8_usize]);
+
+        //The refusers board the next, emptier cab
+        building.elevators[0].floor_on = 1_usize;
+        building.elevators[0].stopped = false;
+        building.elevators[1].floor_on = 0_usize;
+        building.elevators[1].stopped = true;
+        building.exchange_people_on_elevator();
+        let boarded: Vec<usize> = building.elevators[1].people.iter().map(|pers| pers.id).collect();
+        assert_eq!(boarded, vec![6_usize, 8_usize]);
+        assert_eq!(building.get_num_people_waiting_on_floor(0_usize), 0_usize);
+    }
+
+    #[test]
+    fn dwell_lasts_as_long_as_the_slowest_person() {
+        //Someone gets off and three people of mixed boarding ticks get on at the ground floor
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        let mut rider: Person = get_person(0_usize, 0_usize, 0_usize);
+        rider.state = PersonState::Riding;
+        rider.boarding_ticks = 3_usize;
+        building.place_person(rider, Some(0_usize));
+        for (i, boarding_ticks) in [1_usize, 4_usize, 2_usize].iter().enumerate() {
+            let mut pers: Person = get_person(1_usize + i, 0_usize, 3_usize);
+            pers.boarding_ticks = *boarding_ticks;
+            building.place_person(pers, None);
+        }
+        building.elevators[0].stopped = true;
+
+        //The dwell is the longest boarding ticks of anyone getting on or off, less the step of the exchange
+        building.exchange_people_on_elevator();
+        assert_eq!(building.elevators[0].dwell_steps, 3_usize);
+        assert_eq!(building.elevators[0].people.len(), 3_usize);
+
+        //The elevator holds for the dwell while its passengers' wait keeps counting, then leaves
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..3_i32 {
+            let wait_times: Vec<u64> = controller.building.elevators[0].people.iter().map(|pers| pers.wait_time.0).collect();
+            step(&mut controller, i, &mut profiler).unwrap();
+            let elevator: &Elevator = &controller.building.elevators[0];
+            assert!(elevator.stopped && elevator.floor_on == 0_usize, "left during the dwell at step {}", i);
+            let wait_times_now: Vec<u64> = elevator.people.iter().map(|pers| pers.wait_time.0).collect();
+            assert_eq!(wait_times_now, wait_times.iter().map(|x| x + 1_u64).collect::<Vec<u64>>(), "step {}", i);
+        }
+        assert_eq!(controller.building.elevators[0].dwell_steps, 0_usize);
+        step(&mut controller, 3_i32, &mut profiler).unwrap();
+        assert_eq!(controller.building.elevators[0].floor_on, 1_usize);
+    }
+
+    #[test]
+    fn validate_entry_floors_rejects_bad_weights() {
+        assert_eq!(validate_entry_floors(&[(0_usize, 1_f64), (3_usize, 2_f64)], 4_usize), Ok(()));
+        assert_eq!(validate_entry_floors(&[], 4_usize), Ok(()));
+        let cases: [(Vec<(usize, f64)>, &str); 5] = [
+            (vec![(4_usize, 1_f64)], "entry floor 4 is out of range for a building with 4 floors"),
+            (vec![(1_usize, 1_f64), (1_usize, 1_f64)], "entry floor 1 is given more than once"),
+            (vec![(1_usize, f64::INFINITY)], "entry floor 1 weight inf must be in [0, inf)"),
+            (vec![(0_usize, 0_f64), (1_usize, 0_f64)], "the entry floor weights must not all be 0"),
+            (vec![(0_usize, f64::MAX), (1_usize, f64::MAX)], "the entry floor weights sum to inf, which is not finite")
+        ];
+        for (entry_floors, message) in cases {
+            assert_eq!(validate_entry_floors(&entry_floors, 4_usize), Err(String::from(message)));
+        }
+    }
+
+    #[test]
+    fn reset_metrics_averages_energy_over_the_measured_steps() {
+        //Two runs of the same busy building, one of which resets its metrics halfway through
+        let get_controller = || NearestController::from(
+            Building::from(8_usize, 2_usize, 1_f64, 5_f64, 2.5_f64, 0.5_f64, 7_u64).unwrap()
+        );
+        let mut full_controller: NearestController = get_controller();
+        let mut reset_controller: NearestController = get_controller();
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //Total the energy of the full run at the reset, and throughout
+        let mut energy_at_reset: f64 = 0_f64;
+        for i in 0_i32..200_i32 {
+            if i == 100_i32 {
+                let building: &Building = full_controller.get_building();
+                energy_at_reset = building.energy.get_mean() * building.energy.get_count() as f64;
+                reset_controller.get_building_mut().reset_metrics();
+            }
+            step(&mut full_controller, i, &mut profiler).unwrap();
+            step(&mut reset_controller, i, &mut profiler).unwrap();
+        }
+        let full_building: &Building = full_controller.get_building();
+        let total_energy: f64 = full_building.energy.get_mean() * full_building.energy.get_count() as f64;
+        assert!(total_energy > energy_at_reset, "no energy spent after the reset");
+
+        //The reset run counts every step, but averages the energy over the last 100 only
+        let building: &Building = reset_controller.get_building();
+        assert_eq!(building.current_step, 200_u64);
+        assert_eq!(building.measured_steps, 100_u64);
+        assert_eq!(building.energy.get_count(), 100_u64);
+        let expected_mean: f64 = (total_energy - energy_at_reset) / 100_f64;
+        assert!((building.energy.get_mean() - expected_mean).abs() < 1e-9_f64, "{} != {}", building.energy.get_mean(), expected_mean);
+    }
+
+    //Build a building with 6 floors whose stopped elevator on floor 2 carries riders to the given floors
+    fn get_loaded_building(no_reversal: bool, floors_to: &[usize]) -> Building {
+        let mut building: Building = get_empty_building(6_usize, 1_usize);
+        building.set_no_reversal(no_reversal);
+        building.elevators[0].floor_on = 2_usize;
+        for (i, floor_to) in floors_to.iter().enumerate() {
+            let mut rider: Person = get_person(i, 2_usize, *floor_to);
+            rider.state = PersonState::Riding;
+            building.place_person(rider, Some(0_usize));
+        }
+        building
+    }
+
+    #[test]
+    fn no_reversal_overrides_wrong_way_commands() {
+        //A cab loaded with riders headed up is commanded down, and goes up instead
+        let mut building: Building = get_loaded_building(true, &[4_usize, 5_usize]);
+        building.apply_commands(&[ElevatorCommand::Down]);
+        assert_eq!(building.elevators[0].floor_on, 3_usize);
+        assert!(building.elevators[0].moving_up && !building.elevators[0].stopped);
+        assert_eq!(building.events.len(), 1_usize, "{:?}", building.events);
+        assert!(matches!(
+            building.events[0],
+            SimEvent::ReversalOverridden { elevator: 0_usize, floor: 2_usize, moving_up: true }
+        ), "{:?}", building.events);
+
+        //A cab loaded with riders headed down is commanded up, and goes down instead
+        let mut building: Building = get_loaded_building(true, &[0_usize]);
+        building.apply_commands(&[ElevatorCommand::Up]);
+        assert_eq!(building.elevators[0].floor_on, 1_usize);
+        assert!(matches!(
+            building.events[..],
+            [SimEvent::ReversalOverridden { elevator: 0_usize, floor: 2_usize, moving_up: false }]
+        ), "{:?}", building.events);
+    }
+
+    #[test]
+    fn no_reversal_leaves_other_commands_alone() {
+        let cases: [(bool, &[usize], ElevatorCommand, usize); 5] = [
+            (true, &[4_usize], ElevatorCommand::Up, 3_usize),
+            (true, &[4_usize], ElevatorCommand::Stop, 2_usize),
+            (true, &[0_usize, 5_usize], ElevatorCommand::Down, 1_usize),
+            (true, &[], ElevatorCommand::Down, 1_usize),
+            (false, &[4_usize], ElevatorCommand::Down, 1_usize)
+        ];
+        for (no_reversal, floors_to, command, floor_on) in cases {
+            let mut building: Building = get_loaded_building(no_reversal, floors_to);
+            building.apply_commands(&[command]);
+            let context: String = format!("no reversal {}, riders to {:?}, {:?}", no_reversal, floors_to, command);
+            assert_eq!(building.elevators[0].floor_on, floor_on, "{}", context);
+            assert!(building.events.is_empty(), "{}: {:?}", context, building.events);
+        }
+    }
+
+    //Run a seeded building of 8 floors with a passenger and a freight elevator, returning how many each delivered
+    fn get_freight_deliveries(freight_serves_passengers: bool) -> (usize, usize) {
+        let mut config: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.6_f64);
+        config.elevator_profiles = vec![ElevatorProfile::Passenger, ElevatorProfile::Freight];
+        config.freight_serves_passengers = freight_serves_passengers;
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, ControllerKind::Nearest, &ControllerParams::default(), 5_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..300_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            if !freight_serves_passengers {
+                assert!(controller.get_building().elevators[1].people.is_empty(), "the freight elevator carried someone at step {}", i);
+            }
+        }
+        let building: &Building = controller.get_building();
+        (building.elevators[0].num_delivered, building.elevators[1].num_delivered)
+    }
+
+    #[test]
+    fn freight_carries_nobody_by_default() {
+        let (passenger, freight): (usize, usize) = get_freight_deliveries(false);
+        assert!(passenger > 0_usize);
+        assert_eq!(freight, 0_usize);
+
+        //Letting freight elevators serve passengers shares the load
+        let (_, freight): (usize, usize) = get_freight_deliveries(true);
+        assert!(freight > 0_usize);
+    }
+
+    #[test]
+    fn freight_is_opted_in_during_a_surge() {
+        //A lone freight elevator opts in while more than 3 people are waiting
+        let mut building: Building = get_empty_building(6_usize, 1_usize);
+        building.set_profiles(&[ElevatorProfile::Freight]);
+        building.freight_surge_waiting = Some(3_usize);
+        for id in 0_usize..3_usize {
+            building.place_person(get_person(id, 3_usize, 0_usize), None);
+        }
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //At the threshold, nobody is picked up
+        for i in 0_i32..20_i32 {
+            step_with(&mut controller, i, &mut profiler, |_| Ok(())).unwrap();
+            let building: &Building = controller.get_building();
+            assert_eq!(building.get_num_people_waiting_on_floor(3_usize), 3_usize, "step {}", i);
+            assert!(!building.may_serve_passengers(0_usize), "step {}", i);
+        }
+
+        //Past it, the freight elevator answers the call and delivers everyone, then opts out again
+        controller.get_building_mut().place_person(get_person(3_usize, 3_usize, 0_usize), None);
+        for i in 20_i32..60_i32 {
+            step_with(&mut controller, i, &mut profiler, |_| Ok(())).unwrap();
+            if controller.get_building().get_num_delivered() == 4_usize {
+                assert!(!controller.get_building().may_serve_passengers(0_usize));
+                return;
+            }
+        }
+        panic!("the surge was never served");
+    }
+
+    //Build an empty building with 8 floors whose elevator idles on floor 5, and heads home to the lobby after 3 steps
+    fn get_homing_building() -> Building {
+        let mut building: Building = get_empty_building(8_usize, 1_usize);
+        building.set_homes(&[ElevatorHome { elevator: 0_usize, floor: 0_usize, after_idle: 3_usize }]);
+        building.elevators[0].floor_on = 5_usize;
+        building
+    }
+
+    #[test]
+    fn idle_elevator_heads_home_once_timed_out() {
+        //Two steps told to stop count down the timeout, and a command to move restarts it
+        let mut building: Building = get_homing_building();
+        building.apply_commands(&[ElevatorCommand::Stop]);
+        building.apply_commands(&[ElevatorCommand::Stop]);
+        assert_eq!(building.elevators[0].idle_steps, 2_usize);
+        building.apply_commands(&[ElevatorCommand::Up]);
+        building.apply_commands(&[ElevatorCommand::Down]);
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].idle_steps), (5_usize, 0_usize));
+        for _ in 0_usize..2_usize {
+            building.apply_commands(&[ElevatorCommand::Stop]);
+        }
+        assert!(building.events.is_empty() && !building.elevators[0].homing, "{:?}", building.events);
+
+        //The third step sets off home, and telling it to stop keeps it going
+        building.apply_commands(&[ElevatorCommand::Stop]);
+        assert!(matches!(
+            building.events[..],
+            [SimEvent::HomingStarted { elevator: 0_usize, floor: 5_usize, home_floor: 0_usize }]
+        ), "{:?}", building.events);
+        assert_eq!(building.elevators[0].floor_to, Some(0_usize));
+        for floor_on in (0_usize..5_usize).rev() {
+            assert!(building.elevators[0].homing);
+            assert_eq!(building.elevators[0].floor_on, floor_on);
+            building.apply_commands(&[ElevatorCommand::Stop]);
+        }
+
+        //Once home, the trip ends and the elevator stays put
+        assert!(!building.elevators[0].homing && building.elevators[0].stopped);
+        assert_eq!(building.elevators[0].floor_on, 0_usize);
+        assert_eq!(building.events.len(), 1_usize);
+    }
+
+    #[test]
+    fn new_call_cancels_homing() {
+        //Set off home, then a controller answering a call above sends the elevator back up
+        let mut building: Building = get_homing_building();
+        for _ in 0_usize..4_usize {
+            building.apply_commands(&[ElevatorCommand::Stop]);
+        }
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].homing), (3_usize, true));
+        building.apply_commands(&[ElevatorCommand::Up]);
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].homing), (4_usize, false));
+        assert_eq!(building.elevators[0].idle_steps, 0_usize);
+
+        //Set off home again, then someone boarding cancels the trip and the elevator stops for them
+        let mut building: Building = get_homing_building();
+        for _ in 0_usize..4_usize {
+            building.apply_commands(&[ElevatorCommand::Stop]);
+        }
+        let mut rider: Person = get_person(0_usize, 3_usize, 6_usize);
+        rider.state = PersonState::Riding;
+        building.place_person(rider, Some(0_usize));
+        building.apply_commands(&[ElevatorCommand::Stop]);
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].homing), (3_usize, false));
+        assert!(building.elevators[0].stopped);
+    }
+
+    //Build a building with 10 floors whose elevator leaves floor 2 for floor 8 with a rider, and optionally
+    //a second rider headed to the given floor, after which the first rider changes their destination to floor 5
+    fn get_rerouted_building(other_floor_to: Option<usize>, is_waiting_on_8: bool) -> Building {
+        let mut building: Building = get_empty_building(10_usize, 1_usize);
+        building.elevators[0].floor_on = 2_usize;
+        building.elevators[0].floor_to = Some(8_usize);
+        let floors_to: Vec<usize> = std::iter::once(8_usize).chain(other_floor_to).collect();
+        for (id, floor_to) in floors_to.iter().enumerate() {
+            let mut rider: Person = get_person(id, 2_usize, *floor_to);
+            rider.state = PersonState::Riding;
+            building.place_person(rider, Some(0_usize));
+        }
+        if is_waiting_on_8 {
+            building.place_person(get_person(9_usize, 8_usize, 0_usize), None);
+        }
+        building.change_destinations(&[(0_usize, 5_usize)]);
+        building
+    }
+
+    #[test]
+    fn changed_destination_drops_the_unneeded_stop() {
+        //The rider's destination changes, and the elevator heads for it instead of the old one
+        let building: Building = get_rerouted_building(None, false);
+        assert_eq!(building.elevators[0].people[0].floor_to, 5_usize);
+        assert_eq!(building.elevators[0].floor_to, Some(5_usize));
+        assert!(matches!(
+            building.events[..],
+            [SimEvent::DestinationChanged { person_id: 0_usize, elevator: 0_usize, old_floor_to: 8_usize, floor_to: 5_usize }]
+        ), "{:?}", building.events);
+
+        //The old stop is kept while another rider is headed there or someone is waiting there
+        assert_eq!(get_rerouted_building(Some(8_usize), false).elevators[0].floor_to, Some(8_usize));
+        assert_eq!(get_rerouted_building(None, true).elevators[0].floor_to, Some(8_usize));
+        assert_eq!(get_rerouted_building(Some(7_usize), false).elevators[0].floor_to, Some(5_usize));
+
+        //Changing the destination of someone not aboard does nothing
+        let mut building: Building = get_rerouted_building(None, true);
+        building.events.clear();
+        building.change_destinations(&[(9_usize, 3_usize)]);
+        assert!(building.events.is_empty());
+        assert_eq!(building.floors[8].get_people()[0].floor_to, 0_usize);
+    }
+
+    #[test]
+    fn rerouted_rider_is_delivered_to_the_new_floor() {
+        let mut controller: NearestController = NearestController::from(get_rerouted_building(None, false));
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..20_i32 {
+            step_with(&mut controller, i, &mut profiler, |_| Ok(())).unwrap();
+            let building: &Building = controller.get_building();
+            assert!(building.elevators[0].floor_on <= 5_usize, "the elevator passed floor 5 at step {}", i);
+            if building.get_num_delivered() == 1_usize {
+                assert!(building.floors[5].get_people().iter().any(|pers| pers.id == 0_usize));
+                return;
+            }
+        }
+        panic!("the rider was never delivered");
+    }
+
+    #[test]
+    fn forced_destination_changes_are_recorded() {
+        //Every rider changes their destination when changes are certain
+        let mut building: Building = Building::from(10_usize, 1_usize, 0_f64, 5_f64, 2.5_f64, 0.5_f64, 6_u64).unwrap();
+        building.p_change_dest = 1_f64;
+        building.elevators[0].floor_on = 4_usize;
+        for id in 0_usize..4_usize {
+            let mut rider: Person = get_person(id, 4_usize, 9_usize);
+            rider.state = PersonState::Riding;
+            building.place_person(rider, Some(0_usize));
+        }
+        building.gen_destination_changes();
+
+        //Each change is recorded with the rider's new destination
+        assert_eq!(building.events.len(), 4_usize, "{:?}", building.events);
+        for (event, pers) in building.events.iter().zip(building.elevators[0].people.iter()) {
+            match event {
+                SimEvent::DestinationChanged { person_id, elevator: 0_usize, old_floor_to: 9_usize, floor_to } => {
+                    assert_eq!((*person_id, *floor_to), (pers.id, pers.floor_to));
+                },
+                _ => panic!("unexpected event {:?}", event)
+            }
+        }
+    }
+
+    //Reroute 20 riders on floor 4 of a building of 10 floors with the given destination model, 50 times over,
+    //and return how many times each floor was drawn as a new destination
+    fn get_rerouted_counts(destination_model: DestinationModel) -> Vec<usize> {
+        let mut building: Building = Building::from(10_usize, 1_usize, 0_f64, 5_f64, 2.5_f64, 0.5_f64, 7_u64).unwrap();
+        building.set_destination_model(destination_model).unwrap();
+        building.p_change_dest = 1_f64;
+        building.elevators[0].floor_on = 4_usize;
+        for id in 0_usize..20_usize {
+            let mut rider: Person = get_person(id, 4_usize, 9_usize);
+            rider.state = PersonState::Riding;
+            building.place_person(rider, Some(0_usize));
+        }
+        let mut counts: Vec<usize> = vec![0_usize; 10_usize];
+        for _ in 0_usize..50_usize {
+            building.events.clear();
+            building.gen_destination_changes();
+            assert_eq!(building.events.len(), 20_usize);
+            for event in building.events.iter() {
+                match event {
+                    SimEvent::DestinationChanged { floor_to, .. } => counts[*floor_to] += 1_usize,
+                    _ => panic!("unexpected event {:?}", event)
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn rerouted_destinations_follow_the_destination_model() {
+        //Weighted destinations are drawn by their weights, never the floor the riders are on
+        let weights: Vec<f64> = (0_usize..10_usize).map(|x| if x == 4_usize || x == 7_usize { 1_f64 } else { 0_f64 }).collect();
+        let counts: Vec<usize> = get_rerouted_counts(DestinationModel::Weighted { weights });
+        assert_eq!(counts[7], 1000_usize, "{:?}", counts);
+
+        //Zipf destinations favour the low floors
+        let counts: Vec<usize> = get_rerouted_counts(DestinationModel::Zipf { s: 1.5_f64 });
+        assert_eq!(counts[4], 0_usize, "{:?}", counts);
+        assert!(counts[0] > counts[3] && counts[3] > counts[9], "{:?}", counts);
+
+        //Uniform destinations fall back to every other floor alike
+        let counts: Vec<usize> = get_rerouted_counts(DestinationModel::Uniform);
+        assert_eq!(counts[4], 0_usize, "{:?}", counts);
+        for (floor, count) in counts.iter().enumerate().filter(|(x, _)| *x != 4_usize) {
+            assert!((*count as f64 - 1000_f64 / 9_f64).abs() < 40_f64, "floor {}: {:?}", floor, counts);
+        }
+    }
+
+    //Run a step of a building of 10 floors in which the second elevator, stopped on floor 6, picks up the caller
+    //there, while the first heads up from floor 2 to answer the same call, carrying riders to the given floors and
+    //with someone else waiting on the given floor.  Return the building after the step
+    fn get_raced_building(floors_to: &[usize], other_caller: Option<usize>) -> Building {
+        let mut building: Building = get_empty_building(10_usize, 2_usize);
+        building.elevators[0].floor_on = 2_usize;
+        building.elevators[0].stopped = false;
+        building.elevators[0].moving_up = true;
+        building.elevators[0].floor_to = Some(6_usize);
+        building.elevators[1].floor_on = 6_usize;
+        building.place_person(get_person(0_usize, 6_usize, 9_usize), None);
+        for (i, floor_to) in floors_to.iter().enumerate() {
+            let mut rider: Person = get_person(1_usize + i, 2_usize, *floor_to);
+            rider.state = PersonState::Riding;
+            building.place_person(rider, Some(0_usize));
+        }
+        if let Some(floor_on) = other_caller {
+            building.place_person(get_person(9_usize, floor_on, 5_usize), None);
+        }
+        let mut controller: NearestController = NearestController::from(building);
+        step_with(&mut controller, 0_i32, &mut Profiler::from(false), |_| Ok(())).unwrap();
+        controller.building
+    }
+
+    #[test]
+    fn emptied_call_is_cancelled_within_a_step() {
+        //The second elevator empties floor 6 first, so the first gives up on it and stops where it is
+        let building: Building = get_raced_building(&[], None);
+        assert!(building.elevators[1].people.iter().any(|pers| pers.id == 0_usize));
+        let cancelled: Vec<&SimEvent> = building.events.iter().filter(|x| matches!(x, SimEvent::CallCancelled { .. })).collect();
+        assert!(matches!(
+            cancelled[..],
+            [SimEvent::CallCancelled { elevator: 0_usize, floor: 6_usize, emptied_by: 1_usize }]
+        ), "{:?}", building.events);
+        assert!(building.elevators[0].stopped);
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].floor_to), (2_usize, None));
+
+        //With someone else waiting, it changes course for them in the same step
+        let building: Building = get_raced_building(&[], Some(0_usize));
+        assert_eq!(building.elevators[0].floor_to, Some(0_usize));
+        assert_eq!(building.elevators[0].floor_on, 1_usize);
+
+        //With a rider headed further, it heads for their floor instead
+        let building: Building = get_raced_building(&[8_usize], None);
+        assert!(building.events.iter().any(|x| matches!(x, SimEvent::CallCancelled { elevator: 0_usize, .. })));
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].floor_to), (3_usize, Some(8_usize)));
+
+        //With a rider getting off there, it keeps its target
+        let building: Building = get_raced_building(&[6_usize], None);
+        assert!(!building.events.iter().any(|x| matches!(x, SimEvent::CallCancelled { .. })), "{:?}", building.events);
+        assert_eq!((building.elevators[0].floor_on, building.elevators[0].floor_to), (3_usize, Some(6_usize)));
+    }
+
+    #[test]
+    fn boarding_share_splits_the_waiting_evenly() {
+        //Two empty cabs of capacity 6 on floor 3, and a third on another floor, with 10 waiting on floor 3
+        let rooms: [Option<(usize, usize)>; 3] = [Some((3_usize, 6_usize)), Some((3_usize, 6_usize)), Some((5_usize, 6_usize))];
+        let order: [usize; 3] = [0_usize, 1_usize, 2_usize];
+        assert_eq!(get_boarding_share(0_usize, &order, &rooms, 10_usize), Some(5_usize));
+        assert_eq!(get_boarding_share(1_usize, &order, &rooms, 5_usize), None);
+        assert_eq!(get_boarding_share(2_usize, &order, &rooms, 10_usize), None);
+
+        //An odd number waiting rounds the first share up, and a cab with little room after the first leaves it more
+        assert_eq!(get_boarding_share(0_usize, &order, &rooms, 11_usize), Some(6_usize));
+        let rooms: [Option<(usize, usize)>; 3] = [Some((3_usize, 6_usize)), Some((3_usize, 2_usize)), None];
+        assert_eq!(get_boarding_share(0_usize, &order, &rooms, 10_usize), Some(8_usize));
+        assert_eq!(get_boarding_share(1_usize, &order, &rooms, 2_usize), None);
+
+        //Taking on people first, the small cab is offered an even share, which its room then caps
+        assert_eq!(get_boarding_share(0_usize, &[1_usize, 0_usize, 2_usize], &rooms, 10_usize), Some(5_usize));
+    }
+
+    #[test]
+    fn cabs_stopped_together_share_the_waiting() {
+        //Two cabs of capacity 6 stop together on floor 3, where 10 people are waiting
+        let mut building: Building = get_empty_building(6_usize, 2_usize);
+        building.set_capacity(Some(6_usize));
+        for elevator in building.elevators.iter_mut() {
+            elevator.floor_on = 3_usize;
+        }
+        for id in 0_usize..10_usize {
+            building.place_person(get_person(id, 3_usize, id % 3_usize), None);
+        }
+        building.exchange_people_on_elevator();
+
+        //Each takes 5, and nobody is left behind
+        assert_eq!(building.elevators[0].people.len(), 5_usize);
+        assert_eq!(building.elevators[1].people.len(), 5_usize);
+        assert_eq!(building.get_num_people_waiting_on_floor(3_usize), 0_usize);
+        assert_eq!(building.check_invariants(), Ok(()));
+
+        //With 14 waiting, both fill up and only those with no room are left behind
+        let mut building: Building = get_empty_building(6_usize, 2_usize);
+        building.set_capacity(Some(6_usize));
+        for elevator in building.elevators.iter_mut() {
+            elevator.floor_on = 3_usize;
+        }
+        for id in 0_usize..14_usize {
+            building.place_person(get_person(id, 3_usize, 0_usize), None);
+        }
+        building.exchange_people_on_elevator();
+        assert_eq!((building.elevators[0].people.len(), building.elevators[1].people.len()), (6_usize, 6_usize));
+        assert_eq!(building.get_num_people_waiting_on_floor(3_usize), 2_usize);
+    }
+
+    //Run a seeded building whose two cabs hold at the lobby for 1000 time steps, exchanging people in the given
+    //order.  Return how many people boarded each cab
+    fn get_lobby_boardings(exchange_order: ExchangeOrder) -> (usize, usize) {
+        let mut building: Building = Building::from(6_usize, 2_usize, 0.7_f64, 5_f64, 2.5_f64, 0.5_f64, 8_u64).unwrap();
+        building.exchange_order = exchange_order;
+        let mut controller: CommandController = CommandController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..1000_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+        }
+        let building: &Building = controller.get_building();
+        assert_eq!(building.get_num_people_waiting_on_floor(0_usize), 0_usize);
+        (building.elevators[0].people.len(), building.elevators[1].people.len())
+    }
+
+    #[test]
+    fn round_robin_evens_out_symmetric_cabs() {
+        //In a fixed order, the first cab gets the first pick, and every odd caller, each time step
+        let (first, second): (usize, usize) = get_lobby_boardings(ExchangeOrder::Fixed);
+        assert!(first + second > 500_usize);
+        assert!(first > second + (first + second) / 10_usize, "{} and {} boarded", first, second);
+
+        //Taking turns to go first, the cabs take on nearly the same number of people
+        let (first, second): (usize, usize) = get_lobby_boardings(ExchangeOrder::RoundRobin);
+        assert!(first.abs_diff(second) <= (first + second) / 20_usize, "{} and {} boarded", first, second);
+        let (first, second): (usize, usize) = get_lobby_boardings(ExchangeOrder::Shuffled);
+        assert!(first.abs_diff(second) <= (first + second) / 10_usize, "{} and {} boarded", first, second);
+    }
+
+    #[test]
+    fn no_arrivals_never_grow_the_population() {
+        //Nobody arrives in a building expecting no arrivals, however long it runs
+        let mut building: Building = get_empty_building(6_usize, 2_usize);
+        assert!(building.dst_in.is_none());
+        for id in 0_usize..5_usize {
+            building.place_person(get_person(id, 0_usize, 1_usize + id), None);
+        }
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut last_population: usize = 5_usize;
+        for i in 0_i32..100_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            let population: usize = controller.get_building().get_population();
+            assert!(population <= last_population, "the population grew at step {}", i);
+            last_population = population;
+        }
+        assert_eq!(controller.get_building().num_arrivals, 0_usize);
+        assert_eq!(controller.get_building().get_num_delivered(), 5_usize);
+    }
+
+    #[test]
+    fn certain_leavers_leave_once_idle() {
+        //Everyone arriving leaves as soon as they are idle at their destination
+        let mut building: Building = Building::from(6_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 3_u64).unwrap();
+        building.set_p_out(1_f64).unwrap();
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut idle_ids: Vec<usize> = Vec::new();
+        for i in 0_i32..100_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+
+            //Whoever was idle after the last step has decided to leave, and nobody else is idle
+            let building: &Building = controller.get_building();
+            for floor in building.floors.iter() {
+                for pers in floor.get_people().iter().filter(|pers| idle_ids.contains(&pers.id)) {
+                    assert!(pers.is_leaving, "person {} stayed at step {}", pers.id, i);
+                }
+            }
+            idle_ids = building.floors.iter()
+                .flat_map(|floor| floor.get_people().iter())
+                .filter(|pers| pers.state == PersonState::Idle)
+                .map(|pers| pers.id)
+                .collect();
+        }
+        let building: &Building = controller.get_building();
+        assert!(building.num_arrivals > 20_usize);
+        assert!(building.get_population() < building.num_arrivals / 2_usize, "{} of {} stayed", building.get_population(), building.num_arrivals);
+    }
+
+    #[test]
+    fn arrival_and_leaving_rates_are_bounded() {
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+
+        //The arrival rate may be as large as an arrival profile may scale without overflowing
+        assert!(building.set_expected_arrivals(f64::MAX / (MAX_MULTIPLIER + 1_f64)).is_ok());
+        assert!(building.set_expected_arrivals(f64::MAX / (MAX_MULTIPLIER - 1_f64)).is_err());
+        assert!(building.set_expected_arrivals(f64::INFINITY).is_err());
+        assert!(building.set_expected_arrivals(-0.1_f64).is_err());
+        assert!(building.set_expected_arrivals(f64::NAN).is_err());
+        assert!(building.set_expected_arrivals(0_f64).is_ok() && building.dst_in.is_none());
+
+        //Leaving is given a probability in (0, 1], and rejected otherwise with the interval named
+        assert!(building.set_p_out(1_f64).is_ok());
+        for p_out in [0_f64, -0.5_f64, 1.5_f64, f64::NAN] {
+            match building.set_p_out(p_out) {
+                Err(ElevatorError::Distribution(message)) => assert!(message.ends_with("is not in (0, 1]"), "{}", message),
+                _ => panic!("probability of leaving {} was accepted", p_out)
+            }
+        }
+    }
+
+    //Build an empty building with people idle on floors 0, 2, 2 and 3, each certain to leave
+    fn get_leaving_building() -> Building {
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        for (id, floor) in [0_usize, 2_usize, 2_usize, 3_usize].into_iter().enumerate() {
+            let mut pers: Person = Person::from_dest(1_f64, floor, floor).unwrap();
+            pers.id = id;
+            building.place_person(pers, None);
+        }
+        building
+    }
+
+    //Get the person and floor of each leaving event of the building's time step
+    fn get_leaving_events(building: &Building) -> Vec<(usize, usize)> {
+        building.events.iter()
+            .filter_map(|x| match x {
+                SimEvent::PersonLeaving { person_id, floor } => Some((*person_id, *floor)),
+                _ => None
+            })
+            .collect()
+    }
+
+    #[test]
+    fn both_leaving_paths_record_the_hall_calls() {
+        //The people leaving are generated once from the building's own stream, and once through the floors trait
+        let mut own_building: Building = get_leaving_building();
+        own_building.gen_people().unwrap();
+        let mut trait_building: Building = get_leaving_building();
+        let mut rng: StdRng = RngStream::Behavior.get_rng(1_u64);
+        Floors::gen_people_leaving(&mut trait_building, &mut rng);
+
+        //Both record everyone leaving, and count a hall call on each floor above the first
+        let own_leaving: Vec<(usize, usize)> = get_leaving_events(&own_building);
+        assert_eq!(own_leaving, vec![(0_usize, 0_usize), (1_usize, 2_usize), (2_usize, 2_usize), (3_usize, 3_usize)]);
+        assert_eq!(get_leaving_events(&trait_building), own_leaving);
+        own_building.demand.update();
+        trait_building.demand.update();
+        assert_eq!(own_building.demand.hall_calls, trait_building.demand.hall_calls);
+        let smoothing: f64 = trait_building.demand.smoothing;
+        let expected_hall_calls: Vec<f64> = get_empty_building(4_usize, 1_usize).demand.hall_calls.iter()
+            .zip([0_f64, 0_f64, 2_f64, 1_f64])
+            .map(|(estimate, count)| smoothing * count + (1_f64 - smoothing) * estimate)
+            .collect();
+        assert_eq!(trait_building.demand.hall_calls, expected_hall_calls);
+    }
+
+    #[test]
+    fn unscalable_arrivals_are_an_error_rather_than_a_panic() {
+        //Expected arrivals which overflow once the morning peak scales them fail the time step, and nobody arrives
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        building.set_arrival_profile(ArrivalProfile::MorningPeak, 100_u64).unwrap();
+        building.p_in = f64::MAX;
+        building.arrival_multiplier = 1_f64;
+        match building.gen_people() {
+            Err(ElevatorError::Distribution(message)) => assert!(message.contains("is not in [0, inf)"), "{}", message),
+            _ => panic!("arrivals overflowing the distribution were generated")
+        }
+        assert_eq!(building.num_arrivals, 0_usize);
+        assert!(building.events.is_empty());
+    }
+
+    #[test]
+    fn arrivals_follow_the_entry_floor_weights() {
+        //People arrive on the lobby three times as often as on floor 5, and never on floor 3
+        let mut building: Building = Building::from(8_usize, 1_usize, 2_f64, 5_f64, 2.5_f64, 0.5_f64, 9_u64).unwrap();
+        building.set_entry_floors(vec![(0_usize, 3_f64), (3_usize, 0_f64), (5_usize, 1_f64)]).unwrap();
+        let mut num_arrivals: [usize; 8] = [0_usize; 8];
+        for _ in 0_usize..1000_usize {
+            building.events.clear();
+            building.gen_people_arriving().unwrap();
+            for event in building.events.iter() {
+                if let SimEvent::PersonArrived { person_id, floor, floor_to, .. } = event {
+                    assert_ne!(floor, floor_to, "person {} arrived on their destination", person_id);
+                    num_arrivals[*floor] += 1_usize;
+                }
+            }
+        }
+
+        //The arrivals are split by weight, within a few standard deviations
+        let total: usize = num_arrivals.iter().sum();
+        assert_eq!(total, building.num_arrivals);
+        assert!(total > 1500_usize);
+        assert_eq!(num_arrivals[0] + num_arrivals[5], total, "{:?}", num_arrivals);
+        let lobby_share: f64 = num_arrivals[0] as f64 / total as f64;
+        assert!((lobby_share - 0.75_f64).abs() < 0.03_f64, "{} of arrivals were on the lobby", lobby_share);
+    }
+
+    //Generate a time step's arrivals in the building, returning how many arrived and how many were turned away
+    fn count_arrivals(building: &mut Building) -> (usize, usize) {
+        building.events.clear();
+        building.gen_people_arriving().unwrap();
+        let num_arrived: usize = building.events.iter().filter(|x| matches!(x, SimEvent::PersonArrived { .. })).count();
+        let num_rejected: usize = building.events.iter().filter(|x| matches!(x, SimEvent::ArrivalRejected { .. })).count();
+        (num_arrived, num_rejected)
+    }
+
+    #[test]
+    fn population_is_capped_at_the_limit() {
+        //Heavy arrivals fill a building limited to 20 people, and those beyond the limit are turned away
+        let mut building: Building = Building::from(8_usize, 2_usize, 3_f64, 5_f64, 2.5_f64, 0.5_f64, 4_u64).unwrap();
+        building.max_population = Some(20_usize);
+        let mut num_rejected_total: usize = 0_usize;
+        for i in 0_usize..30_usize {
+            let population: usize = building.get_population();
+            let (num_arrived, num_rejected) = count_arrivals(&mut building);
+            assert_eq!(building.get_population(), population + num_arrived, "step {}", i);
+            assert!(building.get_population() <= 20_usize, "step {}", i);
+            if num_rejected > 0_usize {
+                assert_eq!(building.get_population(), 20_usize, "arrivals turned away with room at step {}", i);
+            }
+            num_rejected_total += num_rejected;
+        }
+        assert_eq!((building.get_population(), building.num_arrivals), (20_usize, 20_usize));
+        assert!(num_rejected_total > 40_usize);
+        assert_eq!(building.rejected_arrivals, num_rejected_total);
+
+        //Once people leave without new arrivals, the room they open is filled again
+        building.set_p_out(1_f64).unwrap();
+        building.set_expected_arrivals(0_f64).unwrap();
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut i: i32 = 0_i32;
+        while controller.get_building().get_population() > 10_usize {
+            assert!(i < 200_i32, "nobody left the full building");
+            step(&mut controller, i, &mut profiler).unwrap();
+            i += 1_i32;
+        }
+        let building: &mut Building = controller.get_building_mut();
+        let room: usize = 20_usize - building.get_population();
+        building.set_expected_arrivals(3_f64).unwrap();
+        let mut num_admitted: usize = 0_usize;
+        while num_admitted < room {
+            let (num_arrived, num_rejected) = count_arrivals(building);
+            num_admitted += num_arrived;
+            assert!(num_rejected == 0_usize || num_admitted == room, "arrivals turned away with room");
+        }
+        assert_eq!(building.get_population(), 20_usize);
+        assert_eq!(count_arrivals(building).0, 0_usize);
+    }
+}
@@ -0,0 +1,143 @@
+//Import source modules
+use crate::floor::Floor;
+use crate::elevator::Elevator;
+use crate::people::People;
+
+//Import standard modules
+use std::collections::HashSet;
+
+/** BuildingCache struct schema
+ *
+ * A BuildingCache has the following properties
+ * - dest_floor_set (HashSet<usize>): The destination floors of the people on the elevators
+ * - waiting_floors (Vec<bool>): Whether people are waiting on each floor
+ * - waiting_counts (Vec<usize>): The number of people waiting on each floor
+ * - stale (bool): Whether the people have changed since the cache was computed
+ *
+ * The cache is computed once per time step, after people are
+ * exchanged on the elevators, and is marked stale by the building
+ * whenever its people change until it is next computed.
+ */
+pub struct BuildingCache {
+    pub dest_floor_set: HashSet<usize>,
+    pub waiting_floors: Vec<bool>,
+    pub waiting_counts: Vec<usize>,
+    stale: bool
+}
+
+/** BuildingCache type implementation
+ *
+ * The following functions are implemented for the BuildingCache type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_cache: BuildingCache = BuildingCache::from(4_usize);
+ * my_cache.update(&my_building.floors, &my_building.elevators);
+ * my_cache.invalidate();
+ * let is_stale: bool = my_cache.is_stale();
+ */
+impl BuildingCache {
+    /** BuildingCache constructor function
+     *
+     * Initialize the cache of an empty building with the given
+     * number of floors.
+     */
+    pub fn from(num_floors: usize) -> BuildingCache {
+        BuildingCache {
+            dest_floor_set: HashSet::new(),
+            waiting_floors: vec![false; num_floors],
+            waiting_counts: vec![0_usize; num_floors],
+            stale: false
+        }
+    }
+
+    /** invalidate function
+     *
+     * Mark the cache stale, since the people it was computed from have
+     * changed, until it is next updated.
+     */
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /** is_stale function
+     *
+     * Return whether the people have changed since the cache was last
+     * updated, in which case it must not be read.
+     */
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /** update function
+     *
+     * Recompute the cache in a single scan of the people on the given
+     * floors and elevators, reusing the cache's allocations.
+     */
+    pub fn update(&mut self, floors: &[Floor], elevators: &[Elevator]) {
+        //Count the people waiting on each floor
+        self.waiting_floors.resize(floors.len(), false);
+        self.waiting_counts.resize(floors.len(), 0_usize);
+        for (i, floor) in floors.iter().enumerate() {
+            let num_waiting: usize = floor.get_num_people_waiting();
+            self.waiting_counts[i] = num_waiting;
+            self.waiting_floors[i] = num_waiting > 0_usize;
+        }
+
+        //Collect the destination floors of the people on the elevators
+        self.dest_floor_set.clear();
+        for elevator in elevators.iter() {
+            for pers in elevator.people.iter() {
+                self.dest_floor_set.insert(pers.floor_to);
+            }
+        }
+        self.stale = false;
+    }
+
+    /** get_nearest_wait_floor function
+     *
+     * Return a tuple containing the nearest floor with people waiting
+     * and the distance to it, which is 0 if they are waiting on the
+     * given floor, or None if nobody is waiting.  Ties go to the lower
+     * floor.
+     */
+    pub fn get_nearest_wait_floor(&self, floor_on: usize) -> Option<(usize, usize)> {
+        self.get_nearest_wait_floor_from(floor_on, 0_usize)
+    }
+
+    /** get_nearest_wait_floor_from function
+     *
+     * Return a tuple containing the nearest floor with people waiting
+     * at or above the given lowest floor and the distance to it, or None
+     * if nobody is waiting there.  Ties go to the lower floor.
+     */
+    pub fn get_nearest_wait_floor_from(&self, floor_on: usize, lowest_floor: usize) -> Option<(usize, usize)> {
+        get_nearest_flagged_floor(&self.waiting_floors, floor_on, lowest_floor)
+    }
+}
+
+/** get_nearest_flagged_floor function
+ *
+ * Return a tuple containing the nearest floor at or above the given
+ * lowest floor whose flag is set and the distance to it, or None if
+ * no such floor is flagged.  Ties go to the lower floor.
+ */
+pub fn get_nearest_flagged_floor(flags: &[bool], floor_on: usize, lowest_floor: usize) -> Option<(usize, usize)> {
+    //Track the nearest flagged floor and the min distance to it, if any found yet
+    let mut nearest_floor: Option<(usize, usize)> = None;
+
+    //Loop through the flagged floors and find the minimum distance floor
+    for (i, is_flagged) in flags.iter().enumerate().skip(lowest_floor) {
+        if !is_flagged {
+            continue;
+        }
+        let floor_dist: usize = floor_on.abs_diff(i);
+        match nearest_floor {
+            Some((_, min_floor_dist)) if min_floor_dist <= floor_dist => {},
+            _ => nearest_floor = Some((i, floor_dist))
+        }
+    }
+
+    //Return the nearest flagged floor
+    nearest_floor
+}
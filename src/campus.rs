@@ -0,0 +1,435 @@
+//Import source modules
+use crate::building::Building;
+use crate::controller::{ElevatorController, ControllerKind};
+use crate::params::ControllerParams;
+use crate::profile::Profiler;
+use crate::format::{FormatError, ScenarioFile, read_versioned, write_json};
+use crate::simulation::{SimulationConfig, SimulationResult, build_controller, step_scenario};
+use crate::stats::{StatsSummary, EnergyBreakdown};
+use crate::error::ElevatorError;
+
+//Import external/standard modules
+use std::fmt;
+use serde::{Serialize, Deserialize};
+
+//Constant representing the version of the campus format written
+pub const CAMPUS_VERSION: u32 = 1_u32;
+
+/** CampusFile struct schema
+ *
+ * A CampusFile has the following properties
+ * - version (u32): The version of the campus format
+ * - buildings (Vec<SimulationConfig>): The config of each building on the campus
+ */
+#[derive(Serialize, Deserialize)]
+pub struct CampusFile {
+    pub version: u32,
+    pub buildings: Vec<SimulationConfig>
+}
+
+/** CampusFile type implementation
+ *
+ * The following functions are implemented for the CampusFile type,
+ * and are callable via
+ *
+ * //Example
+ * let my_campus_file: CampusFile = CampusFile::load("campus.json")?;
+ * my_campus_file.write("campus.json")?;
+ */
+impl CampusFile {
+    /** CampusFile constructor function
+     *
+     * Initialize a campus file of the current version listing the
+     * given building configs
+     */
+    pub fn from(buildings: &[SimulationConfig]) -> CampusFile {
+        CampusFile {
+            version: CAMPUS_VERSION,
+            buildings: buildings.to_vec()
+        }
+    }
+
+    /** load function
+     *
+     * Read a campus from the JSON file at the given path.  The
+     * building configs are not validated, see Campus::from.
+     */
+    pub fn load(path: &str) -> Result<CampusFile, FormatError> {
+        read_versioned(path, CAMPUS_VERSION)
+    }
+
+    /** write function
+     *
+     * Write the campus as JSON to the file at the given path
+     */
+    pub fn write(&self, path: &str) -> Result<(), FormatError> {
+        write_json(path, self)
+    }
+}
+
+/** CampusBuilding struct schema
+ *
+ * A CampusBuilding has the following properties
+ * - config (SimulationConfig): The config the building was built from
+ * - seed (u64): The seed the building and its controller's RNG streams are derived from
+ * - controller (Box<dyn ElevatorController>): The controller of the building, which owns the building
+ */
+pub struct CampusBuilding {
+    pub config: SimulationConfig,
+    pub seed: u64,
+    pub controller: Box<dyn ElevatorController>
+}
+
+/** Campus struct schema
+ *
+ * A Campus has the following properties
+ * - buildings (Vec<CampusBuilding>): The buildings, each with its own controller, stepped together
+ * - controller_kind (ControllerKind): The kind of controller every building is run with
+ */
+pub struct Campus {
+    pub buildings: Vec<CampusBuilding>,
+    pub controller_kind: ControllerKind
+}
+
+/** Campus type implementation
+ *
+ * The following functions are implemented for the Campus type, and
+ * are callable via
+ *
+ * //Example
+ * let mut my_campus: Campus = Campus::from(&my_configs, ControllerKind::Nearest, &my_params, seed)?;
+ * my_campus.step(time_step, &mut my_profiler, None);
+ * let my_result: CampusResult = my_campus.get_result();
+ */
+impl Campus {
+    /** Campus constructor function
+     *
+     * Build a building from each of the given configs, each with its
+     * own controller of the given kind tuned with the given validated
+     * parameters.  Each building's seed is derived from the given seed
+     * and its index, so that the first building is seeded exactly as a
+     * lone simulation of the same seed.  If there are no configs, or
+     * any building cannot be built, then an error is returned.
+     */
+    pub fn from(configs: &[SimulationConfig], controller_kind: ControllerKind, params: &ControllerParams,
+                seed: u64) -> Result<Campus, ElevatorError> {
+        //Check that the campus has a building
+        if configs.is_empty() {
+            return Err(ElevatorError::Config(String::from("the campus must have at least one building")));
+        }
+
+        //Build each building with its own controller
+        let mut buildings: Vec<CampusBuilding> = Vec::new();
+        for (i, config) in configs.iter().enumerate() {
+            let building_seed: u64 = get_building_seed(seed, i);
+            buildings.push(CampusBuilding {
+                config: config.clone(),
+                seed: building_seed,
+                controller: build_controller(config, controller_kind, params, building_seed)
+                    .map_err(|e| match e {
+                        ElevatorError::Config(message) => ElevatorError::Config(format!("building {}: {}", i + 1_usize, message)),
+                        e => e
+                    })?
+            });
+        }
+        Ok(Campus {
+            buildings,
+            controller_kind
+        })
+    }
+
+    /** get_num_buildings function
+     *
+     * Return the number of buildings on the campus
+     */
+    pub fn get_num_buildings(&self) -> usize {
+        self.buildings.len()
+    }
+
+    /** get_building function
+     *
+     * Return the building with the given index
+     */
+    pub fn get_building(&self, building_index: usize) -> &Building {
+        self.buildings[building_index].controller.get_building()
+    }
+
+    /** get_building_mut function
+     *
+     * Return the building with the given index mutably, such as to
+     * populate it before it is simulated
+     */
+    pub fn get_building_mut(&mut self, building_index: usize) -> &mut Building {
+        self.buildings[building_index].controller.get_building_mut()
+    }
+
+    /** get_time_steps function
+     *
+     * Return the number of time steps until every building's
+     * simulation is complete
+     */
+    pub fn get_time_steps(&self) -> i32 {
+        self.buildings.iter().map(|x| x.config.time_steps).max().unwrap_or(0_i32)
+    }
+
+    /** step function
+     *
+     * Advance each building whose simulation is not yet complete by
+     * the given time step, applying the scenario's events and arrivals
+     * for the time step to each, if any.  If a building's time step
+     * fails, then its error is returned, naming the building, and the
+     * buildings after it are not advanced.
+     */
+    pub fn step(&mut self, time_step: i32, profiler: &mut Profiler, scenario: Option<&ScenarioFile>) -> Result<(), ElevatorError> {
+        for (i, building) in self.buildings.iter_mut().enumerate() {
+            if time_step < building.config.time_steps {
+                step_scenario(building.controller.as_mut(), time_step, profiler, scenario).map_err(|e| match e {
+                    ElevatorError::Distribution(message) => ElevatorError::Distribution(format!("building {}: {}", i + 1_usize, message)),
+                    e => e
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /** get_results function
+     *
+     * Return the result of each building's simulation so far, in the
+     * order of the buildings
+     */
+    pub fn get_results(&self) -> Vec<SimulationResult> {
+        self.buildings.iter().map(|x| SimulationResult::from(
+            x.controller.get_building(), &x.config, self.controller_kind.get_name(), x.seed
+        )).collect()
+    }
+
+    /** get_result function
+     *
+     * Return the result of each building's simulation so far, along
+     * with their aggregate
+     */
+    pub fn get_result(&self) -> CampusResult {
+        CampusResult::from(self.get_results())
+    }
+}
+
+/** get_building_seed function
+ *
+ * Return the seed of the building with the given index on a campus
+ * seeded with the given seed.  As with replications, consecutive
+ * buildings take consecutive seeds.
+ */
+pub fn get_building_seed(seed: u64, building_index: usize) -> u64 {
+    seed.wrapping_add(building_index as u64)
+}
+
+/** CampusSummary struct schema
+ *
+ * A CampusSummary has the following properties
+ * - num_buildings (usize): The number of buildings aggregated
+ * - wait (StatsSummary): The wait times of the people who reached their destination in any building, from arriving to getting off
+ * - floor_wait (StatsSummary): The time those people spent waiting on a floor, before getting on an elevator
+ * - ride (StatsSummary): The time those people spent on an elevator
+ * - energy (EnergyBreakdown): The energy spent by the elevators of every building
+ * - throughput (f64): The number of people who reached their destination per time step across the campus
+ * - rejected_arrivals (usize): The number of people turned away on arriving because their building was full
+ * - missed_stops (usize): The number of times an elevator left a floor some of its passengers were headed to
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CampusSummary {
+    pub num_buildings: usize,
+    pub wait: StatsSummary,
+    #[serde(default)]
+    pub floor_wait: StatsSummary,
+    pub ride: StatsSummary,
+    pub energy: EnergyBreakdown,
+    pub throughput: f64,
+    #[serde(default)]
+    pub rejected_arrivals: usize,
+    #[serde(default)]
+    pub missed_stops: usize
+}
+
+/** CampusSummary type implementation
+ *
+ * The following functions are implemented for the CampusSummary type,
+ * and are callable via
+ *
+ * //Example
+ * let my_summary: CampusSummary = CampusSummary::from(&my_results);
+ */
+impl CampusSummary {
+    /** CampusSummary constructor function
+     *
+     * Aggregate the results of the buildings on a campus.  The wait
+     * and ride times are pooled across every person delivered, while
+     * the energy spent and the throughput are summed across buildings.
+     */
+    pub fn from(results: &[SimulationResult]) -> CampusSummary {
+        let waits: Vec<StatsSummary> = results.iter().map(|x| x.wait).collect();
+        let floor_waits: Vec<StatsSummary> = results.iter().map(|x| x.floor_wait).collect();
+        let rides: Vec<StatsSummary> = results.iter().map(|x| x.ride).collect();
+        let energies: Vec<EnergyBreakdown> = results.iter().map(|x| x.energy).collect();
+        CampusSummary {
+            num_buildings: results.len(),
+            wait: StatsSummary::combine(&waits),
+            floor_wait: StatsSummary::combine(&floor_waits),
+            ride: StatsSummary::combine(&rides),
+            energy: EnergyBreakdown::combine(&energies),
+            throughput: results.iter().map(|x| x.throughput).sum(),
+            rejected_arrivals: results.iter().map(|x| x.rejected_arrivals).sum(),
+            missed_stops: results.iter().map(|x| x.missed_stops).sum()
+        }
+    }
+}
+
+//Implement display for CampusSummary, as a summary of its headline metrics
+impl fmt::Display for CampusSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{} buildings: average floor wait {:.2}, average ride time {:.2}, average time in system {:.2}, \
+            max time in system {}, average energy spent {:.2}, {:.3} delivered per step",
+            self.num_buildings, self.floor_wait.mean, self.ride.mean, self.wait.mean, self.wait.max,
+            self.energy.avg_per_step, self.throughput
+        )?;
+        if self.rejected_arrivals > 0_usize {
+            write!(f, ", {} arrivals turned away", self.rejected_arrivals)?;
+        }
+        if self.missed_stops > 0_usize {
+            write!(f, ", {} missed stops", self.missed_stops)?;
+        }
+        Ok(())
+    }
+}
+
+/** CampusResult struct schema
+ *
+ * A CampusResult has the following properties
+ * - buildings (Vec<SimulationResult>): The result of each building, in the order of the buildings
+ * - overall (CampusSummary): The aggregate of the buildings' results
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CampusResult {
+    pub buildings: Vec<SimulationResult>,
+    pub overall: CampusSummary
+}
+
+/** CampusResult type implementation
+ *
+ * The following functions are implemented for the CampusResult type,
+ * and are callable via
+ *
+ * //Example
+ * let my_result: CampusResult = CampusResult::from(my_results);
+ */
+impl CampusResult {
+    /** CampusResult constructor function
+     *
+     * Initialize the result of a campus from the results of its
+     * buildings, aggregating them
+     */
+    pub fn from(buildings: Vec<SimulationResult>) -> CampusResult {
+        let overall: CampusSummary = CampusSummary::from(&buildings);
+        CampusResult {
+            buildings,
+            overall
+        }
+    }
+}
+
+//Implement display for CampusResult, as a line per building followed by the aggregate
+impl fmt::Display for CampusResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, result) in self.buildings.iter().enumerate() {
+            writeln!(f, "Building {}: {}", i + 1_usize, result)?;
+        }
+        write!(f, "Overall, {}", self.overall)
+    }
+}
+
+/** run_campus function
+ *
+ * Run full simulations of the configured buildings in lockstep, each
+ * with its own controller of the given kind, tuned with the given
+ * validated parameters, then return the result of each along with
+ * their aggregate.  If the campus cannot be built, or a time step
+ * fails, then an error is returned.
+ */
+pub fn run_campus(configs: &[SimulationConfig], controller_kind: ControllerKind, params: &ControllerParams,
+                  seed: u64) -> Result<CampusResult, ElevatorError> {
+    let mut campus: Campus = Campus::from(configs, controller_kind, params, seed)?;
+    let mut profiler: Profiler = Profiler::from(false);
+    for i in 0..campus.get_time_steps() {
+        campus.step(i, &mut profiler, None)?;
+    }
+    Ok(campus.get_result())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::run_simulation;
+
+    //Get the configs of a quiet and a busy building of 8 floors, the busy one run for fewer steps
+    fn get_configs() -> [SimulationConfig; 2] {
+        let mut quiet: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.2_f64);
+        quiet.time_steps = 300_i32;
+        let mut busy: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 1.2_f64);
+        busy.time_steps = 200_i32;
+        [quiet, busy]
+    }
+
+    //Run a campus of the given configs to completion
+    fn run_campus(configs: &[SimulationConfig], seed: u64) -> CampusResult {
+        let mut campus: Campus = Campus::from(configs, ControllerKind::Nearest, &ControllerParams::default(), seed).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..campus.get_time_steps() {
+            campus.step(i, &mut profiler, None).unwrap();
+        }
+        campus.get_result()
+    }
+
+    #[test]
+    fn buildings_run_apart_and_aggregate() {
+        let configs: [SimulationConfig; 2] = get_configs();
+        let result: CampusResult = run_campus(&configs, 11_u64);
+        let [quiet, busy]: [&SimulationResult; 2] = [&result.buildings[0], &result.buildings[1]];
+
+        //Each building runs for its own steps, with its own seed, and the busy one sees more traffic
+        assert_eq!((quiet.steps, busy.steps), (300_u64, 200_u64));
+        assert_eq!((quiet.seed, busy.seed), (11_u64, 12_u64));
+        assert!(busy.arrivals > 2_usize * quiet.arrivals, "{} and {} arrivals", busy.arrivals, quiet.arrivals);
+        assert!(busy.throughput > quiet.throughput);
+
+        //The first building is seeded exactly as a lone run of the same seed
+        let lone: SimulationResult = run_simulation(&configs[0], ControllerKind::Nearest, &ControllerParams::default(), 11_u64).unwrap();
+        assert_eq!(quiet, &lone);
+
+        //The wait times are pooled across everyone delivered, and the rest summed across buildings
+        let overall: CampusSummary = result.overall;
+        assert_eq!(overall.num_buildings, 2_usize);
+        assert_eq!(overall.wait.count, quiet.wait.count + busy.wait.count);
+        let pooled_mean: f64 = ((quiet.wait.mean * quiet.wait.count as f64) + (busy.wait.mean * busy.wait.count as f64))
+            / overall.wait.count as f64;
+        assert!((overall.wait.mean - pooled_mean).abs() < 1e-9_f64, "{} != {}", overall.wait.mean, pooled_mean);
+        assert_eq!(overall.wait.max, quiet.wait.max.max(busy.wait.max));
+        assert_eq!(overall.ride.count, quiet.ride.count + busy.ride.count);
+        assert!((overall.energy.total.0 - (quiet.energy.total.0 + busy.energy.total.0)).abs() < 1e-9_f64);
+        assert!((overall.energy.avg_per_step.0 - (quiet.energy.avg_per_step.0 + busy.energy.avg_per_step.0)).abs() < 1e-9_f64);
+        assert!((overall.throughput - (quiet.throughput + busy.throughput)).abs() < 1e-9_f64);
+    }
+
+    #[test]
+    fn campus_needs_valid_buildings() {
+        let params: ControllerParams = ControllerParams::default();
+        assert!(matches!(Campus::from(&[], ControllerKind::Nearest, &params, 0_u64), Err(ElevatorError::Config(_))));
+
+        //An invalid building is named by its position
+        let mut configs: [SimulationConfig; 2] = get_configs();
+        configs[1].num_floors = 0_usize;
+        match Campus::from(&configs, ControllerKind::Nearest, &params, 0_u64) {
+            Err(ElevatorError::Config(message)) => assert!(message.starts_with("building 2: "), "{}", message),
+            _ => panic!("the invalid building was accepted")
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//Import source modules
+use crate::building::Building;
+use crate::render::{RenderOptions, Viewport, Ticker, TICKER_LINES, get_frame_lines, get_legend_lines, render_frame};
+use crate::terminal::Renderer;
+
+//Import external/standard modules
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/** CastWriter struct schema
+ *
+ * A CastWriter has the following properties
+ * - file (BufWriter<File>): The asciicast v2 file being written
+ * - tick (Duration): The time between frames in the cast
+ *
+ * Frames are timestamped by their time step and the tick rather than
+ * the wall time, so that casts of fast runs are still watchable.
+ */
+pub struct CastWriter {
+    file: BufWriter<File>,
+    tick: Duration
+}
+
+/** CastWriter type implementation
+ *
+ * The following functions are implemented for the CastWriter type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_cast: CastWriter = CastWriter::create("run.cast", 80_usize, 24_usize, tick)?;
+ * my_cast.write_frame(time_step, &frame_bytes)?;
+ */
+impl CastWriter {
+    /** create function
+     *
+     * Create the cast file at the given path and write the asciicast
+     * v2 header for a terminal of the given size.
+     */
+    pub fn create(path: &str, width: usize, height: usize, tick: Duration) -> io::Result<CastWriter> {
+        let mut file: BufWriter<File> = BufWriter::new(File::create(path)?);
+        let timestamp: u64 = SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0_u64);
+        writeln!(
+            file,
+            "{}",
+            serde_json::json!({ "version": 2, "width": width, "height": height, "timestamp": timestamp })
+        )?;
+        Ok(CastWriter {
+            file,
+            tick
+        })
+    }
+
+    /** write_frame function
+     *
+     * Write the output drawn for the given time step as an output
+     * event.  Time steps which drew nothing are skipped.
+     */
+    pub fn write_frame(&mut self, time_step: i32, data: &[u8]) -> io::Result<()> {
+        self.write_subframe(time_step, 0_usize, 1_usize, data)
+    }
+
+    /** write_subframe function
+     *
+     * Write the output drawn for the given frame of the given time
+     * step as an output event, the frames, numbered from 0, splitting
+     * the tick evenly.  Frames which drew nothing are skipped.
+     */
+    pub fn write_subframe(&mut self, time_step: i32, frame: usize, num_frames: usize, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let time: f64 = self.tick.as_secs_f64() * (time_step as f64 + frame as f64 / num_frames as f64);
+        writeln!(self.file, "{}", serde_json::json!([time, "o", String::from_utf8_lossy(data)]))
+    }
+
+    /** finish function
+     *
+     * Flush the remainder of the cast to the file
+     */
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/** HeadlessCast struct schema
+ *
+ * A HeadlessCast has the following properties
+ * - writer (CastWriter): The writer of the cast file
+ * - renderer (Renderer): The renderer drawing to a virtual terminal fitting the whole building
+ * - viewport (Viewport): The viewport of the virtual terminal
+ * - ticker (Ticker): The latest events, if the ticker is shown
+ * - frame_str (String): The frame buffer, reused across frames
+ * - frame_out (Vec<u8>): The output drawn for each frame, reused across frames
+ *
+ * A HeadlessCast renders the frames a terminal would have shown for a
+ * run which is not rendered to the terminal.
+ */
+pub struct HeadlessCast {
+    writer: CastWriter,
+    renderer: Renderer,
+    viewport: Viewport,
+    ticker: Ticker,
+    frame_str: String,
+    frame_out: Vec<u8>
+}
+
+//Constant representing the width of the virtual terminal of a headless cast
+const HEADLESS_CAST_WIDTH: usize = 80_usize;
+
+//Constant representing the tallest virtual terminal of a headless cast, above which floors are scrolled
+const HEADLESS_CAST_MAX_HEIGHT: usize = 48_usize;
+
+/** HeadlessCast type implementation
+ *
+ * The following functions are implemented for the HeadlessCast type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_cast: HeadlessCast = HeadlessCast::create("run.cast", num_floors, options, tick)?;
+ * my_cast.draw(&my_building, time_step)?;
+ */
+impl HeadlessCast {
+    /** create function
+     *
+     * Create the cast file at the given path, sized to fit a building
+     * with the given number of floors rendered with the given options.
+     * Buildings too tall for a terminal are rendered in compact mode,
+     * following the busiest floor, as they would be in a terminal.
+     */
+    pub fn create(path: &str, num_floors: usize, options: RenderOptions, tick: Duration) -> io::Result<HeadlessCast> {
+        let ticker_lines: usize = if options.ticker { TICKER_LINES } else { 0_usize };
+        let height: usize = (get_frame_lines(num_floors, options.compact) + ticker_lines
+            + get_legend_lines(options.legend, options.keys)).min(HEADLESS_CAST_MAX_HEIGHT);
+        Ok(HeadlessCast {
+            writer: CastWriter::create(path, HEADLESS_CAST_WIDTH, height, tick)?,
+            renderer: Renderer::from(height),
+            viewport: Viewport::from(HEADLESS_CAST_WIDTH, height, options),
+            ticker: Ticker::from(HEADLESS_CAST_WIDTH),
+            frame_str: String::new(),
+            frame_out: Vec::new()
+        })
+    }
+
+    /** draw function
+     *
+     * Render the building after the given time step and write the
+     * output the renderer draws as a frame of the cast.
+     */
+    pub fn draw(&mut self, building: &Building, time_step: i32) -> io::Result<()> {
+        for event in building.events.iter() {
+            self.ticker.push(event.describe());
+        }
+        self.frame_str.clear();
+        let _ = render_frame(&mut self.frame_str, building, &self.viewport.get_view(building), &self.ticker);
+        self.frame_out.clear();
+        self.renderer.draw(&mut self.frame_out, &self.frame_str)?;
+        self.writer.write_frame(time_step, &self.frame_out)
+    }
+
+    /** finish function
+     *
+     * Flush the remainder of the cast to the file
+     */
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.writer.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{ElevatorController, NearestController};
+    use crate::simulation::step;
+    use crate::profile::Profiler;
+
+    //Get a path in the temporary directory unique to this test run
+    fn get_temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("elevator-cast-{}-{}.cast", std::process::id(), name))
+            .display().to_string()
+    }
+
+    //Read the header and the events of a cast file
+    fn read_cast(path: &str) -> (serde_json::Value, Vec<serde_json::Value>) {
+        let cast_str: String = std::fs::read_to_string(path).unwrap();
+        let _ = std::fs::remove_file(path);
+        let mut lines = cast_str.lines().map(|x| serde_json::from_str::<serde_json::Value>(x).unwrap());
+        let header: serde_json::Value = lines.next().unwrap();
+        (header, lines.collect())
+    }
+
+    //Check that each event is an output event later than the one before
+    fn check_events(events: &[serde_json::Value]) {
+        let mut last_time: f64 = -1_f64;
+        for (i, event) in events.iter().enumerate() {
+            let event: &Vec<serde_json::Value> = event.as_array().unwrap();
+            assert_eq!(event.len(), 3_usize, "event {}", i);
+            let time: f64 = event[0].as_f64().unwrap();
+            assert!(time > last_time, "event {} at {} follows {}", i, time, last_time);
+            assert_eq!(event[1], "o", "event {}", i);
+            assert!(!event[2].as_str().unwrap().is_empty(), "event {}", i);
+            last_time = time;
+        }
+    }
+
+    #[test]
+    fn headless_cast_is_valid_asciicast() {
+        //Cast 30 time steps of a building of 8 floors
+        let path: String = get_temp_path("headless");
+        let options: RenderOptions = RenderOptions { ticker: true, ..RenderOptions::new() };
+        let mut cast: HeadlessCast = HeadlessCast::create(&path, 8_usize, options, Duration::from_millis(100_u64)).unwrap();
+        let mut controller: NearestController = NearestController::from(
+            Building::from(8_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap()
+        );
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..30_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            cast.draw(controller.get_building(), i).unwrap();
+        }
+        cast.finish().unwrap();
+
+        //The header is that of an asciicast v2 file sized to fit the building and ticker
+        let (header, events) = read_cast(&path);
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], HEADLESS_CAST_WIDTH);
+        assert_eq!(header["height"], get_frame_lines(8_usize, false) + TICKER_LINES);
+        assert!(header["timestamp"].as_u64().unwrap() > 0_u64);
+
+        //Each frame which drew something is an output event timed by its time step
+        assert!(!events.is_empty() && events.len() <= 30_usize);
+        check_events(&events);
+        assert_eq!(events[0][0].as_f64().unwrap(), 0_f64);
+        assert!(events.iter().all(|x| (x[0].as_f64().unwrap() * 10_f64).fract().abs() < 1e-9_f64));
+    }
+
+    #[test]
+    fn subframes_split_the_tick() {
+        //Three frames of each of two time steps, one of which drew nothing
+        let path: String = get_temp_path("subframes");
+        let mut cast: CastWriter = CastWriter::create(&path, 80_usize, 24_usize, Duration::from_secs(1_u64)).unwrap();
+        for time_step in 0_i32..2_i32 {
+            for frame in 0_usize..3_usize {
+                let data: &[u8] = if time_step == 1_i32 && frame == 1_usize { b"" } else { b"frame" };
+                cast.write_subframe(time_step, frame, 3_usize, data).unwrap();
+            }
+        }
+        cast.finish().unwrap();
+
+        //The empty frame is skipped and the rest are evenly spaced within their time step
+        let (header, events) = read_cast(&path);
+        assert_eq!((header["width"].as_u64(), header["height"].as_u64()), (Some(80_u64), Some(24_u64)));
+        check_events(&events);
+        let times: Vec<f64> = events.iter().map(|x| x[0].as_f64().unwrap()).collect();
+        let expected: [f64; 5] = [0_f64, 1_f64 / 3_f64, 2_f64 / 3_f64, 1_f64, 5_f64 / 3_f64];
+        assert_eq!(times.len(), expected.len());
+        assert!(times.iter().zip(expected.iter()).all(|(x, y)| (x - y).abs() < 1e-9_f64), "{:?}", times);
+    }
+}
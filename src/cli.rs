@@ -1,5 +1,18 @@
+//Import source modules
+use crate::render::Style;
+use crate::terminal::ColorMode;
+use crate::preset::BuildingPreset;
+use crate::results::{ResultsMetric, ResultsAxis};
+use crate::controller::ControllerKind;
+use crate::elevator::ElevatorHome;
+use crate::maintenance::MaintenanceWindow;
+use crate::building::ExchangeOrder;
+use crate::observe::ObservabilityLevel;
+use crate::arrival::ArrivalProfile;
+use crate::sweep::SweepAxis;
+
 //Import library modules
-use clap::{Parser};
+use clap::{Args, Parser, Subcommand};
 
 /** ElevatorCli struct schema
  *
@@ -19,12 +32,637 @@ use clap::{Parser};
                 to these measurements under various conditions."
 )]
 pub struct ElevatorCli {
-    #[arg(long="floors")]
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+
+    #[command(flatten)]
+    pub simulate: SimulateArgs
+}
+
+/** CommonArgs struct schema
+ *
+ * The CommonArgs struct is used to store the command line arguments
+ * shared by every command simulating a building, its preset, energy
+ * model, elevator capacity, arrival profile, length and seed.
+ */
+#[derive(Args, Clone, Default)]
+pub struct CommonArgs {
+    #[arg(long="preset", help="Start from a built-in building, which the floors, elevators and arrivals flags override")]
+    pub preset: Option<BuildingPreset>,
+
+    #[arg(long="seed", help="The seed every random stream of the run is derived from, random if not given, the first of consecutive seeds for several runs")]
+    pub seed: Option<u64>,
+
+    #[arg(long="energy-up", value_parser=parse_nonnegative, allow_negative_numbers=true,
+          help="The base energy spent moving an elevator up a floor, 0 or more, 5 by default or the preset's")]
+    pub energy_up: Option<f64>,
+
+    #[arg(long="energy-down", value_parser=parse_nonnegative, allow_negative_numbers=true,
+          help="The base energy spent moving an elevator down a floor, 0 or more, 2.5 by default or the preset's")]
+    pub energy_down: Option<f64>,
+
+    #[arg(long="energy-coef", value_parser=parse_nonnegative, allow_negative_numbers=true,
+          help="The energy spent moving each person a floor on top of the base energy, 0 or more, 0.5 by default or the preset's")]
+    pub energy_coef: Option<f64>,
+
+    #[arg(long="capacity", value_parser=parse_positive_count,
+          help="The number of people each passenger elevator may carry, at least 1, unlimited by default, freight elevators carrying more")]
+    pub capacity: Option<usize>,
+
+    #[arg(long="p-out", value_parser=parse_leave_probability, allow_negative_numbers=true,
+          help="The probability each person leaves each time step once at their destination, above 0 and at most 1, 0.05 by default")]
+    pub p_out: Option<f64>,
+
+    #[arg(long="arrival-profile", value_enum,
+          help="The schedule scaling the expected arrivals over the run, constant by default")]
+    pub arrival_profile: Option<ArrivalProfile>,
+
+    #[arg(long="time-steps", value_parser=clap::value_parser!(i32).range(1..),
+          help="The number of time steps to simulate, at least 1, 1000 by default or the preset's")]
+    pub time_steps: Option<i32>,
+}
+
+/** BuildingArgs struct schema
+ *
+ * The BuildingArgs struct is used to store the command line arguments
+ * sizing a single building and weighting its floors, which override
+ * its preset's.
+ */
+#[derive(Args, Clone, Default)]
+pub struct BuildingArgs {
+    #[arg(long="floors", value_parser=parse_floor_count, help="The number of floors, at least 2, 4 by default or the preset's")]
     pub floors: Option<usize>,
 
-    #[arg(long="elevators")]
+    #[arg(long="elevators", value_parser=parse_positive_count, help="The number of elevators, at least 1, 2 by default or the preset's")]
     pub elevators: Option<usize>,
 
-    #[arg(long="expected-arrivals")]
-    pub arrivals: Option<f64>
-}
\ No newline at end of file
+    #[arg(long="expected-arrivals", value_parser=parse_positive, allow_negative_numbers=true,
+          help="The expected number of people arriving per time step, above 0, 0.2 by default or the preset's")]
+    pub arrivals: Option<f64>,
+
+    #[arg(long="floor-weights", value_name="PATH",
+          help="Draw the destinations, and optionally the entry floors, of the people arriving by the weights in a CSV file \
+          with a header of destination or destination,arrival and a row per floor")]
+    pub floor_weights: Option<String>,
+}
+
+/** SimulateArgs struct schema
+ *
+ * The SimulateArgs struct is used to store the command line arguments
+ * of a simulation, given either with the simulate subcommand or with
+ * no subcommand at all.
+ */
+#[derive(Args)]
+pub struct SimulateArgs {
+    #[command(flatten)]
+    pub building: BuildingArgs,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[arg(long="demand-smoothing", help="The weight of the latest time step in the estimated arrivals per floor, above 0 and at most 1")]
+    pub demand_smoothing: Option<f64>,
+
+    #[arg(long="change-dest-prob", help="The probability each person on an elevator changes their destination each time step, 0 by default")]
+    pub change_dest_prob: Option<f64>,
+
+    #[arg(long="min-crowding-tolerance", help="The lowest fraction of an elevator's capacity people are willing to ride at, above 0 and at most 1, 1 by default")]
+    pub min_crowding_tolerance: Option<f64>,
+
+    #[arg(long="slow-boarding-prob", help="The probability a person arriving is slow to get on and off elevators, 0 by default")]
+    pub slow_boarding_prob: Option<f64>,
+
+    #[arg(long="slow-boarding-ticks", help="The number of time steps a person slow to board takes to get on or off, 3 by default")]
+    pub slow_boarding_ticks: Option<usize>,
+
+    #[arg(long="wait-weight", help="The weight of the average wait time when scoring runs, 1 by default")]
+    pub wait_weight: Option<f64>,
+
+    #[arg(long="energy-weight", help="The weight of the average energy spent when scoring runs, 0 by default")]
+    pub energy_weight: Option<f64>,
+
+    #[arg(long="fairness-weight", help="The weight of the longest wait time when scoring runs, 0 by default")]
+    pub fairness_weight: Option<f64>,
+
+    #[arg(long="freight", value_delimiter=',', help="The indices of the freight elevators, separated by commas")]
+    pub freight: Vec<usize>,
+
+    #[arg(long="freight-serves-passengers", help="Let freight elevators answer calls and carry people at all times")]
+    pub freight_serves_passengers: bool,
+
+    #[arg(long="freight-surge", help="Let freight elevators carry people while more than this many people are waiting")]
+    pub freight_surge: Option<usize>,
+
+    #[arg(long="home", value_parser=ElevatorHome::parse, help="Return an idle elevator home, given as elevator:floor:after_idle, repeatable")]
+    pub home: Vec<ElevatorHome>,
+
+    #[arg(long="maintenance", value_parser=MaintenanceWindow::parse, help="Take an elevator out of service, given as elevator:every:duration[:start], repeatable")]
+    pub maintenance: Vec<MaintenanceWindow>,
+
+    #[arg(long="exchange-order", value_enum, help="The order the elevators exchange people in each step, round-robin by default")]
+    pub exchange_order: Option<ExchangeOrder>,
+
+    #[arg(long="observability", value_enum, help="How much of the building the controller sees, everything by default or only the hall calls")]
+    pub observability: Option<ObservabilityLevel>,
+
+    #[arg(long="no-reversal", help="Keep an occupied elevator from moving away from all its passengers' destinations")]
+    pub no_reversal: bool,
+
+    #[arg(long="strict-stops", help="Stop an elevator on a floor its passengers are headed to rather than let it pass")]
+    pub strict_stops: bool,
+
+    #[arg(long="compact", help="Render one line per floor, scrolling if the building does not fit")]
+    pub compact: bool,
+
+    #[arg(long="legend", help="Show a legend explaining the rendered building, toggled with h")]
+    pub legend: bool,
+
+    #[arg(long="ticker", help="Show the latest events beneath the building, toggled with t")]
+    pub ticker: bool,
+
+    #[arg(long="style", value_enum, default_value="ascii", help="Draw the building with ASCII or unicode box-drawing characters")]
+    pub style: Style,
+
+    #[arg(long="color", value_enum, default_value="auto", help="When to draw the building in color")]
+    pub color: ColorMode,
+
+    #[arg(long="tick-ms", default_value_t=100,
+          help="The milliseconds each time step is shown for, 0 to run as fast as the building can be drawn")]
+    pub tick_ms: u64,
+
+    #[arg(long="interpolate", value_parser=clap::value_parser!(u32).range(1..), default_value_t=1,
+          help="Render this many frames per time step, moving the cabs between floors rather than a floor at a time")]
+    pub interpolate: u32,
+
+    #[arg(long="verbose", help="Show each floor's destination probability as a number rather than a bar")]
+    pub verbose: bool,
+
+    #[arg(long="fast", help="Run the simulation at maximum speed, printing only a periodic stats line")]
+    pub fast: bool,
+
+    #[arg(long="no-render", visible_alias="headless", help="Do not render the building, implies --fast")]
+    pub no_render: bool,
+
+    #[arg(long="quiet", conflicts_with_all=["fast", "no_render", "debug_step", "replications", "cast"],
+          help="Do not render the building, but still wait the tick between time steps, printing only a closing summary line")]
+    pub quiet: bool,
+
+    #[arg(long="debug-step", conflicts_with_all=["fast", "no_render", "replications", "cast"],
+          help="Pause after each time step with a dump of the building, enter or n to step, d to toggle the people listed, q to quit")]
+    pub debug_step: bool,
+
+    #[arg(long="duration", value_parser=parse_positive, allow_negative_numbers=true,
+          conflicts_with_all=["time_steps", "arrival_profile", "debug_step", "replications", "replay"],
+          help="Run for this many seconds of wall time rather than a number of time steps, finishing the time step under way")]
+    pub duration: Option<f64>,
+
+    #[arg(long="buildings", conflicts_with="campus", help="Simulate this many copies of the building together, each with its own controller and seed")]
+    pub buildings: Option<usize>,
+
+    #[arg(long="campus", help="Simulate the buildings listed in a campus file together, each with its own controller and seed")]
+    pub campus: Option<String>,
+
+    #[arg(long="layout", conflicts_with_all=["campus", "buildings", "replications", "replay"],
+          help="Start from the building, elevators and occupants described in a layout file, in place of the config")]
+    pub layout: Option<String>,
+
+    #[arg(long="replications", visible_alias="runs", value_parser=parse_positive_count,
+          help="Run this many seeded simulations in parallel, printing the result of each and their mean and standard deviation")]
+    pub replications: Option<usize>,
+
+    #[arg(long="profile", help="Measure the time spent in each phase of a time step and print a breakdown at the end")]
+    pub profile: bool,
+
+    #[arg(long="http-status", help="Serve the latest state at GET /state and the results so far at GET /summary on this port")]
+    pub http_status: Option<u16>,
+
+    #[arg(long="record", help="Record the arrivals, leave decisions, destination changes and elevator movements to a trace file")]
+    pub record: Option<String>,
+
+    #[arg(long="replay", conflicts_with_all=["record", "seed"], help="Rerun the simulation recorded in a trace file and print its results")]
+    pub replay: Option<String>,
+
+    #[arg(long="journeys", help="Write a CSV row for each trip completed, with its steps, wait and ride ticks, to this file")]
+    pub journeys: Option<String>,
+
+    #[arg(long="event-log", help="Write a line for each event, prefixed by its time step, such as t=42 PERSON_ARRIVED person=7 floor=0 dest=3, to this file")]
+    pub event_log: Option<String>,
+
+    #[arg(long="snapshots", help="Write the state of the building as JSON to this directory, one file per snapshot, or as JSON-Lines to this .jsonl file")]
+    pub snapshots: Option<String>,
+
+    #[arg(long="snapshot-interval", requires="snapshots", value_parser=clap::value_parser!(u32).range(1..), default_value_t=1,
+          help="Write a snapshot every this many time steps, starting from the first")]
+    pub snapshot_interval: u32,
+
+    #[arg(long="cast", help="Write the rendered frames to an asciicast v2 file, timed by the tick rather than the wall time")]
+    pub cast: Option<String>,
+
+    #[arg(long="scenario", conflicts_with_all=["replay", "replications"], help="Add the scripted arrivals and scheduled events from a scenario file to the simulation")]
+    pub scenario: Option<String>,
+
+    #[arg(long="results-db", help="Append the results of each run to a CSV results log, keyed by a hash of the config")]
+    pub results_db: Option<String>,
+
+    #[arg(long="metrics-csv", conflicts_with="replay",
+          help="Append a row per run with its floors, elevators, arrival rate, controller, seed and metrics to this CSV file, writing a header when it is created")]
+    pub metrics_csv: Option<String>,
+
+    #[arg(long="summary-json", value_name="PATH", num_args=0..=1, default_missing_value="-", conflicts_with="replay",
+          help="Write a JSON summary of each run, one per line, to this file or to stdout if no file or - is given, \
+          in which case everything else is printed to stderr")]
+    pub summary_json: Option<String>,
+
+    #[arg(long="check-invariants", help="Check the consistency of the building after every step, aborting with a dump on a violation")]
+    pub check_invariants: bool,
+
+    #[arg(long="controller", value_enum, default_value="random", conflicts_with="replay",
+          help="The controller dispatching the elevators of every building")]
+    pub controller: ControllerKind,
+
+    #[arg(long="controller-arg", value_name="KEY=VALUE", help="Tune the controller with a parameter, repeatable, see --controller-help")]
+    pub controller_args: Vec<String>,
+
+    #[arg(long="controller-help", value_name="CONTROLLER", help="List the parameters of a controller and their defaults, then exit")]
+    pub controller_help: Option<ControllerKind>
+}
+
+/** CompareArgs struct schema
+ *
+ * The CompareArgs struct is used to store the command line arguments
+ * of a comparison of two controllers on the same building and seeds.
+ */
+#[derive(Args)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub building: BuildingArgs,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[arg(long="baseline", value_enum, default_value="random", help="The controller compared against")]
+    pub baseline: ControllerKind,
+
+    #[arg(long="candidate", value_enum, help="The controller compared")]
+    pub candidate: ControllerKind,
+
+    #[arg(long="runs", value_parser=parse_positive_count, default_value_t=1,
+          help="The number of seeds both controllers are run on, whose metrics are averaged")]
+    pub runs: usize
+}
+
+/** SweepArgs struct schema
+ *
+ * The SweepArgs struct is used to store the command line arguments of
+ * a sweep over the size and traffic of a building.  Each axis is given
+ * as a single value, a comma separated list, or an inclusive range
+ * start:end[:step], and defaults to the preset's value.
+ */
+#[derive(Args)]
+pub struct SweepArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+
+    #[arg(long="floors", value_parser=parse_floor_axis, help="The numbers of floors swept, each at least 2, such as 2:10:2")]
+    pub floors: Option<SweepAxis<usize>>,
+
+    #[arg(long="elevators", value_parser=parse_elevator_axis, help="The numbers of elevators swept, each at least 1, such as 1,2,4")]
+    pub elevators: Option<SweepAxis<usize>>,
+
+    #[arg(long="expected-arrivals", value_parser=parse_arrival_axis,
+          help="The expected arrivals per time step swept, each above 0, such as 0.1:0.5:0.1")]
+    pub arrivals: Option<SweepAxis<f64>>,
+
+    #[arg(long="controller", value_enum, default_value="random", help="The controller dispatching the elevators")]
+    pub controller: ControllerKind,
+
+    #[arg(long="runs", value_parser=parse_positive_count, default_value_t=1,
+          help="The number of seeds each point is run on, whose metrics are averaged")]
+    pub runs: usize,
+
+    #[arg(long="metrics-csv", help="Append a row per run and an aggregate row per point to this CSV file")]
+    pub metrics_csv: Option<String>
+}
+
+/** CliCommand enum
+ *
+ * The subcommands of the application.  Simulating is the default when
+ * no subcommand is given, and the others run the simulation other than
+ * by animating it in the terminal.
+ */
+#[derive(Subcommand)]
+pub enum CliCommand {
+    #[command(about="Simulate the building, as when no subcommand is given")]
+    Simulate(Box<SimulateArgs>),
+
+    #[command(about="Run two controllers on identical arrival sequences and print a table of the differences in their metrics")]
+    Compare(CompareArgs),
+
+    #[command(about="Simulate every combination of ranges of floors, elevators and arrival rates, printing a row for each")]
+    Sweep(SweepArgs),
+
+    #[command(about="Serve the simulation over TCP, sending a JSON snapshot each step and reading U/D/S commands per elevator")]
+    Serve {
+        #[arg(long="port", default_value_t=4242, help="The port to listen on, on the loopback interface")]
+        port: u16,
+
+        #[arg(long="timeout-ms", default_value_t=1000, help="How long to wait for commands before stopping all elevators")]
+        timeout_ms: u64
+    },
+
+    #[command(about="Query the runs appended to a results log")]
+    Results {
+        #[arg(long="results-db", help="The CSV results log to query")]
+        results_db: String,
+
+        #[command(subcommand)]
+        query: ResultsQuery
+    },
+
+    #[command(about="Compare seeded simulations against the golden files, failing if any behavior changed")]
+    Golden {
+        #[arg(long="dir", default_value="golden", help="The directory of golden files")]
+        dir: String,
+
+        #[arg(long="update", help="Overwrite the golden files with the current behavior, as does UPDATE_GOLDEN=1")]
+        update: bool
+    },
+
+    #[command(about="Tune a controller's parameters with simulated annealing over seeded replications of the building")]
+    Tune {
+        #[arg(long="controller", value_enum, help="The controller whose parameters are tuned")]
+        controller: ControllerKind,
+
+        #[arg(long="budget", default_value_t=50, help="The number of parameter sets evaluated, including the defaults")]
+        budget: usize,
+
+        #[arg(long="replications", default_value_t=4, help="The number of seeded simulations each parameter set is evaluated on")]
+        replications: usize,
+
+        #[arg(long="seed", help="The seed of the replications and the proposals, random if not given")]
+        seed: Option<u64>,
+
+        #[arg(long="save", help="Write the best parameters found to a JSON file")]
+        save: Option<String>
+    }
+}
+
+/** ResultsQuery enum
+ *
+ * The queries which may be run against a results log
+ */
+#[derive(Subcommand)]
+pub enum ResultsQuery {
+    #[command(about="List every logged run, in the order they were logged")]
+    List,
+
+    #[command(about="Show the logged run with the lowest value of a metric")]
+    Best {
+        #[arg(long="metric", value_enum, default_value_t=ResultsMetric::AvgWait, help="The metric to minimize")]
+        metric: ResultsMetric
+    },
+
+    #[command(about="Compare the mean of a metric across the logged runs of each controller or building")]
+    Compare {
+        #[arg(long="metric", value_enum, default_value_t=ResultsMetric::AvgWait, help="The metric to compare")]
+        metric: ResultsMetric,
+
+        #[arg(long="by", value_enum, default_value_t=ResultsAxis::Controller, help="Group the runs by controller, or by building config")]
+        by: ResultsAxis
+    }
+}
+
+/** parse_positive_count function
+ *
+ * Parse a count of elevators, people or runs from a flag, rejecting 0
+ * so that it is reported as a bad flag rather than once the building
+ * is built or the runs are started.
+ */
+fn parse_positive_count(count_str: &str) -> Result<usize, String> {
+    match count_str.parse::<usize>() {
+        Ok(0_usize) => Err(String::from("must be at least 1")),
+        Ok(x) => Ok(x),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+/** parse_floor_axis function
+ *
+ * Parse the numbers of floors of a sweep, each at least 2.
+ */
+fn parse_floor_axis(axis_str: &str) -> Result<SweepAxis<usize>, String> {
+    SweepAxis::parse_counts(axis_str, 2_usize)
+}
+
+/** parse_elevator_axis function
+ *
+ * Parse the numbers of elevators of a sweep, each at least 1.
+ */
+fn parse_elevator_axis(axis_str: &str) -> Result<SweepAxis<usize>, String> {
+    SweepAxis::parse_counts(axis_str, 1_usize)
+}
+
+/** parse_arrival_axis function
+ *
+ * Parse the arrival rates of a sweep, each above 0.
+ */
+fn parse_arrival_axis(axis_str: &str) -> Result<SweepAxis<f64>, String> {
+    SweepAxis::parse_rates(axis_str)
+}
+
+/** parse_floor_count function
+ *
+ * Parse a number of floors from a flag, rejecting fewer than 2, since
+ * everyone arriving on the lobby of a single floor would have nowhere
+ * to go.
+ */
+fn parse_floor_count(count_str: &str) -> Result<usize, String> {
+    match count_str.parse::<usize>() {
+        Ok(x) if x < 2_usize => Err(String::from("must be at least 2")),
+        Ok(x) => Ok(x),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+/** parse_positive function
+ *
+ * Parse an arrival rate from a flag, rejecting a value which is not
+ * above 0, with which nobody would ever arrive.
+ */
+fn parse_positive(value_str: &str) -> Result<f64, String> {
+    match value_str.parse::<f64>() {
+        Ok(x) if x.is_finite() && x > 0_f64 => Ok(x),
+        Ok(x) => Err(format!("{} is not a finite number above 0", x)),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+/** parse_leave_probability function
+ *
+ * Parse a probability of leaving from a flag, rejecting 0, with which
+ * nobody would ever leave, and anything above 1.
+ */
+fn parse_leave_probability(value_str: &str) -> Result<f64, String> {
+    match value_str.parse::<f64>() {
+        Ok(x) if x > 0_f64 && x <= 1_f64 => Ok(x),
+        Ok(x) => Err(format!("{} is not above 0 and at most 1", x)),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+/** parse_nonnegative function
+ *
+ * Parse an energy from a flag, rejecting a negative or non-finite
+ * value, which no elevator could spend.
+ */
+fn parse_nonnegative(value_str: &str) -> Result<f64, String> {
+    match value_str.parse::<f64>() {
+        Ok(x) if x.is_finite() && x >= 0_f64 => Ok(x),
+        Ok(x) => Err(format!("{} is not a finite number of 0 or more", x)),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::person::Person;
+    use crate::units::Energy;
+    use clap::error::ErrorKind;
+
+    //Parse the given args after the binary's name
+    fn parse(args: &[&str]) -> Result<ElevatorCli, clap::Error> {
+        ElevatorCli::try_parse_from(std::iter::once("elevator-optimization").chain(args.iter().copied()))
+    }
+
+    //Parse the given args, returning the kind and message of the error they fail with
+    fn parse_err(args: &[&str]) -> (ErrorKind, String) {
+        match parse(args) {
+            Ok(_) => panic!("{:?} should not parse", args),
+            Err(e) => (e.kind(), e.to_string())
+        }
+    }
+
+    #[test]
+    fn building_flags_parse() {
+        let cli_args: ElevatorCli = parse(&["--floors", "10", "--elevators", "3", "--expected-arrivals", "0.5"]).unwrap();
+        assert_eq!(cli_args.simulate.building.floors, Some(10_usize));
+        assert_eq!(cli_args.simulate.building.elevators, Some(3_usize));
+        assert_eq!(cli_args.simulate.building.arrivals, Some(0.5_f64));
+
+        //The flags not given are left for the preset or the defaults
+        let cli_args: ElevatorCli = parse(&[]).unwrap();
+        assert_eq!(cli_args.simulate.building.floors, None);
+        assert_eq!(cli_args.simulate.building.elevators, None);
+        assert_eq!(cli_args.simulate.building.arrivals, None);
+    }
+
+    #[test]
+    fn bad_building_flags_are_rejected() {
+        let cases: [(&[&str], &str); 5] = [
+            (&["--elevators", "0"], "must be at least 1"),
+            (&["--floors", "1"], "must be at least 2"),
+            (&["--expected-arrivals", "-0.5"], "-0.5 is not a finite number above 0"),
+            (&["--expected-arrivals", "0"], "0 is not a finite number above 0"),
+            (&["--elevators", "two"], "invalid digit found in string")
+        ];
+        for (args, message) in cases.iter() {
+            let (kind, err_str): (ErrorKind, String) = parse_err(args);
+            assert_eq!(kind, ErrorKind::ValueValidation, "{:?}", args);
+            assert!(err_str.contains(message), "{:?} gave {}", args, err_str);
+        }
+    }
+
+    #[test]
+    fn conflicting_flags_are_rejected() {
+        let cases: [&[&str]; 5] = [
+            &["--quiet", "--fast"],
+            &["--debug-step", "--replications", "4"],
+            &["--replay", "run.json", "--seed", "1"],
+            &["--duration", "5", "--time-steps", "100"],
+            &["--buildings", "2", "--campus", "campus.json"]
+        ];
+        for args in cases.iter() {
+            assert_eq!(parse_err(args).0, ErrorKind::ArgumentConflict, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn subcommands_parse_their_own_building() {
+        let cli_args: ElevatorCli = parse(&["compare", "--baseline", "nearest", "--candidate", "max-wait", "--elevators", "4"]).unwrap();
+        match cli_args.command {
+            Some(CliCommand::Compare(args)) => assert_eq!(args.building.elevators, Some(4_usize)),
+            _ => panic!("expected the compare subcommand")
+        }
+        assert_eq!(parse_err(&["sweep", "--elevators", "0,2"]).0, ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn energy_flags_set_the_energy_spent() {
+        let cli_args: ElevatorCli = parse(&["--energy-up", "8", "--energy-down", "1.5", "--energy-coef", "0.25"]).unwrap();
+        let common: &CommonArgs = &cli_args.simulate.common;
+        let (energy_up, energy_down, energy_coef) = (common.energy_up.unwrap(), common.energy_down.unwrap(), common.energy_coef.unwrap());
+        assert_eq!((energy_up, energy_down, energy_coef), (8_f64, 1.5_f64, 0.25_f64));
+        for num_people in [0_usize, 1_usize, 6_usize] {
+            //Load an elevator of a building with the parsed energy model with the given number of people
+            let mut building: Building = Building::from(4_usize, 2_usize, 0_f64, energy_up, energy_down, energy_coef, 0_u64).unwrap();
+            for id in 0_usize..num_people {
+                let mut pers: Person = Person::from_dest(0_f64, 0_usize, 3_usize).unwrap();
+                pers.id = id;
+                building.place_person(pers, Some(0_usize));
+            }
+
+            //Move it up a floor, then back down, while the other elevator stays put
+            let people_energy: f64 = energy_coef * num_people as f64;
+            for (moving_up, expected_energy) in [(true, energy_up + people_energy), (false, energy_down + people_energy)] {
+                building.elevators[0].stopped = false;
+                building.elevators[0].moving_up = moving_up;
+                let _ = building.elevators[0].update_floor();
+                let energy_spent: Energy = building.get_energy_spent();
+                assert_eq!(energy_spent, Energy(expected_energy), "{} people moving up {}", num_people, moving_up);
+                building.tick(energy_spent);
+            }
+        }
+    }
+
+    #[test]
+    fn each_invalid_flag_names_itself_and_its_range() {
+        let cases: [(&[&str], &str); 12] = [
+            (&["--floors", "0"], "invalid value '0' for '--floors <FLOORS>': must be at least 2"),
+            (&["--floors", "1"], "invalid value '1' for '--floors <FLOORS>': must be at least 2"),
+            (&["--elevators", "0"], "invalid value '0' for '--elevators <ELEVATORS>': must be at least 1"),
+            (&["--expected-arrivals", "0"], "invalid value '0' for '--expected-arrivals <ARRIVALS>': 0 is not a finite number above 0"),
+            (&["--expected-arrivals", "inf"], "invalid value 'inf' for '--expected-arrivals <ARRIVALS>': inf is not a finite number above 0"),
+            (&["--energy-up", "-1"], "invalid value '-1' for '--energy-up <ENERGY_UP>': -1 is not a finite number of 0 or more"),
+            (&["--energy-up", "nan"], "invalid value 'nan' for '--energy-up <ENERGY_UP>': NaN is not a finite number of 0 or more"),
+            (&["--energy-down", "-0.5"], "invalid value '-0.5' for '--energy-down <ENERGY_DOWN>': -0.5 is not a finite number of 0 or more"),
+            (&["--energy-coef", "-2"], "invalid value '-2' for '--energy-coef <ENERGY_COEF>': -2 is not a finite number of 0 or more"),
+            (&["--replications", "0"], "invalid value '0' for '--replications <REPLICATIONS>': must be at least 1"),
+            (&["--runs", "0"], "invalid value '0' for '--replications <REPLICATIONS>': must be at least 1"),
+            (&["compare", "--floors", "1"], "invalid value '1' for '--floors <FLOORS>': must be at least 2")
+        ];
+        for (args, message) in cases.iter() {
+            let (kind, err_str): (ErrorKind, String) = parse_err(args);
+            assert_eq!(kind, ErrorKind::ValueValidation, "{:?}", args);
+            assert!(err_str.contains(message), "{:?} gave {}", args, err_str);
+        }
+    }
+
+    #[test]
+    fn smallest_building_is_valid() {
+        //Exactly 2 floors and 1 elevator, with a free energy model, parses and builds
+        let cli_args: ElevatorCli = parse(&[
+            "--floors", "2", "--elevators", "1", "--expected-arrivals", "0.01",
+            "--energy-up", "0", "--energy-down", "0", "--energy-coef", "0"
+        ]).unwrap();
+        let building_args: &BuildingArgs = &cli_args.simulate.building;
+        let common: &CommonArgs = &cli_args.simulate.common;
+        assert_eq!((building_args.floors, building_args.elevators), (Some(2_usize), Some(1_usize)));
+        let building: Building = Building::from(
+            building_args.floors.unwrap(), building_args.elevators.unwrap(), building_args.arrivals.unwrap(),
+            common.energy_up.unwrap(), common.energy_down.unwrap(), common.energy_coef.unwrap(), 0_u64
+        ).unwrap();
+        assert_eq!((building.floors.len(), building.elevators.len()), (2_usize, 1_usize));
+    }
+}
@@ -0,0 +1,164 @@
+//Import source modules
+use crate::controller::ControllerKind;
+use crate::params::ControllerParams;
+use crate::simulation::{SimulationConfig, SimulationResult, run_replications};
+use crate::error::ElevatorError;
+
+//Import external/standard modules
+use std::fmt;
+
+//Type representing a function reading a metric from the result of a run
+type MetricGetter = fn(&SimulationResult) -> f64;
+
+/** ComparisonRow struct schema
+ *
+ * A ComparisonRow has the following properties
+ * - metric (&'static str): The name of the metric compared
+ * - baseline (f64): The mean of the metric over the baseline controller's runs
+ * - candidate (f64): The mean of the metric over the candidate controller's runs
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ComparisonRow {
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub candidate: f64
+}
+
+/** ComparisonRow type implementation
+ *
+ * The following functions are implemented for the ComparisonRow type,
+ * and are callable via
+ *
+ * //Example
+ * let my_delta: f64 = my_row.get_delta();
+ * let my_change: Option<f64> = my_row.get_relative_change();
+ */
+impl ComparisonRow {
+    /** get_delta function
+     *
+     * Return how much higher the metric is for the candidate.
+     */
+    pub fn get_delta(&self) -> f64 {
+        self.candidate - self.baseline
+    }
+
+    /** get_relative_change function
+     *
+     * Return the delta as a percentage of the baseline, if the baseline
+     * is not 0.
+     */
+    pub fn get_relative_change(&self) -> Option<f64> {
+        if self.baseline == 0_f64 {
+            None
+        } else {
+            Some(100_f64 * self.get_delta() / self.baseline)
+        }
+    }
+}
+
+/** ControllerComparison struct schema
+ *
+ * A ControllerComparison is the outcome of running two controllers on
+ * the same seeds.  The arrivals and destinations are drawn from RNG
+ * streams of their own, so both controllers serve identical arrival
+ * sequences and any difference in the metrics is down to dispatching.
+ *
+ * A ControllerComparison has the following properties
+ * - baseline (ControllerKind): The controller compared against
+ * - candidate (ControllerKind): The controller compared
+ * - seeds (Vec<u64>): The seeds both controllers were run with
+ * - rows (Vec<ComparisonRow>): The mean of each metric for both controllers
+ */
+pub struct ControllerComparison {
+    pub baseline: ControllerKind,
+    pub candidate: ControllerKind,
+    pub seeds: Vec<u64>,
+    pub rows: Vec<ComparisonRow>
+}
+
+/** ControllerComparison type implementation
+ *
+ * The following functions are implemented for the ControllerComparison
+ * type, and are callable via
+ *
+ * //Example
+ * let my_comparison: ControllerComparison = ControllerComparison::run(&my_config, ControllerKind::Random, ControllerKind::Nearest, &seeds)?;
+ * println!("{}", my_comparison);
+ */
+impl ControllerComparison {
+    /** run function
+     *
+     * Run both controllers with their default parameters on each of the
+     * given seeds, then compare the means of their metrics.
+     */
+    pub fn run(config: &SimulationConfig, baseline: ControllerKind, candidate: ControllerKind,
+               seeds: &[u64]) -> Result<ControllerComparison, ElevatorError> {
+        let params: ControllerParams = ControllerParams::default();
+        let baseline_results: Vec<SimulationResult> = run_replications(config, baseline, &params, seeds)?;
+        let candidate_results: Vec<SimulationResult> = run_replications(config, candidate, &params, seeds)?;
+        Ok(ControllerComparison::from(baseline, candidate, seeds, &baseline_results, &candidate_results))
+    }
+
+    /** ControllerComparison constructor function
+     *
+     * Compare the results of the baseline and candidate controllers run
+     * on the given seeds.
+     */
+    pub fn from(baseline: ControllerKind, candidate: ControllerKind, seeds: &[u64],
+                baseline_results: &[SimulationResult], candidate_results: &[SimulationResult]) -> ControllerComparison {
+        let metrics: [(&'static str, MetricGetter); 8] = [
+            ("average time in system", |x| x.wait.mean),
+            ("max time in system", |x| x.wait.max.as_f64()),
+            ("average floor wait", |x| x.floor_wait.mean),
+            ("average ride time", |x| x.ride.mean),
+            ("average energy spent", |x| x.energy.avg_per_step.0),
+            ("people delivered", |x| x.wait.count as f64),
+            ("delivered per step", |x| x.throughput),
+            ("missed stops", |x| x.missed_stops as f64)
+        ];
+        ControllerComparison {
+            baseline,
+            candidate,
+            seeds: seeds.to_vec(),
+            rows: metrics.iter().map(|(metric, get_value)| ComparisonRow {
+                metric,
+                baseline: get_mean(baseline_results, *get_value),
+                candidate: get_mean(candidate_results, *get_value)
+            }).collect()
+        }
+    }
+}
+
+//Implement display for ControllerComparison, as a table of each metric and its delta
+impl fmt::Display for ControllerComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f, "{:<24} {:>12} {:>12} {:>12} {:>9}",
+            "metric", self.baseline.get_name(), self.candidate.get_name(), "delta", "change"
+        )?;
+        for row in self.rows.iter() {
+            let change: String = match row.get_relative_change() {
+                Some(x) => format!("{:+.1}%", x),
+                None => String::from("-")
+            };
+            writeln!(
+                f, "{:<24} {:>12.3} {:>12.3} {:>+12.3} {:>9}",
+                row.metric, row.baseline, row.candidate, row.get_delta(), change
+            )?;
+        }
+        write!(f, "Means over {} run(s) from seed {}", self.seeds.len(), self.seeds.first().copied().unwrap_or(0_u64))
+    }
+}
+
+/** get_mean function
+ *
+ * Return the mean of a metric over the given results, 0 if there are
+ * none.
+ */
+fn get_mean(results: &[SimulationResult], get_value: MetricGetter) -> f64 {
+    if results.is_empty() {
+        0_f64
+    } else {
+        results.iter().map(get_value).sum::<f64>() / results.len() as f64
+    }
+}
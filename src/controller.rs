@@ -2,10 +2,20 @@
 use crate::building::Building;
 use crate::floors::Floors;
 use crate::people::People;
+use crate::elevator::Elevator;
+use crate::floor::Floor;
+use crate::event::SimEvent;
+use crate::cache::get_nearest_flagged_floor;
+use crate::zone::ZoneMap;
+use crate::units::Ticks;
+use crate::observe::{ObservabilityLevel, HallCalls, CallsOnlyView};
+use crate::params::{ParamSpec, ControllerParams};
+use crate::shuttle::{ShuttleAssignment, SHUTTLE_CAPACITY, SHUTTLE_MAX_DWELL_STEPS};
 
 //Implement standard/imported modules
-use rand::rngs::ThreadRng;
+use rand::Rng;
 use rand::distributions::{Distribution, Uniform};
+use serde::{Serialize, Deserialize};
 
 /** ElevatorController trait
  *
@@ -13,33 +23,159 @@ use rand::distributions::{Distribution, Uniform};
  * control the decisions of a building's elevators.
  */
 pub trait ElevatorController {
+    fn get_building(&self) -> &Building;
+
+    fn get_building_mut(&mut self) -> &mut Building;
+
     fn update_elevators(&mut self);
 }
 
+//Constant representing the parameters of the nearest and odd/even controllers
+pub const NEAREST_PARAMS: [ParamSpec; 6] = [
+    ParamSpec { name: "shuttle", default: -1_f64, step: 1_f64, help: "The elevator pinned as a lobby shuttle, or -1 for none" },
+    ParamSpec { name: "shuttle_capacity", default: SHUTTLE_CAPACITY as f64, step: 1_f64, help: "The number of passengers the shuttle departs the lobby with" },
+    ParamSpec { name: "shuttle_dwell", default: SHUTTLE_MAX_DWELL_STEPS as f64, step: 1_f64, help: "The number of time steps the shuttle waits to fill up" },
+    ParamSpec { name: "preempt", default: 1_f64, step: 1_f64, help: "1 to let a moving elevator insert a stop for a call on its way to its target, 0 to not" },
+    ParamSpec { name: "stop_notice", default: STOP_NOTICE as f64, step: 1_f64, help: "The number of time steps a call must have waited for a moving elevator to insert a stop for it" },
+    ParamSpec { name: "plan_pickups", default: 1_f64, step: 1_f64, help: "1 to let a stopped elevator with room head for a call on the way to its passengers' destination first, 0 to not" }
+];
+
+//Constant representing the number of time steps a call must have waited for a moving elevator to stop for it
+pub const STOP_NOTICE: usize = 1_usize;
+
+//Type representing the hall calls each elevator may answer, if restricted, and the age of each floor's call, if only the calls are seen
+type HallCallInputs = (Option<Vec<Vec<bool>>>, Option<Vec<Option<Ticks>>>);
+
+/** ControllerKind enum
+ *
+ * The kinds of controller which a simulation may be run with
+ */
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControllerKind {
+    Random,
+    Nearest,
+    #[serde(rename = "odd-even")]
+    OddEven,
+    #[serde(rename = "max-wait")]
+    MaxWait
+}
+
+/** ControllerKind type implementation
+ *
+ * The following functions are implemented for the ControllerKind
+ * type, and are callable via
+ *
+ * //Example
+ * let my_name: &str = ControllerKind::Nearest.get_name();
+ */
+impl ControllerKind {
+    /** get_name function
+     *
+     * Return the name of the kind of controller
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ControllerKind::Random => "random",
+            ControllerKind::Nearest => "nearest",
+            ControllerKind::OddEven => "odd-even",
+            ControllerKind::MaxWait => "max-wait"
+        }
+    }
+
+    /** get_param_specs function
+     *
+     * Return the parameters the kind of controller may be tuned with
+     */
+    pub fn get_param_specs(&self) -> &'static [ParamSpec] {
+        match self {
+            ControllerKind::Random | ControllerKind::MaxWait => &[],
+            ControllerKind::Nearest | ControllerKind::OddEven => &NEAREST_PARAMS
+        }
+    }
+
+    /** validate_params function
+     *
+     * Check that the given parameters are those of the kind of
+     * controller, and that their values are valid for a building with
+     * the given number of elevators.
+     */
+    pub fn validate_params(&self, params: &ControllerParams, num_elevators: usize) -> Result<(), String> {
+        params.validate(self.get_name(), self.get_param_specs())?;
+        if self.get_param_specs().is_empty() {
+            return Ok(());
+        }
+        let shuttle: f64 = params.get(&NEAREST_PARAMS[0]);
+        if shuttle.fract() != 0_f64 || shuttle < -1_f64 || shuttle >= num_elevators as f64 {
+            return Err(format!(
+                "shuttle {} is not -1 or an elevator of a building with {} elevators", shuttle, num_elevators
+            ));
+        }
+        let shuttle_capacity: f64 = params.get(&NEAREST_PARAMS[1]);
+        if shuttle_capacity.fract() != 0_f64 || shuttle_capacity < 1_f64 {
+            return Err(format!("shuttle_capacity {} is not a positive whole number", shuttle_capacity));
+        }
+        let shuttle_dwell: f64 = params.get(&NEAREST_PARAMS[2]);
+        if shuttle_dwell.fract() != 0_f64 || shuttle_dwell < 0_f64 {
+            return Err(format!("shuttle_dwell {} is not a nonnegative whole number", shuttle_dwell));
+        }
+        let preempt: f64 = params.get(&NEAREST_PARAMS[3]);
+        if preempt != 0_f64 && preempt != 1_f64 {
+            return Err(format!("preempt {} is not 0 or 1", preempt));
+        }
+        let stop_notice: f64 = params.get(&NEAREST_PARAMS[4]);
+        if stop_notice.fract() != 0_f64 || stop_notice < 0_f64 {
+            return Err(format!("stop_notice {} is not a nonnegative whole number", stop_notice));
+        }
+        let plan_pickups: f64 = params.get(&NEAREST_PARAMS[5]);
+        if plan_pickups != 0_f64 && plan_pickups != 1_f64 {
+            return Err(format!("plan_pickups {} is not 0 or 1", plan_pickups));
+        }
+        Ok(())
+    }
+}
+
+/** get_param_shuttle function
+ *
+ * Return the lobby shuttle described by the given validated parameters
+ * of a nearest or odd/even controller, if any.
+ */
+pub fn get_param_shuttle(params: &ControllerParams) -> Option<ShuttleAssignment> {
+    let shuttle: f64 = params.get(&NEAREST_PARAMS[0]);
+    if shuttle < 0_f64 {
+        return None;
+    }
+    let mut assignment: ShuttleAssignment = ShuttleAssignment::from(shuttle as usize);
+    assignment.capacity = params.get(&NEAREST_PARAMS[1]) as usize;
+    assignment.max_dwell_steps = params.get(&NEAREST_PARAMS[2]) as usize;
+    Some(assignment)
+}
+
 /** RandomController struct schema
  *
  * A RandomController has the following properties
  * - building (Building): A building being controlled by the controller
  * - floors_to (Vec<Option<usize>>): A list tracking the destination floors of each elevator
  * - dst_to (Uniform): A uniform distribution used for randomizing the destination floors
- * - rng (impl Rng): A random number generator for use in randomizing the elevator's dest floors
+ * - rng (R): A random number generator for use in randomizing the elevator's dest floors
  *
  * It MUST implement the ElevatorController trait
  */
- pub struct RandomController {
+ pub struct RandomController<R: Rng> {
     pub building: Building,
     floors_to: Vec<Option<usize>>,
     dst_to: Uniform<usize>,
-    rng: ThreadRng
+    rng: R
 }
 
 //Implement the RandomController interface
-impl RandomController {
+impl<R: Rng> RandomController<R> {
     /** RandomController constructor function
      *
      * Initialize a RandomController given a building and an RNG instance
      */
-    pub fn from(building: Building, rng: ThreadRng) -> RandomController {
+    pub fn from(building: Building, rng: R) -> RandomController<R> {
         //Get the number of floors and elevators in the building
         let num_floors: usize = building.floors.len();
         let num_elevators: usize = building.elevators.len();
@@ -58,46 +194,68 @@ impl RandomController {
 
         //Initialize the controller
         RandomController {
-            building: building,
-            floors_to: floors_to,
-            dst_to: dst_to,
-            rng: rng
+            building,
+            floors_to,
+            dst_to,
+            rng
         }
     }
 }
 
 //Implement the ElevatorController trait for the RandomController
-impl ElevatorController for RandomController {
+impl<R: Rng> ElevatorController for RandomController<R> {
+    /** get_building function
+     *
+     * Return a reference to the building being controlled
+     */
+    fn get_building(&self) -> &Building {
+        &self.building
+    }
+
+    /** get_building_mut function
+     *
+     * Return a mutable reference to the building being controlled
+     */
+    fn get_building_mut(&mut self) -> &mut Building {
+        &mut self.building
+    }
+
     /** update_elevators function
      *
      * Update the building's elevators so that they travel to randomly
-     * generated floors
+     * generated floors, each keeping its floor until reaching it
      */
     fn update_elevators(&mut self) {
         //Loop through the elevators in the building
+        let mut commands: Vec<ElevatorCommand> = Vec::new();
         for (i, elevator) in self.building.elevators.iter_mut().enumerate() {
-            //If the destination floor for the elevator is None, then randomize it
+            //If the destination floor for the elevator is None, then randomize it and keep it until reached
             let floor_to: usize = match self.floors_to[i] {
-                Some(x) => x as usize,
-                None => self.dst_to.sample(&mut self.rng)
+                Some(x) => x,
+                None => {
+                    let floor_to: usize = self.dst_to.sample(&mut self.rng);
+                    self.floors_to[i] = Some(floor_to);
+                    floor_to
+                }
             };
 
             //If the elevator is not on its destination floor, then move toward it
             if floor_to > elevator.floor_on {
-                elevator.stopped = false;
-                elevator.moving_up = true;
+                commands.push(ElevatorCommand::Up);
+                elevator.floor_to = Some(floor_to);
             } else if floor_to < elevator.floor_on {
-                elevator.stopped = false;
-                elevator.moving_up = false;
+                commands.push(ElevatorCommand::Down);
+                elevator.floor_to = Some(floor_to);
             //If the elevator is on its destination floor, then stop and set is destination floor to None
             } else {
-                elevator.stopped = true;
+                commands.push(ElevatorCommand::Stop);
+                elevator.floor_to = None;
                 self.floors_to[i] = None;
             }
-
-            //Update the elevator
-            let _new_floor_index = elevator.update_floor();
         }
+
+        //Update the elevators
+        self.building.apply_commands(&commands);
     }
 }
 
@@ -105,11 +263,19 @@ impl ElevatorController for RandomController {
  *
  * A NearestController has the following properties
  * - building (Building): A building being controlled by the controller
+ * - preempt (bool): Whether a moving elevator may insert a stop for a call on its way to its target
+ * - stop_notice (usize): The number of time steps a call must have waited for a stop to be inserted for it
+ * - plan_pickups (bool): Whether a stopped elevator with room heads for a call on the way to its passengers' destination first
+ * - observability (ObservabilityLevel): How much of the building the controller sees
  *
  * It MUST implement the ElevatorController trait
  */
 pub struct NearestController {
-    pub building: Building
+    pub building: Building,
+    pub preempt: bool,
+    pub stop_notice: usize,
+    pub plan_pickups: bool,
+    pub observability: ObservabilityLevel
 }
 
 //Implement the NearestController interface
@@ -121,48 +287,152 @@ impl NearestController {
     pub fn from(building: Building) -> NearestController {
         //Initialize the controller
         NearestController {
-            building: building
+            building,
+            preempt: true,
+            stop_notice: STOP_NOTICE,
+            plan_pickups: true,
+            observability: ObservabilityLevel::Full
         }
     }
+
+    /** apply_params function
+     *
+     * Tune the controller with the given validated parameters, pinning
+     * the lobby shuttle they describe, if any, in the building.
+     */
+    pub fn apply_params(&mut self, params: &ControllerParams) {
+        self.building.set_shuttle(get_param_shuttle(params));
+        self.preempt = params.get(&NEAREST_PARAMS[3]) != 0_f64;
+        self.stop_notice = params.get(&NEAREST_PARAMS[4]) as usize;
+        self.plan_pickups = params.get(&NEAREST_PARAMS[5]) != 0_f64;
+    }
+
+    /** from_zones function
+     *
+     * Initialize a NearestController given a building and the floors
+     * each of its elevators serves, so that each elevator only answers
+     * the hall calls of people headed to floors in its zone.
+     */
+    pub fn from_zones(mut building: Building, zones: ZoneMap) -> NearestController {
+        building.set_zones(Some(zones));
+        NearestController::from(building)
+    }
 }
 
 //Implement the ElevatorController trait for the NearestController
 impl ElevatorController for NearestController {
+    /** get_building function
+     *
+     * Return a reference to the building being controlled
+     */
+    fn get_building(&self) -> &Building {
+        &self.building
+    }
+
+    /** get_building_mut function
+     *
+     * Return a mutable reference to the building being controlled
+     */
+    fn get_building_mut(&mut self) -> &mut Building {
+        &mut self.building
+    }
+
     /** update_elevators function
      *
      * Update the building's elevators so that they travel to the nearest
      * destination floors first, then nearest wait floors.  Also stop on
-     * floors in the direction of the destination to service waiting people
+     * floors in the direction of the destination to service waiting people.
+     * A stopped elevator with room heads for the nearest call on the way
+     * to its passengers' nearest destination first, if planning pickups.
+     * If the building has a lobby shuttle, then it is left to the shuttle.
+     * Freight elevators only answer hall calls while they may carry people.
+     * If only the calls are seen, then they are read through a calls-only
+     * view, and each elevator answers the calls on the floors it serves.
      */
     fn update_elevators(&mut self) {
-        //Initialize a vector of decisions for the elevators
+        //Initialize a vector of decisions and destination floors for the elevators
         let mut elevator_decisions: Vec<i32> = Vec::new();
+        let mut elevator_floors_to: Vec<Option<usize>> = Vec::new();
+        let num_floors: usize = self.building.floors.len();
+
+        //If there is a lobby shuttle, then the lobby is left to it
+        let lowest_hall_floor: usize = if self.building.shuttle.is_some() { 1_usize } else { 0_usize };
+
+        //If the elevators are zoned, then each only answers the hall calls it may pick up,
+        //and if only the calls are seen, then they are aged by their oldest caller
+        let (hall_calls, call_ages): HallCallInputs = match self.observability {
+            ObservabilityLevel::Full => (self.building.zones.as_ref().map(|zones| {
+                (0_usize..self.building.elevators.len()).map(|i| zones.get_hall_calls(i, &self.building.floors)).collect()
+            }), None),
+            ObservabilityLevel::CallsOnly => {
+                let view: CallsOnlyView = CallsOnlyView::from(&self.building);
+                (
+                    Some((0_usize..view.get_num_elevators()).map(|i| view.get_hall_calls(i)).collect()),
+                    Some((0_usize..num_floors).map(|x| view.get_calls().get_age(x)).collect())
+                )
+            }
+        };
+
+        //Freight elevators only answer hall calls while they may carry people
+        let may_serve: Vec<bool> = (0_usize..self.building.elevators.len())
+            .map(|i| self.building.may_serve_passengers(i))
+            .collect();
+
+        //Initialize the events of the stops inserted on the way to a target
+        let mut inserted_stops: Vec<SimEvent> = Vec::new();
 
         //Loop through the elevators in the building
-        for elevator in self.building.elevators.iter() {
+        for (i, elevator) in self.building.elevators.iter().enumerate() {
+            //If this elevator is the lobby shuttle, then it ignores hall calls
+            if let Some(shuttle) = self.building.shuttle.as_mut().filter(|x| x.elevator == i) {
+                let (decision, floor_to): (i32, Option<usize>) = shuttle.get_decision(elevator, num_floors);
+                elevator_decisions.push(decision);
+                elevator_floors_to.push(floor_to);
+                continue;
+            }
+
             //If stopped, check where to go next
             if elevator.stopped {
                 //Find the nearest destination floor among people on the elevator
-                let (nearest_dest_floor, min_dest_floor_dist): (usize, usize) = elevator.get_nearest_dest_floor();
-
-                //If the nearest dest floor is identified, then update the elevator
-                if min_dest_floor_dist != 0_usize {
-                    //Unstop the elevator and move toward the nearest dest floor
-                    if nearest_dest_floor > elevator.floor_on {
-                        elevator_decisions.push(1_i32);
+                match elevator.get_nearest_dest_floor() {
+                    //If someone is getting off here, then stay stopped
+                    Some((_, 0_usize)) => {
+                        elevator_floors_to.push(None);
+                        elevator_decisions.push(0_i32);
                         continue;
-                    } else {
-                        elevator_decisions.push(-1_i32);
+                    },
+                    //If the nearest dest floor is elsewhere, then unstop the elevator and move toward it,
+                    //heading for the nearest call on the way first if planning pickups and there is room
+                    Some((nearest_dest_floor, _)) => {
+                        let plans_pickup: bool = self.plan_pickups && may_serve[i] && elevator.free_capacity() > 0_usize;
+                        let pickup_floor: Option<usize> = if plans_pickup {
+                            get_pickup_on_the_way(elevator.floor_on, nearest_dest_floor, lowest_hall_floor, |x| match &hall_calls {
+                                Some(calls) => calls[i][x],
+                                None => self.building.cache.waiting_floors[x]
+                            })
+                        } else {
+                            None
+                        };
+                        elevator_floors_to.push(Some(pickup_floor.unwrap_or(nearest_dest_floor)));
+                        if nearest_dest_floor > elevator.floor_on {
+                            elevator_decisions.push(1_i32);
+                        } else {
+                            elevator_decisions.push(-1_i32);
+                        }
                         continue;
-                    }
+                    },
+                    None => {}
                 }
 
-                //Find the nearest waiting floor among people throughout the building
-                let (nearest_wait_floor, min_wait_floor_dist): (usize, usize) = self.building.get_nearest_wait_floor(elevator.floor_on);
-
-                //If the nearest wait floor is identified, then update the elevator
-                if min_wait_floor_dist != 0_usize {
+                //Find the nearest waiting floor among people throughout the building,
+                //if it is elsewhere then update the elevator, else stay to pick them up
+                let nearest_wait_floor: Option<(usize, usize)> = match &hall_calls {
+                    Some(x) => get_nearest_flagged_floor(&x[i], elevator.floor_on, lowest_hall_floor),
+                    None => self.building.cache.get_nearest_wait_floor_from(elevator.floor_on, lowest_hall_floor)
+                };
+                if let (true, Some((nearest_wait_floor, 1_usize..))) = (may_serve[i], nearest_wait_floor) {
                     //Unstop the elevator and move toward the nearest dest floor
+                    elevator_floors_to.push(Some(nearest_wait_floor));
                     if nearest_wait_floor > elevator.floor_on {
                         elevator_decisions.push(1_i32);
                         continue;
@@ -172,6 +442,32 @@ impl ElevatorController for NearestController {
                     }
                 }
             } else {
+                //If an empty elevator's target was cancelled, then head for the nearest waiting floor instead,
+                //or stop if there is none elsewhere
+                if elevator.floor_to.is_none() && elevator.people.is_empty() && may_serve[i] {
+                    let nearest_wait_floor: Option<(usize, usize)> = match &hall_calls {
+                        Some(x) => get_nearest_flagged_floor(&x[i], elevator.floor_on, lowest_hall_floor),
+                        None => self.building.cache.get_nearest_wait_floor_from(elevator.floor_on, lowest_hall_floor)
+                    };
+                    match nearest_wait_floor {
+                        Some((nearest_wait_floor, 1_usize..)) => {
+                            elevator_floors_to.push(Some(nearest_wait_floor));
+                            elevator_decisions.push(if nearest_wait_floor > elevator.floor_on { 1_i32 } else { -1_i32 });
+                        },
+                        _ => {
+                            elevator_floors_to.push(None);
+                            elevator_decisions.push(0_i32);
+                        }
+                    }
+                    continue;
+                }
+
+                //Keep the destination floor while moving, unless it was reached
+                elevator_floors_to.push(match elevator.floor_to {
+                    Some(x) if x == elevator.floor_on => None,
+                    floor_to => floor_to
+                });
+
                 //If moving down and on the bottom floor, then stop
                 if !elevator.moving_up && elevator.floor_on == 0_usize {
                     elevator_decisions.push(0_i32);
@@ -184,10 +480,37 @@ impl ElevatorController for NearestController {
                     continue;
                 }
 
-                //If there are people waiting on the current floor, then stop
-                if self.building.are_people_waiting_on_floor(elevator.floor_on) {
-                    elevator_decisions.push(0_i32);
-                    continue;
+                //If there are people waiting on the current floor who may board, then stop,
+                //only inserting a stop on the way to a target if preempting with enough notice.
+                //A full elevator leaves the call to one with room, unless someone gets off here
+                let is_hall_call: bool = may_serve[i] && elevator.free_capacity() > 0_usize && match &hall_calls {
+                    Some(x) => x[i][elevator.floor_on],
+                    None => self.building.are_people_waiting_on_floor(elevator.floor_on)
+                };
+                if is_hall_call {
+                    match elevator.floor_to {
+                        Some(floor_to) if floor_to != elevator.floor_on => {
+                            let zones: Option<&ZoneMap> = self.building.zones.as_ref();
+                            let may_insert_stop: bool = match &call_ages {
+                                Some(ages) => can_insert_stop_for_call(elevator, ages[elevator.floor_on], self.stop_notice),
+                                None => can_insert_stop(
+                                    elevator, &self.building.floors[elevator.floor_on], self.stop_notice,
+                                    |x| zones.is_none_or(|z| z.serves(i, x))
+                                )
+                            };
+                            if self.preempt && may_insert_stop {
+                                elevator_decisions.push(0_i32);
+                                inserted_stops.push(SimEvent::StopInserted {
+                                    elevator: i, floor: elevator.floor_on, floor_to
+                                });
+                                continue;
+                            }
+                        },
+                        _ => {
+                            elevator_decisions.push(0_i32);
+                            continue;
+                        }
+                    }
                 }
 
                 //If there are people waiting on the elevator for the current floor, then stop
@@ -199,6 +522,7 @@ impl ElevatorController for NearestController {
 
             //If we make it this far without returning, then return the current state
             if elevator.stopped {
+                elevator_floors_to.push(None);
                 elevator_decisions.push(0_i32);
                 continue;
             } else if elevator.moving_up {
@@ -211,20 +535,612 @@ impl ElevatorController for NearestController {
         }
 
         //Loop through the elevator decisions and update the elevators
+        let mut commands: Vec<ElevatorCommand> = Vec::new();
         for (i, decision) in elevator_decisions.iter().enumerate() {
-            //Update the elevator direction
+            //Convert the decision to a command
             if *decision > 0_i32 {
-                self.building.elevators[i].stopped = false;
-                self.building.elevators[i].moving_up = true;
+                commands.push(ElevatorCommand::Up);
             } else if *decision < 0_i32 {
-                self.building.elevators[i].stopped = false;
-                self.building.elevators[i].moving_up = false;
+                commands.push(ElevatorCommand::Down);
+            } else {
+                commands.push(ElevatorCommand::Stop);
+            }
+            self.building.elevators[i].floor_to = elevator_floors_to[i];
+        }
+        self.building.apply_commands(&commands);
+
+        //Record the stops inserted on the way to a target
+        self.building.events.extend(inserted_stops);
+    }
+}
+
+/** MaxWaitController struct schema
+ *
+ * A MaxWaitController has the following properties
+ * - building (Building): A building being controlled by the controller
+ * - observability (ObservabilityLevel): How much of the building the controller sees
+ *
+ * It MUST implement the ElevatorController trait
+ */
+pub struct MaxWaitController {
+    pub building: Building,
+    pub observability: ObservabilityLevel
+}
+
+//Implement the MaxWaitController interface
+impl MaxWaitController {
+    /** MaxWaitController constructor function
+     *
+     * Initialize a MaxWaitController given a building
+     */
+    pub fn from(building: Building) -> MaxWaitController {
+        //Initialize the controller
+        MaxWaitController {
+            building,
+            observability: ObservabilityLevel::Full
+        }
+    }
+}
+
+//Implement the ElevatorController trait for the MaxWaitController
+impl ElevatorController for MaxWaitController {
+    /** get_building function
+     *
+     * Return a reference to the building being controlled
+     */
+    fn get_building(&self) -> &Building {
+        &self.building
+    }
+
+    /** get_building_mut function
+     *
+     * Return a mutable reference to the building being controlled
+     */
+    fn get_building_mut(&mut self) -> &mut Building {
+        &mut self.building
+    }
+
+    /** update_elevators function
+     *
+     * Update the building's elevators so that each empty elevator is
+     * dispatched to the floor of the longest-waiting person not already
+     * targeted by another elevator, nearest elevator first, among those
+     * which may pick someone up there if the elevators are zoned.  An empty
+     * elevator does not stop on its way to its target, so that a newer
+     * call cannot pull it away from an older one.  An occupied elevator
+     * travels to its passengers' nearest destination, stopping for
+     * other destinations and hall calls in its direction of travel.
+     * Freight elevators are only dispatched while they may carry people.
+     * If only the calls are seen, then the oldest call stands in for the
+     * longest-waiting person, and each elevator answers the calls on the
+     * floors it serves.
+     */
+    fn update_elevators(&mut self) {
+        //Initialize a vector of commands and destination floors for the elevators
+        let num_elevators: usize = self.building.elevators.len();
+        let top_floor: usize = self.building.floors.len() - 1_usize;
+        let mut commands: Vec<ElevatorCommand> = vec![ElevatorCommand::Stop; num_elevators];
+        let mut floors_to: Vec<Option<usize>> = vec![None; num_elevators];
+
+        //Claim the floors empty elevators are already headed to, so no other elevator is sent there
+        let mut claimed: Vec<bool> = vec![false; self.building.floors.len()];
+        let mut idle: Vec<usize> = Vec::new();
+        let may_serve: Vec<bool> = (0_usize..num_elevators).map(|i| self.building.may_serve_passengers(i)).collect();
+        for (i, elevator) in self.building.elevators.iter().enumerate() {
+            if !elevator.people.is_empty() {
+                continue;
+            }
+            match elevator.floor_to {
+                Some(floor_to) if !elevator.stopped && floor_to != elevator.floor_on => claimed[floor_to] = true,
+                _ => idle.push(i)
+            }
+        }
+
+        //If the elevators are zoned, then each only answers the hall calls it may pick up,
+        //and if only the calls are seen, then they are read through a calls-only view
+        let (hall_calls, calls): (Option<Vec<Vec<bool>>>, Option<HallCalls>) = match self.observability {
+            ObservabilityLevel::Full => (self.building.zones.as_ref().map(|zones| {
+                (0_usize..num_elevators).map(|i| zones.get_hall_calls(i, &self.building.floors)).collect()
+            }), None),
+            ObservabilityLevel::CallsOnly => {
+                let view: CallsOnlyView = CallsOnlyView::from(&self.building);
+                (Some((0_usize..num_elevators).map(|i| view.get_hall_calls(i)).collect()), Some(view.get_calls().clone()))
+            }
+        };
+
+        //Send the nearest idle elevator which may pick someone up to each longest-waiting floor
+        //in turn, ties to the lower index
+        while !idle.is_empty() {
+            let longest_waiting_floor: Option<(usize, Ticks)> = match &calls {
+                Some(x) => x.get_oldest_call(&claimed),
+                None => self.building.get_longest_waiting_floor(&claimed)
+            };
+            let wait_floor: usize = match longest_waiting_floor {
+                Some((floor, _)) => floor,
+                None => break
+            };
+            claimed[wait_floor] = true;
+            let (idle_index, _): (usize, &usize) = match idle.iter().enumerate()
+                .filter(|(_, i)| may_serve[**i] && hall_calls.as_ref().is_none_or(|x| x[**i][wait_floor]))
+                .min_by_key(|(_, i)| self.building.elevators[**i].floor_on.abs_diff(wait_floor)) {
+                Some(x) => x,
+                None => continue
+            };
+            let i: usize = idle.remove(idle_index);
+            floors_to[i] = Some(wait_floor);
+            commands[i] = if wait_floor > self.building.elevators[i].floor_on {
+                ElevatorCommand::Up
+            } else if wait_floor < self.building.elevators[i].floor_on {
+                ElevatorCommand::Down
             } else {
-                self.building.elevators[i].stopped = true;
+                ElevatorCommand::Stop
+            };
+        }
+
+        //Loop through the elevators which were not just dispatched
+        for (i, elevator) in self.building.elevators.iter().enumerate() {
+            if idle.contains(&i) || floors_to[i].is_some() {
+                continue;
+            }
+
+            //If empty and on the way to a target, then only stop on reaching it
+            if elevator.people.is_empty() {
+                match elevator.floor_to {
+                    Some(floor_to) if floor_to != elevator.floor_on => {
+                        floors_to[i] = Some(floor_to);
+                        commands[i] = if elevator.moving_up { ElevatorCommand::Up } else { ElevatorCommand::Down };
+                    },
+                    _ => {}
+                }
+                continue;
+            }
+
+            //If stopped with passengers, then move toward the nearest destination floor
+            let (nearest_dest_floor, _): (usize, usize) = match elevator.get_nearest_dest_floor() {
+                Some(x) => x,
+                None => continue
+            };
+            if elevator.stopped {
+                if nearest_dest_floor != elevator.floor_on {
+                    floors_to[i] = Some(nearest_dest_floor);
+                    commands[i] = if nearest_dest_floor > elevator.floor_on { ElevatorCommand::Up } else { ElevatorCommand::Down };
+                }
+                continue;
             }
 
-            //Update the elevator
-            let _new_floor_index = self.building.elevators[i].update_floor();
+            //If moving with passengers, then stop for destinations, hall calls if it has room, and either end of the building
+            if elevator.are_people_going_to_floor(elevator.floor_on)
+                || (may_serve[i] && elevator.free_capacity() > 0_usize && match &hall_calls {
+                    Some(x) => x[i][elevator.floor_on],
+                    None => self.building.are_people_waiting_on_floor(elevator.floor_on)
+                })
+                || (!elevator.moving_up && elevator.floor_on == 0_usize)
+                || (elevator.moving_up && elevator.floor_on == top_floor) {
+                continue;
+            }
+            floors_to[i] = elevator.floor_to;
+            commands[i] = if elevator.moving_up { ElevatorCommand::Up } else { ElevatorCommand::Down };
+        }
+
+        //Update the elevators
+        for (elevator, floor_to) in self.building.elevators.iter_mut().zip(floors_to) {
+            elevator.floor_to = floor_to;
         }
+        self.building.apply_commands(&commands);
     }
-}
\ No newline at end of file
+}
+
+/** can_insert_stop function
+ *
+ * Decide whether a moving elevator may insert a stop on the floor it
+ * is passing on the way to its target, which lies beyond the floor in
+ * its direction of travel.  Someone on the floor whose destination
+ * satisfies the given predicate must have waited at least the given
+ * number of time steps, so that a call made as the elevator reaches
+ * the floor is too late for its doors to open.  A full elevator may
+ * not insert a stop, since nobody could board.
+ */
+pub fn can_insert_stop(elevator: &Elevator, floor: &Floor, notice: usize, serves: impl Fn(usize) -> bool) -> bool {
+    is_passing_with_room(elevator)
+        && floor.waiting().any(|pers| pers.wait_time >= Ticks(notice as u64) && serves(pers.floor_to))
+}
+
+/** can_insert_stop_for_call function
+ *
+ * Decide whether a moving elevator may insert a stop as in
+ * can_insert_stop, knowing only the age of the call on the floor it is
+ * passing, if any, rather than who is waiting there.
+ */
+pub fn can_insert_stop_for_call(elevator: &Elevator, call_age: Option<Ticks>, notice: usize) -> bool {
+    is_passing_with_room(elevator) && call_age.is_some_and(|x| x >= Ticks(notice as u64))
+}
+
+/** is_passing_with_room function
+ *
+ * Return whether an elevator with room is passing the floor it is on
+ * toward a target beyond it in its direction of travel.
+ */
+fn is_passing_with_room(elevator: &Elevator) -> bool {
+    if elevator.free_capacity() == 0_usize {
+        return false;
+    }
+    match elevator.floor_to {
+        Some(floor_to) if elevator.moving_up => floor_to > elevator.floor_on,
+        Some(floor_to) => floor_to < elevator.floor_on,
+        None => false
+    }
+}
+
+/** get_pickup_on_the_way function
+ *
+ * Return the nearest floor strictly between the given floor and
+ * target, and not below the lowest hall floor, on which the given
+ * predicate says there is a call, if any.
+ */
+pub fn get_pickup_on_the_way(floor_on: usize, floor_to: usize, lowest_hall_floor: usize,
+                             is_call: impl Fn(usize) -> bool) -> Option<usize> {
+    if floor_to > floor_on {
+        ((floor_on + 1_usize)..floor_to).find(|x| *x >= lowest_hall_floor && is_call(*x))
+    } else {
+        ((floor_to + 1_usize)..floor_on).rev().find(|x| *x >= lowest_hall_floor && is_call(*x))
+    }
+}
+
+/** ElevatorCommand enum
+ *
+ * A command to move an elevator up, down, or to stop it, given by
+ * an external program controlling the elevators.
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ElevatorCommand {
+    Up,
+    Down,
+    Stop
+}
+
+/** parse_commands function
+ *
+ * Parse a line assigning one command to each elevator, written as
+ * U, D or S per elevator with optional whitespace between them, e.g.
+ * "U S D".  Return an error describing the problem if the line is
+ * not valid for the given number of elevators.
+ */
+pub fn parse_commands(line: &str, num_elevators: usize) -> Result<Vec<ElevatorCommand>, String> {
+    //Parse the command for each non-whitespace character
+    let mut commands: Vec<ElevatorCommand> = Vec::new();
+    for c in line.chars().filter(|c| !c.is_whitespace()) {
+        commands.push(match c.to_ascii_uppercase() {
+            'U' => ElevatorCommand::Up,
+            'D' => ElevatorCommand::Down,
+            'S' => ElevatorCommand::Stop,
+            _ => return Err(format!("unknown command '{}', expected U, D or S", c))
+        });
+    }
+
+    //Check that there is exactly one command per elevator
+    if commands.len() != num_elevators {
+        return Err(format!("got {} commands for {} elevators", commands.len(), num_elevators));
+    }
+    Ok(commands)
+}
+
+/** CommandController struct schema
+ *
+ * A CommandController has the following properties
+ * - building (Building): A building being controlled by the controller
+ * - commands (Vec<ElevatorCommand>): The commands applied to each elevator next time step
+ *
+ * It MUST implement the ElevatorController trait
+ */
+pub struct CommandController {
+    pub building: Building,
+    pub commands: Vec<ElevatorCommand>
+}
+
+//Implement the CommandController interface
+impl CommandController {
+    /** CommandController constructor function
+     *
+     * Initialize a CommandController given a building, with every
+     * elevator stopped until commanded otherwise
+     */
+    pub fn from(building: Building) -> CommandController {
+        let commands: Vec<ElevatorCommand> = vec![ElevatorCommand::Stop; building.elevators.len()];
+        CommandController {
+            building,
+            commands
+        }
+    }
+
+    /** stop_all function
+     *
+     * Command every elevator to stop next time step
+     */
+    pub fn stop_all(&mut self) {
+        for command in self.commands.iter_mut() {
+            *command = ElevatorCommand::Stop;
+        }
+    }
+}
+
+//Implement the ElevatorController trait for the CommandController
+impl ElevatorController for CommandController {
+    /** get_building function
+     *
+     * Return a reference to the building being controlled
+     */
+    fn get_building(&self) -> &Building {
+        &self.building
+    }
+
+    /** get_building_mut function
+     *
+     * Return a mutable reference to the building being controlled
+     */
+    fn get_building_mut(&mut self) -> &mut Building {
+        &mut self.building
+    }
+
+    /** update_elevators function
+     *
+     * Update the building's elevators according to their commands,
+     * stopping any elevator commanded past the top or bottom floor
+     */
+    fn update_elevators(&mut self) {
+        let top_floor: usize = self.building.floors.len() - 1_usize;
+        let commands: Vec<ElevatorCommand> = self.building.elevators.iter().enumerate().map(|(i, elevator)| {
+            match self.commands[i] {
+                ElevatorCommand::Up if elevator.floor_on < top_floor => ElevatorCommand::Up,
+                ElevatorCommand::Down if elevator.floor_on > 0_usize => ElevatorCommand::Down,
+                _ => ElevatorCommand::Stop
+            }
+        }).collect();
+        self.building.apply_commands(&commands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{get_empty_building, get_person};
+    use crate::person::{Person, PersonState};
+    use crate::elevator::ElevatorProfile;
+    use crate::rng::RngStream;
+    use crate::simulation::{SimulationConfig, build_controller, step, step_with};
+    use crate::profile::Profiler;
+    use rand::rngs::StdRng;
+
+    //Run a building of 10 floors and 1 elevator in which someone waits on the top floor, while a new call
+    //appears next to the elevator whenever it stops with nobody aboard going further.  Return the step the
+    //top floor caller boarded on, if they did within the given number of steps
+    fn get_starved_boarding_step(kind: ControllerKind, time_steps: i32) -> Option<i32> {
+        let mut building: Building = get_empty_building(10_usize, 1_usize);
+        building.place_person(get_person(0_usize, 9_usize, 8_usize), None);
+        let mut controller: Box<dyn ElevatorController> = match kind {
+            ControllerKind::Nearest => Box::new(NearestController::from(building)),
+            ControllerKind::MaxWait => Box::new(MaxWaitController::from(building)),
+            _ => panic!("unexpected controller kind")
+        };
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut next_id: usize = 1_usize;
+        for i in 0_i32..time_steps {
+            let building: &mut Building = controller.get_building_mut();
+            let num_waiting: usize = (0_usize..9_usize).map(|x| building.get_num_people_waiting_on_floor(x)).sum();
+            let elevator: &Elevator = &building.elevators[0];
+            if elevator.stopped && elevator.people.iter().all(|pers| pers.floor_to == elevator.floor_on) && num_waiting == 0_usize {
+                let floor_on: usize = if elevator.floor_on == 0_usize { 1_usize } else { elevator.floor_on - 1_usize };
+                let floor_to: usize = if floor_on == 0_usize { 1_usize } else { 0_usize };
+                building.place_person(get_person(next_id, floor_on, floor_to), None);
+                next_id += 1_usize;
+            }
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            if controller.get_building().get_num_people_waiting_on_floor(9_usize) == 0_usize {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    //Run a cab of a building of 10 floors up from floor 4 toward a rider's floor 9, past a caller on the given
+    //floor who has waited the given number of time steps.  Return the floors the cab inserted stops on, and
+    //those it stopped on, before reaching floor 9
+    fn get_inserted_stops(caller_floor: usize, wait_time: u64, stop_notice: usize, preempt: bool) -> (Vec<usize>, Vec<usize>) {
+        let mut building: Building = get_empty_building(10_usize, 1_usize);
+        building.elevators[0].floor_on = 4_usize;
+        building.elevators[0].stopped = false;
+        building.elevators[0].moving_up = true;
+        building.elevators[0].floor_to = Some(9_usize);
+        let mut rider: Person = get_person(0_usize, 4_usize, 9_usize);
+        rider.state = PersonState::Riding;
+        building.place_person(rider, Some(0_usize));
+        let mut caller: Person = get_person(1_usize, caller_floor, if caller_floor == 0_usize { 1_usize } else { 0_usize });
+        caller.wait_time = Ticks(wait_time);
+        building.place_person(caller, None);
+        let mut controller: NearestController = NearestController::from(building);
+        controller.stop_notice = stop_notice;
+        controller.preempt = preempt;
+
+        //Step until the cab reaches its rider's floor, recording the stops on the way
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut inserted: Vec<usize> = Vec::new();
+        let mut stopped_on: Vec<usize> = Vec::new();
+        for i in 0_i32..40_i32 {
+            step_with(&mut controller, i, &mut profiler, |_| Ok(())).unwrap();
+            for event in controller.building.events.iter() {
+                if let SimEvent::StopInserted { elevator, floor, floor_to } = event {
+                    assert_eq!((*elevator, *floor_to), (0_usize, 9_usize), "step {}", i);
+                    inserted.push(*floor);
+                }
+            }
+            let elevator: &Elevator = &controller.building.elevators[0];
+            if elevator.floor_on == 9_usize {
+                return (inserted, stopped_on);
+            }
+            if elevator.stopped && stopped_on.last() != Some(&elevator.floor_on) {
+                stopped_on.push(elevator.floor_on);
+            }
+        }
+        panic!("the cab never reached floor 9");
+    }
+
+    #[test]
+    fn max_wait_resolves_starvation() {
+        //The nearest controller keeps answering the calls beside it, and never reaches the top floor
+        assert_eq!(get_starved_boarding_step(ControllerKind::Nearest, 500_i32), None);
+
+        //Serving the longest wait first reaches the top floor once the elevator is first free
+        let boarding_step: Option<i32> = get_starved_boarding_step(ControllerKind::MaxWait, 500_i32);
+        assert!(boarding_step.is_some_and(|x| x < 30_i32), "boarded at step {:?}", boarding_step);
+    }
+
+    #[test]
+    fn random_controller_keeps_its_floor_until_reached() {
+        //Predict the first floor the controller samples from a copy of its RNG
+        let rng: StdRng = RngStream::Controller(0_usize).get_rng(42_u64);
+        let first_floor_to: usize = Uniform::new(0_usize, 12_usize).sample(&mut rng.clone());
+        let building: Building = Building::from(12_usize, 1_usize, 0_f64, 5_f64, 2.5_f64, 0.5_f64, 42_u64).unwrap();
+        let mut controller: RandomController<StdRng> = RandomController::from(building, rng);
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //Each floor drawn is kept, and approached without turning back, until it is reached
+        let mut floors_reached: Vec<usize> = Vec::new();
+        let mut target: Option<(usize, usize)> = None;
+        for i in 0_i32..500_i32 {
+            let floor_from: usize = controller.building.elevators[0].floor_on;
+            step(&mut controller, i, &mut profiler).unwrap();
+            let floor_on: usize = controller.building.elevators[0].floor_on;
+            match (target, controller.floors_to[0]) {
+                (None, Some(floor_to)) => target = Some((floor_to, floor_from.abs_diff(floor_to))),
+                (Some((floor_to, distance)), Some(x)) => {
+                    assert_eq!(x, floor_to, "target changed before it was reached at step {}", i);
+                    assert!(floor_on.abs_diff(floor_to) <= distance, "turned back at step {}", i);
+                    target = Some((floor_to, floor_on.abs_diff(floor_to)));
+                },
+                (Some((floor_to, _)), None) => {
+                    assert_eq!(floor_on, floor_to, "target dropped before it was reached at step {}", i);
+                    floors_reached.push(floor_to);
+                    target = None;
+                },
+                (None, None) => floors_reached.push(floor_on)
+            }
+        }
+        assert_eq!(floors_reached.first(), Some(&first_floor_to));
+        assert!(floors_reached.len() > 5_usize);
+    }
+
+    #[test]
+    fn nearest_sends_a_cab_with_room() {
+        //A full cab is a floor below a call on its way up, and an empty cab waits three floors below it
+        let mut building: Building = get_empty_building(10_usize, 2_usize);
+        building.set_capacity(Some(1_usize));
+        building.elevators[0].floor_on = 4_usize;
+        building.elevators[1].floor_on = 2_usize;
+        let mut passenger: Person = get_person(0_usize, 4_usize, 9_usize);
+        passenger.state = PersonState::Riding;
+        building.place_person(passenger, Some(0_usize));
+        building.place_person(get_person(1_usize, 5_usize, 0_usize), None);
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //The full cab passes the call, which the empty cab answers
+        for i in 0_i32..20_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            let full_cab: &Elevator = &controller.building.elevators[0];
+            assert!(!(full_cab.stopped && full_cab.floor_on == 5_usize), "the full cab stopped for the call at step {}", i);
+            if controller.building.get_num_people_waiting_on_floor(5_usize) == 0_usize {
+                assert!(controller.building.elevators[1].people.iter().any(|pers| pers.id == 1_usize));
+                return;
+            }
+        }
+        panic!("nobody answered the call");
+    }
+
+    #[test]
+    fn stops_are_inserted_only_ahead_with_notice() {
+        //A caller ahead of the cab who has waited long enough gets a stop inserted on the way
+        assert_eq!(get_inserted_stops(6_usize, 1_u64, STOP_NOTICE, true), (vec![6_usize], vec![6_usize]));
+
+        //A caller behind the cab is passed by, however long they have waited
+        assert_eq!(get_inserted_stops(2_usize, 50_u64, STOP_NOTICE, true), (Vec::new(), Vec::new()));
+
+        //A caller ahead who called too late for the doors to open is passed by, unless the notice is shorter
+        assert_eq!(get_inserted_stops(5_usize, 0_u64, 5_usize, true), (Vec::new(), Vec::new()));
+        assert_eq!(get_inserted_stops(5_usize, 0_u64, STOP_NOTICE, true), (vec![5_usize], vec![5_usize]));
+
+        //Without preemption, even a caller ahead with notice is passed by
+        assert_eq!(get_inserted_stops(6_usize, 10_u64, STOP_NOTICE, false), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn can_insert_stop_needs_a_target_beyond_the_floor() {
+        let mut elevator: Elevator = Elevator::from(ElevatorProfile::Passenger, 5_f64, 2.5_f64, 0.5_f64);
+        elevator.floor_on = 5_usize;
+        elevator.stopped = false;
+        let cases: [(bool, Option<usize>, Option<Ticks>, bool); 7] = [
+            (true, Some(8_usize), Some(Ticks(1_u64)), true),
+            (false, Some(2_usize), Some(Ticks(1_u64)), true),
+            (true, Some(2_usize), Some(Ticks(1_u64)), false),
+            (false, Some(8_usize), Some(Ticks(1_u64)), false),
+            (true, Some(5_usize), Some(Ticks(1_u64)), false),
+            (true, None, Some(Ticks(1_u64)), false),
+            (true, Some(8_usize), Some(Ticks(0_u64)), false)
+        ];
+        for (moving_up, floor_to, call_age, expected) in cases {
+            elevator.moving_up = moving_up;
+            elevator.floor_to = floor_to;
+            assert_eq!(
+                can_insert_stop_for_call(&elevator, call_age, 1_usize), expected,
+                "moving up {}, target {:?}, call age {:?}", moving_up, floor_to, call_age
+            );
+        }
+
+        //A call with no caller never gets a stop
+        elevator.moving_up = true;
+        elevator.floor_to = Some(8_usize);
+        assert!(!can_insert_stop_for_call(&elevator, None, 0_usize));
+    }
+
+    //Run a seeded simulation of the nearest controller without preempting, planning pickups or not, and return
+    //the number of floors its elevators traveled per person delivered
+    fn get_floors_per_delivery(config: &SimulationConfig, plan_pickups: bool, seed: u64) -> f64 {
+        let mut params: ControllerParams = ControllerParams::new();
+        params.set("preempt", 0_f64);
+        params.set("plan_pickups", if plan_pickups { 1_f64 } else { 0_f64 });
+        let mut controller: Box<dyn ElevatorController> = build_controller(config, ControllerKind::Nearest, &params, seed).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut num_floors_traveled: usize = 0_usize;
+        for i in 0_i32..config.time_steps {
+            let floors_from: Vec<usize> = controller.get_building().elevators.iter().map(|x| x.floor_on).collect();
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            num_floors_traveled += controller.get_building().elevators.iter()
+                .zip(floors_from.iter())
+                .map(|(x, floor_from)| x.floor_on.abs_diff(*floor_from))
+                .sum::<usize>();
+        }
+        num_floors_traveled as f64 / controller.get_building().get_num_delivered() as f64
+    }
+
+    #[test]
+    fn planned_pickups_travel_fewer_floors_per_delivery() {
+        //Without stops inserted while moving, a stopped elevator which plans a pickup on the way carries
+        //the caller along rather than fetching them on a trip of its own
+        let cases: [(usize, usize, f64); 4] = [
+            (8_usize, 1_usize, 0.5_f64),
+            (12_usize, 1_usize, 0.4_f64),
+            (16_usize, 2_usize, 0.8_f64),
+            (12_usize, 3_usize, 2_f64)
+        ];
+        for (num_floors, num_elevators, expected_arrivals) in cases {
+            let mut config: SimulationConfig = SimulationConfig::from(num_floors, num_elevators, expected_arrivals);
+            config.time_steps = 500_i32;
+            for seed in 0_u64..3_u64 {
+                let planned: f64 = get_floors_per_delivery(&config, true, seed);
+                let unplanned: f64 = get_floors_per_delivery(&config, false, seed);
+                assert!(
+                    planned < unplanned, "{} floors, {} elevators, seed {}: {} floors per delivery planned, {} unplanned",
+                    num_floors, num_elevators, seed, planned, unplanned
+                );
+            }
+        }
+    }
+}
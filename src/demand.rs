@@ -0,0 +1,187 @@
+//Constant representing the default weight of the latest time step in the demand estimates
+pub const DEMAND_SMOOTHING: f64 = 0.1_f64;
+
+/** DemandEstimate struct schema
+ *
+ * A DemandEstimate has the following properties
+ * - smoothing (f64): The weight of the latest time step in each estimate, from 0 exclusive to 1
+ * - arrivals (Vec<f64>): The estimated number of people arriving on each floor per time step
+ * - hall_calls (Vec<f64>): The estimated number of people starting to wait on each floor per time step
+ * - step_arrivals (Vec<usize>): The number of people who arrived on each floor this time step
+ * - step_hall_calls (Vec<usize>): The number of people who started waiting on each floor this time step
+ *
+ * The estimates are exponentially weighted moving averages of the
+ * counts of each time step, so they follow the current demand as the
+ * traffic varies rather than the rate the building was built with.
+ */
+pub struct DemandEstimate {
+    pub smoothing: f64,
+    pub arrivals: Vec<f64>,
+    pub hall_calls: Vec<f64>,
+    step_arrivals: Vec<usize>,
+    step_hall_calls: Vec<usize>
+}
+
+/** DemandEstimate type implementation
+ *
+ * The following functions are implemented for the DemandEstimate
+ * type, and are callable via
+ *
+ * //Example
+ * let mut my_demand: DemandEstimate = DemandEstimate::from(num_floors, p_in, DEMAND_SMOOTHING);
+ * my_demand.record_arrival(0_usize);
+ * my_demand.update();
+ */
+impl DemandEstimate {
+    /** DemandEstimate constructor function
+     *
+     * Initialize the estimates of a building with the given number of
+     * floors, starting from the given expected arrivals per time step on
+     * the first floor, each of whom calls an elevator.
+     */
+    pub fn from(num_floors: usize, p_in: f64, smoothing: f64) -> DemandEstimate {
+        let mut arrivals: Vec<f64> = vec![0_f64; num_floors];
+        let mut hall_calls: Vec<f64> = vec![0_f64; num_floors];
+        if num_floors > 0_usize {
+            arrivals[0] = p_in;
+            hall_calls[0] = p_in;
+        }
+        DemandEstimate {
+            smoothing,
+            arrivals,
+            hall_calls,
+            step_arrivals: vec![0_usize; num_floors],
+            step_hall_calls: vec![0_usize; num_floors]
+        }
+    }
+
+    /** set_entry_floors function
+     *
+     * Restart the arrival and hall call estimates from the given
+     * expected arrivals per time step, spread over the given entry
+     * floors by their weights.
+     */
+    pub fn set_entry_floors(&mut self, p_in: f64, entry_floors: &[(usize, f64)]) {
+        let total_weight: f64 = entry_floors.iter().map(|(_, weight)| weight).sum();
+        self.arrivals.iter_mut().for_each(|x| *x = 0_f64);
+        self.hall_calls.iter_mut().for_each(|x| *x = 0_f64);
+        for (floor, weight) in entry_floors.iter() {
+            self.arrivals[*floor] = p_in * weight / total_weight;
+            self.hall_calls[*floor] = p_in * weight / total_weight;
+        }
+    }
+
+    /** record_arrival function
+     *
+     * Count a person arriving on the given floor this time step, who
+     * also starts waiting for an elevator there.
+     */
+    pub fn record_arrival(&mut self, floor: usize) {
+        self.step_arrivals[floor] += 1_usize;
+        self.step_hall_calls[floor] += 1_usize;
+    }
+
+    /** record_hall_call function
+     *
+     * Count a person starting to wait for an elevator on the given
+     * floor this time step, such as to leave the building.
+     */
+    pub fn record_hall_call(&mut self, floor: usize) {
+        self.step_hall_calls[floor] += 1_usize;
+    }
+
+    /** update function
+     *
+     * Fold this time step's counts into the estimates, weighing them
+     * by the smoothing factor, then reset the counts.
+     */
+    pub fn update(&mut self) {
+        let smoothing: f64 = self.smoothing;
+        for (estimate, count) in self.arrivals.iter_mut().zip(self.step_arrivals.iter_mut()) {
+            *estimate = (smoothing * *count as f64) + ((1_f64 - smoothing) * *estimate);
+            *count = 0_usize;
+        }
+        for (estimate, count) in self.hall_calls.iter_mut().zip(self.step_hall_calls.iter_mut()) {
+            *estimate = (smoothing * *count as f64) + ((1_f64 - smoothing) * *estimate);
+            *count = 0_usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::controller::{ElevatorController, NearestController};
+    use crate::simulation::step;
+    use crate::profile::Profiler;
+
+    //Get the number of steps after which an estimate is within the tolerance of a step change of the given size
+    fn get_steps_to_converge(smoothing: f64, change: f64, tolerance: f64) -> usize {
+        ((tolerance / change).ln() / (1_f64 - smoothing).ln()).ceil() as usize
+    }
+
+    #[test]
+    fn estimate_converges_after_a_step_change() {
+        //The estimate starts at 0.5 arrivals per step on the lobby, then 2 people arrive each step
+        let mut demand: DemandEstimate = DemandEstimate::from(3_usize, 0.5_f64, DEMAND_SMOOTHING);
+        let tolerance: f64 = 0.1_f64;
+        let num_steps: usize = get_steps_to_converge(DEMAND_SMOOTHING, 1.5_f64, tolerance);
+        assert_eq!(num_steps, 26_usize);
+        for i in 0_usize..num_steps {
+            //The estimate rises toward the new rate, but is not yet within the tolerance
+            assert!((demand.arrivals[0] - 2_f64).abs() > tolerance, "converged early at step {}", i);
+            demand.record_arrival(0_usize);
+            demand.record_arrival(0_usize);
+            let last: f64 = demand.arrivals[0];
+            demand.update();
+            assert!(demand.arrivals[0] > last && demand.arrivals[0] < 2_f64, "step {}", i);
+        }
+
+        //It is within the tolerance after the expected number of steps, and the hall calls follow too
+        assert!((demand.arrivals[0] - 2_f64).abs() <= tolerance, "{}", demand.arrivals[0]);
+        assert!((demand.hall_calls[0] - 2_f64).abs() <= tolerance, "{}", demand.hall_calls[0]);
+        assert_eq!(&demand.arrivals[1_usize..], &[0_f64, 0_f64]);
+    }
+
+    #[test]
+    fn hall_calls_are_estimated_apart_from_arrivals() {
+        //A person waiting to leave calls an elevator without arriving
+        let mut demand: DemandEstimate = DemandEstimate::from(3_usize, 0_f64, 0.5_f64);
+        demand.record_hall_call(2_usize);
+        demand.update();
+        assert_eq!(demand.arrivals, vec![0_f64, 0_f64, 0_f64]);
+        assert_eq!(demand.hall_calls, vec![0_f64, 0_f64, 0.5_f64]);
+
+        //The counts are reset after each update
+        demand.update();
+        assert_eq!(demand.hall_calls, vec![0_f64, 0_f64, 0.25_f64]);
+    }
+
+    #[test]
+    fn building_estimate_follows_a_raised_arrival_rate() {
+        //Run a building at a low arrival rate, then raise it
+        let mut controller: NearestController = NearestController::from(
+            Building::from(6_usize, 2_usize, 0.2_f64, 5_f64, 2.5_f64, 0.5_f64, 1_u64).unwrap()
+        );
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..100_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+        }
+        let before: f64 = controller.get_building().demand.arrivals[0];
+        controller.get_building_mut().set_expected_arrivals(2_f64).unwrap();
+
+        //Averaged over the steps after it has had time to converge, the estimate is near the new rate
+        let num_steps: usize = get_steps_to_converge(DEMAND_SMOOTHING, 1.8_f64, 0.1_f64);
+        let mut total: f64 = 0_f64;
+        for i in 0_i32..(num_steps as i32 + 200_i32) {
+            step(&mut controller, 100_i32 + i, &mut profiler).unwrap();
+            if i >= num_steps as i32 {
+                total += controller.get_building().demand.arrivals[0];
+            }
+        }
+        let after: f64 = total / 200_f64;
+        assert!(before < 0.6_f64, "{}", before);
+        assert!((after - 2_f64).abs() < 0.3_f64, "{}", after);
+    }
+}
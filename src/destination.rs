@@ -0,0 +1,177 @@
+//Import external/standard modules
+use rand::distributions::WeightedIndex;
+use serde::{Serialize, Deserialize};
+
+/** DestinationModel enum
+ *
+ * The way the destination floors of the people arriving are drawn,
+ * tagged by its model in a config.  Nobody is given the floor they
+ * arrive on as their destination.
+ * - Uniform: Every other floor is equally likely
+ * - Weighted: Each floor is drawn in proportion to its weight, one per floor
+ * - Zipf: The floor with index i is drawn in proportion to 1 / (i + 1)^s, so the lowest floors are the most popular
+ */
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum DestinationModel {
+    #[default]
+    Uniform,
+    Weighted { weights: Vec<f64> },
+    Zipf { s: f64 }
+}
+
+/** DestinationModel type implementation
+ *
+ * The following functions are implemented for the DestinationModel
+ * type, and are callable via
+ *
+ * //Example
+ * let my_model: DestinationModel = DestinationModel::Zipf { s: 1.1_f64 };
+ * my_model.validate(num_floors)?;
+ * let my_dist: Option<WeightedIndex<f64>> = my_model.get_dist(num_floors, 0_usize);
+ */
+impl DestinationModel {
+    /** is_uniform function
+     *
+     * Return whether every other floor is equally likely.
+     */
+    pub fn is_uniform(&self) -> bool {
+        *self == DestinationModel::Uniform
+    }
+
+    /** validate function
+     *
+     * Check that the model may draw destinations in a building with the
+     * given number of floors.  Weights must be given for every floor,
+     * each a finite nonnegative number with a finite sum, and at least
+     * two must be positive in a building of several floors, so that
+     * whichever floor someone arrives on, another may be drawn.  The
+     * Zipf exponent must be a finite nonnegative number.
+     */
+    pub fn validate(&self, num_floors: usize) -> Result<(), String> {
+        match self {
+            DestinationModel::Uniform => Ok(()),
+            DestinationModel::Weighted { weights } => {
+                if weights.len() != num_floors {
+                    return Err(format!(
+                        "{} destination weights were given for a building with {} floors", weights.len(), num_floors
+                    ));
+                }
+                if let Some(weight) = weights.iter().find(|x| !(x.is_finite() && **x >= 0_f64)) {
+                    return Err(format!("destination weight {} must be in [0, inf)", weight));
+                }
+                let total_weight: f64 = weights.iter().sum();
+                if !total_weight.is_finite() {
+                    return Err(format!("destination weights sum to {}, which is not finite", total_weight));
+                }
+                if num_floors >= 2_usize && weights.iter().filter(|x| **x > 0_f64).count() < 2_usize {
+                    return Err(String::from("at least two destination weights must be positive"));
+                }
+                Ok(())
+            },
+            DestinationModel::Zipf { s } => {
+                if !(s.is_finite() && *s >= 0_f64) {
+                    return Err(format!("zipf exponent {} must be in [0, inf)", s));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /** get_weights function
+     *
+     * Return the weight of each floor of a building with the given
+     * number of floors, or None if every floor is equally likely.
+     */
+    pub fn get_weights(&self, num_floors: usize) -> Option<Vec<f64>> {
+        match self {
+            DestinationModel::Uniform => None,
+            DestinationModel::Weighted { weights } => Some(weights.clone()),
+            DestinationModel::Zipf { s } => Some(
+                (0_usize..num_floors).map(|i| 1_f64 / ((i + 1_usize) as f64).powf(*s)).collect()
+            )
+        }
+    }
+
+    /** get_dist function
+     *
+     * Return the distribution of the destinations of the people
+     * arriving on the given floor of a building with the given number
+     * of floors, or None if every other floor is equally likely.  The
+     * model must have been validated.
+     */
+    pub fn get_dist(&self, num_floors: usize, floor_on: usize) -> Option<WeightedIndex<f64>> {
+        let mut weights: Vec<f64> = self.get_weights(num_floors)?;
+        if let Some(x) = weights.get_mut(floor_on) {
+            *x = 0_f64;
+        }
+        WeightedIndex::new(weights).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Distribution;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    //Constant representing the number of destinations sampled
+    const NUM_SAMPLES: usize = 50_000_usize;
+
+    //Sample destinations from the model and return the fraction drawn of each floor
+    fn sample_frequencies(model: &DestinationModel, num_floors: usize, floor_on: usize) -> Vec<f64> {
+        let dist: WeightedIndex<f64> = model.get_dist(num_floors, floor_on).unwrap();
+        let mut rng: StdRng = StdRng::seed_from_u64(0_u64);
+        let mut counts: Vec<usize> = vec![0_usize; num_floors];
+        for _ in 0_usize..NUM_SAMPLES {
+            counts[dist.sample(&mut rng)] += 1_usize;
+        }
+        counts.iter().map(|x| *x as f64 / NUM_SAMPLES as f64).collect()
+    }
+
+    #[test]
+    fn weighted_draws_in_proportion() {
+        let model: DestinationModel = DestinationModel::Weighted { weights: vec![1_f64, 2_f64, 3_f64, 4_f64] };
+        model.validate(4_usize).unwrap();
+        let freqs: Vec<f64> = sample_frequencies(&model, 4_usize, 0_usize);
+        let expected: [f64; 4] = [0_f64, 2_f64 / 9_f64, 3_f64 / 9_f64, 4_f64 / 9_f64];
+        assert_eq!(freqs[0], 0_f64);
+        for (freq, p) in freqs.iter().zip(expected.iter()) {
+            assert!((freq - p).abs() < 0.01_f64, "{:?} != {:?}", freqs, expected);
+        }
+    }
+
+    #[test]
+    fn zipf_frequency_falls_with_rank() {
+        let model: DestinationModel = DestinationModel::Zipf { s: 1_f64 };
+        model.validate(10_usize).unwrap();
+        let freqs: Vec<f64> = sample_frequencies(&model, 10_usize, 9_usize);
+        let total_weight: f64 = (1_usize..10_usize).map(|x| 1_f64 / x as f64).sum();
+
+        //Each floor is drawn in proportion to 1 / rank, less often than the floor beneath it
+        for i in 0_usize..9_usize {
+            let p: f64 = 1_f64 / ((i + 1_usize) as f64 * total_weight);
+            assert!((freqs[i] - p).abs() < 0.01_f64, "floor {} drawn {} != {}", i, freqs[i], p);
+            if i > 0_usize {
+                assert!(freqs[i] < freqs[i - 1_usize], "{:?}", freqs);
+            }
+        }
+        assert_eq!(freqs[9], 0_f64);
+    }
+
+    #[test]
+    fn validate_rejects_bad_weights() {
+        let cases: [(Vec<f64>, &str); 4] = [
+            (vec![1_f64, 1_f64], "2 destination weights were given for a building with 3 floors"),
+            (vec![1_f64, -1_f64, 1_f64], "destination weight -1 must be in [0, inf)"),
+            (vec![1_f64, 0_f64, 0_f64], "at least two destination weights must be positive"),
+            (vec![f64::MAX, f64::MAX, 1_f64], "destination weights sum to inf, which is not finite")
+        ];
+        for (weights, message) in cases {
+            let model: DestinationModel = DestinationModel::Weighted { weights };
+            assert_eq!(model.validate(3_usize), Err(String::from(message)));
+        }
+        assert!(DestinationModel::Zipf { s: f64::NAN }.validate(3_usize).is_err());
+    }
+}
@@ -1,6 +1,136 @@
 //Import source modules
 use crate::person::Person;
-use crate::people::People;
+use crate::people::{People, drain_where};
+use crate::energy::{EnergyMeter, OverloadPenalty};
+use crate::units::{Energy, Ticks};
+
+//Import external modules
+use serde::{Serialize, Deserialize};
+
+/** DoorState enum
+ *
+ * The state of an elevator's doors, which are open while people are
+ * exchanged on a floor, and closing on the time step the elevator
+ * leaves a floor it exchanged people on.
+ */
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DoorState {
+    Closed,
+    Open,
+    Closing
+}
+
+//Constants representing how a freight elevator differs from a passenger elevator
+pub const FREIGHT_CAPACITY_MULTIPLIER: usize = 2_usize;
+pub const FREIGHT_TICKS_PER_FLOOR: usize = 2_usize;
+pub const FREIGHT_ENERGY_MULTIPLIER: f64 = 2_f64;
+
+/** ElevatorProfile enum
+ *
+ * The kind of an elevator, which sets its capacity, speed and energy.
+ * - Passenger: An elevator carrying people, moving one floor per time step
+ * - Freight: A slower elevator with a larger capacity and higher energy per floor,
+ *   which only carries people when the building lets it
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElevatorProfile {
+    #[default]
+    Passenger,
+    Freight
+}
+
+/** ElevatorProfile type implementation
+ *
+ * The following functions are implemented for the ElevatorProfile
+ * type, and are callable via
+ *
+ * //Example
+ * let my_ticks: usize = ElevatorProfile::Freight.get_ticks_per_floor();
+ */
+impl ElevatorProfile {
+    /** get_capacity function
+     *
+     * Return the capacity of an elevator of the profile, given the
+     * capacity of a passenger elevator.
+     */
+    pub fn get_capacity(&self, capacity: usize) -> usize {
+        match self {
+            ElevatorProfile::Passenger => capacity,
+            ElevatorProfile::Freight => capacity.saturating_mul(FREIGHT_CAPACITY_MULTIPLIER)
+        }
+    }
+
+    /** get_ticks_per_floor function
+     *
+     * Return the number of time steps an elevator of the profile takes
+     * to move one floor.
+     */
+    pub fn get_ticks_per_floor(&self) -> usize {
+        match self {
+            ElevatorProfile::Passenger => 1_usize,
+            ElevatorProfile::Freight => FREIGHT_TICKS_PER_FLOOR
+        }
+    }
+
+    /** get_energy_multiplier function
+     *
+     * Return the factor the energy an elevator of the profile spends
+     * moving one floor is multiplied by.
+     */
+    pub fn get_energy_multiplier(&self) -> f64 {
+        match self {
+            ElevatorProfile::Passenger => 1_f64,
+            ElevatorProfile::Freight => FREIGHT_ENERGY_MULTIPLIER
+        }
+    }
+}
+
+/** ElevatorHome struct schema
+ *
+ * An ElevatorHome has the following properties
+ * - elevator (usize): The index of the elevator
+ * - floor (usize): The floor the elevator returns to when idle
+ * - after_idle (usize): The number of consecutive time steps the elevator must be told to stop while empty before it returns
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ElevatorHome {
+    pub elevator: usize,
+    pub floor: usize,
+    pub after_idle: usize
+}
+
+/** ElevatorHome type implementation
+ *
+ * The following functions are implemented for the ElevatorHome type,
+ * and are callable via
+ *
+ * //Example
+ * let my_home: ElevatorHome = ElevatorHome::parse("1:6:10")?;
+ */
+impl ElevatorHome {
+    /** parse function
+     *
+     * Parse a home written as elevator:floor:after_idle, e.g. "1:6:10"
+     * for the second elevator returning to floor 6 after 10 idle time
+     * steps.
+     */
+    pub fn parse(home_str: &str) -> Result<ElevatorHome, String> {
+        let fields: Vec<usize> = home_str.split(':')
+            .map(|x| x.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| format!("home '{}' is not of the form elevator:floor:after_idle", home_str))?;
+        match fields[..] {
+            [elevator, floor, after_idle] => Ok(ElevatorHome {
+                elevator,
+                floor,
+                after_idle
+            }),
+            _ => Err(format!("home '{}' is not of the form elevator:floor:after_idle", home_str))
+        }
+    }
+}
 
 /** Elevator struct schema
  *
@@ -8,19 +138,55 @@ use crate::people::People;
  * - floor_on (usize): The floor that the elevator is currently on
  * - moving_up (bool): If true, the elevator is moving up, else it is moving down
  * - stopped (bool): If true, the elevator is stopped, else it is moving
+ * - floor_to (Option<usize>): The floor the elevator is currently headed to, if any
+ * - door_state (DoorState): The state of the elevator's doors
  * - people (Vec<Person>): A vector listing the people on the elevator
+ * - stopped_steps (usize): The number of consecutive time steps the elevator has stayed stopped
+ * - capacity (Option<usize>): The number of passengers the elevator may carry, if limited
+ * - num_delivered (usize): The number of people who got off the elevator at their destination
+ * - total_wait_time (Ticks): The total wait time of the people who got off the elevator
+ * - profile (ElevatorProfile): The kind of elevator, setting its speed and energy
+ * - move_ticks (usize): The number of time steps the elevator has spent moving toward the next floor
+ * - home_floor (Option<usize>): The floor the elevator returns to when idle, if any
+ * - home_after_idle (usize): The number of idle time steps after which the elevator returns to its home floor
+ * - idle_steps (usize): The number of consecutive time steps the elevator has been told to stop while empty
+ * - homing (bool): Whether the elevator is returning to its home floor
+ * - in_service (bool): Whether the elevator is in service, rather than out for maintenance
+ * - draining (bool): Whether the elevator has stopped taking on people ahead of maintenance
+ * - downtime (usize): The number of time steps the elevator has spent out of service
+ * - dwell_steps (usize): The number of further time steps the elevator holds its doors open for people getting on and off
+ * - energy_meter (EnergyMeter): The energy the elevator has spent moving, per time step and in total
  * - energy_up (f64): Base energy spent per floor when empty & moving up
  * - energy_down (f64): Base energy spent per floor when empty & moving down
  * - energy_coef (f64): Multiplier for calculating energy spent while traveling with people
+ * - overload (Option<OverloadPenalty>): Extra energy spent while traveling with more people than a nominal load, if any
  */
 pub struct Elevator {
     pub floor_on: usize,
     pub moving_up: bool,
     pub stopped: bool,
+    pub floor_to: Option<usize>,
+    pub door_state: DoorState,
     pub people: Vec<Person>,
+    pub stopped_steps: usize,
+    pub capacity: Option<usize>,
+    pub num_delivered: usize,
+    pub total_wait_time: Ticks,
+    pub profile: ElevatorProfile,
+    pub move_ticks: usize,
+    pub home_floor: Option<usize>,
+    pub home_after_idle: usize,
+    pub idle_steps: usize,
+    pub homing: bool,
+    pub in_service: bool,
+    pub draining: bool,
+    pub downtime: usize,
+    pub dwell_steps: usize,
+    pub energy_meter: EnergyMeter,
     energy_up: f64,
     energy_down: f64,
-    energy_coef: f64
+    energy_coef: f64,
+    pub overload: Option<OverloadPenalty>
 }
 
 /** Elevator type implementation
@@ -35,53 +201,150 @@ pub struct Elevator {
 impl Elevator {
     /** Elevator constructor function
      *
-     * Initialize an elevator given its energy values, those being
-     * energy spent traveling up and down, as well as the energy
-     * coefficient/multiplier for when people are on the elevator
+     * Initialize an elevator given its profile and its energy values,
+     * those being energy spent traveling up and down, as well as the
+     * energy coefficient/multiplier for when people are on the elevator
      *
-     * The floor_on, moving_up, and stopped attributes are initialized
-     * to 0_i32, true, and true respectively.
+     * The floor_on, moving_up, stopped, floor_to, door_state and
+     * capacity attributes are initialized to 0_i32, true, true, None,
+     * DoorState::Closed and None respectively.
      */
-    pub fn from(energy_up: f64, energy_down: f64, energy_coef: f64) -> Elevator {
+    pub fn from(profile: ElevatorProfile, energy_up: f64, energy_down: f64, energy_coef: f64) -> Elevator {
         Elevator {
             floor_on: 0_usize,
             moving_up: false,
             stopped: true,
+            floor_to: None,
+            door_state: DoorState::Closed,
             people: Vec::new(),
-            energy_up: energy_up,
-            energy_down: energy_down,
-            energy_coef: energy_coef
+            stopped_steps: 0_usize,
+            capacity: None,
+            num_delivered: 0_usize,
+            total_wait_time: Ticks(0_u64),
+            profile,
+            move_ticks: 0_usize,
+            home_floor: None,
+            home_after_idle: 0_usize,
+            idle_steps: 0_usize,
+            homing: false,
+            in_service: true,
+            draining: false,
+            downtime: 0_usize,
+            dwell_steps: 0_usize,
+            energy_meter: EnergyMeter::new(),
+            energy_up,
+            energy_down,
+            energy_coef,
+            overload: None
         }
     }
     
+    /** free_capacity function
+     *
+     * Return the number of people who may still board the elevator,
+     * which is unlimited if the elevator has no capacity.
+     */
+    pub fn free_capacity(&self) -> usize {
+        match self.capacity {
+            Some(x) => x.saturating_sub(self.people.len()),
+            None => usize::MAX
+        }
+    }
+
+    /** get_discharge_steps function
+     *
+     * Return the number of time steps the elevator needs to let off its
+     * passengers and anyone boarding it now, from its position in a
+     * building with the given number of floors.  Whoever boards is taken
+     * to be headed for whichever end of the building takes longest, and
+     * the elevator to sweep to the nearer end of its stops, then the
+     * farther, stopping once at each.
+     */
+    pub fn get_discharge_steps(&self, num_floors: usize) -> usize {
+        let dest_floors: Vec<usize> = self.get_dest_floors();
+        let num_stops: usize = dest_floors.len() + 2_usize;
+        let num_floors_moved: usize = [0_usize, num_floors.saturating_sub(1_usize)].iter().map(|end| {
+            let lowest: usize = dest_floors.iter().copied().fold(self.floor_on.min(*end), usize::min);
+            let highest: usize = dest_floors.iter().copied().fold(self.floor_on.max(*end), usize::max);
+            (highest - lowest) + (self.floor_on - lowest).min(highest - self.floor_on)
+        }).max().unwrap_or(0_usize);
+        (num_floors_moved * self.profile.get_ticks_per_floor()) + num_stops
+    }
+
     /** get_energy_spent function
      *
      * Calculate the energy spent while the elevator is moving.
      * Accept the number of people currently on the elevator.
-     * Return the total energy spent moving one floor.
+     * Return the energy spent moving one floor, including any penalty
+     * for overloading, spread over the time steps the elevator's
+     * profile takes to move it.
      */
-    pub fn get_energy_spent(&mut self) -> f64 {
-        let energy_spent = if self.stopped {
+    pub fn get_energy_spent(&self) -> Energy {
+        let energy_spent: f64 = if self.stopped {
                 0.0_f64
             } else if self.moving_up {
                 self.energy_up + (self.energy_coef * (self.people.len() as f64))
             } else {
                 self.energy_down + (self.energy_coef * (self.people.len() as f64))
             };
-        energy_spent
+        let energy_spent: f64 = match &self.overload {
+            Some(overload) if !self.stopped => energy_spent + overload.get_energy(self.people.len()),
+            _ => energy_spent
+        };
+        Energy(energy_spent * self.profile.get_energy_multiplier() / self.profile.get_ticks_per_floor() as f64)
+    }
+
+    /** energy_this_step function
+     *
+     * Return the energy the elevator spent moving during the current
+     * time step, as metered when its floor was updated.
+     */
+    pub fn energy_this_step(&self) -> Energy {
+        self.energy_meter.get_this_step()
+    }
+
+    /** energy_total function
+     *
+     * Return the energy the elevator has spent moving since its meter
+     * was last reset.
+     */
+    pub fn energy_total(&self) -> Energy {
+        self.energy_meter.get_total()
     }
 
     /** update_floor function
      *
      * Update the floor the elevator is on.
      * Increment or decrement the floor_on usize based on whether
-     * the elevator is stopped and/or moving up.
+     * the elevator is stopped and/or moving up, once it has moved for
+     * as many time steps as its profile takes per floor.  Clear the
+     * elevator's destination floor once it is reached.
      */
     pub fn update_floor(&mut self) -> usize {
-        //If the elevator is stopped, then return early
+        //Meter the energy spent moving during this time step, which is none if stopped
+        self.energy_meter.record(self.get_energy_spent(), self.moving_up);
+
+        //If the elevator is stopped, then count the time step and return early
         if self.stopped {
+            self.stopped_steps += 1_usize;
+            self.move_ticks = 0_usize;
+            return self.floor_on;
+        }
+        self.stopped_steps = 0_usize;
+
+        //If the elevator is leaving a floor with its doors open, then close them
+        self.door_state = if self.door_state == DoorState::Open {
+            DoorState::Closing
+        } else {
+            DoorState::Closed
+        };
+
+        //If the elevator has not yet moved long enough to reach the next floor, then stay on this one
+        self.move_ticks += 1_usize;
+        if self.move_ticks < self.profile.get_ticks_per_floor() {
             return self.floor_on;
         }
+        self.move_ticks = 0_usize;
 
         //If the elevator is moving then update the floor the elevator is on
         self.floor_on = if self.moving_up {
@@ -95,6 +358,11 @@ impl Elevator {
             pers.floor_on = self.floor_on;
         }
 
+        //If the elevator reached its destination floor, then clear it
+        if self.floor_to == Some(self.floor_on) {
+            self.floor_to = None;
+        }
+
         //Return the floor the elevator is on
         self.floor_on
     }
@@ -103,22 +371,18 @@ impl Elevator {
      *
      * Check the elevator for people, if found then find the nearest
      * destination floor to the elevator's current floor among those
-     * people.  Return a tuple with the floor and the distance to it.
+     * people.  Return a tuple with the floor and the distance to it,
+     * or None if the elevator is empty.  Ties go to the first person.
      */
-    pub fn get_nearest_dest_floor(&self) -> (usize, usize) {
+    pub fn get_nearest_dest_floor(&self) -> Option<(usize, usize)> {
         //Get the current floor the elevator is on
         let floor_index: usize = self.floor_on;
 
-        //Get the destination floors from the elevator, if none then return
+        //Get the destination floors from the elevator
         let dest_floors: Vec<usize> = self.get_dest_floors();
-        if dest_floors.len() == 0_usize {
-            return (0_usize, 0_usize);
-        }
 
-        //Initialize variables to track the nearest destination floor
-        //and the min distance between here and a destination floor
-        let mut nearest_dest_floor: usize = 0_usize;
-        let mut min_dest_floor_dist: usize = 0_usize;
+        //Initialize the nearest destination floor and the distance to it, if any found yet
+        let mut nearest_dest_floor: Option<(usize, usize)> = None;
 
         //Calculate the distance between each dest floor and the current floor
         for dest_floor_index in dest_floors.iter() {
@@ -129,50 +393,67 @@ impl Elevator {
             };
 
             //Check whether this is less than the current minimum, or if no
-            //minimum has been assigned yet (in which case it is 0_usize)
-            if min_dest_floor_dist == 0_usize || dest_floor_dist < min_dest_floor_dist {
-                min_dest_floor_dist = dest_floor_dist;
-                nearest_dest_floor = *dest_floor_index;
+            //minimum has been found yet
+            match nearest_dest_floor {
+                Some((_, min_dest_floor_dist)) if min_dest_floor_dist <= dest_floor_dist => {},
+                _ => nearest_dest_floor = Some((*dest_floor_index, dest_floor_dist))
             }
         }
 
         //Return the nearest destination floor
-        (nearest_dest_floor, min_dest_floor_dist)
+        nearest_dest_floor
     }
 
     /** flush_people_leaving_elevator function
      *
      * Remove the people on the elevator whose destination
-     * floor is the current floor and return a vector containing
-     * those people.
+     * floor is the current floor, alight them, and return a vector
+     * containing those people.
      */
     pub fn flush_people_leaving_elevator(&mut self) -> Vec<Person> {
-        //Initialize a vector of people for the people leaving
-        let mut people_leaving: Vec<Person> = Vec::new();
-
-        //If the elevator is not stopped then return the empty vector
+        //If the elevator is not stopped then return an empty vector
         if !self.stopped {
-            return people_leaving;
+            return Vec::new();
         }
 
-        //Loop through the people on the elevator and add to the vec
-        let mut removals = 0_usize;
-        for i in 0..self.people.len() {
-            //If the person is not on their destination floor, then skip
-            if self.people[i-removals].floor_on != self.people[i-removals].floor_to {
-                continue;
-            }
-
-            //If the person is on their destination floor, then remove them from
-            //the elevator and add them to the leaving vec, incrementing the removals
-            let person_leaving: Person = self.people.remove(i - removals);
-            people_leaving.push(person_leaving);
-            removals += 1_usize;
+        //Remove the people who are on their destination floor, and return them once they have alighted
+        let mut people_leaving: Vec<Person> = drain_where(&mut self.people, |pers| !pers.is_travelling());
+        for pers in people_leaving.iter_mut() {
+            pers.alight();
         }
-
-        //Return the vector of people leaving
         people_leaving
     }
+
+    /** hold_doors function
+     *
+     * Hold the elevator at its floor with its doors open for as long as
+     * the slowest of the people getting on and off takes, given their
+     * boarding ticks.  People use the doors together, so the dwell is
+     * the longest of their boarding ticks rather than the sum, and the
+     * time step of the exchange itself counts toward it.  A dwell still
+     * running is never shortened.
+     */
+    pub fn hold_doors(&mut self, boarding_ticks: impl Iterator<Item=usize>) {
+        let dwell_steps: usize = boarding_ticks.max().unwrap_or(0_usize).saturating_sub(1_usize);
+        self.dwell_steps = self.dwell_steps.max(dwell_steps);
+    }
+
+    /** update_door_state function
+     *
+     * Update the state of the elevator's doors given the number of
+     * people who got on and off the elevator this time step.  The doors
+     * are open if anyone got on or off, closing if they were open but
+     * nobody got on or off, and closed otherwise.
+     */
+    pub fn update_door_state(&mut self, num_people_exchanged: usize) {
+        self.door_state = if num_people_exchanged > 0_usize || self.dwell_steps > 0_usize {
+            DoorState::Open
+        } else if self.door_state == DoorState::Open {
+            DoorState::Closing
+        } else {
+            DoorState::Closed
+        };
+    }
 }
 
 //Implement the extend trait for the elevator struct
@@ -218,7 +499,7 @@ impl People for Elevator {
      * Call the people vec implementation of the function and return
      * the result.
      */
-    fn get_aggregate_wait_time(&self) -> usize {
+    fn get_aggregate_wait_time(&self) -> Ticks {
         self.people.get_aggregate_wait_time()
     }
 
@@ -231,6 +512,15 @@ impl People for Elevator {
         self.people.are_people_waiting()
     }
 
+    /** waiting function
+     *
+     * Call the people vec implementation of the function and return
+     * the result.
+     */
+    fn waiting(&self) -> impl Iterator<Item=&Person> {
+        self.people.waiting()
+    }
+
     /** are_people_going_to_floor funciton
      *
      * Call the people vec implementation of the function and return
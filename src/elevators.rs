@@ -2,12 +2,13 @@
 use crate::elevator::Elevator;
 use crate::person::Person;
 use crate::people::People;
+use crate::units::Energy;
 
 //Define elevators trait
 pub trait Elevators {
     fn get_dest_floors(&self) -> Vec<usize>;
 
-    fn get_energy_spent(&mut self) -> f64;
+    fn get_energy_spent(&self) -> Energy;
 
     fn flush_people_leaving_elevators(&mut self) -> Vec<Vec<Person>>;
 
@@ -16,6 +17,19 @@ pub trait Elevators {
     fn increment_wait_times(&mut self);
 }
 
+/** get_elevator_label function
+ *
+ * Return the label of the elevator with the given index, lettered
+ * A through Z, then numbered for any elevators beyond those.
+ */
+pub fn get_elevator_label(elevator_index: usize) -> String {
+    if elevator_index < 26_usize {
+        String::from((b'A' + elevator_index as u8) as char)
+    } else {
+        format!("#{}", elevator_index)
+    }
+}
+
 //Implement elevators trait for Vec<Elevators>
 impl Elevators for Vec<Elevator> {
     /** get_dest_floors function
@@ -47,16 +61,16 @@ impl Elevators for Vec<Elevator> {
 
     /** get_energy_spent function
      *
-     * Calculate the energy spent across all elevators during
-     * a time step.
+     * Aggregate the energy metered across all elevators during
+     * the current time step.
      */
-    fn get_energy_spent(&mut self) -> f64 {
-        //Initialize an f64 to aggregate the total energy spent
-        let mut energy_spent: f64 = 0.0_f64;
+    fn get_energy_spent(&self) -> Energy {
+        //Initialize the total energy spent
+        let mut energy_spent: Energy = Energy(0_f64);
 
-        //Loop through the elevators and calculate their energy spent
-        for elevator in self.iter_mut() {
-            let elevator_energy_spent: f64 = elevator.get_energy_spent();
+        //Loop through the elevators and read their energy spent
+        for elevator in self.iter() {
+            let elevator_energy_spent: Energy = elevator.energy_this_step();
 
             //Add the energy spent to the total
             energy_spent += elevator_energy_spent;
@@ -0,0 +1,306 @@
+//Import source modules
+use crate::units::Energy;
+
+//Import external modules
+use serde::{Serialize, Deserialize};
+
+/** EnergyMeter struct schema
+ *
+ * An EnergyMeter accumulates the energy an elevator spends as it
+ * moves, split by the direction it was moving in.
+ *
+ * An EnergyMeter has the following properties
+ * - this_step (Energy): The energy spent during the current time step
+ * - spent_up (Energy): The energy spent moving up since the meter was last reset
+ * - spent_down (Energy): The energy spent moving down since the meter was last reset
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct EnergyMeter {
+    this_step: Energy,
+    spent_up: Energy,
+    spent_down: Energy
+}
+
+/** EnergyMeter type implementation
+ *
+ * The following functions are implemented for the EnergyMeter type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_meter: EnergyMeter = EnergyMeter::new();
+ * my_meter.record(Energy(2.5_f64), true);
+ * let my_total: Energy = my_meter.get_total();
+ */
+impl EnergyMeter {
+    /** EnergyMeter constructor function
+     *
+     * Initialize a meter which has measured no energy spent.
+     */
+    pub fn new() -> EnergyMeter {
+        EnergyMeter {
+            this_step: Energy(0_f64),
+            spent_up: Energy(0_f64),
+            spent_down: Energy(0_f64)
+        }
+    }
+
+    /** record function
+     *
+     * Record the energy spent during the current time step while
+     * moving in the given direction, replacing any recorded for it
+     * already.
+     */
+    pub fn record(&mut self, energy_spent: Energy, moving_up: bool) {
+        self.this_step = energy_spent;
+        if moving_up {
+            self.spent_up += energy_spent;
+        } else {
+            self.spent_down += energy_spent;
+        }
+    }
+
+    /** end_step function
+     *
+     * End the current time step, so that nothing is spent during the
+     * next one until recorded.
+     */
+    pub fn end_step(&mut self) {
+        self.this_step = Energy(0_f64);
+    }
+
+    /** reset function
+     *
+     * Forget the energy spent so far, such as after a warm-up.
+     */
+    pub fn reset(&mut self) {
+        self.spent_up = Energy(0_f64);
+        self.spent_down = Energy(0_f64);
+    }
+
+    /** get_this_step function
+     *
+     * Return the energy spent during the current time step.
+     */
+    pub fn get_this_step(&self) -> Energy {
+        self.this_step
+    }
+
+    /** get_spent_up function
+     *
+     * Return the energy spent moving up since the meter was last reset.
+     */
+    pub fn get_spent_up(&self) -> Energy {
+        self.spent_up
+    }
+
+    /** get_spent_down function
+     *
+     * Return the energy spent moving down since the meter was last reset.
+     */
+    pub fn get_spent_down(&self) -> Energy {
+        self.spent_down
+    }
+
+    /** get_total function
+     *
+     * Return the energy spent since the meter was last reset.
+     */
+    pub fn get_total(&self) -> Energy {
+        self.spent_up + self.spent_down
+    }
+}
+
+/** get_default_overload_exponent function
+ *
+ * Return the exponent of the overload penalty when a config does not
+ * give one, making the penalty quadratic.
+ */
+fn get_default_overload_exponent() -> f64 {
+    2_f64
+}
+
+/** OverloadPenalty struct schema
+ *
+ * An OverloadPenalty is extra energy an elevator spends per floor when
+ * carrying more people than its nominal load, growing faster than the
+ * load itself so that one packed trip costs more than two lighter ones.
+ *
+ * An OverloadPenalty has the following properties
+ * - nominal (usize): The number of people the elevator carries without penalty
+ * - coef (f64): The energy per floor per overloaded person, raised to the exponent
+ * - exponent (f64): The power the number of people beyond the nominal load is raised to, 2 by default
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OverloadPenalty {
+    pub nominal: usize,
+    pub coef: f64,
+    #[serde(default = "get_default_overload_exponent")]
+    pub exponent: f64
+}
+
+/** OverloadPenalty type implementation
+ *
+ * The following functions are implemented for the OverloadPenalty
+ * type, and are callable via
+ *
+ * //Example
+ * let my_penalty: OverloadPenalty = OverloadPenalty::from(8_usize, 0.25_f64);
+ * let my_energy: f64 = my_penalty.get_energy(10_usize);
+ */
+impl OverloadPenalty {
+    /** OverloadPenalty constructor function
+     *
+     * Initialize a quadratic penalty beyond the given nominal load
+     * with the given coefficient.
+     */
+    pub fn from(nominal: usize, coef: f64) -> OverloadPenalty {
+        OverloadPenalty {
+            nominal,
+            coef,
+            exponent: get_default_overload_exponent()
+        }
+    }
+
+    /** validate function
+     *
+     * Check that the coefficient is a finite nonnegative number and the
+     * exponent a finite number of at least 1.
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.coef.is_finite() && self.coef >= 0_f64) {
+            return Err(format!("overload coefficient {} must be in [0, inf)", self.coef));
+        }
+        if !(self.exponent.is_finite() && self.exponent >= 1_f64) {
+            return Err(format!("overload exponent {} must be in [1, inf)", self.exponent));
+        }
+        Ok(())
+    }
+
+    /** get_energy function
+     *
+     * Return the extra energy spent moving one floor with the given
+     * number of people, coef * max(0, load - nominal)^exponent.
+     */
+    pub fn get_energy(&self, load: usize) -> f64 {
+        self.coef * (load.saturating_sub(self.nominal) as f64).powf(self.exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elevator::{Elevator, ElevatorProfile};
+    use crate::person::Person;
+
+    //Move the elevator for a time step as commanded, ending the step, and return the energy it metered during it
+    fn meter_step(elevator: &mut Elevator, stopped: bool, moving_up: bool, num_riders: usize) -> f64 {
+        elevator.people = (0_usize..num_riders).map(|_| Person::from_dest(0_f64, 0_usize, 0_usize).unwrap()).collect();
+        elevator.stopped = stopped;
+        elevator.moving_up = moving_up;
+        elevator.update_floor();
+        let energy_spent: f64 = elevator.energy_this_step().0;
+        elevator.energy_meter.end_step();
+        assert_eq!(elevator.energy_this_step(), Energy(0_f64));
+        energy_spent
+    }
+
+    #[test]
+    fn meter_splits_the_energy_by_direction() {
+        let mut meter: EnergyMeter = EnergyMeter::new();
+        meter.record(Energy(5_f64), true);
+        assert_eq!(meter.get_this_step(), Energy(5_f64));
+
+        //Recording again within a step replaces the step's energy, but both count toward the totals
+        meter.record(Energy(2.5_f64), false);
+        assert_eq!(meter.get_this_step(), Energy(2.5_f64));
+        meter.end_step();
+        meter.record(Energy(6_f64), true);
+        meter.end_step();
+        assert_eq!((meter.get_spent_up(), meter.get_spent_down(), meter.get_total()), (Energy(11_f64), Energy(2.5_f64), Energy(13.5_f64)));
+
+        //Resetting forgets the totals
+        meter.reset();
+        assert_eq!(meter, EnergyMeter::new());
+    }
+
+    #[test]
+    fn scripted_movement_meters_each_step() {
+        //A passenger elevator spending 5 up, 2.5 down, and 0.5 more per rider per floor
+        let mut elevator: Elevator = Elevator::from(ElevatorProfile::Passenger, 5_f64, 2.5_f64, 0.5_f64);
+        let script: [(bool, bool, usize, f64); 6] = [
+            (true, true, 0_usize, 0_f64),
+            (false, true, 0_usize, 5_f64),
+            (false, true, 2_usize, 6_f64),
+            (true, true, 2_usize, 0_f64),
+            (false, false, 2_usize, 3.5_f64),
+            (false, false, 0_usize, 2.5_f64)
+        ];
+        let mut total: f64 = 0_f64;
+        for (i, (stopped, moving_up, num_riders, expected)) in script.iter().enumerate() {
+            assert_eq!(meter_step(&mut elevator, *stopped, *moving_up, *num_riders), *expected, "step {}", i);
+            total += expected;
+            assert_eq!(elevator.energy_total(), Energy(total), "step {}", i);
+        }
+        assert_eq!(elevator.floor_on, 0_usize);
+        assert_eq!(elevator.energy_meter.get_spent_up(), Energy(11_f64));
+        assert_eq!(elevator.energy_meter.get_spent_down(), Energy(6_f64));
+    }
+
+    #[test]
+    fn freight_and_overload_scale_the_energy() {
+        //A freight elevator spends twice the energy per floor over two time steps
+        let mut freight: Elevator = Elevator::from(ElevatorProfile::Freight, 5_f64, 2.5_f64, 0.5_f64);
+        assert_eq!(meter_step(&mut freight, false, true, 2_usize), 6_f64);
+        assert_eq!(freight.floor_on, 0_usize);
+        assert_eq!(meter_step(&mut freight, false, true, 2_usize), 6_f64);
+        assert_eq!((freight.floor_on, freight.energy_total()), (1_usize, Energy(12_f64)));
+
+        //An overloaded elevator spends the penalty on top of the riders' energy, only while moving
+        let mut elevator: Elevator = Elevator::from(ElevatorProfile::Passenger, 5_f64, 2.5_f64, 0.5_f64);
+        elevator.overload = Some(OverloadPenalty::from(1_usize, 0.25_f64));
+        assert_eq!(meter_step(&mut elevator, false, true, 3_usize), 7.5_f64);
+        assert_eq!(meter_step(&mut elevator, true, true, 3_usize), 0_f64);
+        assert_eq!(meter_step(&mut elevator, false, true, 1_usize), 5.5_f64);
+    }
+
+    #[test]
+    fn overload_penalty_follows_its_formula() {
+        //The penalty is coef * max(0, load - nominal)^exponent
+        let cases: [(usize, f64, f64, usize, f64); 8] = [
+            (8_usize, 0.25_f64, 2_f64, 0_usize, 0_f64),
+            (8_usize, 0.25_f64, 2_f64, 8_usize, 0_f64),
+            (8_usize, 0.25_f64, 2_f64, 9_usize, 0.25_f64),
+            (8_usize, 0.25_f64, 2_f64, 10_usize, 1_f64),
+            (8_usize, 0.25_f64, 2_f64, 12_usize, 4_f64),
+            (8_usize, 0.25_f64, 1_f64, 12_usize, 1_f64),
+            (8_usize, 0.25_f64, 3_f64, 12_usize, 16_f64),
+            (0_usize, 0_f64, 2_f64, 12_usize, 0_f64)
+        ];
+        for (nominal, coef, exponent, load, expected) in cases {
+            let penalty: OverloadPenalty = OverloadPenalty { nominal, coef, exponent };
+            assert_eq!(penalty.get_energy(load), expected, "{:?} with {} people", penalty, load);
+        }
+
+        //The default penalty is quadratic, so one packed trip costs more than two lighter ones
+        let penalty: OverloadPenalty = OverloadPenalty::from(8_usize, 0.25_f64);
+        assert_eq!(penalty.exponent, 2_f64);
+        assert!(penalty.get_energy(12_usize) > 2_f64 * penalty.get_energy(10_usize));
+        assert_eq!(serde_json::from_str::<OverloadPenalty>("{\"nominal\": 8, \"coef\": 0.25}").unwrap(), penalty);
+    }
+
+    #[test]
+    fn overload_penalty_validation() {
+        let cases: [(f64, f64, Result<(), String>); 6] = [
+            (0_f64, 1_f64, Ok(())),
+            (0.25_f64, 2_f64, Ok(())),
+            (-0.25_f64, 2_f64, Err(String::from("overload coefficient -0.25 must be in [0, inf)"))),
+            (f64::INFINITY, 2_f64, Err(String::from("overload coefficient inf must be in [0, inf)"))),
+            (0.25_f64, 0.5_f64, Err(String::from("overload exponent 0.5 must be in [1, inf)"))),
+            (0.25_f64, f64::NAN, Err(String::from("overload exponent NaN must be in [1, inf)")))
+        ];
+        for (coef, exponent, expected) in cases {
+            let penalty: OverloadPenalty = OverloadPenalty { nominal: 4_usize, coef, exponent };
+            assert_eq!(penalty.validate(), expected, "{:?}", penalty);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//Import source modules
+use crate::format::FormatError;
+
+//Import external/standard modules
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/** ElevatorError enum
+ *
+ * An ElevatorError is a failure of the library to set up or run a
+ * simulation, returned in place of panicking so that callers may
+ * report or recover from it.
+ * - Config: A simulation config or its arguments are invalid
+ * - Io: A file, stream or socket could not be read or written
+ * - Format: A file could not be parsed, or its contents are invalid
+ * - Distribution: A probability distribution was given invalid parameters
+ */
+#[derive(Debug)]
+pub enum ElevatorError {
+    Config(String),
+    Io(io::Error),
+    Format(FormatError),
+    Distribution(String)
+}
+
+/** ElevatorError type implementation
+ *
+ * The following functions are implemented for the ElevatorError type,
+ * and are callable via
+ *
+ * //Example
+ * let my_error: ElevatorError = ElevatorError::Config(String::from("the building must have at least one floor"));
+ * let my_exit_code: i32 = my_error.get_exit_code();
+ */
+impl ElevatorError {
+    /** get_exit_code function
+     *
+     * Return the code to exit the process with on the error, 2 for
+     * an invalid config, as for invalid command line arguments, and 1
+     * otherwise.
+     */
+    pub fn get_exit_code(&self) -> i32 {
+        match self {
+            ElevatorError::Config(_) => 2_i32,
+            _ => 1_i32
+        }
+    }
+}
+
+//Implement display for ElevatorError
+impl fmt::Display for ElevatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElevatorError::Config(message) => write!(f, "invalid config: {}", message),
+            ElevatorError::Io(err) => write!(f, "i/o error: {}", err),
+            ElevatorError::Format(err) => write!(f, "invalid file: {}", err),
+            ElevatorError::Distribution(message) => write!(f, "invalid distribution: {}", message)
+        }
+    }
+}
+
+//Implement error for ElevatorError, exposing the error it wraps, if any
+impl Error for ElevatorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ElevatorError::Io(err) => Some(err),
+            ElevatorError::Format(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+//Implement conversion from an I/O error, so that it may be propagated with ?
+impl From<io::Error> for ElevatorError {
+    fn from(err: io::Error) -> ElevatorError {
+        ElevatorError::Io(err)
+    }
+}
+
+//Implement conversion from a format error, so that it may be propagated with ?
+impl From<FormatError> for ElevatorError {
+    fn from(err: FormatError) -> ElevatorError {
+        ElevatorError::Format(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_variant_displays_its_message() {
+        //Each variant is prefixed by what kind of error it is
+        let config_error: ElevatorError = ElevatorError::Config(String::from("the building must have at least one floor"));
+        assert_eq!(config_error.to_string(), "invalid config: the building must have at least one floor");
+        let io_error: ElevatorError = ElevatorError::from(io::Error::new(io::ErrorKind::NotFound, "run.csv is missing"));
+        assert_eq!(io_error.to_string(), "i/o error: run.csv is missing");
+        let format_error: ElevatorError = ElevatorError::from(FormatError::from("run.json", Some(3_usize), "arrivals[2]", "step is negative"));
+        assert_eq!(format_error.to_string(), "invalid file: run.json:3: arrivals[2]: step is negative");
+        let distribution_error: ElevatorError = ElevatorError::Distribution(String::from("the rate must be positive"));
+        assert_eq!(distribution_error.to_string(), "invalid distribution: the rate must be positive");
+    }
+
+    #[test]
+    fn exit_code_and_source_follow_the_variant() {
+        //An invalid config exits as invalid arguments do, and only wrapped errors have a source
+        let config_error: ElevatorError = ElevatorError::Config(String::from("no floors"));
+        assert_eq!(config_error.get_exit_code(), 2_i32);
+        assert!(config_error.source().is_none());
+        let io_error: ElevatorError = ElevatorError::from(io::Error::other("disk full"));
+        assert_eq!(io_error.get_exit_code(), 1_i32);
+        assert_eq!(io_error.source().map(|x| x.to_string()), Some(String::from("disk full")));
+        let format_error: ElevatorError = ElevatorError::from(FormatError::from("", None, "", "not JSON"));
+        assert_eq!(format_error.get_exit_code(), 1_i32);
+        assert!(format_error.source().is_some());
+        assert_eq!(ElevatorError::Distribution(String::from("bad")).get_exit_code(), 1_i32);
+    }
+}
@@ -0,0 +1,260 @@
+//Import source modules
+use crate::building::Building;
+use crate::elevators::get_elevator_label;
+use crate::units::Ticks;
+
+//Import external/standard modules
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/** SimEvent enum
+ *
+ * A notable occurrence during a time step of the simulation, emitted
+ * by the building as people arrive and are exchanged on elevators,
+ * and by the controllers as they insert stops or are overridden, such as
+ * by idle elevators returning home, or by another elevator emptying
+ * the floor an elevator was headed to, as elevators pass floors their
+ * passengers are headed to, and as meetings are called and let out.
+ */
+#[derive(Debug)]
+pub enum SimEvent {
+    PersonArrived { person_id: usize, floor: usize, floor_to: usize, p_out: f64, crowding_tolerance: f64, boarding_ticks: usize },
+    PersonLeaving { person_id: usize, floor: usize },
+    ArrivalRejected { floor: usize, floor_to: usize },
+    PersonBoarded { person_id: usize, elevator: usize, floor: usize, wait_time: Ticks },
+    PersonAlighted { person_id: usize, elevator: usize, floor: usize },
+    DestinationChanged { person_id: usize, elevator: usize, old_floor_to: usize, floor_to: usize },
+    ElevatorStopped { elevator: usize, floor: usize, num_alighted: usize },
+    StopInserted { elevator: usize, floor: usize, floor_to: usize },
+    CallCancelled { elevator: usize, floor: usize, emptied_by: usize },
+    ReversalOverridden { elevator: usize, floor: usize, moving_up: bool },
+    StopMissed { elevator: usize, floor: usize, num_passengers: usize, forced: bool },
+    HomingStarted { elevator: usize, floor: usize, home_floor: usize },
+    MaintenanceNotice { elevator: usize, floor: usize, steps_until_start: usize },
+    MaintenanceStarted { elevator: usize, floor: usize, num_discharged: usize },
+    MaintenanceEnded { elevator: usize, floor: usize },
+    MeetingStarted { floor: usize },
+    MeetingEnded { floor: usize, num_leaving: usize }
+}
+
+/** SimEvent type implementation
+ *
+ * The following functions are implemented for the SimEvent type,
+ * and are callable via
+ *
+ * //Example
+ * let my_event: SimEvent = SimEvent::PersonLeaving { person_id: 42_usize, floor: 3_usize };
+ * let event_str: String = my_event.describe();
+ * let entry_str: String = my_event.get_log_entry();
+ */
+impl SimEvent {
+    /** describe function
+     *
+     * Return a short human-readable description of the event.
+     */
+    pub fn describe(&self) -> String {
+        match self {
+            SimEvent::PersonArrived { person_id, floor, floor_to, .. } => {
+                format!("P#{} arrived at {} headed to {}", person_id, floor, floor_to)
+            },
+            SimEvent::PersonLeaving { person_id, floor } => {
+                format!("P#{} is leaving from {}", person_id, floor)
+            },
+            SimEvent::ArrivalRejected { floor, floor_to } => {
+                format!("someone arriving at {} for {} was turned away, the building being full", floor, floor_to)
+            },
+            SimEvent::PersonBoarded { person_id, elevator, floor, wait_time } => {
+                format!("P#{} boarded {} at {} after {} ticks", person_id, get_elevator_label(*elevator), floor, wait_time)
+            },
+            SimEvent::PersonAlighted { person_id, elevator, floor } => {
+                format!("P#{} alighted {} at {}", person_id, get_elevator_label(*elevator), floor)
+            },
+            SimEvent::DestinationChanged { person_id, elevator, old_floor_to, floor_to } => {
+                format!("P#{} on {} changed their destination from {} to {}", person_id, get_elevator_label(*elevator), old_floor_to, floor_to)
+            },
+            SimEvent::ElevatorStopped { elevator, floor, num_alighted } => {
+                format!("{} reached {}, {} alighted", get_elevator_label(*elevator), floor, num_alighted)
+            },
+            SimEvent::StopInserted { elevator, floor, floor_to } => {
+                format!("{} stopped at {} on its way to {}", get_elevator_label(*elevator), floor, floor_to)
+            },
+            SimEvent::CallCancelled { elevator, floor, emptied_by } => {
+                format!("{} dropped its trip to {}, emptied by {}", get_elevator_label(*elevator), floor, get_elevator_label(*emptied_by))
+            },
+            SimEvent::ReversalOverridden { elevator, floor, moving_up } => {
+                let direction: &str = if *moving_up { "up" } else { "down" };
+                format!("{} was turned {} at {} toward its passengers", get_elevator_label(*elevator), direction, floor)
+            },
+            SimEvent::StopMissed { elevator, floor, num_passengers, forced: false } => {
+                format!("{} passed {} with {} aboard headed there", get_elevator_label(*elevator), floor, num_passengers)
+            },
+            SimEvent::StopMissed { elevator, floor, num_passengers, forced: true } => {
+                format!("{} was made to stop at {} for {} aboard headed there", get_elevator_label(*elevator), floor, num_passengers)
+            },
+            SimEvent::HomingStarted { elevator, floor, home_floor } => {
+                format!("{} idled at {} and is returning home to {}", get_elevator_label(*elevator), floor, home_floor)
+            },
+            SimEvent::MaintenanceNotice { elevator, floor, steps_until_start } => {
+                format!("{} stopped taking on people at {}, {} steps before maintenance", get_elevator_label(*elevator), floor, steps_until_start)
+            },
+            SimEvent::MaintenanceStarted { elevator, floor, num_discharged } => {
+                format!("{} went out of service at {}, letting {} off", get_elevator_label(*elevator), floor, num_discharged)
+            },
+            SimEvent::MaintenanceEnded { elevator, floor } => {
+                format!("{} returned to service at {}", get_elevator_label(*elevator), floor)
+            },
+            SimEvent::MeetingStarted { floor } => format!("a meeting was called on {}", floor),
+            SimEvent::MeetingEnded { floor, num_leaving } => {
+                format!("the meeting on {} let out, {} leaving", floor, num_leaving)
+            }
+        }
+    }
+
+    /** get_log_entry function
+     *
+     * Return the event as its kind in capitals followed by its fields
+     * as key=value pairs, for an event log to be searched and parsed.
+     */
+    pub fn get_log_entry(&self) -> String {
+        match self {
+            SimEvent::PersonArrived { person_id, floor, floor_to, .. } => {
+                format!("PERSON_ARRIVED person={} floor={} dest={}", person_id, floor, floor_to)
+            },
+            SimEvent::PersonLeaving { person_id, floor } => {
+                format!("PERSON_LEAVING person={} floor={}", person_id, floor)
+            },
+            SimEvent::ArrivalRejected { floor, floor_to } => {
+                format!("ARRIVAL_REJECTED floor={} dest={}", floor, floor_to)
+            },
+            SimEvent::PersonBoarded { person_id, elevator, floor, wait_time } => {
+                format!("PERSON_BOARDED person={} elev={} floor={} wait={}", person_id, elevator, floor, wait_time)
+            },
+            SimEvent::PersonAlighted { person_id, elevator, floor } => {
+                format!("PERSON_ALIGHTED person={} elev={} floor={}", person_id, elevator, floor)
+            },
+            SimEvent::DestinationChanged { person_id, elevator, old_floor_to, floor_to } => {
+                format!("DESTINATION_CHANGED person={} elev={} from={} dest={}", person_id, elevator, old_floor_to, floor_to)
+            },
+            SimEvent::ElevatorStopped { elevator, floor, num_alighted } => {
+                format!("ELEVATOR_STOPPED elev={} floor={} alighted={}", elevator, floor, num_alighted)
+            },
+            SimEvent::StopInserted { elevator, floor, floor_to } => {
+                format!("STOP_INSERTED elev={} floor={} dest={}", elevator, floor, floor_to)
+            },
+            SimEvent::CallCancelled { elevator, floor, emptied_by } => {
+                format!("CALL_CANCELLED elev={} floor={} emptied_by={}", elevator, floor, emptied_by)
+            },
+            SimEvent::ReversalOverridden { elevator, floor, moving_up } => {
+                format!("REVERSAL_OVERRIDDEN elev={} floor={} up={}", elevator, floor, moving_up)
+            },
+            SimEvent::StopMissed { elevator, floor, num_passengers, forced } => {
+                format!("STOP_MISSED elev={} floor={} passengers={} forced={}", elevator, floor, num_passengers, forced)
+            },
+            SimEvent::HomingStarted { elevator, floor, home_floor } => {
+                format!("HOMING_STARTED elev={} floor={} home={}", elevator, floor, home_floor)
+            },
+            SimEvent::MaintenanceNotice { elevator, floor, steps_until_start } => {
+                format!("MAINTENANCE_NOTICE elev={} floor={} steps_until_start={}", elevator, floor, steps_until_start)
+            },
+            SimEvent::MaintenanceStarted { elevator, floor, num_discharged } => {
+                format!("MAINTENANCE_STARTED elev={} floor={} discharged={}", elevator, floor, num_discharged)
+            },
+            SimEvent::MaintenanceEnded { elevator, floor } => {
+                format!("MAINTENANCE_ENDED elev={} floor={}", elevator, floor)
+            },
+            SimEvent::MeetingStarted { floor } => format!("MEETING_STARTED floor={}", floor),
+            SimEvent::MeetingEnded { floor, num_leaving } => {
+                format!("MEETING_ENDED floor={} leaving={}", floor, num_leaving)
+            }
+        }
+    }
+}
+
+/** EventLog struct schema
+ *
+ * An EventLog streams every event of a simulation to a writer as it
+ * happens, one line per event prefixed by its time step, such as
+ * t=45 PERSON_BOARDED person=7 elev=1 floor=3 wait=7.  Lines are
+ * buffered by the writer, so the log must be finished to flush them.
+ *
+ * An EventLog has the following properties
+ * - writer (W): The writer the lines of the log are streamed to
+ */
+pub struct EventLog<W: Write> {
+    writer: W
+}
+
+/** EventLog type implementation
+ *
+ * The following functions are implemented for the EventLog type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_log: EventLog<BufWriter<File>> = EventLog::create("events.log")?;
+ * my_log.record_step(&my_building, time_step)?;
+ * my_log.finish()?;
+ */
+impl EventLog<BufWriter<File>> {
+    /** create function
+     *
+     * Create the log file at the given path, replacing any there.
+     */
+    pub fn create(path: &str) -> io::Result<EventLog<BufWriter<File>>> {
+        Ok(EventLog::from(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> EventLog<W> {
+    /** EventLog constructor function
+     *
+     * Initialize an event log streaming to the given writer.
+     */
+    pub fn from(writer: W) -> EventLog<W> {
+        EventLog {
+            writer
+        }
+    }
+
+    /** record_step function
+     *
+     * Write a line for each of the building's events after the given
+     * time step, in the order they happened.
+     */
+    pub fn record_step(&mut self, building: &Building, time_step: i32) -> io::Result<()> {
+        for event in building.events.iter() {
+            writeln!(self.writer, "t={} {}", time_step, event.get_log_entry())?;
+        }
+        Ok(())
+    }
+
+    /** finish function
+     *
+     * Flush the lines buffered by the writer.
+     */
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_describe_themselves() {
+        let cases: [(SimEvent, &str); 6] = [
+            (
+                SimEvent::PersonArrived { person_id: 7_usize, floor: 0_usize, floor_to: 5_usize, p_out: 0.05_f64, crowding_tolerance: 1_f64, boarding_ticks: 1_usize },
+                "P#7 arrived at 0 headed to 5"
+            ),
+            (SimEvent::PersonBoarded { person_id: 7_usize, elevator: 1_usize, floor: 0_usize, wait_time: Ticks(4_u64) }, "P#7 boarded B at 0 after 4 ticks"),
+            (SimEvent::PersonAlighted { person_id: 7_usize, elevator: 27_usize, floor: 5_usize }, "P#7 alighted #27 at 5"),
+            (SimEvent::ElevatorStopped { elevator: 0_usize, floor: 5_usize, num_alighted: 2_usize }, "A reached 5, 2 alighted"),
+            (SimEvent::StopMissed { elevator: 0_usize, floor: 3_usize, num_passengers: 1_usize, forced: true }, "A was made to stop at 3 for 1 aboard headed there"),
+            (SimEvent::MeetingEnded { floor: 4_usize, num_leaving: 12_usize }, "the meeting on 4 let out, 12 leaving")
+        ];
+        for (event, event_str) in cases {
+            assert_eq!(event.describe(), event_str);
+        }
+    }
+}
@@ -2,18 +2,23 @@
 use rand::Rng;
 
 //Import source modules
-use crate::person::Person;
-use crate::people::People;
+use crate::person::{Person, PersonState};
+use crate::people::{People, drain_where};
+use crate::units::Ticks;
 
 /** Floor struct schema
  *
  * A Floor has the following properties
  * - people (Vec<Person>): A vector of people currently on the floor
  * - dest_prob (f64): The probability that this floor is a destination
+ * - num_boarded (usize): The number of people who got on an elevator at this floor
+ * - num_alighted (usize): The number of people who got off an elevator at this floor
  */
 pub struct Floor {
     people: Vec<Person>,
-    pub dest_prob: f64
+    pub dest_prob: f64,
+    pub num_boarded: usize,
+    pub num_alighted: usize
 }
 
 /** Floor type implementation
@@ -30,99 +35,140 @@ impl Floor {
      *
      * Initialize a new empty floor.
      */
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Floor {
         Floor {
             people: Vec::new(),
-            dest_prob: 0_f64
+            dest_prob: 0_f64,
+            num_boarded: 0_usize,
+            num_alighted: 0_usize
         }
     }
 
     /** get_p_out function
      *
      * Calculate the probability that at least one person on this floor
-     * will decide to leave next time step
+     * will decide to leave next time step, which is one minus the
+     * probability that nobody does.  This is linear in the number of
+     * people, since large buildings have thousands on the first floor.
      */
     pub fn get_p_out(&self) -> f64 {
-        //If there is no one on the floor, return 0_f64
-        if self.people.len() == 0 {
-            return 0_f64;
-        }
-
-        //Initialize a p_out variable and a vec for each p_out
-        let mut p_out: f64 = 0_f64;
-        let mut past_p_outs: Vec<f64> = Vec::new();
-
-        //Loop through the people in the floor and iteratively calculate
-        //the p_out value
+        //Multiply the probabilities of each person staying
+        let mut p_stay: f64 = 1_f64;
         for pers in self.people.iter() {
-            //Calculate the product of each of the past people's inverse
-            //p_out values
-            let inverse_p_outs: f64 = {
-                let mut tmp_inverse_p_outs: f64 = 1_f64;
-                for past_p_out in &past_p_outs {
-                    tmp_inverse_p_outs = tmp_inverse_p_outs * (1_f64 - past_p_out);
-                }
-                tmp_inverse_p_outs
-            };
-
-            //Calculate the summand value based on the person's p_out and
-            //the product of each of the past people's p_out values
-            let tmp_p_out: f64 = pers.p_out * inverse_p_outs;
-
-            //Add the newly calculated value onto the p_out value and then
-            //append the current p_out
-            p_out += tmp_p_out;
-            past_p_outs.push(pers.p_out);
+            p_stay *= 1_f64 - pers.p_out;
         }
 
-        //Return the p_out value
-        p_out
+        //Return the probability that not everyone stays
+        1_f64 - p_stay
     }
 
     /** gen_people_leaving function
      *
      * Generate the people on the floor who are leaving using
-     * each person's gen_is_leaving function
+     * each person's gen_is_leaving function.  Return the ids of the
+     * people who decided to leave.
      */
-    pub fn gen_people_leaving(&mut self, rng: &mut impl Rng) {
+    pub fn gen_people_leaving(&mut self, rng: &mut impl Rng) -> Vec<usize> {
         //Loop through the people on the floor and decide if they are leaving
+        let mut leaving_ids: Vec<usize> = Vec::new();
         for pers in self.people.iter_mut() {
-            //Skip people who are waiting for the elevator
-            if pers.floor_on != pers.floor_to {
+            //Skip people who are not idle, such as those waiting for the elevator or exiting
+            if pers.state != PersonState::Idle {
                 continue;
             }
 
-            //Randomly generate whether someone not waiting for the elevator will leave
-            let _is_person_leaving: bool = pers.gen_is_leaving(rng);
+            //Randomly generate whether someone idle will leave
+            if pers.gen_is_leaving(rng) {
+                leaving_ids.push(pers.id);
+            }
         }
+        leaving_ids
+    }
+
+    /** gen_people_leaving_with function
+     *
+     * Generate the people on the floor who are leaving as in
+     * gen_people_leaving, each with the given probability rather than
+     * their own, such as when a meeting lets out.  Return the ids of
+     * the people who decided to leave.
+     */
+    pub fn gen_people_leaving_with(&mut self, rng: &mut impl Rng, p_out: f64) -> Vec<usize> {
+        let mut leaving_ids: Vec<usize> = Vec::new();
+        for pers in self.people.iter_mut() {
+            if pers.state == PersonState::Idle && rng.gen_bool(p_out) {
+                pers.begin_exit();
+                leaving_ids.push(pers.id);
+            }
+        }
+        leaving_ids
+    }
+
+    /** set_people_leaving function
+     *
+     * Mark the people on the floor with the given ids as leaving, as
+     * gen_people_leaving would have if they had decided to leave.
+     * Return the ids of the people who were marked.
+     */
+    pub fn set_people_leaving(&mut self, person_ids: &[usize]) -> Vec<usize> {
+        let mut leaving_ids: Vec<usize> = Vec::new();
+        for pers in self.people.iter_mut() {
+            if pers.state == PersonState::Idle && person_ids.contains(&pers.id) {
+                pers.begin_exit();
+                leaving_ids.push(pers.id);
+            }
+        }
+        leaving_ids
     }
 
     /** flush_people_entering_elevator function
      *
      * Remove the people on the floor who are waiting for the
-     * elevator. Return a vector containing those people.
+     * elevator and board them. Return a vector containing those people.
      */
     pub fn flush_people_entering_elevator(&mut self) -> Vec<Person> {
-        //Initialize a vector of people for the people entering the elevator
-        let mut people_entering_elevator: Vec<Person> = Vec::new();
+        //Remove the people who are waiting, and return them once they have boarded
+        let mut people_entering: Vec<Person> = drain_where(&mut self.people, |pers| pers.is_waiting());
+        for pers in people_entering.iter_mut() {
+            pers.board();
+        }
+        people_entering
+    }
 
-        //Loop through the people on the floor and add to the vec
-        let mut removals = 0_usize;
-        for i in 0..self.people.len() {
-            //If the person is not waiting, then skip
-            if self.people[i-removals].floor_on == self.people[i-removals].floor_to {
-                continue;
+    /** flush_people_entering_elevator_to function
+     *
+     * Remove the people on the floor who are waiting for the elevator
+     * and whose destination floors satisfy the given predicate, such as
+     * being served by the elevator, in the order they are on the floor.
+     * If the elevator has a capacity, then only those who fit and who
+     * tolerate the load once aboard get on, while those who refuse keep
+     * waiting without holding up anyone behind them.  If a share is
+     * given, then at most that many people get on, leaving the rest for
+     * another elevator.  Accept the number of people already on the
+     * elevator, and return a vector containing the people getting on,
+     * once boarded.
+     */
+    pub fn flush_people_entering_elevator_to(&mut self, serves: impl Fn(usize) -> bool, capacity: Option<usize>,
+                                             num_aboard: usize, share: Option<usize>) -> Vec<Person> {
+        let mut num_people: usize = num_aboard;
+        let max_people: usize = share.map_or(usize::MAX, |x| num_aboard + x);
+        let mut people_entering: Vec<Person> = drain_where(&mut self.people, |pers| {
+            if !pers.is_waiting() || !serves(pers.floor_to) || num_people >= max_people {
+                return false;
             }
-
-            //If the person is waiting, then remove them from the elevator
-            //and add them to the leaving vec, incrementing the removals
-            let person_entering_elevator: Person = self.people.remove(i - removals);
-            people_entering_elevator.push(person_entering_elevator);
-            removals += 1_usize;
+            if let Some(x) = capacity {
+                if num_people >= x || !pers.tolerates_load(num_people + 1_usize, x) {
+                    sim_event!(debug, person_id = pers.id, num_aboard = num_people, capacity = x, "refused a crowded elevator");
+                    return false;
+                }
+            }
+            num_people += 1_usize;
+            true
+        });
+        for pers in people_entering.iter_mut() {
+            pers.board();
         }
-
-        //Return the vector of people leaving
-        people_entering_elevator
+        people_entering
     }
 
     /** flush_people_leaving_floor function
@@ -131,15 +177,21 @@ impl Floor {
      * If so then remove them from the floor.
      *
      * This function presumably will only be executed when this is the first
-     * floor.
+     * floor.  Return the number of people who left.
      */
-    pub fn flush_people_leaving_floor(&mut self) {
+    pub fn flush_people_leaving_floor(&mut self) -> usize {
         //Loop through the floor and determine if anyone is leaving
-        self.people.retain_mut(|pers| if pers.is_leaving {
-            false
-        } else {
-            true
-        });
+        let num_people: usize = self.people.len();
+        self.people.retain_mut(|pers| pers.state != PersonState::Exiting);
+        num_people - self.people.len()
+    }
+
+    /** get_people function
+     *
+     * Return the people currently on the floor
+     */
+    pub fn get_people(&self) -> &[Person] {
+        &self.people
     }
 }
 
@@ -186,7 +238,7 @@ impl People for Floor {
      * Call the people vec implementation of the function and return
      * the result.
      */
-    fn get_aggregate_wait_time(&self) -> usize {
+    fn get_aggregate_wait_time(&self) -> Ticks {
         self.people.get_aggregate_wait_time()
     }
 
@@ -208,6 +260,15 @@ impl People for Floor {
         self.people.are_people_waiting()
     }
 
+    /** waiting function
+     *
+     * Call the people vec implementation of the function and return
+     * the result.
+     */
+    fn waiting(&self) -> impl Iterator<Item=&Person> {
+        self.people.waiting()
+    }
+
     /** increment_wait_times funciton
      *
      * Only increment the wait times of people who are waiting
@@ -216,7 +277,7 @@ impl People for Floor {
         //Loop through the people
         for pers in self.people.iter_mut() {
             //If the person is not waiting, then skip
-            if pers.floor_on == pers.floor_to {
+            if !pers.is_waiting() {
                 continue;
             }
 
@@ -233,7 +294,7 @@ impl People for Floor {
         //Loop through the people
         for pers in self.people.iter_mut() {
             //If the person is waiting, then skip
-            if pers.floor_on != pers.floor_to {
+            if pers.is_waiting() {
                 continue;
             }
 
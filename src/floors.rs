@@ -1,6 +1,7 @@
 //Import source modules
 use crate::floor::Floor;
 use crate::people::People;
+use crate::units::Ticks;
 
 //Import external/standard modules
 use rand::Rng;
@@ -9,13 +10,17 @@ use rand::Rng;
 pub trait Floors {
     fn are_people_waiting_on_floor(&self, floor_index: usize) -> bool;
 
-    fn get_nearest_wait_floor(&self, floor_on: usize) -> (usize, usize);
+    fn get_nearest_wait_floor(&self, floor_on: usize) -> Option<(usize, usize)>;
+
+    fn get_longest_waiting_floor(&self, claimed: &[bool]) -> Option<(usize, Ticks)>;
 
     fn get_dest_probabilities(&self) -> Vec<f64>;
 
+    fn get_called_destinations(&self) -> Vec<Vec<usize>>;
+
     fn gen_people_leaving(&mut self, rng: &mut impl Rng);
 
-    fn flush_first_floor(&mut self);
+    fn flush_first_floor(&mut self) -> usize;
 
     fn increment_wait_times(&mut self);
 }
@@ -34,13 +39,13 @@ impl Floors for Vec<Floor> {
     /** get_nearest_wait_floor function
      *
      * For a collection of floors, return a tuple containing the
-     * nearest destination floor and the distance to it.
+     * nearest floor with people waiting and the distance to it, which
+     * is 0 if they are waiting on the given floor.  If nobody is
+     * waiting, then return None.  Ties go to the lower floor.
      */
-    fn get_nearest_wait_floor(&self, floor_on: usize) -> (usize, usize) {
-        //Initialize variables to track the nearest waiting floor and
-        //the min distance between here and that floor
-        let mut nearest_wait_floor: usize = 0_usize;
-        let mut min_wait_floor_dist: usize = 0_usize;
+    fn get_nearest_wait_floor(&self, floor_on: usize) -> Option<(usize, usize)> {
+        //Initialize the nearest waiting floor and the distance to it, if any found yet
+        let mut nearest_wait_floor: Option<(usize, usize)> = None;
 
         //Loop through the floors and find the minimum distance floor
         //with waiting people
@@ -53,23 +58,47 @@ impl Floors for Vec<Floor> {
 
             //Calculate the distance between this floor and the waiting
             //floor
-            let wait_floor_dist: usize = if floor_on > i {
-                floor_on - i
-            } else {
-                i - floor_on
-            };
+            let wait_floor_dist: usize = floor_on.abs_diff(i);
 
             //Check whether this is less than the current minimum, or
-            //if no minimum has been assigned yet (in which case it is
-            //0_usize)
-            if min_wait_floor_dist == 0_usize || wait_floor_dist < min_wait_floor_dist {
-                min_wait_floor_dist = wait_floor_dist;
-                nearest_wait_floor = i;
+            //if no minimum has been found yet
+            match nearest_wait_floor {
+                Some((_, min_wait_floor_dist)) if min_wait_floor_dist <= wait_floor_dist => {},
+                _ => nearest_wait_floor = Some((i, wait_floor_dist))
             }
         }
 
         //Return the nearest waiting floor
-        (nearest_wait_floor, min_wait_floor_dist)
+        nearest_wait_floor
+    }
+
+    /** get_longest_waiting_floor function
+     *
+     * For a collection of floors, return a tuple containing the floor
+     * of the person who has waited longest among the floors not flagged
+     * as claimed, and their wait time.  If nobody is waiting on those
+     * floors, then return None.  Ties go to the lower floor.
+     */
+    fn get_longest_waiting_floor(&self, claimed: &[bool]) -> Option<(usize, Ticks)> {
+        //Initialize the longest waiting floor and its wait time, if any found yet
+        let mut longest_waiting_floor: Option<(usize, Ticks)> = None;
+
+        //Loop through the unclaimed floors and their waiting people
+        for (i, floor) in self.iter().enumerate() {
+            if claimed.get(i).copied().unwrap_or(false) {
+                continue;
+            }
+            for pers in floor.waiting() {
+                //Check whether this is longer than the current maximum
+                match longest_waiting_floor {
+                    Some((_, max_wait_time)) if max_wait_time >= pers.wait_time => {},
+                    _ => longest_waiting_floor = Some((i, pers.wait_time))
+                }
+            }
+        }
+
+        //Return the longest waiting floor
+        longest_waiting_floor
     }
 
     /** get_dest_probabilities function
@@ -90,6 +119,19 @@ impl Floors for Vec<Floor> {
         dest_probabilities
     }
 
+    /** get_called_destinations function
+     *
+     * For a collection of floors, return for each floor the destination
+     * floors of the people waiting on it, one per person, so that a
+     * floor of several people going to the same floor lists it several
+     * times.  Floors with nobody waiting have an empty list.
+     */
+    fn get_called_destinations(&self) -> Vec<Vec<usize>> {
+        self.iter()
+            .map(|floor| floor.waiting().map(|pers| pers.floor_to).collect())
+            .collect()
+    }
+
     /** gen_people_leaving function
      *
      * Given an RNG, generate people leaving based on their leaving
@@ -99,16 +141,17 @@ impl Floors for Vec<Floor> {
         //Loop through the floors of the building
         for floor in self.iter_mut() {
             //Generate the people leaving on that floor
-            floor.gen_people_leaving(&mut rng);
+            let _leaving_ids: Vec<usize> = floor.gen_people_leaving(&mut rng);
         }
     }
 
     /** flush_first_floor function
      *
-     * Clear the first floor of anyone waiting to leave the building.
+     * Clear the first floor of anyone waiting to leave the building,
+     * and return the number of people who left.
      */
-    fn flush_first_floor(&mut self) {
-        self[0].flush_people_leaving_floor();
+    fn flush_first_floor(&mut self) -> usize {
+        self[0].flush_people_leaving_floor()
     }
 
     /** increment_wait_times function
@@ -120,4 +163,62 @@ impl Floors for Vec<Floor> {
             floor.increment_wait_times();
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::get_empty_building;
+    use crate::building::Building;
+    use crate::person::Person;
+
+    //Build the people waiting to go to the given floors from each floor, and someone idle on the last floor
+    fn get_people(calls: &[&[usize]]) -> Vec<Person> {
+        let mut people: Vec<Person> = Vec::new();
+        for (floor_on, floors_to) in calls.iter().enumerate() {
+            people.extend(floors_to.iter().map(|floor_to| Person::from_dest(0_f64, floor_on, *floor_to).unwrap()));
+        }
+        people.push(Person::from_dest(0_f64, calls.len() - 1_usize, calls.len() - 1_usize).unwrap());
+        for (id, pers) in people.iter_mut().enumerate() {
+            pers.id = id;
+        }
+        people
+    }
+
+    //Build floors holding the given people
+    fn get_floors(num_floors: usize, people: Vec<Person>) -> Vec<Floor> {
+        let mut floors: Vec<Floor> = (0_usize..num_floors).map(|_| Floor::new()).collect();
+        for pers in people {
+            let floor_on: usize = pers.floor_on;
+            floors[floor_on].extend(std::iter::once(pers));
+        }
+        floors
+    }
+
+    #[test]
+    fn called_destinations_list_each_waiting_person() {
+        let calls: [&[usize]; 4] = [&[3_usize, 1_usize, 3_usize], &[], &[0_usize], &[]];
+        let expected: Vec<Vec<usize>> = vec![vec![3_usize, 1_usize, 3_usize], Vec::new(), vec![0_usize], Vec::new()];
+        assert_eq!(get_floors(4_usize, get_people(&calls)).get_called_destinations(), expected);
+
+        //A building lists the same destinations as its floors
+        let mut building: Building = get_empty_building(4_usize, 1_usize);
+        for pers in get_people(&calls) {
+            building.place_person(pers, None);
+        }
+        assert_eq!(building.get_called_destinations(), expected);
+    }
+
+    #[test]
+    fn nearest_and_longest_waits_break_ties_low() {
+        let mut people: Vec<Person> = get_people(&[&[2_usize], &[], &[0_usize], &[]]);
+        people[1].wait_time = Ticks(5_u64);
+        let mut floors: Vec<Floor> = get_floors(4_usize, people);
+        assert_eq!(floors.get_nearest_wait_floor(1_usize), Some((0_usize, 1_usize)));
+        assert_eq!(floors.get_nearest_wait_floor(2_usize), Some((2_usize, 0_usize)));
+        assert_eq!(floors.get_nearest_wait_floor(3_usize), Some((2_usize, 1_usize)));
+        floors.increment_wait_times();
+        assert_eq!(floors.get_longest_waiting_floor(&[]), Some((2_usize, Ticks(6_u64))));
+        assert_eq!(floors.get_longest_waiting_floor(&[false, false, true]), Some((0_usize, Ticks(1_u64))));
+        assert_eq!(get_floors(2_usize, Vec::new()).get_nearest_wait_floor(0_usize), None);
+    }
+}
@@ -0,0 +1,504 @@
+//Import source modules
+use crate::building::{Building, P_OUT, validate_entry_floors};
+use crate::person::Person;
+use crate::shuttle::ShuttleAssignment;
+use crate::destination::DestinationModel;
+use crate::arrival::MAX_MULTIPLIER;
+use crate::simulation::SimulationConfig;
+
+//Import external/standard modules
+use std::fmt;
+use std::fs;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+//Constant representing the version of the scenario format written
+pub const SCENARIO_VERSION: u32 = 1_u32;
+
+/** FormatError struct schema
+ *
+ * A FormatError has the following properties
+ * - path (String): The path of the file which failed to load
+ * - line (Option<usize>): The line of the file the error was found on, if known
+ * - context (String): The part of the file the error was found in, if known
+ * - message (String): A description of the error
+ */
+#[derive(Debug)]
+pub struct FormatError {
+    pub path: String,
+    pub line: Option<usize>,
+    pub context: String,
+    pub message: String
+}
+
+/** FormatError type implementation
+ *
+ * The following functions are implemented for the FormatError type,
+ * and are callable via
+ *
+ * //Example
+ * let my_error: FormatError = FormatError::from("run.json", None, "arrivals[2]", "step is negative");
+ */
+impl FormatError {
+    /** FormatError constructor function
+     *
+     * Initialize an error found in the file at the given path
+     */
+    pub fn from(path: &str, line: Option<usize>, context: &str, message: &str) -> FormatError {
+        FormatError {
+            path: String::from(path),
+            line,
+            context: String::from(context),
+            message: String::from(message)
+        }
+    }
+
+    /** from_json function
+     *
+     * Initialize an error from a JSON parse error, keeping its line
+     */
+    fn from_json(path: &str, err: serde_json::Error) -> FormatError {
+        let line: Option<usize> = if err.line() > 0_usize { Some(err.line()) } else { None };
+        FormatError::from(path, line, "", &err.to_string())
+    }
+}
+
+//Implement display for FormatError, as path:line: context: message, leaving out the path if unknown
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}", self.path)?;
+            if let Some(line) = self.line {
+                write!(f, ":{}", line)?;
+            }
+            write!(f, ": ")?;
+        }
+        if !self.context.is_empty() {
+            write!(f, "{}: ", self.context)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+//Implement error for FormatError
+impl std::error::Error for FormatError {}
+
+/** VersionHeader struct schema
+ *
+ * A VersionHeader has the following properties
+ * - version (u32): The version of the file's format
+ *
+ * Only the version is read from a file before the rest of it, so that
+ * a file of another version is reported as such rather than as
+ * whichever field changed between the versions.
+ */
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32
+}
+
+/** read_versioned function
+ *
+ * Read the JSON file at the given path, checking that its version is
+ * the expected version before parsing the rest of it.
+ */
+pub fn read_versioned<T: DeserializeOwned>(path: &str, expected_version: u32) -> Result<T, FormatError> {
+    let file_str: String = fs::read_to_string(path)
+        .map_err(|e| FormatError::from(path, None, "", &e.to_string()))?;
+    let header: VersionHeader = serde_json::from_str(&file_str)
+        .map_err(|e| FormatError::from_json(path, e))?;
+    if header.version != expected_version {
+        return Err(FormatError::from(
+            path, None, "version",
+            &format!("unsupported version {}, expected {}", header.version, expected_version)
+        ));
+    }
+    serde_json::from_str(&file_str).map_err(|e| FormatError::from_json(path, e))
+}
+
+/** write_json function
+ *
+ * Write the given value as JSON to the file at the given path
+ */
+pub fn write_json<T: Serialize>(path: &str, value: &T) -> Result<(), FormatError> {
+    let file_str: String = serde_json::to_string(value)
+        .map_err(|e| FormatError::from(path, None, "", &e.to_string()))?;
+    fs::write(path, file_str).map_err(|e| FormatError::from(path, None, "", &e.to_string()))
+}
+
+/** get_default_p_out function
+ *
+ * Return the probability of leaving of a scripted arrival when none
+ * is given, which is that of a generated arrival.
+ */
+fn get_default_p_out() -> f64 {
+    P_OUT
+}
+
+/** ScenarioArrival struct schema
+ *
+ * A ScenarioArrival has the following properties
+ * - step (i32): The time step the person arrives on the first floor
+ * - floor_to (usize): The destination floor of the person
+ * - p_out (f64): The probability the person leaves each time step, P_OUT by default
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ScenarioArrival {
+    pub step: i32,
+    pub floor_to: usize,
+    #[serde(default = "get_default_p_out")]
+    pub p_out: f64
+}
+
+/** ScenarioAction enum
+ *
+ * A ScenarioAction is a change to the building scheduled by a
+ * scenario, tagged by its type in the file.
+ * - SetArrivals: Set the expected number of generated arrivals per time step
+ * - SetShuttle: Pin an elevator as the lobby shuttle, or release it if null
+ */
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScenarioAction {
+    SetArrivals { expected_arrivals: f64 },
+    SetShuttle { shuttle: Option<ShuttleAssignment> }
+}
+
+/** ScenarioEvent struct schema
+ *
+ * A ScenarioEvent has the following properties
+ * - step (i32): The time step the action happens on
+ * - action (ScenarioAction): The change to the building, flattened into the event
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ScenarioEvent {
+    pub step: i32,
+    #[serde(flatten)]
+    pub action: ScenarioAction
+}
+
+/** ScenarioFile struct schema
+ *
+ * A ScenarioFile has the following properties
+ * - version (u32): The version of the scenario format
+ * - arrivals (Vec<ScenarioArrival>): The scripted arrivals, in order of their time steps
+ * - events (Vec<ScenarioEvent>): The scheduled events, in order of their time steps
+ * - path (String): The path the scenario was loaded from, if any, for describing errors
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ScenarioFile {
+    pub version: u32,
+    #[serde(default)]
+    pub arrivals: Vec<ScenarioArrival>,
+    #[serde(default)]
+    pub events: Vec<ScenarioEvent>,
+    #[serde(skip)]
+    pub path: String
+}
+
+/** ScenarioFile type implementation
+ *
+ * The following functions are implemented for the ScenarioFile type,
+ * and are callable via
+ *
+ * //Example
+ * let my_scenario: ScenarioFile = ScenarioFile::load("rush.json")?;
+ * my_scenario.validate(num_floors, num_elevators)?;
+ * my_scenario.apply(&mut my_building, time_step);
+ */
+impl ScenarioFile {
+    /** ScenarioFile constructor function
+     *
+     * Initialize an empty scenario of the current version
+     */
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> ScenarioFile {
+        ScenarioFile {
+            version: SCENARIO_VERSION,
+            arrivals: Vec::new(),
+            events: Vec::new(),
+            path: String::new()
+        }
+    }
+
+    /** load function
+     *
+     * Read a scenario from the JSON file at the given path.  The
+     * scenario is not validated against a building, see validate.
+     */
+    pub fn load(path: &str) -> Result<ScenarioFile, FormatError> {
+        let mut scenario: ScenarioFile = read_versioned(path, SCENARIO_VERSION)?;
+        scenario.path = String::from(path);
+        Ok(scenario)
+    }
+
+    /** write function
+     *
+     * Write the scenario as JSON to the file at the given path
+     */
+    pub fn write(&self, path: &str) -> Result<(), FormatError> {
+        write_json(path, self)
+    }
+
+    /** validate function
+     *
+     * Check that the scenario can be run in a building with the given
+     * numbers of floors and elevators.  Each arrival's destination must
+     * be a floor of the building and its probability of leaving a
+     * probability, each arrival rate must be positive and stay finite
+     * once scaled by an arrival profile, each shuttle must be an
+     * elevator of the building, and the time steps of both the arrivals
+     * and the events must be nonnegative and nondecreasing.
+     */
+    pub fn validate(&self, num_floors: usize, num_elevators: usize) -> Result<(), FormatError> {
+        let path: &str = &self.path;
+
+        //Validate the arrivals
+        let mut last_step: i32 = 0_i32;
+        for (i, arrival) in self.arrivals.iter().enumerate() {
+            let context: String = format!("arrivals[{}]", i);
+            check_step(path, &context, arrival.step, last_step)?;
+            last_step = arrival.step;
+            if arrival.floor_to >= num_floors {
+                return Err(FormatError::from(path, None, &context, &format!(
+                    "floor_to {} is out of range for a building with {} floors", arrival.floor_to, num_floors
+                )));
+            }
+            if !(0_f64..=1_f64).contains(&arrival.p_out) {
+                return Err(FormatError::from(path, None, &context, &format!(
+                    "p_out {} is not in [0, 1]", arrival.p_out
+                )));
+            }
+        }
+
+        //Validate the events
+        let mut last_step: i32 = 0_i32;
+        for (i, event) in self.events.iter().enumerate() {
+            let context: String = format!("events[{}]", i);
+            check_step(path, &context, event.step, last_step)?;
+            last_step = event.step;
+            match &event.action {
+                ScenarioAction::SetArrivals { expected_arrivals } => {
+                    if !(expected_arrivals.is_finite() && *expected_arrivals > 0_f64) {
+                        return Err(FormatError::from(path, None, &context, &format!(
+                            "expected_arrivals {} is not a positive number", expected_arrivals
+                        )));
+                    }
+                    if !(expected_arrivals * MAX_MULTIPLIER).is_finite() {
+                        return Err(FormatError::from(path, None, &context, &format!(
+                            "expected_arrivals {} overflow when scaled by an arrival profile", expected_arrivals
+                        )));
+                    }
+                },
+                ScenarioAction::SetShuttle { shuttle: Some(shuttle) } => {
+                    if shuttle.elevator >= num_elevators {
+                        return Err(FormatError::from(path, None, &context, &format!(
+                            "shuttle elevator {} is out of range for a building with {} elevators",
+                            shuttle.elevator, num_elevators
+                        )));
+                    }
+                    if shuttle.capacity == 0_usize {
+                        return Err(FormatError::from(path, None, &context, "shuttle capacity must be positive"));
+                    }
+                },
+                ScenarioAction::SetShuttle { shuttle: None } => {}
+            }
+        }
+        Ok(())
+    }
+
+    /** apply function
+     *
+     * Apply the events scheduled for the given time step to the
+     * building, then add the arrivals scripted for it to the first
+     * floor.  The scenario must have been validated.
+     */
+    pub fn apply(&self, building: &mut Building, time_step: i32) {
+        //Apply the events for this time step
+        let events_start: usize = self.events.partition_point(|x| x.step < time_step);
+        for event in self.events[events_start..].iter().take_while(|x| x.step == time_step) {
+            match &event.action {
+                //Validation checked each arrival rate, so none is refused here
+                ScenarioAction::SetArrivals { expected_arrivals } => {
+                    building.set_expected_arrivals(*expected_arrivals).expect("the scenario's arrival rates are validated");
+                },
+                ScenarioAction::SetShuttle { shuttle } => building.set_shuttle(shuttle.clone())
+            }
+        }
+
+        //Add the arrivals for this time step
+        let arrivals_start: usize = self.arrivals.partition_point(|x| x.step < time_step);
+        //Validation checked the probability of leaving of each arrival, so none are dropped here
+        let arrivals: Vec<Person> = self.arrivals[arrivals_start..].iter()
+            .take_while(|x| x.step == time_step)
+            .filter_map(|x| Person::from_dest(x.p_out, 0_usize, x.floor_to).ok())
+            .collect();
+        if !arrivals.is_empty() {
+            building.add_arrivals(arrivals);
+        }
+    }
+}
+
+/** check_step function
+ *
+ * Check that a time step in a file is nonnegative and no earlier
+ * than the time step before it.
+ */
+fn check_step(path: &str, context: &str, step: i32, last_step: i32) -> Result<(), FormatError> {
+    if step < 0_i32 {
+        return Err(FormatError::from(path, None, context, &format!("step {} is negative", step)));
+    }
+    if step < last_step {
+        return Err(FormatError::from(path, None, context, &format!(
+            "step {} comes before the previous step {}, steps must be in order", step, last_step
+        )));
+    }
+    Ok(())
+}
+
+/** FloorWeightsFile struct schema
+ *
+ * A FloorWeightsFile gives each floor of a building its relative
+ * weight as a destination, and optionally as the floor people arrive
+ * on, as a CSV file with a header row of destination or
+ * destination,arrival followed by a row per floor from the lowest.
+ * Blank lines and lines starting with # are ignored.  For example
+ *
+ * destination,arrival
+ * 1,1
+ * 1,0
+ * 4,0
+ *
+ * sends 4 of every 6 people to the top floor, all arriving on the
+ * first floor.
+ *
+ * A FloorWeightsFile has the following properties
+ * - destination (Vec<f64>): The weight of each floor as a destination
+ * - arrival (Option<Vec<f64>>): The weight of each floor as the floor people arrive on, if given
+ * - path (String): The path the weights were loaded from, for describing errors
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct FloorWeightsFile {
+    pub destination: Vec<f64>,
+    pub arrival: Option<Vec<f64>>,
+    pub path: String
+}
+
+/** FloorWeightsFile type implementation
+ *
+ * The following functions are implemented for the FloorWeightsFile
+ * type, and are callable via
+ *
+ * //Example
+ * let my_weights: FloorWeightsFile = FloorWeightsFile::load("floors.csv")?;
+ * my_weights.validate(num_floors)?;
+ * my_weights.apply(&mut my_config);
+ */
+impl FloorWeightsFile {
+    /** load function
+     *
+     * Read the floor weights from the CSV file at the given path.  The
+     * weights are not validated against a building, see validate.
+     */
+    pub fn load(path: &str) -> Result<FloorWeightsFile, FormatError> {
+        let file_str: String = fs::read_to_string(path)
+            .map_err(|e| FormatError::from(path, None, "", &e.to_string()))?;
+        let mut rows = file_str.lines()
+            .enumerate()
+            .map(|(i, x)| (i + 1_usize, x.trim()))
+            .filter(|(_, x)| !x.is_empty() && !x.starts_with('#'));
+
+        //Read which weights are given from the header
+        let has_arrival: bool = match rows.next() {
+            Some((_, "destination")) => false,
+            Some((_, "destination,arrival")) => true,
+            Some((line, header)) => return Err(FormatError::from(path, Some(line), "header", &format!(
+                "expected destination or destination,arrival, found {}", header
+            ))),
+            None => return Err(FormatError::from(path, None, "header", "the file is empty"))
+        };
+
+        //Read the weights of each floor
+        let mut destination: Vec<f64> = Vec::new();
+        let mut arrival: Vec<f64> = Vec::new();
+        for (line, row) in rows {
+            let fields: Vec<&str> = row.split(',').map(|x| x.trim()).collect();
+            let weights: Vec<f64> = fields.iter()
+                .map(|x| x.parse::<f64>().map_err(|e| FormatError::from(path, Some(line), "", &format!("{}: {}", x, e))))
+                .collect::<Result<Vec<f64>, FormatError>>()?;
+            match (has_arrival, weights.as_slice()) {
+                (false, [destination_weight]) => destination.push(*destination_weight),
+                (true, [destination_weight, arrival_weight]) => {
+                    destination.push(*destination_weight);
+                    arrival.push(*arrival_weight);
+                },
+                _ => return Err(FormatError::from(path, Some(line), "", &format!(
+                    "expected {} weights, found {}", if has_arrival { 2_usize } else { 1_usize }, weights.len()
+                )))
+            }
+        }
+        Ok(FloorWeightsFile {
+            destination,
+            arrival: if has_arrival { Some(arrival) } else { None },
+            path: String::from(path)
+        })
+    }
+
+    /** validate function
+     *
+     * Check that the weights can be used in a building with the given
+     * number of floors.  There must be a row for every floor, and the
+     * weights must be valid for the destination model and the entry
+     * floors they are applied as.
+     */
+    pub fn validate(&self, num_floors: usize) -> Result<(), FormatError> {
+        let path: &str = &self.path;
+        if self.destination.len() != num_floors {
+            return Err(FormatError::from(path, None, "", &format!(
+                "{} floors were given weights, expected {}, one per floor of the building", self.destination.len(), num_floors
+            )));
+        }
+        self.get_destination_model().validate(num_floors)
+            .map_err(|e| FormatError::from(path, None, "destination", &e))?;
+        if let Some(arrival) = &self.arrival {
+            let entry_floors: Vec<(usize, f64)> = arrival.iter().copied().enumerate().collect();
+            validate_entry_floors(&entry_floors, num_floors)
+                .map_err(|e| FormatError::from(path, None, "arrival", &e))?;
+        }
+        Ok(())
+    }
+
+    /** get_destination_model function
+     *
+     * Return the destination model drawing floors by their weights.
+     */
+    pub fn get_destination_model(&self) -> DestinationModel {
+        DestinationModel::Weighted { weights: self.destination.clone() }
+    }
+
+    /** get_entry_floors function
+     *
+     * Return the floors people arrive on with their weights, leaving out
+     * those of weight 0, if arrival weights are given.
+     */
+    pub fn get_entry_floors(&self) -> Option<Vec<(usize, f64)>> {
+        self.arrival.as_ref().map(|x| x.iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, weight)| *weight != 0_f64)
+            .collect())
+    }
+
+    /** apply function
+     *
+     * Draw the destinations, and the entry floors if given, of the
+     * people arriving in the configured building by the weights.  The
+     * weights must have been validated.
+     */
+    pub fn apply(&self, config: &mut SimulationConfig) {
+        config.destinations = self.get_destination_model();
+        if let Some(entry_floors) = self.get_entry_floors() {
+            config.entry_floors = entry_floors;
+        }
+    }
+}
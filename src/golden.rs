@@ -0,0 +1,161 @@
+//Import source modules
+use crate::building::{Building, ExchangeOrder};
+use crate::controller::{ElevatorController, ControllerKind, RandomController, NearestController, MaxWaitController};
+use crate::preset::BuildingPreset;
+use crate::simulation::{SimulationConfig, SimulationResult, step};
+use crate::profile::Profiler;
+use crate::results::get_stable_hash;
+use crate::rng::RngStream;
+use crate::zone::ZoneMap;
+use crate::error::ElevatorError;
+
+//Import external/standard modules
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+
+//Constant representing the number of time steps each golden case is simulated for
+const GOLDEN_TIME_STEPS: i32 = 300_i32;
+
+//Constant representing the seed each golden case is simulated with
+const GOLDEN_SEED: u64 = 42_u64;
+
+/** GoldenCase struct schema
+ *
+ * A GoldenCase has the following properties
+ * - preset (BuildingPreset): The building which is simulated
+ * - controller (ControllerKind): The controller the building is simulated with
+ */
+pub struct GoldenCase {
+    pub preset: BuildingPreset,
+    pub controller: ControllerKind
+}
+
+//Constant representing the cases compared against the golden files
+pub const GOLDEN_CASES: [GoldenCase; 7] = [
+    GoldenCase { preset: BuildingPreset::SmallOffice, controller: ControllerKind::Random },
+    GoldenCase { preset: BuildingPreset::SmallOffice, controller: ControllerKind::Nearest },
+    GoldenCase { preset: BuildingPreset::Midrise, controller: ControllerKind::Random },
+    GoldenCase { preset: BuildingPreset::Midrise, controller: ControllerKind::Nearest },
+    GoldenCase { preset: BuildingPreset::Midrise, controller: ControllerKind::OddEven },
+    GoldenCase { preset: BuildingPreset::Midrise, controller: ControllerKind::MaxWait },
+    GoldenCase { preset: BuildingPreset::BankedTower, controller: ControllerKind::Nearest }
+];
+
+/** GoldenRecord struct schema
+ *
+ * A GoldenRecord has the following properties
+ * - summary (SimulationResult): The result at the end of the case
+ * - num_events (usize): The number of events emitted during the case
+ * - event_checksum (String): The hash of every event emitted during the case, in order
+ */
+#[derive(Serialize)]
+pub struct GoldenRecord {
+    pub summary: SimulationResult,
+    pub num_events: usize,
+    pub event_checksum: String
+}
+
+/** GoldenCase type implementation
+ *
+ * The following functions are implemented for the GoldenCase type,
+ * and are callable via
+ *
+ * //Example
+ * let my_name: String = GOLDEN_CASES[0].get_name();
+ * let my_record: GoldenRecord = GOLDEN_CASES[0].run()?;
+ */
+impl GoldenCase {
+    /** get_name function
+     *
+     * Return the name of the case, which names its golden file
+     */
+    pub fn get_name(&self) -> String {
+        format!("{}-{}", self.preset.get_name(), self.controller.get_name())
+    }
+
+    /** run function
+     *
+     * Simulate the case from its fixed seed, and record its result and
+     * the events emitted along the way.  The elevators exchange people
+     * in the fixed order, so that the files only change when the order
+     * they are pinned to does.  If the building cannot be built from the
+     * preset, or a time step fails, then an error is returned.
+     */
+    pub fn run(&self) -> Result<GoldenRecord, ElevatorError> {
+        let mut config: SimulationConfig = self.preset.get_config();
+        config.time_steps = GOLDEN_TIME_STEPS;
+        config.exchange_order = ExchangeOrder::Fixed;
+        let building: Building = config.build(GOLDEN_SEED)?;
+        match self.controller {
+            ControllerKind::Random => run_golden_controller(RandomController::from(
+                building, RngStream::Controller(0_usize).get_rng(GOLDEN_SEED)
+            ), &config, self.controller),
+            ControllerKind::Nearest => run_golden_controller(NearestController::from(building), &config, self.controller),
+            ControllerKind::OddEven => run_golden_controller(NearestController::from_zones(
+                building, ZoneMap::odd_even(config.num_floors, config.num_elevators)
+            ), &config, self.controller),
+            ControllerKind::MaxWait => run_golden_controller(MaxWaitController::from(building), &config, self.controller)
+        }
+    }
+}
+
+/** run_golden_controller function
+ *
+ * Run the configured number of time steps with the given controller,
+ * of the given kind, accumulating the events of every time step for
+ * the checksum, or return the error of any time step which failed.
+ */
+fn run_golden_controller(mut controller: impl ElevatorController, config: &SimulationConfig,
+                         controller_kind: ControllerKind) -> Result<GoldenRecord, ElevatorError> {
+    let mut profiler: Profiler = Profiler::from(false);
+    let mut events_str: String = String::new();
+    let mut num_events: usize = 0_usize;
+    for i in 0..config.time_steps {
+        step(&mut controller, i, &mut profiler)?;
+        for event in controller.get_building().events.iter() {
+            let _ = writeln!(events_str, "{} {:?}", i, event);
+            num_events += 1_usize;
+        }
+    }
+    Ok(GoldenRecord {
+        summary: SimulationResult::from(controller.get_building(), config, controller_kind.get_name(), GOLDEN_SEED),
+        num_events,
+        event_checksum: get_stable_hash(&events_str)
+    })
+}
+
+/** check_golden_files function
+ *
+ * Run every golden case and compare its record against its golden
+ * file in the given directory, or overwrite the golden files with the
+ * records if updating.  Return a description of each case which did
+ * not match, which is empty if every case matched.
+ */
+pub fn check_golden_files(dir: &str, update: bool) -> Result<Vec<String>, String> {
+    if update {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir, e))?;
+    }
+    let mut mismatches: Vec<String> = Vec::new();
+    for case in GOLDEN_CASES.iter() {
+        //Serialize the record, so that it is compared exactly as it is written
+        let path = Path::new(dir).join(format!("{}.json", case.get_name()));
+        let record: GoldenRecord = case.run().map_err(|e| format!("{}: {}", case.get_name(), e))?;
+        let record_json: String = serde_json::to_string_pretty(&record).map_err(|e| e.to_string())? + "\n";
+
+        //Overwrite the golden file, or compare the record against it
+        if update {
+            fs::write(&path, &record_json).map_err(|e| format!("{}: {}", path.display(), e))?;
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(golden_json) if golden_json == record_json => {},
+            Ok(golden_json) => mismatches.push(format!(
+                "{} differs from {}\nexpected:\n{}actual:\n{}", case.get_name(), path.display(), golden_json, record_json
+            )),
+            Err(e) => mismatches.push(format!("{} could not be read from {}: {}", case.get_name(), path.display(), e))
+        }
+    }
+    Ok(mismatches)
+}
@@ -0,0 +1,177 @@
+//Import external/standard modules
+use std::time::{Duration, Instant};
+use crossterm::{terminal, event};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/** Action enum
+ *
+ * An action requested by the user via a key press or by resizing
+ * the terminal while the simulation is running.
+ */
+pub enum Action {
+    PanUp,
+    PanDown,
+    FollowElevator(usize),
+    FollowBusiest,
+    ToggleLegend,
+    ToggleTicker,
+    NextBuilding,
+    Step,
+    ToggleDump,
+    Resize(usize, usize),
+    Quit
+}
+
+/** Input struct schema
+ *
+ * An Input has the following properties
+ * - enabled (bool): If true, the terminal is in raw mode and key presses are read
+ */
+pub struct Input {
+    enabled: bool
+}
+
+/** Input type implementation
+ *
+ * The following functions are implemented for the Input type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_input: Input = Input::new();
+ * let actions: Vec<Action> = my_input.wait(Duration::from_millis(100_u64));
+ */
+impl Input {
+    /** Input constructor function
+     *
+     * Put the terminal into raw mode so that key presses can be read
+     * as they happen.  If the terminal does not support raw mode, as is
+     * the case when not attached to a TTY, then input is disabled.
+     * Input has no default, since a default must not touch the terminal.
+     */
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Input {
+        Input {
+            enabled: terminal::enable_raw_mode().is_ok()
+        }
+    }
+
+    /** wait function
+     *
+     * Wait for the given duration, collecting the actions requested
+     * by any keys pressed or terminal resizes in the meantime, or those
+     * already pending if the duration is zero.  If input is disabled
+     * then simply sleep.
+     */
+    pub fn wait(&mut self, duration: Duration) -> Vec<Action> {
+        //Initialize a vector of actions
+        let mut actions: Vec<Action> = Vec::new();
+
+        //If input is disabled, then sleep and return early
+        if !self.enabled {
+            std::thread::sleep(duration);
+            return actions;
+        }
+
+        //Poll for events until the deadline passes, reading any already pending even without a wait
+        let deadline: Instant = Instant::now() + duration;
+        while let Ok(true) = event::poll(deadline.saturating_duration_since(Instant::now())) {
+            //Translate the key press or resize into an action, if any
+            match event::read() {
+                Ok(Event::Key(key)) => if let Some(action) = get_action(key) {
+                    actions.push(action);
+                },
+                Ok(Event::Resize(cols, rows)) => actions.push(Action::Resize(cols as usize, rows as usize)),
+                _ => {}
+            }
+        }
+
+        //Return the actions
+        actions
+    }
+
+    /** wait_for_key function
+     *
+     * Block until a key requesting an action is pressed, returning the
+     * action, for stepping through a run.  If input is disabled, then
+     * lines are read from stdin instead, an empty line or n stepping,
+     * d toggling the dump and q or the end of input quitting.
+     */
+    pub fn wait_for_key(&mut self) -> Action {
+        //If input is disabled, then read lines until one requests an action
+        if !self.enabled {
+            let mut line: String = String::new();
+            loop {
+                line.clear();
+                match std::io::stdin().read_line(&mut line) {
+                    Ok(0) | Err(_) => return Action::Quit,
+                    Ok(_) => {}
+                }
+                match line.trim() {
+                    "" | "n" => return Action::Step,
+                    "d" => return Action::ToggleDump,
+                    "q" => return Action::Quit,
+                    _ => {}
+                }
+            }
+        }
+
+        //Read events until a key press requests an action
+        loop {
+            match event::read() {
+                Ok(Event::Key(key)) => if let Some(action) = get_action(key) {
+                    return action;
+                },
+                Ok(_) => {},
+                Err(_) => return Action::Quit
+            }
+        }
+    }
+
+    /** is_enabled function
+     *
+     * Return whether key presses are being read.
+     */
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+//Restore the terminal when the input is dropped
+impl Drop for Input {
+    fn drop(&mut self) {
+        if self.enabled {
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+/** get_action function
+ *
+ * Translate a key press into the action it requests, if any.  Since
+ * raw mode disables the interrupt signal, ctrl+c is handled as quit.
+ */
+fn get_action(key: KeyEvent) -> Option<Action> {
+    //Ignore key releases and repeats
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+
+    //Match the key to its action
+    match key.code {
+        KeyCode::Up => Some(Action::PanUp),
+        KeyCode::Down => Some(Action::PanDown),
+        KeyCode::Char('b') => Some(Action::FollowBusiest),
+        KeyCode::Char('h') => Some(Action::ToggleLegend),
+        KeyCode::Char('t') => Some(Action::ToggleTicker),
+        KeyCode::Tab => Some(Action::NextBuilding),
+        KeyCode::Enter | KeyCode::Char('n') => Some(Action::Step),
+        KeyCode::Char('d') => Some(Action::ToggleDump),
+        KeyCode::Char('q') => Some(Action::Quit),
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+        KeyCode::Char(c) => match c.to_digit(10) {
+            Some(d) if d > 0 => Some(Action::FollowElevator(d as usize - 1_usize)),
+            _ => None
+        },
+        _ => None
+    }
+}
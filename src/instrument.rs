@@ -0,0 +1,57 @@
+/** sim_span macro
+ *
+ * Enter a tracing span, bound to the given guard until the guard is
+ * exited with sim_exit or goes out of scope, such as one per time step
+ * or per phase of a time step.  Without the tracing feature, the span
+ * compiles away.
+ *
+ * //Example
+ * sim_span!(arrivals_span, "arrivals");
+ * sim_exit!(arrivals_span);
+ */
+#[cfg(feature = "tracing")]
+macro_rules! sim_span {
+    ($guard:ident, $($arg:tt)*) => {
+        let $guard = tracing::info_span!($($arg)*).entered();
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! sim_span {
+    ($guard:ident, $($arg:tt)*) => {};
+}
+
+/** sim_exit macro
+ *
+ * Exit the span bound to the given guard by sim_span before the end
+ * of its scope
+ */
+#[cfg(feature = "tracing")]
+macro_rules! sim_exit {
+    ($guard:ident) => {
+        drop($guard);
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! sim_exit {
+    ($guard:ident) => {};
+}
+
+/** sim_event macro
+ *
+ * Emit a tracing event of the given level for a notable occurrence,
+ * such as an elevator going out of service.  Without the tracing
+ * feature, the event compiles away, and its fields are not evaluated.
+ *
+ * //Example
+ * sim_event!(info, elevator = 0_usize, "elevator out of service");
+ */
+#[cfg(feature = "tracing")]
+macro_rules! sim_event {
+    ($level:ident, $($arg:tt)*) => {
+        tracing::$level!($($arg)*)
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! sim_event {
+    ($level:ident, $($arg:tt)*) => {};
+}
@@ -0,0 +1,63 @@
+//Import source modules
+use crate::person::PersonState;
+
+//Import external/standard modules
+use std::fmt;
+
+/** InvariantViolation enum
+ *
+ * An InvariantViolation is an inconsistency found in the state of a
+ * building, which indicates a bug in the simulation or a controller.
+ * - ElevatorOutOfBounds: An elevator is not on a floor of the building
+ * - ElevatorTargetOutOfBounds: An elevator is headed to a floor not in the building
+ * - PersonOffFloor: A person on a floor does not think they are on it
+ * - PersonOffElevator: A person on an elevator does not think they are on its floor
+ * - PersonTargetOutOfBounds: A person is headed to a floor not in the building
+ * - PassengerNotAlighted: A passenger stayed on at their destination while the elevator was stopped
+ * - PersonStateMismatch: A person's state does not match where they are and where they are headed
+ * - PopulationMismatch: People appeared or vanished other than by arriving and leaving
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub enum InvariantViolation {
+    ElevatorOutOfBounds { elevator: usize, floor_on: usize, num_floors: usize },
+    ElevatorTargetOutOfBounds { elevator: usize, floor_to: usize, num_floors: usize },
+    PersonOffFloor { person_id: usize, floor: usize, floor_on: usize },
+    PersonOffElevator { person_id: usize, elevator: usize, elevator_floor: usize, floor_on: usize },
+    PersonTargetOutOfBounds { person_id: usize, floor_to: usize, num_floors: usize },
+    PassengerNotAlighted { person_id: usize, elevator: usize, floor: usize },
+    PersonStateMismatch { person_id: usize, state: PersonState, on_elevator: bool, floor_on: usize, floor_to: usize },
+    PopulationMismatch { expected: usize, found: usize }
+}
+
+//Implement display for InvariantViolation
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvariantViolation::ElevatorOutOfBounds { elevator, floor_on, num_floors } => write!(
+                f, "elevator {} is on floor {}, outside a building with {} floors", elevator, floor_on, num_floors
+            ),
+            InvariantViolation::ElevatorTargetOutOfBounds { elevator, floor_to, num_floors } => write!(
+                f, "elevator {} is headed to floor {}, outside a building with {} floors", elevator, floor_to, num_floors
+            ),
+            InvariantViolation::PersonOffFloor { person_id, floor, floor_on } => write!(
+                f, "P#{} is on floor {} but has floor_on {}", person_id, floor, floor_on
+            ),
+            InvariantViolation::PersonOffElevator { person_id, elevator, elevator_floor, floor_on } => write!(
+                f, "P#{} is on elevator {} at floor {} but has floor_on {}", person_id, elevator, elevator_floor, floor_on
+            ),
+            InvariantViolation::PersonTargetOutOfBounds { person_id, floor_to, num_floors } => write!(
+                f, "P#{} is headed to floor {}, outside a building with {} floors", person_id, floor_to, num_floors
+            ),
+            InvariantViolation::PassengerNotAlighted { person_id, elevator, floor } => write!(
+                f, "P#{} stayed on elevator {} at their destination floor {} while it was stopped", person_id, elevator, floor
+            ),
+            InvariantViolation::PersonStateMismatch { person_id, state, on_elevator, floor_on, floor_to } => write!(
+                f, "P#{} is {:?} on {} at floor {} headed to floor {}", person_id, state,
+                if *on_elevator { "an elevator" } else { "a floor" }, floor_on, floor_to
+            ),
+            InvariantViolation::PopulationMismatch { expected, found } => write!(
+                f, "{} people are in the building, but {} arrived and have not left", found, expected
+            )
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//Import source modules
+use crate::building::Building;
+use crate::event::SimEvent;
+
+//Import external/standard modules
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+//Constant representing the header row of a journey log
+pub const JOURNEY_HEADER: &str = "person_id,trip,origin,destination,request_step,board_step,alight_step,wait_ticks,ride_ticks,elevator,abandoned";
+
+/** JourneyTrip struct schema
+ *
+ * A JourneyTrip has the following properties
+ * - trip (usize): The number of the trip among the person's trips, starting from 1
+ * - origin (usize): The floor the person requested the trip from
+ * - request_step (i32): The time step the person started waiting for an elevator
+ * - board_step (Option<i32>): The time step the person first got on an elevator, if they have
+ * - elevator (usize): The elevator the person last got on
+ */
+struct JourneyTrip {
+    trip: usize,
+    origin: usize,
+    request_step: i32,
+    board_step: Option<i32>,
+    elevator: usize
+}
+
+/** JourneyLog struct schema
+ *
+ * A JourneyLog has the following properties
+ * - writer (W): The writer the rows of the log are streamed to
+ * - trips (HashMap<usize, JourneyTrip>): The trip each person in the building is on or last completed, by id
+ *
+ * A JourneyLog writes a CSV row for each trip completed during a
+ * simulation, as soon as it is completed, so that its memory only
+ * grows with the people in the building.  A person's first trip is
+ * from the lobby on arrival, and each decision to leave starts
+ * another.  A trip ends when the person gets off at their destination.
+ */
+pub struct JourneyLog<W: Write> {
+    writer: W,
+    trips: HashMap<usize, JourneyTrip>
+}
+
+/** JourneyLog type implementation
+ *
+ * The following functions are implemented for the JourneyLog type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_log: JourneyLog<BufWriter<File>> = JourneyLog::create("journeys.csv")?;
+ * my_log.record_step(&my_building, time_step)?;
+ * my_log.finish()?;
+ */
+impl JourneyLog<BufWriter<File>> {
+    /** create function
+     *
+     * Create the CSV file at the given path, replacing any there, and
+     * write its header.
+     */
+    pub fn create(path: &str) -> io::Result<JourneyLog<BufWriter<File>>> {
+        JourneyLog::from(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> JourneyLog<W> {
+    /** JourneyLog constructor function
+     *
+     * Initialize a journey log streaming to the given writer, and write
+     * its header.
+     */
+    pub fn from(mut writer: W) -> io::Result<JourneyLog<W>> {
+        writeln!(writer, "{}", JOURNEY_HEADER)?;
+        Ok(JourneyLog {
+            writer,
+            trips: HashMap::new()
+        })
+    }
+
+    /** record_step function
+     *
+     * Follow the trips of the people in the building from its events
+     * after the given time step, writing a row for each trip completed.
+     * Nobody abandons a trip in this model, so the abandoned column is
+     * always false.
+     */
+    pub fn record_step(&mut self, building: &Building, time_step: i32) -> io::Result<()> {
+        for event in building.events.iter() {
+            match event {
+                //Arriving at the lobby for another floor starts a person's first trip
+                SimEvent::PersonArrived { person_id, floor, floor_to, .. } if floor_to != floor => {
+                    self.trips.insert(*person_id, JourneyTrip {
+                        trip: 1_usize,
+                        origin: *floor,
+                        request_step: time_step,
+                        board_step: None,
+                        elevator: 0_usize
+                    });
+                },
+                //Deciding to leave from a floor above the lobby starts another trip
+                SimEvent::PersonLeaving { person_id, floor } if *floor > 0_usize => {
+                    let trip: usize = self.trips.get(person_id).map_or(1_usize, |x| x.trip + 1_usize);
+                    self.trips.insert(*person_id, JourneyTrip {
+                        trip,
+                        origin: *floor,
+                        request_step: time_step,
+                        board_step: None,
+                        elevator: 0_usize
+                    });
+                },
+                SimEvent::PersonBoarded { person_id, elevator, .. } => {
+                    if let Some(x) = self.trips.get_mut(person_id) {
+                        x.board_step.get_or_insert(time_step);
+                        x.elevator = *elevator;
+                    }
+                },
+                //Getting off at the destination completes the trip, and nobody travels on from the lobby
+                SimEvent::PersonAlighted { person_id, floor, .. } => {
+                    if let Some(x) = self.trips.get(person_id) {
+                        let board_step: i32 = x.board_step.unwrap_or(time_step);
+                        writeln!(
+                            self.writer, "{},{},{},{},{},{},{},{},{},{},false",
+                            person_id, x.trip, x.origin, floor, x.request_step, board_step, time_step,
+                            board_step - x.request_step, time_step - board_step, x.elevator
+                        )?;
+                    }
+                    if *floor == 0_usize {
+                        self.trips.remove(person_id);
+                    }
+                },
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /** finish function
+     *
+     * Flush the remainder of the log to its writer
+     */
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::get_empty_building;
+    use crate::controller::{ElevatorController, NearestController};
+    use crate::format::{ScenarioArrival, ScenarioFile};
+    use crate::simulation::step_scenario;
+    use crate::profile::Profiler;
+
+    #[test]
+    fn two_trips_by_one_person_write_two_rows() {
+        //A single scripted person rides up to floor 2, then leaves as soon as they get there
+        let mut scenario: ScenarioFile = ScenarioFile::new();
+        scenario.arrivals.push(ScenarioArrival { step: 0_i32, floor_to: 2_usize, p_out: 1_f64 });
+        scenario.validate(4_usize, 1_usize).unwrap();
+        let building: Building = get_empty_building(4_usize, 1_usize);
+        let mut controller: NearestController = NearestController::from(building);
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //Log the journeys until the person is back in the lobby
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut log: JourneyLog<&mut Vec<u8>> = JourneyLog::from(&mut buffer).unwrap();
+        for i in 0_i32..100_i32 {
+            step_scenario(&mut controller, i, &mut profiler, Some(&scenario)).unwrap();
+            log.record_step(controller.get_building(), i).unwrap();
+        }
+        log.finish().unwrap();
+        drop(log);
+
+        //Both trips are logged in order, each consistent with its own steps
+        let text: String = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3_usize, "{}", text);
+        assert_eq!(lines[0], JOURNEY_HEADER);
+        let rows: Vec<Vec<&str>> = lines[1..].iter().map(|x| x.split(',').collect()).collect();
+        for (i, (row, (origin, destination))) in rows.iter().zip([(0_usize, 2_usize), (2_usize, 0_usize)]).enumerate() {
+            let fields: Vec<i32> = row[..10].iter().map(|x| x.parse().unwrap()).collect();
+            assert_eq!(fields[0], 0_i32, "person id of row {}", i);
+            assert_eq!(fields[1], i as i32 + 1_i32, "trip of row {}", i);
+            assert_eq!((fields[2], fields[3]), (origin as i32, destination as i32), "floors of row {}", i);
+            assert!(fields[4] <= fields[5] && fields[5] < fields[6], "steps of row {}", i);
+            assert_eq!(fields[7], fields[5] - fields[4], "wait ticks of row {}", i);
+            assert_eq!(fields[8], fields[6] - fields[5], "ride ticks of row {}", i);
+            assert_eq!(fields[9], 0_i32, "elevator of row {}", i);
+            assert_eq!(row[10], "false", "abandoned of row {}", i);
+        }
+
+        //The second trip is requested no earlier than the first ends
+        assert!(rows[1][4].parse::<i32>().unwrap() >= rows[0][6].parse::<i32>().unwrap());
+    }
+}
@@ -0,0 +1,357 @@
+//Import source modules
+use crate::building::{Building, P_OUT};
+use crate::format::{FormatError, read_versioned, write_json};
+use crate::person::{Person, PersonState};
+use crate::simulation::SimulationConfig;
+use crate::units::Ticks;
+
+//Import external/standard modules
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+//Constant representing the version of the layout format written
+pub const LAYOUT_VERSION: u32 = 1_u32;
+
+/** get_default_p_out function
+ *
+ * Return the probability of leaving of a person in a layout when none
+ * is given, which is that of a generated arrival.
+ */
+fn get_default_p_out() -> f64 {
+    P_OUT
+}
+
+/** get_default_crowding_tolerance function
+ *
+ * Return the crowding tolerance of a person in a layout when none is
+ * given, riding however full an elevator gets.
+ */
+fn get_default_crowding_tolerance() -> f64 {
+    1_f64
+}
+
+/** get_default_boarding_ticks function
+ *
+ * Return the boarding ticks of a person in a layout when none are
+ * given, as for most people.
+ */
+fn get_default_boarding_ticks() -> usize {
+    1_usize
+}
+
+/** LayoutPerson struct schema
+ *
+ * A LayoutPerson has the following properties
+ * - id (usize): The id of the person, unique in the layout
+ * - floor_on (usize): The floor the person is on, which must be that of the floor or elevator listing them
+ * - floor_to (usize): The floor the person is headed to
+ * - is_leaving (bool): Whether the person is on their way out of the building, false by default
+ * - wait_time (Ticks): The number of time steps of the person's current trip so far, 0 by default
+ * - ride_time (Ticks): The number of those time steps spent on an elevator, 0 by default
+ * - p_out (f64): The probability the person leaves each time step, P_OUT by default
+ * - crowding_tolerance (f64): The fraction of an elevator's capacity the person rides at, 1 by default
+ * - boarding_ticks (usize): The number of time steps the person takes to get on or off, 1 by default
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LayoutPerson {
+    pub id: usize,
+    pub floor_on: usize,
+    pub floor_to: usize,
+    #[serde(default)]
+    pub is_leaving: bool,
+    #[serde(default)]
+    pub wait_time: Ticks,
+    #[serde(default)]
+    pub ride_time: Ticks,
+    #[serde(default = "get_default_p_out")]
+    pub p_out: f64,
+    #[serde(default = "get_default_crowding_tolerance")]
+    pub crowding_tolerance: f64,
+    #[serde(default = "get_default_boarding_ticks")]
+    pub boarding_ticks: usize
+}
+
+/** LayoutFloor struct schema
+ *
+ * A LayoutFloor has the following properties
+ * - people (Vec<LayoutPerson>): The people on the floor, none by default
+ */
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct LayoutFloor {
+    #[serde(default)]
+    pub people: Vec<LayoutPerson>
+}
+
+/** LayoutElevator struct schema
+ *
+ * A LayoutElevator has the following properties
+ * - floor_on (usize): The floor the elevator is on
+ * - floor_to (Option<usize>): The floor the elevator is headed to, stopped if none, which is the default
+ * - moving_up (bool): Whether the elevator is moving up, false by default
+ * - passengers (Vec<LayoutPerson>): The people on the elevator, none by default
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LayoutElevator {
+    pub floor_on: usize,
+    #[serde(default)]
+    pub floor_to: Option<usize>,
+    #[serde(default)]
+    pub moving_up: bool,
+    #[serde(default)]
+    pub passengers: Vec<LayoutPerson>
+}
+
+/** LayoutFile struct schema
+ *
+ * A LayoutFile has the following properties
+ * - version (u32): The version of the layout format
+ * - config (SimulationConfig): The parameters of the building
+ * - seed (Option<u64>): The seed the building's RNG streams are derived from, if fixed
+ * - floors (Vec<LayoutFloor>): The people on each floor, bottom floor first, any floors left out being empty
+ * - elevators (Vec<LayoutElevator>): The position and passengers of each elevator, any left out being stopped and empty on the first floor
+ * - path (String): The path the layout was loaded from, if any, for describing errors
+ *
+ * A LayoutFile describes the exact state a building starts a run in,
+ * for fixtures and demos, beyond the parameters of its config.
+ */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LayoutFile {
+    pub version: u32,
+    pub config: SimulationConfig,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub floors: Vec<LayoutFloor>,
+    #[serde(default)]
+    pub elevators: Vec<LayoutElevator>,
+    #[serde(skip)]
+    pub path: String
+}
+
+/** LayoutFile type implementation
+ *
+ * The following functions are implemented for the LayoutFile type,
+ * and are callable via
+ *
+ * //Example
+ * let my_layout: LayoutFile = LayoutFile::load("lobby_rush.json")?;
+ * my_layout.validate()?;
+ * my_layout.populate(&mut my_building)?;
+ */
+impl LayoutFile {
+    /** LayoutFile constructor function
+     *
+     * Initialize an empty layout of the current version for a building
+     * with the given config
+     */
+    pub fn from(config: &SimulationConfig) -> LayoutFile {
+        LayoutFile {
+            version: LAYOUT_VERSION,
+            config: config.clone(),
+            seed: None,
+            floors: Vec::new(),
+            elevators: Vec::new(),
+            path: String::new()
+        }
+    }
+
+    /** load function
+     *
+     * Read a layout from the JSON file at the given path.  The layout
+     * is not validated, see validate.
+     */
+    pub fn load(path: &str) -> Result<LayoutFile, FormatError> {
+        let mut layout: LayoutFile = read_versioned(path, LAYOUT_VERSION)?;
+        layout.path = String::from(path);
+        Ok(layout)
+    }
+
+    /** write function
+     *
+     * Write the layout as JSON to the file at the given path
+     */
+    pub fn write(&self, path: &str) -> Result<(), FormatError> {
+        write_json(path, self)
+    }
+
+    /** validate function
+     *
+     * Check that the layout is consistent with itself and its config.
+     * The config must be valid, and the floors and elevators listed no
+     * more than the building has.  Everyone must be on the floor of the
+     * floor or elevator listing them, headed to a floor of the building,
+     * and have an id no one else has, with a ride time no longer than
+     * their wait time.  Anyone leaving must be headed to the first
+     * floor.  Each elevator must be on and headed to floors of the
+     * building.
+     */
+    pub fn validate(&self) -> Result<(), FormatError> {
+        let path: &str = &self.path;
+        let num_floors: usize = self.config.num_floors;
+        let num_elevators: usize = self.config.num_elevators;
+        self.config.validate().map_err(|e| FormatError::from(path, None, "config", &e))?;
+
+        //Validate the numbers of floors and elevators listed
+        if self.floors.len() > num_floors {
+            return Err(FormatError::from(path, None, "floors", &format!(
+                "{} floors are listed for a building with {} floors", self.floors.len(), num_floors
+            )));
+        }
+        if self.elevators.len() > num_elevators {
+            return Err(FormatError::from(path, None, "elevators", &format!(
+                "{} elevators are listed for a building with {} elevators", self.elevators.len(), num_elevators
+            )));
+        }
+
+        //Validate the people on each floor
+        let mut ids: HashMap<usize, String> = HashMap::new();
+        for (i, floor) in self.floors.iter().enumerate() {
+            for (j, pers) in floor.people.iter().enumerate() {
+                let context: String = format!("floors[{}].people[{}]", i, j);
+                check_person(path, &context, pers, i, num_floors, &mut ids)?;
+            }
+        }
+
+        //Validate each elevator and its passengers
+        for (i, elevator) in self.elevators.iter().enumerate() {
+            let context: String = format!("elevators[{}]", i);
+            if elevator.floor_on >= num_floors {
+                return Err(FormatError::from(path, None, &context, &format!(
+                    "floor_on {} is out of range for a building with {} floors", elevator.floor_on, num_floors
+                )));
+            }
+            if let Some(floor_to) = elevator.floor_to.filter(|x| *x >= num_floors) {
+                return Err(FormatError::from(path, None, &context, &format!(
+                    "floor_to {} is out of range for a building with {} floors", floor_to, num_floors
+                )));
+            }
+            for (j, pers) in elevator.passengers.iter().enumerate() {
+                let context: String = format!("elevators[{}].passengers[{}]", i, j);
+                check_person(path, &context, pers, elevator.floor_on, num_floors, &mut ids)?;
+            }
+        }
+        Ok(())
+    }
+
+    /** build function
+     *
+     * Build the building of the layout's config from its seed, or 0 if
+     * none is fixed, and populate it.  The layout must have been
+     * validated.
+     */
+    pub fn build(&self) -> Result<Building, FormatError> {
+        let mut building: Building = self.config.build(self.seed.unwrap_or(0_u64))
+            .map_err(|e| FormatError::from(&self.path, None, "config", &e.to_string()))?;
+        self.populate(&mut building)?;
+        Ok(building)
+    }
+
+    /** populate function
+     *
+     * Place the elevators of the validated layout and everyone in it in
+     * the given building, built from its config and not yet simulated.
+     * If an elevator's passengers exceed its capacity, then an error is
+     * returned and the building is left unchanged.
+     */
+    pub fn populate(&self, building: &mut Building) -> Result<(), FormatError> {
+        //Check that each elevator's passengers fit, which depends on its profile
+        for (i, elevator) in self.elevators.iter().enumerate() {
+            if let Some(capacity) = building.elevators[i].capacity.filter(|x| elevator.passengers.len() > *x) {
+                return Err(FormatError::from(&self.path, None, &format!("elevators[{}]", i), &format!(
+                    "{} passengers exceed the capacity of {}", elevator.passengers.len(), capacity
+                )));
+            }
+        }
+
+        //Place the elevators and their passengers
+        for (i, elevator) in self.elevators.iter().enumerate() {
+            building.elevators[i].floor_on = elevator.floor_on;
+            building.elevators[i].floor_to = elevator.floor_to;
+            building.elevators[i].moving_up = elevator.moving_up;
+            building.elevators[i].stopped = elevator.floor_to.is_none();
+            for mut new_person in elevator.passengers.iter().filter_map(get_person) {
+                new_person.state = PersonState::Riding;
+                building.place_person(new_person, Some(i));
+            }
+        }
+
+        //Place the people on the floors
+        for floor in self.floors.iter() {
+            for new_person in floor.people.iter().filter_map(get_person) {
+                building.place_person(new_person, None);
+            }
+        }
+        building.update_cache();
+        Ok(())
+    }
+}
+
+/** check_person function
+ *
+ * Check that a person in a layout is on the floor of the floor or
+ * elevator listing them, headed to a floor of the building, leaving
+ * only for the first floor, with valid parameters, a ride time within
+ * their wait time and an id no one else listed so far has, recording
+ * where their id was listed.
+ */
+fn check_person(path: &str, context: &str, pers: &LayoutPerson, floor_on: usize, num_floors: usize,
+                ids: &mut HashMap<usize, String>) -> Result<(), FormatError> {
+    if pers.floor_on != floor_on {
+        return Err(FormatError::from(path, None, context, &format!(
+            "floor_on {} does not match the floor {} they are listed on", pers.floor_on, floor_on
+        )));
+    }
+    if pers.floor_to >= num_floors {
+        return Err(FormatError::from(path, None, context, &format!(
+            "floor_to {} is out of range for a building with {} floors", pers.floor_to, num_floors
+        )));
+    }
+    if pers.is_leaving && pers.floor_to != 0_usize {
+        return Err(FormatError::from(path, None, context, &format!(
+            "someone leaving must be headed to floor 0, not {}", pers.floor_to
+        )));
+    }
+    if !(0_f64..=1_f64).contains(&pers.p_out) {
+        return Err(FormatError::from(path, None, context, &format!("p_out {} is not in [0, 1]", pers.p_out)));
+    }
+    if !(pers.crowding_tolerance > 0_f64 && pers.crowding_tolerance <= 1_f64) {
+        return Err(FormatError::from(path, None, context, &format!(
+            "crowding_tolerance {} is not in (0, 1]", pers.crowding_tolerance
+        )));
+    }
+    if pers.boarding_ticks == 0_usize {
+        return Err(FormatError::from(path, None, context, "boarding_ticks must be positive"));
+    }
+    if pers.ride_time > pers.wait_time {
+        return Err(FormatError::from(path, None, context, &format!(
+            "ride_time {} is longer than wait_time {}", pers.ride_time, pers.wait_time
+        )));
+    }
+    if let Some(other_context) = ids.insert(pers.id, String::from(context)) {
+        return Err(FormatError::from(path, None, context, &format!(
+            "id {} is already taken by {}", pers.id, other_context
+        )));
+    }
+    Ok(())
+}
+
+/** get_person function
+ *
+ * Return the person a layout describes, waiting for an elevator to
+ * their destination, idle on it, or exiting if they are leaving and
+ * on the first floor.  Validation checked their probability of
+ * leaving, so none is returned only for an unvalidated layout.
+ */
+fn get_person(pers: &LayoutPerson) -> Option<Person> {
+    let mut new_person: Person = Person::from_dest(pers.p_out, pers.floor_on, pers.floor_to).ok()?;
+    new_person.id = pers.id;
+    new_person.is_leaving = pers.is_leaving;
+    new_person.wait_time = pers.wait_time;
+    new_person.ride_time = pers.ride_time;
+    new_person.crowding_tolerance = pers.crowding_tolerance;
+    new_person.boarding_ticks = pers.boarding_ticks;
+    if pers.is_leaving && new_person.state == PersonState::Idle {
+        new_person.state = PersonState::Exiting;
+    }
+    Some(new_person)
+}
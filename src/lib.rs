@@ -0,0 +1,71 @@
+//The tracing instrumentation, declared first so that every module may use its macros
+#[macro_use]
+mod instrument;
+
+//The simulation core, which builds for any target
+pub mod person;
+pub mod people;
+pub mod building;
+pub mod elevator;
+pub mod elevators;
+pub mod floor;
+pub mod floors;
+pub mod controller;
+pub mod render;
+pub mod event;
+pub mod simulation;
+pub mod cache;
+pub mod profile;
+pub mod snapshot;
+pub mod trace;
+pub mod format;
+pub mod preset;
+pub mod results;
+pub mod rng;
+pub mod invariant;
+pub mod golden;
+pub mod shuttle;
+pub mod zone;
+pub mod params;
+pub mod tune;
+pub mod demand;
+pub mod score;
+pub mod bank;
+pub mod maintenance;
+pub mod journey;
+pub mod stats;
+pub mod energy;
+pub mod destination;
+pub mod meeting;
+pub mod arrival;
+pub mod layout;
+pub mod observe;
+pub mod units;
+pub mod campus;
+pub mod compare;
+pub mod sweep;
+pub mod error;
+
+//The terminal frontend, which requires the cli feature
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "cli")]
+pub mod input;
+#[cfg(feature = "cli")]
+pub mod terminal;
+#[cfg(feature = "cli")]
+pub mod server;
+#[cfg(feature = "cli")]
+pub mod status;
+#[cfg(feature = "cli")]
+pub mod cast;
+#[cfg(feature = "cli")]
+pub mod progress;
+
+//The web frontend, which requires the wasm feature
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+//The fixtures shared by the unit tests of every module
+#[cfg(test)]
+mod testing;
@@ -1,98 +1,1191 @@
-mod person;
-mod people;
-mod building;
-mod elevator;
-mod elevators;
-mod floor;
-mod floors;
-mod cli;
-mod controller;
-
-//Import source modules
-use crate::building::Building;
-use crate::elevators::Elevators;
-use crate::floors::Floors;
-use crate::cli::ElevatorCli;
-use crate::controller::{ElevatorController, RandomController};
+//Import library modules
+use elevator_optimization::building::Building;
+use elevator_optimization::elevator::ElevatorProfile;
+use elevator_optimization::cli::{ElevatorCli, CliCommand, SimulateArgs, CommonArgs, BuildingArgs, CompareArgs, SweepArgs, ResultsQuery};
+use elevator_optimization::controller::ControllerKind;
+use elevator_optimization::simulation::{SimulationConfig, SimulationResult, SimulationSummary, ReplicationSummary, ReplicationProgress,
+                                        enforce_invariants, run_replications_with_progress};
+use elevator_optimization::campus::{Campus, CampusFile, CampusSummary, CampusResult};
+use elevator_optimization::compare::ControllerComparison;
+use elevator_optimization::sweep::{SWEEP_HEADER, run_sweep};
+use elevator_optimization::render::{RenderOptions, Viewport, View, Focus, Ticker, render_frame, get_frame_floors};
+use elevator_optimization::terminal::Renderer;
+use elevator_optimization::input::{Input, Action};
+use elevator_optimization::profile::{Profiler, Phase};
+use elevator_optimization::server::run_server;
+use elevator_optimization::status::StatusServer;
+use elevator_optimization::snapshot::{BuildingSnapshot, SnapshotLog};
+use elevator_optimization::trace::Trace;
+use elevator_optimization::journey::JourneyLog;
+use elevator_optimization::event::EventLog;
+use elevator_optimization::format::{ScenarioFile, FloorWeightsFile, FormatError};
+use elevator_optimization::layout::LayoutFile;
+use elevator_optimization::results::{MetricsLog, ResultsLog, ResultsRow};
+use elevator_optimization::golden::{GOLDEN_CASES, check_golden_files};
+use elevator_optimization::cast::{CastWriter, HeadlessCast};
+use elevator_optimization::progress::ProgressBar;
+use elevator_optimization::params::{ControllerParams, ParamSpec};
+use elevator_optimization::tune::{TuneOptions, TunedParams, tune};
+use elevator_optimization::error::ElevatorError;
 
 //Import libraries
-use std::{thread, time};
-use std::io::{Write, stdout};
-use crossterm::{terminal, cursor, QueueableCommand};
+use std::time;
+use std::thread;
+use std::io::{self, stdout, Write, BufWriter};
+use std::fs::File;
+use rand::Rng;
+use crossterm::terminal;
 use clap::Parser;
 
-//Main function
-fn main() {
-    //Parse the command line args
-    let cli_args = ElevatorCli::parse();
-    let num_floors: usize = match cli_args.floors {
-        Some(x) => x as usize,
-        None => 4_usize
-    };
-    let num_elevators: usize = match cli_args.elevators {
-        Some(x) => x as usize,
-        None => 2_usize
-    };
-    let expected_arrivals: f64 = match cli_args.arrivals {
-        Some(x) => x as f64,
-        None => 0.2_f64
-    };
-
-    //Initialize the building
-    let building = Building::from(
-        num_floors,
-        num_elevators,
-        expected_arrivals,
-        5.0_f64, //Base energy spent moving elevator up
-        2.5_f64, //Base energy spent moving elevator down
-        0.5_f64  //Coefficient for energy spent by moving N people
-    );
-
-    //Initialize the controller
-    let controller_rng = rand::thread_rng();
-    let mut controller = RandomController::from(
-        building, controller_rng
-    );
-
-    //Initialize the RNG and stdout
-    let mut rng = rand::thread_rng();
-    let mut stdout = stdout();
-    
-    //Loop until the numer of time steps are complete
-    let time_steps: i32 = 1000_i32;
+//Constant representing the number of stats lines printed in fast mode
+const FAST_STATS_LINES: i32 = 10_i32;
+
+/** StepOutputs struct schema
+ *
+ * A StepOutputs has the following properties
+ * - status_server (Option<StatusServer>): The status server, if serving the status was requested
+ * - trace (Option<Trace>): The trace, if recording was requested
+ * - journeys (Option<JourneyLog<BufWriter<File>>>): The log of the trips completed, if requested
+ * - event_log (Option<EventLog<BufWriter<File>>>): The log of every event, if requested
+ * - snapshots (Option<SnapshotLog>): The log of the building's state every few time steps, if requested
+ * - headless_cast (Option<HeadlessCast>): The cast of a run which is not rendered, if requested
+ * - check_invariants (bool): Whether to check the invariants of the building, which debug builds always do
+ */
+struct StepOutputs {
+    status_server: Option<StatusServer>,
+    trace: Option<Trace>,
+    journeys: Option<JourneyLog<BufWriter<File>>>,
+    event_log: Option<EventLog<BufWriter<File>>>,
+    snapshots: Option<SnapshotLog>,
+    headless_cast: Option<HeadlessCast>,
+    check_invariants: bool
+}
+
+/** StepOutputs type implementation
+ *
+ * The following functions are implemented for the StepOutputs type,
+ * and are callable via
+ *
+ * //Example
+ * my_outputs.update(&my_building, time_step);
+ */
+impl StepOutputs {
+    /** update function
+     *
+     * Check the invariants of every building if requested, then update
+     * each of the outputs with the first building after the given time
+     * step, since the outputs are only requested of a lone building.
+     * If the journey log, the event log, the snapshots or the cast
+     * cannot be written, then it is abandoned.
+     */
+    fn update(&mut self, campus: &Campus, time_step: i32) {
+        if self.check_invariants {
+            for i in 0..campus.get_num_buildings() {
+                enforce_invariants(campus.get_building(i), time_step);
+            }
+        }
+        let building: &Building = campus.get_building(0_usize);
+        if let Some(x) = &self.status_server {
+            x.update(building, time_step);
+        }
+        if let Some(x) = self.trace.as_mut() {
+            x.record_step(building);
+        }
+        if let Some(x) = self.journeys.as_mut() {
+            if let Err(e) = x.record_step(building, time_step) {
+                eprintln!("Failed to write the journeys: {}", e);
+                self.journeys = None;
+            }
+        }
+        if let Some(x) = self.event_log.as_mut() {
+            if let Err(e) = x.record_step(building, time_step) {
+                eprintln!("Failed to write the event log: {}", e);
+                self.event_log = None;
+            }
+        }
+        if let Some(x) = self.snapshots.as_mut() {
+            if let Err(e) = x.record_step(building, time_step) {
+                eprintln!("Failed to write the snapshots: {}", e);
+                self.snapshots = None;
+            }
+        }
+        if let Some(x) = self.headless_cast.as_mut() {
+            if let Err(e) = x.draw(building, time_step) {
+                eprintln!("Failed to write the cast: {}", e);
+                self.headless_cast = None;
+            }
+        }
+    }
+}
+
+/** run_fast function
+ *
+ * Run the simulation at maximum speed, without rendering, reading
+ * keys or sleeping between time steps, until the time steps are
+ * complete or the duration, if any, has passed.  Unless quiet, a
+ * progress bar is drawn beneath a line of statistics across the
+ * buildings printed periodically, so that progress can be followed,
+ * and the outputs are updated each time step.  The number of time
+ * steps run is returned, or the error of any time step which failed.
+ */
+fn run_fast(campus: &mut Campus, profiler: &mut Profiler, scenario: Option<&ScenarioFile>, outputs: &mut StepOutputs,
+            quiet: bool, duration: Option<time::Duration>) -> Result<i32, ElevatorError> {
+    let time_steps: i32 = campus.get_time_steps();
+    let stats_interval: i32 = (time_steps / FAST_STATS_LINES).max(1_i32);
+    let run_start: time::Instant = time::Instant::now();
+    let mut num_stats_lines: i32 = 0_i32;
+    let mut num_steps_run: i32 = 0_i32;
+    let mut progress_bar: ProgressBar = ProgressBar::from(!quiet);
+    profiler.start();
     for i in 0..time_steps {
-        //Generate people arriving and leaving
-        controller.building.gen_people_arriving(&mut rng);
-        controller.building.gen_people_leaving(&mut rng);
+        if is_duration_over(run_start, duration) {
+            break;
+        }
+        campus.step(i, profiler, scenario)?;
+        outputs.update(campus, i);
+        num_steps_run += 1_i32;
+
+        //Redraw the progress bar, by the fraction of the time steps or of the duration run
+        let fraction_done: f64 = match duration {
+            Some(x) => run_start.elapsed().as_secs_f64() / x.as_secs_f64(),
+            None => (i + 1_i32) as f64 / time_steps as f64
+        };
+        if progress_bar.is_due(fraction_done) {
+            let summary: CampusSummary = CampusSummary::from(&campus.get_results());
+            progress_bar.draw(fraction_done, &format!("step {}, average wait time {:.2}", i + 1_i32, summary.wait.mean));
+        }
+
+        //Print the statistics each tenth of the time steps, or of the duration if running for one
+        let print_stats: bool = match duration {
+            Some(x) => {
+                let stats_line: i32 = (run_start.elapsed().as_secs_f64() / x.as_secs_f64() * FAST_STATS_LINES as f64) as i32;
+                let is_due: bool = stats_line > num_stats_lines;
+                num_stats_lines = num_stats_lines.max(stats_line);
+                is_due
+            },
+            None => (i + 1_i32) % stats_interval == 0_i32 || i + 1_i32 == time_steps
+        };
+        if !quiet && print_stats {
+            progress_bar.clear();
+            let summary: CampusSummary = CampusSummary::from(&campus.get_results());
+            let progress: String = match duration {
+                Some(_) => format!(" ({:.1}s)", run_start.elapsed().as_secs_f64()),
+                None => format!("/{}", time_steps)
+            };
+            println!(
+                "Step {}{}: average wait time {:.2}, average energy spent {:.2}",
+                i + 1_i32, progress, summary.wait.mean, summary.energy.avg_per_step
+            );
+        }
+    }
+    progress_bar.clear();
+    profiler.stop();
+    Ok(num_steps_run)
+}
+
+/** run_debug_step function
+ *
+ * Run the simulation a time step at a time, printing a dump of the
+ * first building after each and waiting for a key, enter or n for the
+ * next time step, d to toggle whether everyone in the building is
+ * listed, and q to quit.  The dumps are written to the given writer,
+ * and the outputs are updated each time step.  If a time step fails,
+ * then its error is returned.
+ */
+fn run_debug_step(campus: &mut Campus, profiler: &mut Profiler, scenario: Option<&ScenarioFile>,
+                  outputs: &mut StepOutputs, out: &mut impl Write) -> Result<(), ElevatorError> {
+    let mut input: Input = Input::new();
+    let mut verbose: bool = true;
+    for i in 0..campus.get_time_steps() {
+        campus.step(i, profiler, scenario)?;
+        outputs.update(campus, i);
+
+        //Print the dump, reprinting it whenever its verbosity is toggled, until the next time step or quit
+        let mut redraw: bool = true;
+        loop {
+            if redraw {
+                let building: &Building = campus.get_building(0_usize);
+                let dump: String = BuildingSnapshot::from_detailed(building, i).get_dump(&building.events, verbose);
+                let prompt: &str = "[enter/n] next step, [d] toggle people, [q] quit\n";
+
+                //Raw mode does not return the cursor on a new line, so do so explicitly
+                let text: String = format!("{}{}", dump, prompt);
+                let text: String = if input.is_enabled() { text.replace('\n', "\r\n") } else { text };
+                let _ = out.write_all(text.as_bytes());
+                let _ = out.flush();
+                redraw = false;
+            }
+            match input.wait_for_key() {
+                Action::Step => break,
+                Action::ToggleDump => {
+                    verbose = !verbose;
+                    redraw = true;
+                },
+                Action::Quit => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/** run_quiet function
+ *
+ * Run the simulation without rendering or reading keys, so that the
+ * terminal is never touched, but still waiting the given tick between
+ * time steps, until the time steps are complete or the duration, if
+ * any, has passed.  The outputs are updated each time step.  Then
+ * the summary of each building, and nothing else, is written to the
+ * given writer.  If a time step fails or the summaries cannot be
+ * written, then an error is returned.
+ */
+fn run_quiet(campus: &mut Campus, profiler: &mut Profiler, scenario: Option<&ScenarioFile>, outputs: &mut StepOutputs,
+             tick: time::Duration, duration: Option<time::Duration>, out: &mut impl Write) -> Result<(), ElevatorError> {
+    let run_start: time::Instant = time::Instant::now();
+    let mut num_steps_run: i32 = 0_i32;
+    profiler.start();
+    for i in 0..campus.get_time_steps() {
+        if is_duration_over(run_start, duration) {
+            break;
+        }
+        campus.step(i, profiler, scenario)?;
+        outputs.update(campus, i);
+        num_steps_run += 1_i32;
+        thread::sleep(tick);
+        if profiler.enabled {
+            profiler.lap(Phase::Sleep);
+        }
+    }
+    profiler.stop();
+    if duration.is_some() {
+        end_duration(campus, outputs, num_steps_run);
+    }
+
+    //Write the summary of each building, numbered if there are several
+    for i in 0_usize..campus.get_num_buildings() {
+        let building: &Building = campus.get_building(i);
+        if campus.get_num_buildings() > 1_usize {
+            writeln!(out, "Building {}: {}", i + 1_usize, building.summary())?;
+        } else {
+            writeln!(out, "{}", building.summary())?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/** is_duration_over function
+ *
+ * Return whether the duration of a run started at the given instant
+ * has passed, which it never does if there is none.  This is checked
+ * before each time step, so that the time step under way is finished.
+ */
+fn is_duration_over(run_start: time::Instant, duration: Option<time::Duration>) -> bool {
+    duration.is_some_and(|x| run_start.elapsed() >= x)
+}
+
+/** end_duration function
+ *
+ * Record the number of time steps run as the length of each building's
+ * config and of the trace, if any, once a run bounded by its duration
+ * rather than its time steps is over.
+ */
+fn end_duration(campus: &mut Campus, outputs: &mut StepOutputs, num_steps_run: i32) {
+    for building in campus.buildings.iter_mut() {
+        building.config.time_steps = num_steps_run;
+    }
+    if let Some(x) = outputs.trace.as_mut() {
+        x.config.time_steps = num_steps_run;
+    }
+}
+
+/** print_speed function
+ *
+ * Print the number of time steps run, the controller and seed they
+ * were run with, the wall time they took and the time steps run per
+ * second, on a line of its own below the building if it was rendered,
+ * then the energy parameters of the given config, and the people who
+ * arrived if an arrival profile scaled the arrivals, to the given
+ * writer.
+ */
+fn print_speed(out: &mut impl Write, campus: &Campus, config: &SimulationConfig, seed: u64, num_steps: i32,
+               elapsed: time::Duration, rendered: bool) -> io::Result<()> {
+    let steps_per_sec: f64 = if elapsed.is_zero() {
+        0_f64
+    } else {
+        num_steps as f64 / elapsed.as_secs_f64()
+    };
+    writeln!(
+        out, "{}Ran {} time steps of the {} controller with seed {} in {:.2}s, {:.1} steps per second",
+        if rendered { "\n" } else { "" }, num_steps, campus.controller_kind.get_name(), seed,
+        elapsed.as_secs_f64(), steps_per_sec
+    )?;
+    writeln!(
+        out, "Energy per floor moving up {}, moving down {}, and {} per person aboard",
+        config.energy_up, config.energy_down, config.energy_coef
+    )?;
+    if !config.arrival_profile.is_constant() {
+        let num_arrivals: usize = campus.buildings.iter().map(|x| x.controller.get_building().num_arrivals).sum();
+        writeln!(out, "Arrivals scaled by the {} profile, {} people arrived", config.arrival_profile.get_name(), num_arrivals)?;
+    }
+    Ok(())
+}
+
+/** get_io_error function
+ *
+ * Wrap an I/O error with a description of what failed, so that the
+ * path it failed on is reported along with it.
+ */
+fn get_io_error(e: io::Error, context: String) -> ElevatorError {
+    ElevatorError::Io(io::Error::new(e.kind(), format!("{}: {}", context, e)))
+}
+
+/** write_trace function
+ *
+ * Write the recorded trace, if any, to the given path, returning an
+ * error if it cannot be written.
+ */
+fn write_trace(path: Option<&str>, trace: Option<&Trace>) -> Result<(), ElevatorError> {
+    if let (Some(path), Some(trace)) = (path, trace) {
+        trace.write(path)?;
+    }
+    Ok(())
+}
+
+/** finish_journeys function
+ *
+ * Flush the remainder of the journey log, if any.
+ */
+fn finish_journeys(journeys: Option<&mut JourneyLog<BufWriter<File>>>) {
+    if let Some(x) = journeys {
+        if let Err(e) = x.finish() {
+            eprintln!("Failed to write the journeys: {}", e);
+        }
+    }
+}
+
+/** finish_event_log function
+ *
+ * Flush the remainder of the event log, if any.
+ */
+fn finish_event_log(event_log: Option<&mut EventLog<BufWriter<File>>>) {
+    if let Some(x) = event_log {
+        if let Err(e) = x.finish() {
+            eprintln!("Failed to write the event log: {}", e);
+        }
+    }
+}
+
+/** finish_snapshots function
+ *
+ * Flush the remainder of the snapshot log, if any.
+ */
+fn finish_snapshots(snapshots: Option<&mut SnapshotLog>) {
+    if let Some(x) = snapshots {
+        if let Err(e) = x.finish() {
+            eprintln!("Failed to write the snapshots: {}", e);
+        }
+    }
+}
+
+/** log_result function
+ *
+ * Append the result of a run to the results log and to the metrics
+ * log at the given paths, if any, returning an error if it cannot be
+ * appended.
+ */
+fn log_result(path: Option<&str>, metrics_path: Option<&str>, result: &SimulationResult) -> Result<(), ElevatorError> {
+    if let Some(path) = path {
+        ResultsLog::from(path).append(result)
+            .map_err(|e| get_io_error(e, format!("failed to append to the results log {}", path)))?;
+    }
+    if let Some(metrics_path) = metrics_path {
+        MetricsLog::from(metrics_path).append(result)
+            .map_err(|e| get_io_error(e, format!("failed to append to the metrics log {}", metrics_path)))?;
+    }
+    Ok(())
+}
+
+/** log_campus function
+ *
+ * Append the result of each building on the campus to the results log
+ * and to the metrics log at the given paths, if any, then print the
+ * results if there are several buildings, along with their aggregate,
+ * unless quiet, returning an error if they cannot be appended.
+ */
+fn log_campus(path: Option<&str>, metrics_path: Option<&str>, campus: &Campus, quiet: bool) -> Result<(), ElevatorError> {
+    let result: CampusResult = campus.get_result();
+    for building_result in result.buildings.iter() {
+        log_result(path, metrics_path, building_result)?;
+    }
+    if !quiet && result.buildings.len() > 1_usize {
+        println!("{}", result);
+    }
+    Ok(())
+}
+
+/** write_summaries function
+ *
+ * Write the summary of each run as a line of JSON to the file at the
+ * given path, or to stdout if it is -, returning an error if it cannot
+ * be written.
+ */
+fn write_summaries(path: Option<&str>, results: &[SimulationResult]) -> Result<(), ElevatorError> {
+    if let Some(path) = path {
+        let mut json: String = String::new();
+        for result in results.iter() {
+            let summary_json: String = SimulationSummary::from(result).to_json()
+                .map_err(|e| ElevatorError::Format(FormatError::from(path, None, "summary", &e)))?;
+            json.push_str(&format!("{}\n", summary_json));
+        }
+        let write_result: io::Result<()> = if path == "-" {
+            stdout().write_all(json.as_bytes()).and_then(|_| stdout().flush())
+        } else {
+            std::fs::write(path, json)
+        };
+        write_result.map_err(|e| get_io_error(e, format!("failed to write the summary to {}", path)))?;
+    }
+    Ok(())
+}
+
+/** print_controller_help function
+ *
+ * Print the parameters the given kind of controller may be tuned with
+ * via --controller-arg, along with their defaults.
+ */
+fn print_controller_help(controller_kind: ControllerKind) {
+    let specs: &[ParamSpec] = controller_kind.get_param_specs();
+    if specs.is_empty() {
+        println!("The {} controller takes no parameters", controller_kind.get_name());
+        return;
+    }
+    println!("Parameters of the {} controller, given as --controller-arg key=value:", controller_kind.get_name());
+    for spec in specs.iter() {
+        println!("  {}", spec);
+    }
+}
+
+/** run_results_query function
+ *
+ * Print the logged runs matching a query of the results log at the
+ * given path, returning an error if it cannot be read.
+ */
+fn run_results_query(path: &str, query: &ResultsQuery) -> Result<(), ElevatorError> {
+    let rows: Vec<ResultsRow> = ResultsLog::from(path).read()
+        .map_err(|e| get_io_error(e, format!("failed to read the results log {}", path)))?;
+    match query {
+        ResultsQuery::List => {
+            for row in rows.iter() {
+                println!("{}", row);
+            }
+        },
+        ResultsQuery::Best { metric } => match metric.get_best(&rows) {
+            Some(row) => println!("{}\n{}", row, row.config),
+            None => println!("No runs have been logged")
+        },
+        ResultsQuery::Compare { metric, by } => {
+            for group in by.compare(&rows, *metric).iter() {
+                println!("{}", group);
+            }
+        }
+    }
+    Ok(())
+}
+
+/** get_config function
+ *
+ * Initialize a simulation config from the preset, if any, then
+ * override it with the building's size and the shared flags given,
+ * and weight its floors by the floor weights file, if any.  If the
+ * file cannot be read or does not fit the building, then an error is
+ * returned.
+ */
+fn get_config(common: &CommonArgs, building: &BuildingArgs) -> Result<SimulationConfig, ElevatorError> {
+    let mut config: SimulationConfig = match common.preset {
+        Some(x) => x.get_config(),
+        None => SimulationConfig::from(4_usize, 2_usize, 0.2_f64)
+    };
+    if let Some(x) = building.floors {
+        config.num_floors = x;
+    }
+    if let Some(x) = building.elevators {
+        config.num_elevators = x;
+    }
+    if let Some(x) = building.arrivals {
+        config.expected_arrivals = x;
+    }
+    if let Some(x) = common.energy_up {
+        config.energy_up = x;
+    }
+    if let Some(x) = common.energy_down {
+        config.energy_down = x;
+    }
+    if let Some(x) = common.energy_coef {
+        config.energy_coef = x;
+    }
+    if let Some(x) = common.capacity {
+        config.capacity = Some(x);
+    }
+    if let Some(x) = common.p_out {
+        config.p_out = x;
+    }
+    if let Some(x) = common.arrival_profile {
+        config.arrival_profile = x;
+    }
+    if let Some(x) = common.time_steps {
+        config.time_steps = x;
+    }
+    if let Some(weights_path) = &building.floor_weights {
+        let floor_weights: FloorWeightsFile = FloorWeightsFile::load(weights_path)?;
+        floor_weights.validate(config.num_floors)?;
+        floor_weights.apply(&mut config);
+    }
+    Ok(config)
+}
+
+/** run_compare function
+ *
+ * Run the baseline and candidate controllers on the same seeds and
+ * print a table of the differences in their metrics.
+ */
+fn run_compare(args: &CompareArgs) -> Result<(), ElevatorError> {
+    let config: SimulationConfig = get_config(&args.common, &args.building)?;
+    config.validate().map_err(ElevatorError::Config)?;
+    let base_seed: u64 = args.common.seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+    let seeds: Vec<u64> = (0..args.runs as u64).map(|i| base_seed.wrapping_add(i)).collect();
+    println!("{}", ControllerComparison::run(&config, args.baseline, args.candidate, &seeds)?);
+    Ok(())
+}
+
+/** run_sweep_command function
+ *
+ * Run the controller on every combination of the swept floors,
+ * elevators and arrival rates, printing a row for each as it is run
+ * and logging the runs to the metrics log, if any.  An axis which is
+ * not swept keeps the preset's value.
+ */
+fn run_sweep_command(args: &SweepArgs) -> Result<(), ElevatorError> {
+    let config: SimulationConfig = get_config(&args.common, &BuildingArgs::default())?;
+    let floors: Vec<usize> = args.floors.as_ref().map_or(vec![config.num_floors], |x| x.values.clone());
+    let elevators: Vec<usize> = args.elevators.as_ref().map_or(vec![config.num_elevators], |x| x.values.clone());
+    let arrivals: Vec<f64> = args.arrivals.as_ref().map_or(vec![config.expected_arrivals], |x| x.values.clone());
+    let base_seed: u64 = args.common.seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+    let seeds: Vec<u64> = (0..args.runs as u64).map(|i| base_seed.wrapping_add(i)).collect();
+    if let Some(metrics_path) = &args.metrics_csv {
+        MetricsLog::from(metrics_path).check()
+            .map_err(|e| get_io_error(e, format!("failed to open the metrics log {}", metrics_path)))?;
+    }
+    println!("Sweeping the {} controller over {} run(s) per point from seed {}", args.controller.get_name(), args.runs, base_seed);
+    println!("{}", SWEEP_HEADER);
+    run_sweep(&config, &floors, &elevators, &arrivals, args.controller, &seeds, |point| {
+        println!("{}", point);
+        if let Some(metrics_path) = &args.metrics_csv {
+            for result in point.results.iter() {
+                log_result(None, Some(metrics_path), result)?;
+            }
+            MetricsLog::from(metrics_path).append_aggregate(&point.results)
+                .map_err(|e| get_io_error(e, format!("failed to append to the metrics log {}", metrics_path)))?;
+        }
+        Ok(())
+    })
+}
+
+/** run function
+ *
+ * Run whichever command the command line args request, returning any
+ * error which ends the run early so that main may report it.
+ */
+fn run(cli_args: ElevatorCli) -> Result<(), ElevatorError> {
+    //If checking the golden files was requested, then compare each golden case against its file
+    if let Some(CliCommand::Golden { dir, update }) = &cli_args.command {
+        let update: bool = *update || std::env::var("UPDATE_GOLDEN").map(|x| x == "1").unwrap_or(false);
+        let mismatches: Vec<String> = check_golden_files(dir, update)
+            .map_err(|e| ElevatorError::Format(FormatError::from(dir, None, "", &format!("failed to check the golden files: {}", e))))?;
+        if update {
+            println!("Updated {} golden files in {}", GOLDEN_CASES.len(), dir);
+            return Ok(());
+        }
+        for mismatch in mismatches.iter() {
+            eprintln!("{}", mismatch);
+        }
+        if !mismatches.is_empty() {
+            return Err(ElevatorError::Format(FormatError::from(
+                dir, None, "", &format!("{} of {} golden cases differ", mismatches.len(), GOLDEN_CASES.len())
+            )));
+        }
+        println!("All {} golden cases match", GOLDEN_CASES.len());
+        return Ok(());
+    }
+
+    //If querying the results log was requested, then print the matching runs
+    if let Some(CliCommand::Results { results_db, query }) = &cli_args.command {
+        return run_results_query(results_db, query);
+    }
+
+    //If comparing controllers or sweeping was requested, then run it, otherwise simulate with the subcommand's flags if given
+    let (cli_args, command): (SimulateArgs, Option<CliCommand>) = match cli_args.command {
+        Some(CliCommand::Compare(args)) => return run_compare(&args),
+        Some(CliCommand::Sweep(args)) => return run_sweep_command(&args),
+        Some(CliCommand::Simulate(args)) => (*args, None),
+        command => (cli_args.simulate, command)
+    };
+
+    //If listing a controller's parameters was requested, then print them with their defaults
+    if let Some(controller_kind) = cli_args.controller_help {
+        print_controller_help(controller_kind);
+        return Ok(());
+    }
+
+    //Initialize the simulation config from the shared flags, then override it with the flags given
+    let mut config: SimulationConfig = get_config(&cli_args.common, &cli_args.building)?;
+    if let Some(x) = cli_args.demand_smoothing {
+        config.demand_smoothing = x;
+    }
+    if let Some(x) = cli_args.change_dest_prob {
+        config.p_change_dest = x;
+    }
+    if let Some(x) = cli_args.min_crowding_tolerance {
+        config.min_crowding_tolerance = x;
+    }
+    if let Some(x) = cli_args.slow_boarding_prob {
+        config.p_slow_boarding = x;
+    }
+    if let Some(x) = cli_args.slow_boarding_ticks {
+        config.slow_boarding_ticks = x;
+    }
+    if let Some(x) = cli_args.wait_weight {
+        config.objective.wait_weight = x;
+    }
+    if let Some(x) = cli_args.energy_weight {
+        config.objective.energy_weight = x;
+    }
+    if let Some(x) = cli_args.fairness_weight {
+        config.objective.fairness_weight = x;
+    }
+    if !cli_args.freight.is_empty() {
+        if let Some(x) = cli_args.freight.iter().find(|x| **x >= config.num_elevators) {
+            return Err(ElevatorError::Config(format!(
+                "freight elevator {} is out of range for a building with {} elevators", x, config.num_elevators
+            )));
+        }
+        config.elevator_profiles = (0_usize..config.num_elevators).map(|i| {
+            if cli_args.freight.contains(&i) { ElevatorProfile::Freight } else { ElevatorProfile::Passenger }
+        }).collect();
+    }
+    if cli_args.freight_serves_passengers {
+        config.freight_serves_passengers = true;
+    }
+    if let Some(x) = cli_args.freight_surge {
+        config.freight_surge_waiting = Some(x);
+    }
+    if !cli_args.home.is_empty() {
+        config.homes = cli_args.home.clone();
+    }
+    if !cli_args.maintenance.is_empty() {
+        config.maintenance = cli_args.maintenance.clone();
+    }
+    if cli_args.no_reversal {
+        config.no_reversal = true;
+    }
+    if cli_args.strict_stops {
+        config.strict_stops = true;
+    }
+    if let Some(x) = cli_args.exchange_order {
+        config.exchange_order = x;
+    }
+    if let Some(x) = cli_args.observability {
+        config.observability = x;
+    }
+
+    //Load and validate the layout, if any, before any simulation starts
+    let layout: Option<LayoutFile> = match &cli_args.layout {
+        Some(layout_path) => {
+            let layout: LayoutFile = LayoutFile::load(layout_path)?;
+            layout.validate()?;
+            Some(layout)
+        },
+        None => None
+    };
+
+    //Initialize the config of each building, from the layout or campus file if any, or else as copies of the config
+    let configs: Vec<SimulationConfig> = match (&layout, &cli_args.campus) {
+        (Some(x), _) => vec![x.config.clone()],
+        (None, Some(campus_path)) => CampusFile::load(campus_path)?.buildings,
+        (None, None) => vec![config; cli_args.buildings.unwrap_or(1_usize)]
+    };
+
+    //Run without a limit on the time steps if running for a duration, recording those run once it is over
+    let duration: Option<time::Duration> = cli_args.duration.map(time::Duration::from_secs_f64);
+    let configs: Vec<SimulationConfig> = match duration {
+        Some(_) => configs.into_iter().map(|mut x| {
+            x.time_steps = i32::MAX;
+            x
+        }).collect(),
+        None => configs
+    };
+    let config: SimulationConfig = match configs.first() {
+        Some(x) => x.clone(),
+        None => return Err(ElevatorError::Config(String::from("the campus must have at least one building")))
+    };
+
+    //Check that the outputs which follow a lone building are not requested of several
+    if configs.len() > 1_usize {
+        let lone_options: [(&str, bool); 10] = [
+            ("--record", cli_args.record.is_some()),
+            ("--replay", cli_args.replay.is_some()),
+            ("--replications", cli_args.replications.is_some()),
+            ("--journeys", cli_args.journeys.is_some()),
+            ("--event-log", cli_args.event_log.is_some()),
+            ("--snapshots", cli_args.snapshots.is_some()),
+            ("--http-status", cli_args.http_status.is_some()),
+            ("--scenario", cli_args.scenario.is_some()),
+            ("--cast without rendering", cli_args.cast.is_some() && (cli_args.fast || cli_args.no_render)),
+            ("a subcommand", command.is_some())
+        ];
+        if let Some((option, _)) = lone_options.iter().find(|(_, given)| *given) {
+            return Err(ElevatorError::Config(format!("{} cannot be combined with multiple buildings", option)));
+        }
+    }
+
+    //Validate each building's config before any simulation starts
+    for (i, x) in configs.iter().enumerate() {
+        x.validate().map_err(|e| if configs.len() > 1_usize {
+            ElevatorError::Config(format!("building {}: {}", i + 1_usize, e))
+        } else {
+            ElevatorError::Config(e)
+        })?;
+    }
+
+    //Parse and validate the controller's parameters against each building before any simulation starts
+    let controller_kind: ControllerKind = cli_args.controller;
+    let controller_params: ControllerParams = ControllerParams::parse(&cli_args.controller_args)
+        .and_then(|x| configs.iter().try_for_each(|c| controller_kind.validate_params(&x, c.num_elevators)).map(|_| x))
+        .map_err(|e| ElevatorError::Config(format!("controller arguments: {}", e)))?;
+
+    //Load and validate the scenario, if any, before any simulation starts
+    let scenario: Option<ScenarioFile> = match &cli_args.scenario {
+        Some(scenario_path) => {
+            let scenario: ScenarioFile = ScenarioFile::load(scenario_path)?;
+            scenario.validate(config.num_floors, config.num_elevators)?;
+            Some(scenario)
+        },
+        None => None
+    };
+
+    //If tuning a controller was requested, then anneal its parameters and print the best found
+    if let Some(CliCommand::Tune { controller, budget, replications, seed, save }) = &command {
+        let options: TuneOptions = TuneOptions {
+            controller_kind: *controller,
+            objective: config.objective,
+            budget: *budget,
+            replications: *replications,
+            seed: seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>())
+        };
+        let tuned: TunedParams = tune(&config, &options, |step| eprintln!(
+            "Evaluation {}/{}: {:.2}{} {}", step.evaluation + 1_usize, options.budget, step.value,
+            if step.accepted { " accepted" } else { "" }, step.params.get_args()
+        ))?;
+        println!("Best of seed {}: {:.2}", options.seed, tuned.value);
+        println!("{}", tuned.params.get_args());
+        if let Some(save_path) = save {
+            tuned.write(save_path)?;
+        }
+        return Ok(());
+    }
+
+    //If serving the simulation was requested, then serve it to a single client
+    if let Some(CliCommand::Serve { port, timeout_ms }) = command {
+        let seed: u64 = cli_args.common.seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+        let result: SimulationResult = run_server(&config, port, time::Duration::from_millis(timeout_ms), seed)?;
+        println!("Served {}", result);
+        return Ok(());
+    }
+
+    //If replaying a trace was requested, then rerun the recorded simulation
+    if let Some(replay_path) = &cli_args.replay {
+        let result: SimulationResult = Trace::read(replay_path)?.replay()?;
+        println!("Replayed {}", result);
+        return Ok(());
+    }
+
+    //Check the metrics log may be appended to before running, rather than failing after the run
+    if let Some(metrics_path) = &cli_args.metrics_csv {
+        MetricsLog::from(metrics_path).check()
+            .map_err(|e| get_io_error(e, format!("failed to open the metrics log {}", metrics_path)))?;
+    }
+
+    //Print nothing but the summaries to stdout if they are written there, and everything else to stderr
+    let quiet: bool = cli_args.summary_json.as_deref() == Some("-");
+    let mut report: Box<dyn Write> = if quiet { Box::new(io::stderr()) } else { Box::new(stdout()) };
+
+    //If replications were requested, then run them in parallel from consecutive seeds
+    if let Some(num_replications) = cli_args.replications {
+        let base_seed: u64 = cli_args.common.seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+        let seeds: Vec<u64> = (0..num_replications as u64).map(|i| base_seed.wrapping_add(i)).collect();
+        let mut progress_bar: ProgressBar = ProgressBar::from(!quiet);
+        let mut num_runs_reported: usize = 0_usize;
+        let results: Vec<SimulationResult> = run_replications_with_progress(
+            &config, controller_kind, &controller_params, &seeds, |progress: &ReplicationProgress| {
+                //Draw the bar if it is shown, or else report each completed run on stderr
+                if !progress_bar.is_enabled() {
+                    for i in num_runs_reported..progress.num_runs_done {
+                        eprintln!("Completed run {}/{}", i + 1_usize, progress.num_runs);
+                    }
+                    num_runs_reported = progress.num_runs_done;
+                } else if progress_bar.is_due(progress.get_fraction_done()) {
+                    progress_bar.draw(progress.get_fraction_done(), &format!(
+                        "{}/{} runs complete, {}/{} steps",
+                        progress.num_runs_done, progress.num_runs, progress.num_steps_done, progress.num_steps
+                    ));
+                }
+            }
+        )?;
+        progress_bar.clear();
+        for (i, result) in results.iter().enumerate() {
+            log_result(cli_args.results_db.as_deref(), cli_args.metrics_csv.as_deref(), result)?;
+            if !quiet {
+                println!("Run {}: {}", i + 1_usize, result);
+            }
+        }
+        if let Some(metrics_path) = &cli_args.metrics_csv {
+            MetricsLog::from(metrics_path).append_aggregate(&results)
+                .map_err(|e| get_io_error(e, format!("failed to append to the metrics log {}", metrics_path)))?;
+        }
+        if !quiet {
+            println!("{}", ReplicationSummary::from(&results));
+        }
+        write_summaries(cli_args.summary_json.as_deref(), &results)?;
+        return Ok(());
+    }
+
+    //Initialize the buildings, each with its own controller, whose RNG streams are derived from the seed
+    let seed: u64 = cli_args.common.seed
+        .or(layout.as_ref().and_then(|x| x.seed))
+        .unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+    let mut campus: Campus = Campus::from(&configs, controller_kind, &controller_params, seed)?;
+
+    //Populate the building from the layout, if any
+    if let Some(x) = &layout {
+        x.populate(campus.get_building_mut(0_usize))?;
+    }
+
+    //Initialize the profiler
+    let mut profiler: Profiler = Profiler::from(cli_args.profile);
+
+    //Initialize the status server, if requested
+    let status_server: Option<StatusServer> = match cli_args.http_status {
+        Some(port) => Some(StatusServer::from(port, &config, controller_kind.get_name(), seed)
+            .map_err(|e| get_io_error(e, format!("failed to serve the status on port {}", port)))?),
+        None => None
+    };
+
+    //Initialize the time between time steps and the render options
+    let tick: time::Duration = time::Duration::from_millis(cli_args.tick_ms);
+    if tick.is_zero() && cli_args.cast.is_some() {
+        return Err(ElevatorError::Config(String::from("--cast needs a --tick-ms above 0, which times its frames")));
+    }
+    let mut render_options: RenderOptions = RenderOptions {
+        compact: cli_args.compact,
+        legend: cli_args.legend,
+        ticker: cli_args.ticker,
+        verbose: cli_args.verbose,
+        keys: false,
+        style: cli_args.style,
+        colored: cli_args.color.is_enabled()
+    };
 
-        //Move people on and off the elevators and out of the building
-        controller.building.flush_first_floor();
-        controller.building.exchange_people_on_elevator();
+    //Initialize the journey log, if requested
+    let journeys: Option<JourneyLog<BufWriter<File>>> = match &cli_args.journeys {
+        Some(journeys_path) => Some(JourneyLog::create(journeys_path)
+            .map_err(|e| get_io_error(e, format!("failed to create the journeys {}", journeys_path)))?),
+        None => None
+    };
+
+    //Initialize the event log, if requested
+    let event_log: Option<EventLog<BufWriter<File>>> = match &cli_args.event_log {
+        Some(event_log_path) => Some(EventLog::create(event_log_path)
+            .map_err(|e| get_io_error(e, format!("failed to create the event log {}", event_log_path)))?),
+        None => None
+    };
+
+    //Initialize the snapshot log, if requested
+    let snapshots: Option<SnapshotLog> = match &cli_args.snapshots {
+        Some(snapshots_path) => Some(SnapshotLog::create(snapshots_path, cli_args.snapshot_interval as i32)
+            .map_err(|e| get_io_error(e, format!("failed to create the snapshots {}", snapshots_path)))?),
+        None => None
+    };
+
+    //Initialize the outputs of each time step, checking the invariants if not already checked by a debug build
+    let mut outputs: StepOutputs = StepOutputs {
+        status_server,
+        trace: cli_args.record.as_ref().map(|_| Trace::from(seed, &config)),
+        journeys,
+        event_log,
+        snapshots,
+        headless_cast: None,
+        check_invariants: cli_args.check_invariants && !cfg!(debug_assertions)
+    };
+
+    //If stepping through the run was requested, then print a dump after each time step
+    if cli_args.debug_step {
+        run_debug_step(&mut campus, &mut profiler, scenario.as_ref(), &mut outputs, &mut report)?;
+        log_campus(cli_args.results_db.as_deref(), cli_args.metrics_csv.as_deref(), &campus, quiet)?;
+        write_summaries(cli_args.summary_json.as_deref(), &campus.get_results())?;
+        write_trace(cli_args.record.as_deref(), outputs.trace.as_ref())?;
+        finish_journeys(outputs.journeys.as_mut());
+        finish_event_log(outputs.event_log.as_mut());
+        finish_snapshots(outputs.snapshots.as_mut());
+        return Ok(());
+    }
+
+    //If quiet was requested, then run the simulation at the tick rate and print only the summary of each building
+    if cli_args.quiet {
+        run_quiet(&mut campus, &mut profiler, scenario.as_ref(), &mut outputs, tick, duration, &mut report)?;
+        log_campus(cli_args.results_db.as_deref(), cli_args.metrics_csv.as_deref(), &campus, true)?;
+        write_summaries(cli_args.summary_json.as_deref(), &campus.get_results())?;
+        write_trace(cli_args.record.as_deref(), outputs.trace.as_ref())?;
+        finish_journeys(outputs.journeys.as_mut());
+        finish_event_log(outputs.event_log.as_mut());
+        finish_snapshots(outputs.snapshots.as_mut());
+        if profiler.enabled {
+            writeln!(report, "{}", profiler.render_table())?;
+        }
+        return Ok(());
+    }
+
+    //If rendering is disabled, then run the simulation at maximum speed, casting the frames if requested
+    if cli_args.fast || cli_args.no_render {
+        if let Some(cast_path) = &cli_args.cast {
+            cli_args.color.apply();
+            outputs.headless_cast = Some(HeadlessCast::create(cast_path, config.num_floors, render_options, tick)
+                .map_err(|e| get_io_error(e, format!("failed to create the cast {}", cast_path)))?);
+        }
+        if !quiet {
+            println!("Running the {} controller with seed {}", controller_kind.get_name(), seed);
+        }
+        let run_start: time::Instant = time::Instant::now();
+        let num_steps_run: i32 = run_fast(&mut campus, &mut profiler, scenario.as_ref(), &mut outputs, quiet, duration)?;
+        if duration.is_some() {
+            end_duration(&mut campus, &mut outputs, num_steps_run);
+        }
+        if !quiet {
+            print_speed(&mut report, &campus, &config, seed, num_steps_run, run_start.elapsed(), false)?;
+        }
+        log_campus(cli_args.results_db.as_deref(), cli_args.metrics_csv.as_deref(), &campus, quiet)?;
+        write_summaries(cli_args.summary_json.as_deref(), &campus.get_results())?;
+        if let Some(x) = outputs.headless_cast.as_mut() {
+            if let Err(e) = x.finish() {
+                eprintln!("Failed to write the cast: {}", e);
+            }
+        }
+        write_trace(cli_args.record.as_deref(), outputs.trace.as_ref())?;
+        finish_journeys(outputs.journeys.as_mut());
+        finish_event_log(outputs.event_log.as_mut());
+        finish_snapshots(outputs.snapshots.as_mut());
+        if profiler.enabled {
+            writeln!(report, "{}", profiler.render_table())?;
+        }
+        return Ok(());
+    }
 
-        //Update the elevators
-        controller.update_elevators();
+    //Enable or disable colored output
+    cli_args.color.apply();
 
-        //Increment the wait times, update average energy, update dest probabilities
-        let energy_spent: f64 = controller.building.elevators.get_energy_spent();
-        controller.building.increment_wait_times();
-        controller.building.update_average_energy(i, energy_spent);
-        controller.building.update_dest_probabilities();
+    //Initialize the buffer each frame is drawn into before it is written
+    let mut frame_out: Vec<u8> = Vec::new();
 
-        //Print the rendered building status
-        let building_str: String = String::from(controller.building.to_string());
-        let building_str_len = building_str.matches("\n").count() as u16;
-        let _ = stdout.write_all(building_str.as_bytes());
-        stdout.flush().unwrap();
+    //Initialize the input, then the viewport and renderer from the terminal height
+    let (term_width, term_height): (usize, usize) = match terminal::size() {
+        Ok((cols, rows)) => (cols as usize, rows as usize),
+        Err(_) => (usize::MAX, usize::MAX)
+    };
+    let mut input: Input = Input::new();
+    render_options.keys = input.is_enabled();
+
+    //Initialize the cast of the terminal, if requested
+    let mut cast: Option<CastWriter> = match &cli_args.cast {
+        Some(cast_path) => {
+            let cast_width: usize = if term_width == usize::MAX { 80_usize } else { term_width };
+            let cast_height: usize = if term_height == usize::MAX { 24_usize } else { term_height };
+            Some(CastWriter::create(cast_path, cast_width, cast_height, tick)
+                .map_err(|e| get_io_error(e, format!("failed to create the cast {}", cast_path)))?)
+        },
+        None => None
+    };
+    let num_buildings: usize = campus.get_num_buildings();
+    let header_lines: usize = if num_buildings > 1_usize { 1_usize } else { 0_usize };
+    let mut viewport: Viewport = Viewport::from(term_width, term_height.saturating_sub(header_lines), render_options);
+    let mut renderer: Renderer = Renderer::from(term_height);
+    let mut ticker: Ticker = Ticker::from(term_width);
 
-        //Sleep for one second in between time steps
-        let one_sec = time::Duration::from_millis(100_u64);
-        thread::sleep(one_sec);
+    //Initialize the frame buffer, which keeps its capacity across frames, and the building shown
+    let mut frame_str: String = String::new();
+    let mut shown: usize = 0_usize;
 
-        //Reset the cursor and clear the previous console output
-        if i < time_steps - 1 {
-            stdout.queue(cursor::MoveUp(building_str_len)).unwrap();
-            stdout.queue(cursor::MoveToColumn(0)).unwrap();
-            stdout.queue(terminal::Clear(terminal::ClearType::FromCursorDown)).unwrap();
+    //Initialize the number of frames drawn per time step, which split the tick between them
+    let num_frames: usize = cli_args.interpolate as usize;
+    let frame_tick: time::Duration = tick / cli_args.interpolate;
+    
+    //Loop until the numer of time steps are complete, counting those run in case of quitting early
+    let run_start: time::Instant = time::Instant::now();
+    let mut num_steps_run: i32 = 0_i32;
+    profiler.start();
+    'steps: for i in 0..campus.get_time_steps() {
+        //Stop once the duration, if any, has passed, having finished the time step under way
+        if is_duration_over(run_start, duration) {
+            break 'steps;
         }
+
+        //Note the floors of the elevators shown before the time step, which their cabs move from
+        let mut floors_from: Vec<usize> = campus.get_building(shown).elevators.iter().map(|x| x.floor_on).collect();
+
+        //Advance the simulation
+        campus.step(i, &mut profiler, scenario.as_ref())?;
+        outputs.update(&campus, i);
+        num_steps_run += 1_i32;
+
+        //Add the time step's events of the building shown to the ticker
+        for event in campus.get_building(shown).events.iter() {
+            ticker.push(event.describe());
+        }
+
+        for frame in 0_usize..num_frames {
+            //Print the rendered building status, redrawing the lines which changed, and cast it if requested
+            let building: &Building = campus.get_building(shown);
+            frame_str.clear();
+            if num_buildings > 1_usize {
+                frame_str.push_str(&format!("Building {} of {} (tab for the next)\n", shown + 1_usize, num_buildings));
+            }
+            let mut view: View = viewport.get_view(building);
+            if num_frames > 1_usize {
+                view.cab_floors = get_frame_floors(&floors_from, building, frame + 1_usize, num_frames);
+            }
+            let _ = render_frame(&mut frame_str, building, &view, &ticker);
+            frame_out.clear();
+            renderer.draw(&mut frame_out, &frame_str)?;
+            report.write_all(&frame_out).and_then(|_| report.flush())?;
+            if let Some(x) = cast.as_mut() {
+                if let Err(e) = x.write_subframe(i, frame, num_frames, &frame_out) {
+                    eprintln!("Failed to write the cast: {}", e);
+                    cast = None;
+                }
+            }
+            if profiler.enabled {
+                profiler.lap(Phase::Render);
+            }
+
+            //Wait in between frames, handling any keys pressed
+            for action in input.wait(frame_tick) {
+                match action {
+                    Action::PanUp => viewport.pan(campus.get_building(shown), true),
+                    Action::PanDown => viewport.pan(campus.get_building(shown), false),
+                    Action::FollowElevator(j) => viewport.focus = Focus::Elevator(j),
+                    Action::FollowBusiest => viewport.focus = Focus::Busiest,
+                    Action::ToggleLegend => viewport.options.legend = !viewport.options.legend,
+                    Action::ToggleTicker => viewport.options.ticker = !viewport.options.ticker,
+                    Action::NextBuilding => {
+                        //Show the next building's cabs on their floors for the rest of the time step
+                        shown = (shown + 1_usize) % num_buildings;
+                        viewport.focus = Focus::Busiest;
+                        floors_from.clear();
+                    },
+                    Action::Step | Action::ToggleDump => {},
+                    Action::Resize(cols, rows) => {
+                        viewport.resize(cols, rows.saturating_sub(header_lines));
+                        renderer.resize(rows);
+                        ticker.resize(cols);
+                    },
+                    Action::Quit => break 'steps
+                }
+            }
+            if profiler.enabled {
+                profiler.lap(Phase::Sleep);
+            }
+        }
+    }
+    profiler.stop();
+    if duration.is_some() {
+        end_duration(&mut campus, &mut outputs, num_steps_run);
+    }
+
+    //Restore the terminal, then print the speed of the run, log the result, write the trace and print the time spent in each phase if requested
+    drop(input);
+    print_speed(&mut report, &campus, &config, seed, num_steps_run, run_start.elapsed(), true)?;
+    log_campus(cli_args.results_db.as_deref(), cli_args.metrics_csv.as_deref(), &campus, quiet)?;
+    write_summaries(cli_args.summary_json.as_deref(), &campus.get_results())?;
+    if let Some(x) = cast.as_mut() {
+        if let Err(e) = x.finish() {
+            eprintln!("Failed to write the cast: {}", e);
+        }
+    }
+    write_trace(cli_args.record.as_deref(), outputs.trace.as_ref())?;
+    finish_journeys(outputs.journeys.as_mut());
+    finish_event_log(outputs.event_log.as_mut());
+    finish_snapshots(outputs.snapshots.as_mut());
+    if profiler.enabled {
+        writeln!(report, "\n{}", profiler.render_table())?;
+    }
+    Ok(())
+}
+
+//Main function
+fn main() {
+    //Parse the command line args and run, exiting with the error's code if it fails
+    if let Err(e) = run(ElevatorCli::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(e.get_exit_code());
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Get the outputs of a run which writes nothing but its summaries
+    fn get_no_outputs() -> StepOutputs {
+        StepOutputs {
+            status_server: None,
+            trace: None,
+            journeys: None,
+            event_log: None,
+            snapshots: None,
+            headless_cast: None,
+            check_invariants: false
+        }
+    }
+
+    //Run a quiet campus of the given number of buildings without sleeping, returning the campus and what was written
+    fn run_quiet_campus(num_buildings: usize) -> (Campus, String) {
+        let mut config: SimulationConfig = SimulationConfig::from(6_usize, 2_usize, 2_f64);
+        config.time_steps = 50_i32;
+        let configs: Vec<SimulationConfig> = vec![config; num_buildings];
+        let mut campus: Campus = Campus::from(&configs, ControllerKind::Nearest, &ControllerParams::default(), 4_u64).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut out: Vec<u8> = Vec::new();
+        run_quiet(&mut campus, &mut profiler, None, &mut get_no_outputs(), time::Duration::ZERO, None, &mut out).unwrap();
+        (campus, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn quiet_run_writes_no_terminal_commands() {
+        for num_buildings in [1_usize, 2_usize] {
+            let (_, written): (Campus, String) = run_quiet_campus(num_buildings);
+            assert!(!written.contains('\u{1b}'), "{:?}", written);
+            assert!(!written.chars().any(|x| x.is_control() && x != '\n'), "{:?}", written);
+        }
+    }
+
+    #[test]
+    fn quiet_run_writes_only_the_summaries() {
+        //A lone building's summary is written as is
+        let (campus, written): (Campus, String) = run_quiet_campus(1_usize);
+        assert_eq!(written, format!("{}\n", campus.get_building(0_usize).summary()));
+        assert!(written.starts_with("50 steps, "), "{:?}", written);
+
+        //Each of several buildings' summaries is numbered
+        let (campus, written): (Campus, String) = run_quiet_campus(2_usize);
+        assert_eq!(written, format!(
+            "Building 1: {}\nBuilding 2: {}\n",
+            campus.get_building(0_usize).summary(), campus.get_building(1_usize).summary()
+        ));
     }
-}
\ No newline at end of file
+}
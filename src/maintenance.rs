@@ -0,0 +1,198 @@
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+/** MaintenanceWindow struct schema
+ *
+ * A MaintenanceWindow has the following properties
+ * - elevator (usize): The index of the elevator taken out of service
+ * - every (usize): The number of time steps between the starts of consecutive windows
+ * - duration (usize): The number of time steps the elevator is out of service each window
+ * - start (usize): The time step the first window starts on
+ *
+ * The elevator is out of service on every time step from start + k *
+ * every, inclusive, to start + k * every + duration, exclusive.  Ahead
+ * of each window it stops taking on people, so that its passengers
+ * have been let off by the time the window starts.
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub elevator: usize,
+    pub every: usize,
+    pub duration: usize,
+    pub start: usize
+}
+
+/** MaintenanceWindow type implementation
+ *
+ * The following functions are implemented for the MaintenanceWindow
+ * type, and are callable via
+ *
+ * //Example
+ * let my_window: MaintenanceWindow = MaintenanceWindow::parse("2:500:50")?;
+ * my_window.validate(num_elevators)?;
+ * let is_active: bool = my_window.is_active(time_step);
+ */
+impl MaintenanceWindow {
+    /** MaintenanceWindow constructor function
+     *
+     * Initialize a window taking the given elevator out of service for
+     * the given duration every given number of time steps, starting one
+     * interval into the simulation.
+     */
+    pub fn from(elevator: usize, every: usize, duration: usize) -> MaintenanceWindow {
+        MaintenanceWindow {
+            elevator,
+            every,
+            duration,
+            start: every
+        }
+    }
+
+    /** parse function
+     *
+     * Parse a window written as elevator:every:duration, optionally
+     * followed by :start, e.g. "2:500:50" for the third elevator being
+     * out of service for 50 time steps every 500 time steps.
+     */
+    pub fn parse(window_str: &str) -> Result<MaintenanceWindow, String> {
+        let fields: Vec<usize> = window_str.split(':')
+            .map(|x| x.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| format!("maintenance '{}' is not of the form elevator:every:duration[:start]", window_str))?;
+        match fields[..] {
+            [elevator, every, duration] => Ok(MaintenanceWindow::from(elevator, every, duration)),
+            [elevator, every, duration, start] => Ok(MaintenanceWindow {
+                elevator,
+                every,
+                duration,
+                start
+            }),
+            _ => Err(format!("maintenance '{}' is not of the form elevator:every:duration[:start]", window_str))
+        }
+    }
+
+    /** validate function
+     *
+     * Check that the window's elevator is in a building with the given
+     * number of elevators, and that its duration is positive and shorter
+     * than the interval between windows.
+     */
+    pub fn validate(&self, num_elevators: usize) -> Result<(), String> {
+        if self.elevator >= num_elevators {
+            return Err(format!(
+                "maintenance elevator {} is out of range for a building with {} elevators", self.elevator, num_elevators
+            ));
+        }
+        if self.duration == 0_usize || self.duration >= self.every {
+            return Err(format!(
+                "maintenance duration {} must be positive and shorter than its interval {}", self.duration, self.every
+            ));
+        }
+        Ok(())
+    }
+
+    /** is_active function
+     *
+     * Return whether the elevator is out of service on the given time
+     * step.
+     */
+    pub fn is_active(&self, time_step: usize) -> bool {
+        time_step >= self.start && (time_step - self.start) % self.every < self.duration
+    }
+
+    /** get_steps_until_start function
+     *
+     * Return the number of time steps from the given time step until
+     * the next window starts, which is 0 during a window.
+     */
+    pub fn get_steps_until_start(&self, time_step: usize) -> usize {
+        if time_step < self.start {
+            self.start - time_step
+        } else if self.is_active(time_step) {
+            0_usize
+        } else {
+            self.every - ((time_step - self.start) % self.every)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::controller::{ElevatorController, ControllerKind};
+    use crate::event::SimEvent;
+    use crate::simulation::{SimulationConfig, build_controller, step};
+    use crate::params::ControllerParams;
+    use crate::profile::Profiler;
+
+    #[test]
+    fn window_boundaries_are_exact() {
+        let window: MaintenanceWindow = MaintenanceWindow::parse("0:10:3").unwrap();
+        assert_eq!(window, MaintenanceWindow { elevator: 0_usize, every: 10_usize, duration: 3_usize, start: 10_usize });
+        let active: Vec<usize> = (0_usize..35_usize).filter(|x| window.is_active(*x)).collect();
+        assert_eq!(active, vec![10_usize, 11_usize, 12_usize, 20_usize, 21_usize, 22_usize, 30_usize, 31_usize, 32_usize]);
+        let steps_until_start: Vec<usize> = [0_usize, 9_usize, 10_usize, 12_usize, 13_usize, 19_usize].iter()
+            .map(|x| window.get_steps_until_start(*x))
+            .collect();
+        assert_eq!(steps_until_start, vec![10_usize, 1_usize, 0_usize, 0_usize, 7_usize, 1_usize]);
+    }
+
+    #[test]
+    fn parse_and_validate_reject_bad_windows() {
+        assert_eq!(MaintenanceWindow::parse("1:50:5:7").unwrap().start, 7_usize);
+        assert!(MaintenanceWindow::parse("1:50").is_err());
+        assert!(MaintenanceWindow::parse("1:x:5").is_err());
+        assert!(MaintenanceWindow::from(2_usize, 50_usize, 5_usize).validate(2_usize).is_err());
+        assert!(MaintenanceWindow::from(0_usize, 50_usize, 0_usize).validate(2_usize).is_err());
+        assert!(MaintenanceWindow::from(0_usize, 50_usize, 50_usize).validate(2_usize).is_err());
+        assert_eq!(MaintenanceWindow::from(0_usize, 50_usize, 49_usize).validate(2_usize), Ok(()));
+    }
+
+    #[test]
+    fn elevator_is_out_of_service_for_exactly_its_window() {
+        //Take the first of 2 elevators out of service for 20 time steps from step 100
+        let window: MaintenanceWindow = MaintenanceWindow::from(0_usize, 100_usize, 20_usize);
+        let mut config: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.6_f64);
+        config.maintenance = vec![window];
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, ControllerKind::Nearest, &ControllerParams::default(), 3_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut notice_step: Option<i32> = None;
+        let mut num_riders_at_notice: usize = 0_usize;
+        for i in 0_i32..150_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+
+            //Out of service on the window's first step, and back in service on the step after its last
+            assert_eq!(building.elevators[0].in_service, !window.is_active(i as usize), "step {}", i);
+            assert!(building.elevators[1].in_service, "step {}", i);
+            for event in building.events.iter() {
+                match event {
+                    SimEvent::MaintenanceNotice { elevator: 0_usize, .. } => {
+                        notice_step = Some(i);
+                        num_riders_at_notice = building.elevators[0].people.len();
+                        assert!(num_riders_at_notice > 0_usize, "nobody was aboard to let off at step {}", i);
+                    },
+                    SimEvent::MaintenanceStarted { elevator: 0_usize, num_discharged, .. } => {
+                        assert_eq!(i, 100_i32);
+                        assert_eq!(*num_discharged, 0_usize, "riders were still aboard when the window started");
+                    },
+                    SimEvent::MaintenanceEnded { elevator: 0_usize, .. } => assert_eq!(i, 120_i32),
+                    _ => {}
+                }
+            }
+
+            //During the notice period, nobody boards and the riders are let off
+            if notice_step.is_some() && i < 100_i32 {
+                let num_riders: usize = building.elevators[0].people.len();
+                assert!(num_riders <= num_riders_at_notice, "someone boarded at step {}", i);
+                num_riders_at_notice = num_riders;
+                assert!(!building.may_serve_passengers(0_usize), "step {}", i);
+            }
+        }
+        assert!(notice_step.is_some_and(|x| x < 100_i32), "no notice was given");
+        assert_eq!(controller.get_building().elevators[0].downtime, 20_usize);
+    }
+}
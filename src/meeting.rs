@@ -0,0 +1,227 @@
+//Import external/standard modules
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+/** MeetingModel struct schema
+ *
+ * A MeetingModel layers meetings over the destination model.  While no
+ * meeting is under way, one is called with some probability each time
+ * step on a random floor other than the lobby and the entry floors.
+ * For the duration of the meeting, a fraction of the people arriving
+ * head for its floor rather than their drawn destination.  Once it
+ * ends, the same fraction of the people idle on its floor leave the
+ * building at once, making a surge down from it.
+ *
+ * A MeetingModel has the following properties
+ * - p_start (f64): The probability a meeting is called each time step while none is under way
+ * - duration (usize): The number of time steps people gather for each meeting
+ * - fraction (f64): The fraction of the people arriving who head for the meeting, and of those on its floor who leave after it
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MeetingModel {
+    pub p_start: f64,
+    pub duration: usize,
+    pub fraction: f64
+}
+
+/** Meeting struct schema
+ *
+ * A Meeting has the following properties
+ * - floor (usize): The floor the meeting is on
+ * - steps_left (usize): The number of time steps people gather for it, counting the current one
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Meeting {
+    pub floor: usize,
+    pub steps_left: usize
+}
+
+/** MeetingModel type implementation
+ *
+ * The following functions are implemented for the MeetingModel type,
+ * and are callable via
+ *
+ * //Example
+ * let my_model: MeetingModel = MeetingModel::from(0.01_f64, 30_usize, 0.5_f64);
+ * my_model.validate()?;
+ * let my_meeting: Option<Meeting> = my_model.gen_meeting(&mut rng, num_floors, &entry_floors);
+ */
+impl MeetingModel {
+    /** MeetingModel constructor function
+     *
+     * Initialize a model calling meetings with the given probability
+     * each time step, lasting the given number of time steps, and drawing
+     * the given fraction of the people arriving.
+     */
+    pub fn from(p_start: f64, duration: usize, fraction: f64) -> MeetingModel {
+        MeetingModel {
+            p_start,
+            duration,
+            fraction
+        }
+    }
+
+    /** validate function
+     *
+     * Check that the probability of calling a meeting and the fraction
+     * drawn to it are in [0, 1], and that meetings last at least one
+     * time step.
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0_f64..=1_f64).contains(&self.p_start) {
+            return Err(format!("meeting probability {} must be in [0, 1]", self.p_start));
+        }
+        if self.duration == 0_usize {
+            return Err(String::from("meeting duration must be positive"));
+        }
+        if !(0_f64..=1_f64).contains(&self.fraction) {
+            return Err(format!("meeting fraction {} must be in [0, 1]", self.fraction));
+        }
+        Ok(())
+    }
+
+    /** gen_meeting function
+     *
+     * Decide whether a meeting is called this time step, and if so draw
+     * its floor uniformly from the floors of a building with the given
+     * number of floors other than the lobby and the entry floors.  If
+     * there are no such floors, then no meeting is called.
+     */
+    pub fn gen_meeting(&self, rng: &mut impl Rng, num_floors: usize, entry_floors: &[(usize, f64)]) -> Option<Meeting> {
+        if !rng.gen_bool(self.p_start) {
+            return None;
+        }
+        let meeting_floors: Vec<usize> = (1_usize..num_floors)
+            .filter(|floor| !entry_floors.iter().any(|(entry_floor, _)| entry_floor == floor))
+            .collect();
+        if meeting_floors.is_empty() {
+            return None;
+        }
+        Some(Meeting {
+            floor: meeting_floors[rng.gen_range(0_usize..meeting_floors.len())],
+            steps_left: self.duration
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::controller::{ElevatorController, ControllerKind};
+    use crate::simulation::{SimulationConfig, build_controller, step};
+    use crate::params::ControllerParams;
+    use crate::profile::Profiler;
+    use crate::event::SimEvent;
+    use crate::person::PersonState;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    //Step a building of 10 floors with the given meetings for 60 time steps, the last of the first meeting called, and
+    //return the controller, along with the meeting's floor and how many of those arriving elsewhere headed for it
+    fn run_until_meeting_ends(meetings: Option<MeetingModel>) -> (Box<dyn ElevatorController>, usize, usize, usize) {
+        let mut config: SimulationConfig = SimulationConfig::from(10_usize, 3_usize, 2_f64);
+        config.meetings = meetings;
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, ControllerKind::Nearest, &ControllerParams::default(), 3_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut meeting_floor: usize = 7_usize;
+        let (mut num_arrivals, mut num_to_meeting): (usize, usize) = (0_usize, 0_usize);
+        for i in 0_i32..60_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            for event in controller.get_building().events.iter() {
+                match event {
+                    SimEvent::MeetingStarted { floor } => meeting_floor = *floor,
+                    SimEvent::PersonArrived { floor, floor_to, .. } if *floor != meeting_floor => {
+                        num_arrivals += 1_usize;
+                        if *floor_to == meeting_floor {
+                            num_to_meeting += 1_usize;
+                        }
+                    },
+                    _ => {}
+                }
+            }
+        }
+        (controller, meeting_floor, num_arrivals, num_to_meeting)
+    }
+
+    #[test]
+    fn meetings_are_called_off_the_entry_floors() {
+        let model: MeetingModel = MeetingModel::from(1_f64, 30_usize, 0.5_f64);
+        let mut rng: StdRng = StdRng::seed_from_u64(0_u64);
+        for _ in 0_usize..200_usize {
+            let meeting: Meeting = model.gen_meeting(&mut rng, 6_usize, &[(0_usize, 1_f64), (3_usize, 1_f64)]).unwrap();
+            assert!([1_usize, 2_usize, 4_usize, 5_usize].contains(&meeting.floor), "meeting on {}", meeting.floor);
+            assert_eq!(meeting.steps_left, 30_usize);
+        }
+
+        //No meeting is called if it is never likely, or there is no floor to call it on
+        assert!(MeetingModel::from(0_f64, 30_usize, 0.5_f64).gen_meeting(&mut rng, 6_usize, &[(0_usize, 1_f64)]).is_none());
+        assert!(model.gen_meeting(&mut rng, 2_usize, &[(0_usize, 1_f64), (1_usize, 1_f64)]).is_none());
+        assert!(model.gen_meeting(&mut rng, 1_usize, &[(0_usize, 1_f64)]).is_none());
+    }
+
+    #[test]
+    fn validate_rejects_bad_meetings() {
+        let cases: [(MeetingModel, Result<(), String>); 4] = [
+            (MeetingModel::from(0.01_f64, 30_usize, 1_f64), Ok(())),
+            (MeetingModel::from(1.5_f64, 30_usize, 0.5_f64), Err(String::from("meeting probability 1.5 must be in [0, 1]"))),
+            (MeetingModel::from(0.01_f64, 0_usize, 0.5_f64), Err(String::from("meeting duration must be positive"))),
+            (MeetingModel::from(0.01_f64, 30_usize, -0.5_f64), Err(String::from("meeting fraction -0.5 must be in [0, 1]")))
+        ];
+        for (model, expected) in cases {
+            assert_eq!(model.validate(), expected, "{:?}", model);
+        }
+    }
+
+    #[test]
+    fn meeting_draws_arrivals_then_sends_them_down() {
+        //Without meetings, about one in nine of those arriving elsewhere head for any one floor
+        let (_, _, num_arrivals, num_to_floor) = run_until_meeting_ends(None);
+        assert!((num_to_floor as f64) < 0.2_f64 * num_arrivals as f64, "{} of {} headed for floor 7", num_to_floor, num_arrivals);
+
+        //During a meeting drawing three quarters of those arriving, the rest still head anywhere
+        let (mut controller, meeting_floor, num_arrivals, num_to_meeting) = run_until_meeting_ends(
+            Some(MeetingModel::from(1_f64, 60_usize, 0.75_f64))
+        );
+        let expected: f64 = 0.75_f64 + 0.25_f64 / 9_f64;
+        let share: f64 = num_to_meeting as f64 / num_arrivals as f64;
+        assert!(num_arrivals > 50_usize && (share - expected).abs() < 0.1_f64, "{} of {} headed for the meeting", num_to_meeting, num_arrivals);
+
+        //Stop the arrivals and meetings as the meeting ends, and note who is idle on its floor
+        let building: &mut Building = controller.get_building_mut();
+        assert_eq!(building.meeting.map(|x| x.steps_left), Some(1_usize));
+        building.set_expected_arrivals(0_f64).unwrap();
+        let num_idle: usize = building.floors[meeting_floor].get_people().iter().filter(|x| x.state == PersonState::Idle).count();
+        let mut profiler: Profiler = Profiler::from(false);
+        step(controller.as_mut(), 60_i32, &mut profiler).unwrap();
+        controller.get_building_mut().meetings = None;
+
+        //The meeting lets out about three quarters of those idle on its floor, each deciding to leave
+        let building: &Building = controller.get_building();
+        assert!(building.meeting.is_none());
+        let num_leaving: usize = building.events.iter().find_map(|x| match x {
+            SimEvent::MeetingEnded { floor, num_leaving } if *floor == meeting_floor => Some(*num_leaving),
+            _ => None
+        }).unwrap();
+        let leaving_ids: Vec<usize> = building.events.iter().filter_map(|x| match x {
+            SimEvent::PersonLeaving { person_id, floor } if *floor == meeting_floor => Some(*person_id),
+            _ => None
+        }).collect();
+        assert!(num_idle > 20_usize && num_leaving > num_idle / 2_usize && num_leaving < num_idle, "{} of {} left", num_leaving, num_idle);
+        assert!(leaving_ids.len() >= num_leaving);
+
+        //Those leaving flow down and out of the building
+        for i in 61_i32..160_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+        }
+        let building: &Building = controller.get_building();
+        for floor in building.floors.iter() {
+            assert!(!floor.get_people().iter().any(|x| leaving_ids.contains(&x.id)), "someone let out of the meeting stayed");
+        }
+        for elevator in building.elevators.iter() {
+            assert!(!elevator.people.iter().any(|x| leaving_ids.contains(&x.id)), "someone let out of the meeting stayed");
+        }
+    }
+}
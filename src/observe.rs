@@ -0,0 +1,390 @@
+//Import source modules
+use crate::building::Building;
+use crate::elevator::Elevator;
+use crate::floor::Floor;
+use crate::people::People;
+use crate::units::Ticks;
+
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+/** ObservabilityLevel enum
+ *
+ * How much of the building a controller sees when deciding where to
+ * send the elevators.
+ * - Full: Everything, including who is waiting on each floor and where they are going
+ * - CallsOnly: Only the hall calls, with their direction and age, and the elevators' states, as a real dispatcher would
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum ObservabilityLevel {
+    #[default]
+    Full,
+    CallsOnly
+}
+
+/** ObservabilityLevel type implementation
+ *
+ * The following functions are implemented for the ObservabilityLevel
+ * type, and are callable via
+ *
+ * //Example
+ * let is_full: bool = ObservabilityLevel::CallsOnly.is_full();
+ */
+impl ObservabilityLevel {
+    /** is_full function
+     *
+     * Return whether the controller sees everything.
+     */
+    pub fn is_full(&self) -> bool {
+        *self == ObservabilityLevel::Full
+    }
+}
+
+/** HallCalls struct schema
+ *
+ * A HallCalls is the registry of the up and down buttons pressed on
+ * each floor, which is all a real dispatcher knows of the people
+ * waiting.
+ *
+ * A HallCalls has the following properties
+ * - up (Vec<Option<Ticks>>): The age of the up call on each floor, None if there is none
+ * - down (Vec<Option<Ticks>>): The age of the down call on each floor, None if there is none
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct HallCalls {
+    up: Vec<Option<Ticks>>,
+    down: Vec<Option<Ticks>>
+}
+
+/** HallCalls type implementation
+ *
+ * The following functions are implemented for the HallCalls type,
+ * and are callable via
+ *
+ * //Example
+ * let my_calls: HallCalls = HallCalls::from(&my_building.floors);
+ * let is_called: bool = my_calls.is_called(3_usize);
+ */
+impl HallCalls {
+    /** HallCalls constructor function
+     *
+     * Register the calls of the people waiting on each floor, each call
+     * as old as the longest anyone has waited for it.
+     */
+    pub fn from(floors: &[Floor]) -> HallCalls {
+        let mut up: Vec<Option<Ticks>> = vec![None; floors.len()];
+        let mut down: Vec<Option<Ticks>> = vec![None; floors.len()];
+        for (i, floor) in floors.iter().enumerate() {
+            for pers in floor.waiting() {
+                let call: &mut Option<Ticks> = if pers.floor_to > i { &mut up[i] } else { &mut down[i] };
+                *call = Some(call.map_or(pers.wait_time, |x| x.max(pers.wait_time)));
+            }
+        }
+        HallCalls {
+            up,
+            down
+        }
+    }
+
+    /** is_called function
+     *
+     * Return whether either button is pressed on the given floor.
+     */
+    pub fn is_called(&self, floor_index: usize) -> bool {
+        self.up[floor_index].is_some() || self.down[floor_index].is_some()
+    }
+
+    /** get_direction_calls function
+     *
+     * Return whether the up and down buttons are pressed on the given
+     * floor.
+     */
+    pub fn get_direction_calls(&self, floor_index: usize) -> (bool, bool) {
+        (self.up[floor_index].is_some(), self.down[floor_index].is_some())
+    }
+
+    /** get_age function
+     *
+     * Return the age of the older call on the given floor, if any.
+     */
+    pub fn get_age(&self, floor_index: usize) -> Option<Ticks> {
+        match (self.up[floor_index], self.down[floor_index]) {
+            (Some(up), Some(down)) => Some(up.max(down)),
+            (up, down) => up.or(down)
+        }
+    }
+
+    /** get_oldest_call function
+     *
+     * Return the floor with the oldest call among the floors not
+     * claimed, and its age, ties to the lower floor, if any.
+     */
+    pub fn get_oldest_call(&self, claimed: &[bool]) -> Option<(usize, Ticks)> {
+        let mut oldest_call: Option<(usize, Ticks)> = None;
+        for i in 0_usize..self.up.len() {
+            if claimed.get(i).copied().unwrap_or(false) {
+                continue;
+            }
+            match (oldest_call, self.get_age(i)) {
+                (Some((_, max_age)), Some(age)) if max_age >= age => {},
+                (_, Some(age)) => oldest_call = Some((i, age)),
+                (_, None) => {}
+            }
+        }
+        oldest_call
+    }
+}
+
+/** CarState struct schema
+ *
+ * A CarState is what a real dispatcher knows of an elevator, its
+ * position and motion, its load and the buttons pressed inside it.
+ *
+ * A CarState has the following properties
+ * - floor_on (usize): The floor the elevator is on
+ * - floor_to (Option<usize>): The floor the elevator is headed to, if any
+ * - moving_up (bool): Whether the elevator is moving up
+ * - stopped (bool): Whether the elevator is stopped
+ * - free_capacity (usize): The number of people who may still get on
+ * - dest_counts (Vec<usize>): The number of passengers headed to each floor
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct CarState {
+    pub floor_on: usize,
+    pub floor_to: Option<usize>,
+    pub moving_up: bool,
+    pub stopped: bool,
+    pub free_capacity: usize,
+    pub dest_counts: Vec<usize>
+}
+
+/** CallsOnlyView struct schema
+ *
+ * A CallsOnlyView is a read-only view of a building restricted to
+ * what a real dispatcher knows, the hall calls, the elevators' states
+ * and the floors each elevator serves.  The people waiting on each
+ * floor, their destinations and the floors' destination probabilities
+ * are hidden behind it.
+ *
+ * A CallsOnlyView has the following properties
+ * - building (&Building): The building viewed
+ * - calls (HallCalls): The hall calls registered on each floor
+ */
+pub struct CallsOnlyView<'a> {
+    building: &'a Building,
+    calls: HallCalls
+}
+
+/** CallsOnlyView type implementation
+ *
+ * The following functions are implemented for the CallsOnlyView type,
+ * and are callable via
+ *
+ * //Example
+ * let my_view: CallsOnlyView = CallsOnlyView::from(&my_building);
+ * let my_hall_calls: Vec<bool> = my_view.get_hall_calls(0_usize);
+ */
+impl<'a> CallsOnlyView<'a> {
+    /** CallsOnlyView constructor function
+     *
+     * Initialize a view of the given building, registering its hall
+     * calls.
+     */
+    pub fn from(building: &'a Building) -> CallsOnlyView<'a> {
+        CallsOnlyView {
+            building,
+            calls: HallCalls::from(&building.floors)
+        }
+    }
+
+    /** get_num_floors function
+     *
+     * Return the number of floors in the building.
+     */
+    pub fn get_num_floors(&self) -> usize {
+        self.building.floors.len()
+    }
+
+    /** get_num_elevators function
+     *
+     * Return the number of elevators in the building.
+     */
+    pub fn get_num_elevators(&self) -> usize {
+        self.building.elevators.len()
+    }
+
+    /** get_calls function
+     *
+     * Return the hall calls registered on each floor.
+     */
+    pub fn get_calls(&self) -> &HallCalls {
+        &self.calls
+    }
+
+    /** serves function
+     *
+     * Return whether the elevator at the given index serves the given
+     * floor.  Without zones, every elevator serves every floor.
+     */
+    pub fn serves(&self, elevator: usize, floor_index: usize) -> bool {
+        self.building.zones.as_ref().is_none_or(|zones| zones.serves(elevator, floor_index))
+    }
+
+    /** get_hall_calls function
+     *
+     * Return whether each floor has a call the elevator at the given
+     * index may answer, being on a floor it serves.  Without knowing
+     * the destinations of the people calling, the elevator answers them
+     * even if they are headed to a floor it does not serve.
+     */
+    pub fn get_hall_calls(&self, elevator: usize) -> Vec<bool> {
+        (0_usize..self.get_num_floors())
+            .map(|i| self.calls.is_called(i) && self.serves(elevator, i))
+            .collect()
+    }
+
+    /** get_car_state function
+     *
+     * Return the state of the elevator at the given index.
+     */
+    pub fn get_car_state(&self, elevator: usize) -> CarState {
+        let car: &Elevator = &self.building.elevators[elevator];
+        let mut dest_counts: Vec<usize> = vec![0_usize; self.get_num_floors()];
+        for pers in car.people.iter() {
+            dest_counts[pers.floor_to] += 1_usize;
+        }
+        CarState {
+            floor_on: car.floor_on,
+            floor_to: car.floor_to,
+            moving_up: car.moving_up,
+            stopped: car.stopped,
+            free_capacity: car.free_capacity(),
+            dest_counts
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{get_empty_building, get_caller};
+    use crate::person::Person;
+    use crate::zone::ZoneMap;
+    use crate::controller::{ElevatorController, NearestController, MaxWaitController};
+
+    //Build a building of 10 floors and 2 elevators on the ground floor, with the given people waiting
+    fn get_called_building(callers: Vec<Person>) -> Building {
+        let mut building: Building = get_empty_building(10_usize, 2_usize);
+        for pers in callers {
+            building.place_person(pers, None);
+        }
+        building.update_cache();
+        building
+    }
+
+    //Let the controller decide where to send the elevators, returning where each is headed and whether it is stopped
+    fn get_decisions(controller: &mut impl ElevatorController) -> Vec<(Option<usize>, bool)> {
+        controller.update_elevators();
+        controller.get_building().elevators.iter().map(|x| (x.floor_to, x.stopped)).collect()
+    }
+
+    #[test]
+    fn hall_calls_are_aged_by_their_oldest_caller() {
+        let building: Building = get_called_building(vec![
+            get_caller(0_usize, 3_usize, 7_usize, 2_u64),
+            get_caller(1_usize, 3_usize, 9_usize, 6_u64),
+            get_caller(2_usize, 3_usize, 0_usize, 4_u64),
+            get_caller(3_usize, 8_usize, 1_usize, 6_u64)
+        ]);
+        let calls: HallCalls = HallCalls::from(&building.floors);
+
+        //Floor 3 has both buttons pressed, each as old as its oldest caller, and floor 8 only the down button
+        assert_eq!(calls.get_direction_calls(3_usize), (true, true));
+        assert_eq!(calls.get_direction_calls(8_usize), (false, true));
+        assert_eq!((calls.get_age(3_usize), calls.get_age(8_usize), calls.get_age(5_usize)), (Some(Ticks(6_u64)), Some(Ticks(6_u64)), None));
+        assert!(calls.is_called(3_usize) && calls.is_called(8_usize) && !calls.is_called(0_usize));
+
+        //The oldest calls tie, so the lower floor is first, then the other once it is claimed
+        let mut claimed: Vec<bool> = vec![false; 10_usize];
+        assert_eq!(calls.get_oldest_call(&claimed), Some((3_usize, Ticks(6_u64))));
+        claimed[3] = true;
+        assert_eq!(calls.get_oldest_call(&claimed), Some((8_usize, Ticks(6_u64))));
+        claimed[8] = true;
+        assert_eq!(calls.get_oldest_call(&claimed), None);
+    }
+
+    #[test]
+    fn view_shows_calls_and_cars_only() {
+        let mut building: Building = get_called_building(vec![get_caller(0_usize, 4_usize, 7_usize, 1_u64)]);
+        building.set_capacity(Some(6_usize));
+        building.elevators[1].floor_on = 2_usize;
+        building.elevators[1].floor_to = Some(5_usize);
+        building.elevators[1].stopped = false;
+        building.elevators[1].moving_up = true;
+        for (i, floor_to) in [5_usize, 5_usize, 9_usize].iter().enumerate() {
+            let mut rider: Person = get_caller(1_usize + i, 2_usize, *floor_to, 0_u64);
+            rider.board();
+            building.place_person(rider, Some(1_usize));
+        }
+        building.set_zones(Some(ZoneMap::odd_even(10_usize, 2_usize)));
+        let view: CallsOnlyView = CallsOnlyView::from(&building);
+
+        //The elevators' states count their passengers by destination
+        assert_eq!((view.get_num_floors(), view.get_num_elevators()), (10_usize, 2_usize));
+        assert_eq!(view.get_car_state(1_usize), CarState {
+            floor_on: 2_usize,
+            floor_to: Some(5_usize),
+            moving_up: true,
+            stopped: false,
+            free_capacity: 3_usize,
+            dest_counts: vec![0_usize, 0_usize, 0_usize, 0_usize, 0_usize, 2_usize, 0_usize, 0_usize, 0_usize, 1_usize]
+        });
+        assert_eq!(view.get_car_state(0_usize).dest_counts, vec![0_usize; 10_usize]);
+
+        //The call on floor 4 is answered by the elevator serving floor 4, though its caller is headed to an odd floor
+        let expected: Vec<bool> = (0_usize..10_usize).map(|x| x == 4_usize).collect();
+        assert_eq!(view.get_hall_calls(0_usize), expected);
+        assert_eq!(view.get_hall_calls(1_usize), vec![false; 10_usize]);
+    }
+
+    #[test]
+    fn calls_only_controllers_ignore_what_the_calls_hide() {
+        //Two buildings with the same calls, one with a single caller to an even floor and one with several
+        //callers, whose oldest is headed to an odd floor no elevator serving floor 4 serves
+        let get_buildings = || (
+            get_called_building(vec![get_caller(0_usize, 4_usize, 8_usize, 5_u64)]),
+            get_called_building(vec![
+                get_caller(0_usize, 4_usize, 7_usize, 5_u64),
+                get_caller(1_usize, 4_usize, 9_usize, 2_u64),
+                get_caller(2_usize, 4_usize, 5_usize, 0_u64)
+            ])
+        );
+
+        //Seeing everything, the zoned controller sends an elevator for the one caller it may deliver, but not the others
+        let (lone, crowd) = get_buildings();
+        let mut lone_controller: NearestController = NearestController::from_zones(lone, ZoneMap::odd_even(10_usize, 2_usize));
+        let mut crowd_controller: NearestController = NearestController::from_zones(crowd, ZoneMap::odd_even(10_usize, 2_usize));
+        assert_ne!(get_decisions(&mut lone_controller), get_decisions(&mut crowd_controller));
+
+        //Seeing only the calls, each controller decides the same in both buildings
+        let (lone, crowd) = get_buildings();
+        let mut lone_controller: NearestController = NearestController::from_zones(lone, ZoneMap::odd_even(10_usize, 2_usize));
+        let mut crowd_controller: NearestController = NearestController::from_zones(crowd, ZoneMap::odd_even(10_usize, 2_usize));
+        lone_controller.observability = ObservabilityLevel::CallsOnly;
+        crowd_controller.observability = ObservabilityLevel::CallsOnly;
+        let decisions: Vec<(Option<usize>, bool)> = get_decisions(&mut lone_controller);
+        assert_eq!(decisions, get_decisions(&mut crowd_controller));
+        assert_eq!(decisions[0], (Some(4_usize), false));
+
+        let (lone, crowd) = get_buildings();
+        let mut lone_controller: MaxWaitController = MaxWaitController::from(lone);
+        let mut crowd_controller: MaxWaitController = MaxWaitController::from(crowd);
+        lone_controller.observability = ObservabilityLevel::CallsOnly;
+        crowd_controller.observability = ObservabilityLevel::CallsOnly;
+        let decisions: Vec<(Option<usize>, bool)> = get_decisions(&mut lone_controller);
+        assert_eq!(decisions, get_decisions(&mut crowd_controller));
+        assert_eq!(decisions[0], (Some(4_usize), false));
+    }
+}
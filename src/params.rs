@@ -0,0 +1,218 @@
+//Import external/standard modules
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{Serialize, Deserialize};
+
+/** ParamSpec struct schema
+ *
+ * A ParamSpec has the following properties
+ * - name (&str): The key the parameter is given by
+ * - default (f64): The value of the parameter when it is not given
+ * - step (f64): The amount a tuner perturbs the parameter by
+ * - help (&str): A description of the parameter
+ */
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub default: f64,
+    pub step: f64,
+    pub help: &'static str
+}
+
+//Display trait implementation for a parameter spec, as name=default: help
+impl fmt::Display for ParamSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}\t{}", self.name, self.default, self.help)
+    }
+}
+
+/** ControllerParams struct schema
+ *
+ * A ControllerParams has the following properties
+ * - values (BTreeMap<String, f64>): The value of each parameter given, by key
+ *
+ * Parameters not given take the default of their spec, so an empty
+ * map runs a controller exactly as it was built.
+ */
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ControllerParams {
+    pub values: BTreeMap<String, f64>
+}
+
+/** ControllerParams type implementation
+ *
+ * The following functions are implemented for the ControllerParams
+ * type, and are callable via
+ *
+ * //Example
+ * let my_params: ControllerParams = ControllerParams::parse(&my_args)?;
+ * my_params.validate("nearest", ControllerKind::Nearest.get_param_specs())?;
+ * let my_capacity: f64 = my_params.get(&NEAREST_PARAMS[1]);
+ */
+impl ControllerParams {
+    /** ControllerParams constructor function
+     *
+     * Initialize an empty map, in which every parameter is its default
+     */
+    pub fn new() -> ControllerParams {
+        ControllerParams {
+            values: BTreeMap::new()
+        }
+    }
+
+    /** parse function
+     *
+     * Parse a list of key=value arguments into a map.  A key given
+     * more than once takes its last value.
+     */
+    pub fn parse(args: &[String]) -> Result<ControllerParams, String> {
+        let mut params: ControllerParams = ControllerParams::new();
+        for arg in args.iter() {
+            let (key, value_str): (&str, &str) = match arg.split_once('=') {
+                Some((key, value_str)) if !key.trim().is_empty() => (key.trim(), value_str.trim()),
+                _ => return Err(format!("controller argument '{}' is not of the form key=value", arg))
+            };
+            let value: f64 = match value_str.parse::<f64>() {
+                Ok(x) if x.is_finite() => x,
+                _ => return Err(format!("controller argument {} has value '{}', which is not a number", key, value_str))
+            };
+            params.values.insert(String::from(key), value);
+        }
+        Ok(params)
+    }
+
+    /** validate function
+     *
+     * Check that every parameter given is one of the given specs of
+     * the named controller, listing the valid keys if not.
+     */
+    pub fn validate(&self, controller_name: &str, specs: &[ParamSpec]) -> Result<(), String> {
+        for key in self.values.keys() {
+            if specs.iter().any(|spec| spec.name == key) {
+                continue;
+            }
+            if specs.is_empty() {
+                return Err(format!("unknown parameter {}, the {} controller takes no parameters", key, controller_name));
+            }
+            let valid_keys: Vec<&str> = specs.iter().map(|spec| spec.name).collect();
+            return Err(format!(
+                "unknown parameter {} for the {} controller, valid keys are {}", key, controller_name, valid_keys.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /** get function
+     *
+     * Return the value of the given parameter, or its default if it
+     * was not given.
+     */
+    pub fn get(&self, spec: &ParamSpec) -> f64 {
+        self.values.get(spec.name).copied().unwrap_or(spec.default)
+    }
+
+    /** set function
+     *
+     * Set the value of the parameter with the given key
+     */
+    pub fn set(&mut self, key: &str, value: f64) {
+        self.values.insert(String::from(key), value);
+    }
+
+    /** get_args function
+     *
+     * Return the parameters as the --controller-arg flags which give
+     * them on the command line.
+     */
+    pub fn get_args(&self) -> String {
+        let args: Vec<String> = self.values.iter()
+            .map(|(key, value)| format!("--controller-arg {}={}", key, value))
+            .collect();
+        args.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{ControllerKind, NEAREST_PARAMS};
+    use crate::simulation::{SimulationConfig, SimulationResult, run_simulation};
+
+    //Parse the given key=value arguments
+    fn parse(args: &[&str]) -> Result<ControllerParams, String> {
+        ControllerParams::parse(&args.iter().map(|x| String::from(*x)).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn parse_reads_key_value_pairs() {
+        //Keys and values are trimmed, and a key given twice takes its last value
+        let params: ControllerParams = parse(&["shuttle=1", " stop_notice = 3 ", "shuttle=0"]).unwrap();
+        assert_eq!(params.values.len(), 2_usize);
+        assert_eq!(params.get(&NEAREST_PARAMS[0]), 0_f64);
+        assert_eq!(params.get(&NEAREST_PARAMS[4]), 3_f64);
+
+        //Parameters not given take their default
+        assert_eq!(params.get(&NEAREST_PARAMS[3]), NEAREST_PARAMS[3].default);
+        assert_eq!(params.get_args(), "--controller-arg shuttle=0 --controller-arg stop_notice=3");
+        assert!(parse(&[]).unwrap().values.is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_malformed_arguments() {
+        let cases: [(&str, &str); 5] = [
+            ("shuttle", "controller argument 'shuttle' is not of the form key=value"),
+            ("=1", "controller argument '=1' is not of the form key=value"),
+            ("shuttle=one", "controller argument shuttle has value 'one', which is not a number"),
+            ("shuttle=", "controller argument shuttle has value '', which is not a number"),
+            ("shuttle=inf", "controller argument shuttle has value 'inf', which is not a number")
+        ];
+        for (arg, message) in cases {
+            assert_eq!(parse(&[arg]).unwrap_err(), message);
+        }
+    }
+
+    #[test]
+    fn validate_params_names_each_invalid_parameter() {
+        let cases: [(ControllerKind, &str, &str); 11] = [
+            (ControllerKind::Random, "shuttle=0", "unknown parameter shuttle, the random controller takes no parameters"),
+            (
+                ControllerKind::Nearest, "speed=2",
+                "unknown parameter speed for the nearest controller, valid keys are shuttle, shuttle_capacity, shuttle_dwell, preempt, stop_notice, plan_pickups"
+            ),
+            (ControllerKind::Nearest, "shuttle=2", "shuttle 2 is not -1 or an elevator of a building with 2 elevators"),
+            (ControllerKind::OddEven, "shuttle=-2", "shuttle -2 is not -1 or an elevator of a building with 2 elevators"),
+            (ControllerKind::Nearest, "shuttle=0.5", "shuttle 0.5 is not -1 or an elevator of a building with 2 elevators"),
+            (ControllerKind::Nearest, "shuttle_capacity=0", "shuttle_capacity 0 is not a positive whole number"),
+            (ControllerKind::Nearest, "shuttle_dwell=-1", "shuttle_dwell -1 is not a nonnegative whole number"),
+            (ControllerKind::Nearest, "preempt=2", "preempt 2 is not 0 or 1"),
+            (ControllerKind::Nearest, "stop_notice=1.5", "stop_notice 1.5 is not a nonnegative whole number"),
+            (ControllerKind::Nearest, "plan_pickups=0.5", "plan_pickups 0.5 is not 0 or 1"),
+            (ControllerKind::MaxWait, "preempt=0", "unknown parameter preempt, the max-wait controller takes no parameters")
+        ];
+        for (controller_kind, arg, message) in cases {
+            assert_eq!(controller_kind.validate_params(&parse(&[arg]).unwrap(), 2_usize), Err(String::from(message)), "{}", arg);
+        }
+
+        //Valid parameters, and none at all, are accepted
+        let params: ControllerParams = parse(&["shuttle=1", "shuttle_dwell=0", "preempt=0", "stop_notice=0", "plan_pickups=1"]).unwrap();
+        assert_eq!(ControllerKind::Nearest.validate_params(&params, 2_usize), Ok(()));
+        assert_eq!(ControllerKind::Random.validate_params(&ControllerParams::new(), 2_usize), Ok(()));
+    }
+
+    #[test]
+    fn changed_parameter_changes_the_seeded_run() {
+        let config: SimulationConfig = SimulationConfig::from(10_usize, 2_usize, 0.8_f64);
+        let default_result: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &ControllerParams::new(), 3_u64).unwrap();
+
+        //Giving a parameter its default leaves the run unchanged
+        let params: ControllerParams = parse(&["stop_notice=1"]).unwrap();
+        let same_result: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &params, 3_u64).unwrap();
+        assert_eq!(same_result.wait, default_result.wait);
+
+        //Whereas a longer notice before inserting a stop changes it
+        let params: ControllerParams = parse(&["stop_notice=8"]).unwrap();
+        let changed_result: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &params, 3_u64).unwrap();
+        assert_ne!(changed_result.wait, default_result.wait);
+        assert_eq!(changed_result.arrivals, default_result.arrivals);
+    }
+}
@@ -1,5 +1,32 @@
 //Import source modules
 use crate::person::Person;
+use crate::units::Ticks;
+
+/** drain_where function
+ *
+ * Remove the people matching the given predicate from a vector of
+ * people in a single pass, preserving the order of both the people
+ * removed and the people remaining.  Return a vector containing the
+ * people removed.
+ */
+pub fn drain_where(people: &mut Vec<Person>, mut pred: impl FnMut(&Person) -> bool) -> Vec<Person> {
+    //Initialize vectors for the people removed and the people remaining
+    let mut drained: Vec<Person> = Vec::new();
+    let mut remaining: Vec<Person> = Vec::with_capacity(people.len());
+
+    //Move each person into one of the vectors based on the predicate
+    for pers in people.drain(..) {
+        if pred(&pers) {
+            drained.push(pers);
+        } else {
+            remaining.push(pers);
+        }
+    }
+
+    //Replace the people with the people remaining and return the people removed
+    *people = remaining;
+    drained
+}
 
 //Define people trait
 pub trait People {
@@ -9,19 +36,21 @@ pub trait People {
 
     fn get_num_people_waiting(&self) -> usize;
 
-    fn get_aggregate_wait_time(&self) -> usize;
+    fn get_aggregate_wait_time(&self) -> Ticks;
 
     fn are_people_going_to_floor(&self, floor_index: usize) -> bool;
 
     fn are_people_waiting(&self) -> bool;
 
+    fn waiting(&self) -> impl Iterator<Item=&Person>;
+
     fn increment_wait_times(&mut self);
 
     fn reset_wait_times(&mut self);
 }
 
-//Implement people trait for Vec<Person>
-impl People for Vec<Person> {
+//Implement people trait for a slice of people, and so for Vec<Person> through deref
+impl People for [Person] {
     /** get_dest_floors function
      *
      * For a collection of people, return a vector of their destination
@@ -65,7 +94,7 @@ impl People for Vec<Person> {
         //Loop through the vector of persons
         for pers in self.iter() {
             //Skip if the person is not waiting
-            if pers.floor_on == pers.floor_to {
+            if !pers.is_travelling() {
                 continue;
             }
 
@@ -79,20 +108,20 @@ impl People for Vec<Person> {
 
     /** get_aggregate_wait_time function
      *
-     * For a collection of people, return a usize counting the
-     * total number of time steps they've been waiting.
+     * For a collection of people, return the total number of time
+     * steps they've been waiting.
      */
-    fn get_aggregate_wait_time(&self) -> usize {
-        //Initialize a usize for the number of time steps the people spent waiting
-        let mut aggregate_wait_time: usize = 0_usize;
+    fn get_aggregate_wait_time(&self) -> Ticks {
+        //Initialize the number of time steps the people spent waiting
+        let mut aggregate_wait_time: Ticks = Ticks(0_u64);
 
         //Loop through the vector of persons
         for pers in self.iter() {
-            //Increment the usize with their wait time
-            aggregate_wait_time += pers.wait_time;
+            //Increment the total with their wait time, saturating rather than overflowing
+            aggregate_wait_time = aggregate_wait_time.saturating_add(pers.wait_time);
         }
 
-        //Return the usize
+        //Return the total
         aggregate_wait_time
     }
 
@@ -133,7 +162,7 @@ impl People for Vec<Person> {
         //Loop through the people and check if they are waiting
         for pers in self.iter() {
             //If the person is not waiting, then skip
-            if pers.floor_on == pers.floor_to {
+            if !pers.is_travelling() {
                 continue;
             }
 
@@ -146,6 +175,15 @@ impl People for Vec<Person> {
         is_waiting
     }
 
+    /** waiting function
+     *
+     * For a collection of people, return an iterator over those of them
+     * waiting for an elevator, without collecting them.
+     */
+    fn waiting(&self) -> impl Iterator<Item=&Person> {
+        self.iter().filter(|pers| pers.is_waiting())
+    }
+
     /** increment_wait_times function
      *
      * For a collection of people, increment their wait times.
@@ -167,4 +205,171 @@ impl People for Vec<Person> {
             pers.reset_wait_time();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::floor::Floor;
+    use crate::elevator::{Elevator, ElevatorProfile};
+    use rand::Rng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    //Constant representing the number of people drained in each trial
+    const NUM_PEOPLE: usize = 10_000_usize;
+
+    //Initialize people with consecutive ids on random floors, heading to random floors
+    fn get_people(rng: &mut StdRng) -> Vec<Person> {
+        (0_usize..NUM_PEOPLE).map(|i| {
+            let mut pers: Person = Person::from_dest(0.5_f64, rng.gen_range(0_usize..20_usize), rng.gen_range(0_usize..20_usize)).unwrap();
+            pers.id = i;
+            pers
+        }).collect()
+    }
+
+    //Initialize people in every state, some riding to their floor and some exiting, with random wait times
+    fn get_mixed_people(rng: &mut StdRng) -> Vec<Person> {
+        (0_usize..200_usize).map(|i| {
+            let mut pers: Person = Person::from_dest(0.5_f64, rng.gen_range(0_usize..6_usize), rng.gen_range(0_usize..6_usize)).unwrap();
+            pers.id = i;
+            pers.wait_time = Ticks(rng.gen_range(0_u64..50_u64));
+            if pers.is_waiting() && rng.gen_bool(0.5_f64) {
+                pers.board();
+                if rng.gen_bool(0.5_f64) {
+                    pers.floor_on = pers.floor_to;
+                }
+            } else if !pers.is_waiting() && rng.gen_bool(0.5_f64) {
+                pers.begin_exit();
+            }
+            pers
+        }).collect()
+    }
+
+    #[test]
+    fn slices_agree_with_the_whole_vec() {
+        let mut rng: StdRng = StdRng::seed_from_u64(2_u64);
+        let people: Vec<Person> = get_mixed_people(&mut rng);
+
+        //The vec, through deref, agrees with a plain pass over the people
+        let num_travelling: usize = people.iter().filter(|pers| pers.is_travelling()).count();
+        let waiting_ids: Vec<usize> = people.iter().filter(|pers| pers.is_waiting()).map(|pers| pers.id).collect();
+        assert!(num_travelling > waiting_ids.len() && !waiting_ids.is_empty() && waiting_ids.len() < people.len());
+        assert_eq!(people.get_num_people(), people.len());
+        assert_eq!(people.get_num_people_waiting(), num_travelling);
+        assert_eq!(people.get_dest_floors(), people.iter().map(|pers| pers.floor_to).collect::<Vec<usize>>());
+        assert_eq!(people.get_aggregate_wait_time().0, people.iter().map(|pers| pers.wait_time.0).sum::<u64>());
+        assert_eq!(people.waiting().map(|pers| pers.id).collect::<Vec<usize>>(), waiting_ids);
+        for floor_index in 0_usize..7_usize {
+            assert_eq!(
+                people.are_people_going_to_floor(floor_index),
+                people.iter().any(|pers| pers.floor_to == floor_index),
+                "floor {}", floor_index
+            );
+        }
+
+        //Splitting the people into slices, the results of the slices combine into those of the vec
+        for chunk_size in [1_usize, 7_usize, 64_usize, 200_usize] {
+            let chunks: Vec<&[Person]> = people.chunks(chunk_size).collect();
+            assert_eq!(chunks.iter().map(|x| x.get_num_people()).sum::<usize>(), people.get_num_people(), "chunks of {}", chunk_size);
+            assert_eq!(chunks.iter().map(|x| x.get_num_people_waiting()).sum::<usize>(), people.get_num_people_waiting(), "chunks of {}", chunk_size);
+            assert_eq!(chunks.iter().flat_map(|x| x.get_dest_floors()).collect::<Vec<usize>>(), people.get_dest_floors(), "chunks of {}", chunk_size);
+            assert_eq!(
+                chunks.iter().map(|x| x.get_aggregate_wait_time().0).sum::<u64>(),
+                people.get_aggregate_wait_time().0,
+                "chunks of {}", chunk_size
+            );
+            assert_eq!(chunks.iter().any(|x| x.are_people_waiting()), people.are_people_waiting(), "chunks of {}", chunk_size);
+            assert_eq!(
+                chunks.iter().flat_map(|x| x.waiting()).map(|pers| pers.id).collect::<Vec<usize>>(),
+                waiting_ids,
+                "chunks of {}", chunk_size
+            );
+            for floor_index in 0_usize..7_usize {
+                assert_eq!(
+                    chunks.iter().any(|x| x.are_people_going_to_floor(floor_index)),
+                    people.are_people_going_to_floor(floor_index),
+                    "chunks of {}, floor {}", chunk_size, floor_index
+                );
+            }
+        }
+
+        //An empty slice has nobody in it
+        let empty: &[Person] = &people[..0_usize];
+        assert_eq!((empty.get_num_people(), empty.get_num_people_waiting()), (0_usize, 0_usize));
+        assert!(!empty.are_people_waiting() && !empty.are_people_going_to_floor(0_usize) && empty.waiting().next().is_none());
+    }
+
+    #[test]
+    fn slice_wait_times_change_only_the_slice() {
+        let mut rng: StdRng = StdRng::seed_from_u64(3_u64);
+        let mut people: Vec<Person> = get_mixed_people(&mut rng);
+        let wait_times: Vec<Ticks> = people.iter().map(|pers| pers.wait_time).collect();
+
+        //Incrementing the wait times of the first half leaves the second half alone
+        people[..100_usize].increment_wait_times();
+        for (i, (pers, wait_time)) in people.iter().zip(wait_times.iter()).enumerate() {
+            let expected: u64 = if i < 100_usize { wait_time.0 + 1_u64 } else { wait_time.0 };
+            assert_eq!(pers.wait_time, Ticks(expected), "person {}", i);
+        }
+
+        //Resetting the wait times of the second half leaves the first half alone
+        people[100_usize..].reset_wait_times();
+        for (i, (pers, wait_time)) in people.iter().zip(wait_times.iter()).enumerate() {
+            let expected: u64 = if i < 100_usize { wait_time.0 + 1_u64 } else { 0_u64 };
+            assert_eq!(pers.wait_time, Ticks(expected), "person {}", i);
+        }
+    }
+
+    #[test]
+    fn floors_and_elevators_agree_with_their_people() {
+        //Give the same people to a floor and to an elevator
+        let people: Vec<Person> = get_mixed_people(&mut StdRng::seed_from_u64(4_u64));
+        let mut floor: Floor = Floor::new();
+        floor.extend(get_mixed_people(&mut StdRng::seed_from_u64(4_u64)));
+        let mut elevator: Elevator = Elevator::from(ElevatorProfile::Passenger, 5_f64, 2.5_f64, 0.5_f64);
+        elevator.extend(get_mixed_people(&mut StdRng::seed_from_u64(4_u64)));
+
+        //The floor and the elevator answer as their people do
+        let waiting_ids: Vec<usize> = people.waiting().map(|pers| pers.id).collect();
+        assert_eq!(floor.waiting().map(|pers| pers.id).collect::<Vec<usize>>(), waiting_ids);
+        assert_eq!(floor.get_num_people_waiting(), people.get_num_people_waiting());
+        assert_eq!(floor.get_dest_floors(), people.get_dest_floors());
+        assert_eq!(floor.get_aggregate_wait_time(), people.get_aggregate_wait_time());
+        assert_eq!(
+            elevator.waiting().map(|pers| pers.id).collect::<Vec<usize>>(),
+            waiting_ids
+        );
+        assert_eq!(elevator.get_num_people_waiting(), people.get_num_people_waiting());
+        assert_eq!(elevator.get_dest_floors(), people.get_dest_floors());
+        assert_eq!(elevator.get_aggregate_wait_time(), people.get_aggregate_wait_time());
+    }
+
+    #[test]
+    fn drain_where_partitions_in_order() {
+        let mut rng: StdRng = StdRng::seed_from_u64(0_u64);
+        for trial in 0_usize..10_usize {
+            //Drain the people on a random subset of the floors
+            let mut people: Vec<Person> = get_people(&mut rng);
+            let drained_floors: Vec<bool> = (0_usize..20_usize).map(|_| rng.gen_bool(0.5_f64)).collect();
+            let drained: Vec<Person> = drain_where(&mut people, |pers| drained_floors[pers.floor_on]);
+
+            //Every person is kept exactly once, each side matches the predicate and keeps its order
+            assert_eq!(drained.len() + people.len(), NUM_PEOPLE, "trial {}", trial);
+            assert!(drained.iter().all(|pers| drained_floors[pers.floor_on]), "trial {}", trial);
+            assert!(people.iter().all(|pers| !drained_floors[pers.floor_on]), "trial {}", trial);
+            assert!(drained.windows(2_usize).all(|x| x[0_usize].id < x[1_usize].id), "trial {}", trial);
+            assert!(people.windows(2_usize).all(|x| x[0_usize].id < x[1_usize].id), "trial {}", trial);
+        }
+    }
+
+    #[test]
+    fn drain_where_all_or_none() {
+        let mut rng: StdRng = StdRng::seed_from_u64(1_u64);
+        let mut people: Vec<Person> = get_people(&mut rng);
+        assert!(drain_where(&mut people, |_| false).is_empty());
+        assert_eq!(people.len(), NUM_PEOPLE);
+        assert_eq!(drain_where(&mut people, |_| true).len(), NUM_PEOPLE);
+        assert!(people.is_empty());
+    }
+}
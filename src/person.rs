@@ -2,19 +2,64 @@
 use std::fmt;
 use rand::Rng;
 use rand::distributions::{Distribution, Uniform, Bernoulli};
+use serde::{Serialize, Deserialize};
+
+//Import source modules
+use crate::error::ElevatorError;
+use crate::units::Ticks;
+
+/** MAX_WAIT_TIME constant
+ *
+ * The number of time steps at which a person's wait and ride time
+ * counters saturate, so that someone who is never served cannot
+ * overflow them, nor the wider sums they are aggregated into.
+ */
+pub const MAX_WAIT_TIME: Ticks = Ticks(u32::MAX as u64);
+
+/** PersonState enum
+ *
+ * The stage of a trip a person is at, changed only by the transitions
+ * of the Person type.
+ * - Idle: On a floor, at their destination
+ * - WaitingUp: On a floor, waiting for an elevator up to their destination
+ * - WaitingDown: On a floor, waiting for an elevator down to their destination
+ * - Riding: On an elevator, headed for their destination
+ * - Exiting: On the first floor, about to leave the building
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum PersonState {
+    Idle,
+    WaitingUp,
+    WaitingDown,
+    Riding,
+    Exiting
+}
 
 /** Person struct schema
  *
  * A person has a Bernoulli distribution which is sampled at each
  * time step to decide whether the person is leaving.  The person
- * also has a current and destination floor.
+ * also has a current and destination floor, and an id identifying
+ * them among the people who have arrived at the building.  The
+ * crowding tolerance is the fraction of an elevator's capacity the
+ * person is willing to ride at, 1 being as full as it may get.  The
+ * boarding ticks are the number of time steps the person takes to get
+ * on or off an elevator, 1 for most people.  The state is the stage
+ * of a trip the person is at, while is_leaving is whether the trip is
+ * the one out of the building.  The wait time counts the time steps
+ * of the current trip, and the ride time those spent on an elevator.
  */
 pub struct Person {
+    pub id: usize,
     pub floor_on: usize,
     pub floor_to: usize,
     pub is_leaving: bool,
-    pub wait_time: usize,
+    pub state: PersonState,
+    pub wait_time: Ticks,
+    pub ride_time: Ticks,
     pub p_out: f64,
+    pub crowding_tolerance: f64,
+    pub boarding_ticks: usize,
     dst_out: Bernoulli
 }
 
@@ -24,7 +69,7 @@ pub struct Person {
  * and are callable via
  *
  * //Example
- * let my_person: Person = Person::from(0.5_f64, 5_usize, &mut rng);
+ * let my_person: Person = Person::from(0.5_f64, 5_usize, &mut rng)?;
  * let is_leaving: bool = my_person.is_leaving(&mut rng);
  */
 impl Person {
@@ -39,69 +84,386 @@ impl Person {
      *
      * The num_floors and rng instance are used together to randomly
      * generate that person's destination floor on instatiation.
+     *
+     * The person's id is initialized to 0_usize, and is assigned by
+     * the building the person arrives at.  If p_out is not a
+     * probability, then an error is returned.
      */
-    pub fn from(p_out: f64, num_floors: usize, mut rng: &mut impl Rng) -> Person {
+    pub fn from(p_out: f64, num_floors: usize, mut rng: &mut impl Rng) -> Result<Person, ElevatorError> {
         let dst_to = Uniform::new(0_usize, num_floors);
         let floor_to: usize = dst_to.sample(&mut rng);
-        Person {
-            floor_on: 0_usize,
-            floor_to: floor_to,
+        Person::from_dest(p_out, 0_usize, floor_to)
+    }
+
+    /** Person constructor function
+     *
+     * Initialize a person given a probability of that person leaving
+     * the building, the floor they arrive on and their destination floor,
+     * such as when replaying a recorded arrival.  A p_out of 0 means the person never leaves,
+     * and of 1 that they leave as soon as they are idle.  If p_out is
+     * not in [0, 1], then an error is returned.
+     */
+    pub fn from_dest(p_out: f64, floor_on: usize, floor_to: usize) -> Result<Person, ElevatorError> {
+        let dst_out: Bernoulli = Bernoulli::new(p_out)
+            .map_err(|_| ElevatorError::Distribution(format!("probability of leaving {} is not in [0, 1]", p_out)))?;
+        Ok(Person::from_dist(p_out, dst_out, floor_on, floor_to))
+    }
+
+    /** Person constructor function
+     *
+     * Initialize a person given their already constructed distribution
+     * of leaving, the probability it was constructed from, the floor
+     * they arrive on and their destination floor, which cannot fail.  The building constructs
+     * the distribution of the people it generates only once.
+     */
+    pub fn from_dist(p_out: f64, dst_out: Bernoulli, floor_on: usize, floor_to: usize) -> Person {
+        let mut new_person: Person = Person {
+            id: 0_usize,
+            floor_on,
+            floor_to: 0_usize,
             is_leaving: false,
-            wait_time: 0_usize,
-            p_out: p_out,
-            dst_out: Bernoulli::new(p_out).unwrap()
+            state: PersonState::Idle,
+            wait_time: Ticks(0_u64),
+            ride_time: Ticks(0_u64),
+            p_out,
+            crowding_tolerance: 1_f64,
+            boarding_ticks: 1_usize,
+            dst_out
+        };
+        new_person.request_trip(floor_to);
+        new_person
+    }
+
+    /** request_trip function
+     *
+     * Start waiting on the current floor for an elevator to the given
+     * floor, or stay idle if already on it.  Only an idle person may
+     * request a trip.
+     */
+    pub fn request_trip(&mut self, floor_to: usize) {
+        debug_assert!(self.state == PersonState::Idle, "person {} requested a trip while {:?}", self.id, self.state);
+        self.floor_to = floor_to;
+        self.state = if floor_to > self.floor_on {
+            PersonState::WaitingUp
+        } else if floor_to < self.floor_on {
+            PersonState::WaitingDown
+        } else {
+            PersonState::Idle
+        };
+    }
+
+    /** board function
+     *
+     * Get on an elevator.  Only a waiting person may board.
+     */
+    pub fn board(&mut self) {
+        debug_assert!(self.is_waiting(), "person {} boarded while {:?}", self.id, self.state);
+        self.state = PersonState::Riding;
+    }
+
+    /** alight function
+     *
+     * Get off an elevator at the destination floor, becoming idle there,
+     * or exiting if this is the trip out of the building.  Only a riding
+     * person at their destination may alight.
+     */
+    pub fn alight(&mut self) {
+        debug_assert!(
+            self.state == PersonState::Riding && self.floor_on == self.floor_to,
+            "person {} alighted at {} for {} while {:?}", self.id, self.floor_on, self.floor_to, self.state
+        );
+        self.state = if self.is_leaving { PersonState::Exiting } else { PersonState::Idle };
+    }
+
+    /** discharge function
+     *
+     * Get off an elevator before reaching the destination floor, such
+     * as when it goes out of service, and wait for another there.  If
+     * the elevator happens to be on the destination floor, then this is
+     * the same as alighting.  Only a riding person may be discharged.
+     */
+    pub fn discharge(&mut self) {
+        debug_assert!(self.state == PersonState::Riding, "person {} was discharged while {:?}", self.id, self.state);
+        if self.floor_on == self.floor_to {
+            self.alight();
+            return;
         }
+        self.state = PersonState::Idle;
+        self.request_trip(self.floor_to);
     }
 
-    /** gen_is_leaving function
+    /** change_destination function
      *
-     * Decide whether the person is leaving the building.
-     * If so, then update the person's floor_to value to 0.
-     * Then return the is_leaving boolean.
+     * Change the destination floor while riding an elevator.  Only a
+     * riding person may change their destination.
      */
-    pub fn gen_is_leaving(&mut self, mut rng: &mut impl Rng) -> bool {
-        //Check if the is_leaving boolean is true, if so return it
-        if self.is_leaving {
-            return self.is_leaving;
+    pub fn change_destination(&mut self, floor_to: usize) {
+        debug_assert!(self.state == PersonState::Riding, "person {} changed destination while {:?}", self.id, self.state);
+        self.floor_to = floor_to;
+    }
+
+    /** begin_exit function
+     *
+     * Decide to leave the building, heading for the first floor, or
+     * exiting straight away if already on it.  Only an idle person may
+     * begin to exit.
+     */
+    pub fn begin_exit(&mut self) {
+        debug_assert!(self.state == PersonState::Idle, "person {} began to exit while {:?}", self.id, self.state);
+        self.is_leaving = true;
+        self.request_trip(0_usize);
+        if self.state == PersonState::Idle {
+            self.state = PersonState::Exiting;
         }
+    }
 
-        //If the person is not leaving, then randomly generate whether they wish to leave
+    /** is_state_consistent function
+     *
+     * Return whether the person's state matches where they are, given
+     * whether they are on an elevator, and where they are headed.
+     */
+    pub fn is_state_consistent(&self, on_elevator: bool) -> bool {
+        match self.state {
+            PersonState::Riding => on_elevator,
+            _ if on_elevator => false,
+            PersonState::Idle | PersonState::Exiting => self.floor_to == self.floor_on,
+            PersonState::WaitingUp => self.floor_to > self.floor_on,
+            PersonState::WaitingDown => self.floor_to < self.floor_on
+        }
+    }
+
+    /** is_waiting function
+     *
+     * Return whether the person is on a floor waiting for an elevator
+     */
+    pub fn is_waiting(&self) -> bool {
+        matches!(self.state, PersonState::WaitingUp | PersonState::WaitingDown)
+    }
+
+    /** is_travelling function
+     *
+     * Return whether the person has yet to reach their destination,
+     * either waiting for an elevator or riding one which has not yet
+     * reached it.
+     */
+    pub fn is_travelling(&self) -> bool {
+        match self.state {
+            PersonState::WaitingUp | PersonState::WaitingDown => true,
+            PersonState::Riding => self.floor_on != self.floor_to,
+            PersonState::Idle | PersonState::Exiting => false
+        }
+    }
+
+    /** gen_is_leaving function
+     *
+     * Decide whether the idle person is leaving the building.
+     * If so, then begin their exit.
+     * Then return whether they decided to leave.
+     */
+    pub fn gen_is_leaving(&mut self, mut rng: &mut impl Rng) -> bool {
+        //Randomly generate whether the person wishes to leave
         let pers_is_leaving: bool = self.dst_out.sample(&mut rng);
         if pers_is_leaving {
-            self.floor_to = 0_usize;
-            self.is_leaving = pers_is_leaving;
+            self.begin_exit();
         }
-        self.is_leaving
+        pers_is_leaving
+    }
+
+    /** tolerates_load function
+     *
+     * Return whether the person is willing to ride an elevator of the
+     * given capacity with the given number of people on it, counting
+     * themself.  Nobody refuses an otherwise empty elevator.
+     */
+    pub fn tolerates_load(&self, num_people: usize, capacity: usize) -> bool {
+        num_people <= 1_usize || num_people as f64 <= self.crowding_tolerance * capacity as f64
     }
 
     /** increment_wait_time function
      *
-     * Increment the person's wait time counter
+     * Increment the person's wait time counter, and their ride time
+     * counter too if they are riding an elevator, saturating both at
+     * MAX_WAIT_TIME
      */
     pub fn increment_wait_time(&mut self) {
         //Increment the person's wait time counter
-        self.wait_time += 1_usize;
+        self.wait_time = self.wait_time.saturating_add(Ticks(1_u64)).min(MAX_WAIT_TIME);
+        if self.state == PersonState::Riding {
+            self.ride_time = self.ride_time.saturating_add(Ticks(1_u64)).min(MAX_WAIT_TIME);
+        }
     }
 
     /** reset_wait_time function
      *
-     * Reset the person's wait time counter, presumably
+     * Reset the person's wait and ride time counters, presumably
      * once they reach their destination floor.
      */
     pub fn reset_wait_time(&mut self) {
         //Reset the person's wait time counter
-        self.wait_time = 0_usize;
+        self.wait_time = Ticks(0_u64);
+        self.ride_time = Ticks(0_u64);
     }
 }
 
 //Display trait implementation for a person
 impl fmt::Display for Person {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let display_str: String = if self.floor_on != self.floor_to {
+        let display_str: String = if self.is_travelling() {
             format!("Person {} -> {}", self.floor_on, self.floor_to)
         } else {
             format!("Person {}", self.floor_on)
         };
         f.write_str(&display_str)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_counters_saturate_at_the_cap() {
+        //Someone riding just short of the cap stops counting once they reach it
+        let mut pers: Person = Person::from_dest(0.5_f64, 0_usize, 3_usize).unwrap();
+        pers.state = PersonState::Riding;
+        pers.wait_time = Ticks(MAX_WAIT_TIME.0 - 1_u64);
+        pers.ride_time = Ticks(MAX_WAIT_TIME.0 - 1_u64);
+        for _ in 0_usize..3_usize {
+            pers.increment_wait_time();
+        }
+        assert_eq!(pers.wait_time, MAX_WAIT_TIME);
+        assert_eq!(pers.ride_time, MAX_WAIT_TIME);
+
+        //Someone waiting past the cap, as if loaded from elsewhere, is brought back to it
+        pers.state = PersonState::WaitingUp;
+        pers.wait_time = Ticks(u64::MAX);
+        pers.increment_wait_time();
+        assert_eq!(pers.wait_time, MAX_WAIT_TIME);
+    }
+
+    //Build a person who never leaves, on the given floor and headed for the given floor
+    fn get_person(floor_on: usize, floor_to: usize) -> Person {
+        Person::from_dest(0_f64, floor_on, floor_to).unwrap()
+    }
+
+    //Build a person riding an elevator from the given floor toward the given floor
+    fn get_rider(floor_on: usize, floor_to: usize) -> Person {
+        let mut pers: Person = get_person(floor_on, floor_to);
+        pers.board();
+        pers
+    }
+
+    #[test]
+    fn new_people_wait_toward_their_destination() {
+        let cases: [(usize, usize, PersonState); 3] = [
+            (0_usize, 3_usize, PersonState::WaitingUp),
+            (3_usize, 0_usize, PersonState::WaitingDown),
+            (2_usize, 2_usize, PersonState::Idle)
+        ];
+        for (floor_on, floor_to, state) in cases {
+            let pers: Person = get_person(floor_on, floor_to);
+            assert_eq!(pers.state, state, "{} -> {}", floor_on, floor_to);
+            assert!(pers.is_state_consistent(false), "{} -> {}", floor_on, floor_to);
+            assert_eq!(pers.is_travelling(), floor_on != floor_to, "{} -> {}", floor_on, floor_to);
+        }
+    }
+
+    #[test]
+    fn trips_go_through_every_legal_transition() {
+        //Waiting, then riding, then idle at the destination
+        let mut pers: Person = get_rider(0_usize, 3_usize);
+        assert_eq!(pers.state, PersonState::Riding);
+        assert!(pers.is_state_consistent(true) && !pers.is_state_consistent(false));
+        pers.change_destination(2_usize);
+        assert_eq!((pers.floor_to, pers.state), (2_usize, PersonState::Riding));
+        pers.floor_on = 2_usize;
+        assert!(!pers.is_travelling());
+        pers.alight();
+        assert_eq!(pers.state, PersonState::Idle);
+
+        //Idle, then leaving down to the first floor, then exiting there
+        pers.begin_exit();
+        assert!(pers.is_leaving);
+        assert_eq!((pers.floor_to, pers.state), (0_usize, PersonState::WaitingDown));
+        pers.board();
+        pers.floor_on = 0_usize;
+        pers.alight();
+        assert_eq!(pers.state, PersonState::Exiting);
+        assert!(pers.is_state_consistent(false));
+
+        //Idle on the first floor, then exiting straight away
+        let mut pers: Person = get_person(0_usize, 0_usize);
+        pers.begin_exit();
+        assert_eq!(pers.state, PersonState::Exiting);
+
+        //Riding, then discharged short of the destination to wait again, or at it to be idle
+        let mut pers: Person = get_rider(1_usize, 4_usize);
+        pers.floor_on = 5_usize;
+        pers.discharge();
+        assert_eq!(pers.state, PersonState::WaitingDown);
+        let mut pers: Person = get_rider(1_usize, 4_usize);
+        pers.floor_on = 4_usize;
+        pers.discharge();
+        assert_eq!(pers.state, PersonState::Idle);
+
+        //Idle, then requesting another trip
+        pers.request_trip(6_usize);
+        assert_eq!(pers.state, PersonState::WaitingUp);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "began to exit while Riding")]
+    fn riders_cannot_begin_to_exit() {
+        get_rider(0_usize, 3_usize).begin_exit();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "began to exit while WaitingUp")]
+    fn waiting_people_cannot_begin_to_exit() {
+        get_person(0_usize, 3_usize).begin_exit();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "changed destination while WaitingUp")]
+    fn waiting_people_cannot_change_destination() {
+        get_person(0_usize, 3_usize).change_destination(2_usize);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "changed destination while Idle")]
+    fn idle_people_cannot_change_destination() {
+        get_person(2_usize, 2_usize).change_destination(4_usize);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "boarded while Idle")]
+    fn idle_people_cannot_board() {
+        get_person(2_usize, 2_usize).board();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "alighted at 0 for 3 while Riding")]
+    fn riders_cannot_alight_short_of_their_destination() {
+        get_rider(0_usize, 3_usize).alight();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "was discharged while WaitingDown")]
+    fn waiting_people_cannot_be_discharged() {
+        get_person(3_usize, 0_usize).discharge();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "requested a trip while Riding")]
+    fn riders_cannot_request_a_trip() {
+        get_rider(0_usize, 3_usize).request_trip(1_usize);
+    }
+}
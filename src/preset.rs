@@ -0,0 +1,145 @@
+//Import source modules
+use crate::simulation::SimulationConfig;
+use crate::bank::Bank;
+
+/** BuildingPreset enum
+ *
+ * A BuildingPreset is a built-in building configuration which can be
+ * selected by name.
+ * - SmallOffice: 4 floors served by a single elevator
+ * - Midrise: 12 floors served by 2 elevators
+ * - Tower: 40 floors served by 6 elevators, with heavy arrivals
+ * - BankedTower: 26 floors served by a low-rise bank of 2 elevators and a high-rise bank of 2 elevators
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum BuildingPreset {
+    SmallOffice,
+    Midrise,
+    Tower,
+    BankedTower
+}
+
+//Constant representing every preset, in order of building size
+pub const BUILDING_PRESETS: [BuildingPreset; 4] = [
+    BuildingPreset::SmallOffice,
+    BuildingPreset::Midrise,
+    BuildingPreset::BankedTower,
+    BuildingPreset::Tower
+];
+
+/** BuildingPreset type implementation
+ *
+ * The following functions are implemented for the BuildingPreset
+ * type, and are callable via
+ *
+ * //Example
+ * let my_config: Option<SimulationConfig> = BuildingPreset::by_name("tower");
+ * let my_config: SimulationConfig = BuildingPreset::Midrise.get_config();
+ */
+impl BuildingPreset {
+    /** get_name function
+     *
+     * Return the name the preset is selected by
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            BuildingPreset::SmallOffice => "small-office",
+            BuildingPreset::Midrise => "midrise",
+            BuildingPreset::Tower => "tower",
+            BuildingPreset::BankedTower => "banked-tower"
+        }
+    }
+
+    /** get_config function
+     *
+     * Return the config of the preset's building, with the default
+     * energy parameters and number of time steps.  The banked tower's
+     * low-rise bank serves floors 0-10 and its high-rise bank serves
+     * the lobby and floors 11-25.
+     */
+    pub fn get_config(&self) -> SimulationConfig {
+        match self {
+            BuildingPreset::SmallOffice => SimulationConfig::from(4_usize, 1_usize, 0.2_f64),
+            BuildingPreset::Midrise => SimulationConfig::from(12_usize, 2_usize, 0.8_f64),
+            BuildingPreset::Tower => SimulationConfig::from(40_usize, 6_usize, 2.5_f64),
+            BuildingPreset::BankedTower => {
+                let mut config: SimulationConfig = SimulationConfig::from(26_usize, 4_usize, 1.5_f64);
+                config.banks = vec![
+                    Bank::from("low-rise", vec![0_usize, 1_usize], (0_usize..11_usize).collect()),
+                    Bank::from("high-rise", vec![2_usize, 3_usize], std::iter::once(0_usize).chain(11_usize..26_usize).collect())
+                ];
+                config
+            }
+        }
+    }
+
+    /** by_name function
+     *
+     * Return the config of the preset with the given name, if any
+     */
+    pub fn by_name(name: &str) -> Option<SimulationConfig> {
+        BUILDING_PRESETS.iter()
+            .find(|x| x.get_name() == name)
+            .map(|x| x.get_config())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::controller::{ElevatorController, ControllerKind};
+    use crate::simulation::{build_controller, step};
+    use crate::params::ControllerParams;
+    use crate::profile::Profiler;
+
+    #[test]
+    fn every_preset_builds_and_runs() {
+        let expected: [(usize, usize, f64, usize); 4] = [
+            (4_usize, 1_usize, 0.2_f64, 0_usize),
+            (12_usize, 2_usize, 0.8_f64, 0_usize),
+            (26_usize, 4_usize, 1.5_f64, 2_usize),
+            (40_usize, 6_usize, 2.5_f64, 0_usize)
+        ];
+        for (preset, (num_floors, num_elevators, expected_arrivals, num_banks)) in BUILDING_PRESETS.iter().zip(expected) {
+            //The preset is selected by its name, and its config is valid
+            let config: SimulationConfig = BuildingPreset::by_name(preset.get_name()).unwrap();
+            assert_eq!(config.validate(), Ok(()), "{}", preset.get_name());
+            assert_eq!((config.num_floors, config.num_elevators), (num_floors, num_elevators), "{}", preset.get_name());
+            assert_eq!(config.expected_arrivals, expected_arrivals, "{}", preset.get_name());
+
+            //Its building runs 50 time steps headless, staying consistent
+            let mut controller: Box<dyn ElevatorController> = build_controller(
+                &config, ControllerKind::Nearest, &ControllerParams::default(), 0_u64
+            ).unwrap();
+            let mut profiler: Profiler = Profiler::from(false);
+            for i in 0_i32..50_i32 {
+                step(controller.as_mut(), i, &mut profiler).unwrap();
+                if let Err(violation) = controller.get_building().check_invariants() {
+                    panic!("{} at step {}: {}", preset.get_name(), i, violation);
+                }
+            }
+            let building: &Building = controller.get_building();
+            assert_eq!(building.floors.len(), num_floors, "{}", preset.get_name());
+            assert_eq!(building.elevators.len(), num_elevators, "{}", preset.get_name());
+            assert_eq!(building.banks.len(), num_banks, "{}", preset.get_name());
+            assert!(building.num_arrivals > 0_usize, "nobody arrived in the {}", preset.get_name());
+        }
+    }
+
+    #[test]
+    fn banked_tower_banks_cover_every_floor() {
+        let config: SimulationConfig = BuildingPreset::BankedTower.get_config();
+        for floor in 0_usize..config.num_floors {
+            assert!(config.banks.iter().any(|x| x.floors.contains(&floor)), "floor {} is served by no bank", floor);
+        }
+        assert!(config.banks.iter().all(|x| x.floors.contains(&0_usize)), "a bank does not serve the lobby");
+    }
+
+    #[test]
+    fn unknown_preset_is_not_found() {
+        assert!(BuildingPreset::by_name("skyscraper").is_none());
+        assert!(BuildingPreset::by_name("").is_none());
+    }
+}
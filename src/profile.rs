@@ -0,0 +1,217 @@
+//Import standard modules
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/** Phase enum
+ *
+ * A phase of a time step whose duration is measured when profiling
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Phase {
+    Arrivals,
+    Exchange,
+    Controller,
+    Metrics,
+    Render,
+    Sleep
+}
+
+//Constant listing each phase, in the order they occur during a time step
+const PHASES: [Phase; 6] = [
+    Phase::Arrivals,
+    Phase::Exchange,
+    Phase::Controller,
+    Phase::Metrics,
+    Phase::Render,
+    Phase::Sleep
+];
+
+/** Phase type implementation
+ *
+ * The following functions are implemented for the Phase type,
+ * and are callable via
+ *
+ * //Example
+ * let phase_name: &str = Phase::Arrivals.get_name();
+ */
+impl Phase {
+    /** get_name function
+     *
+     * Return the name of the phase as shown in the profile table
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Phase::Arrivals => "arrivals",
+            Phase::Exchange => "exchange",
+            Phase::Controller => "controller",
+            Phase::Metrics => "metrics",
+            Phase::Render => "render",
+            Phase::Sleep => "sleep"
+        }
+    }
+}
+
+/** Profiler struct schema
+ *
+ * A Profiler has the following properties
+ * - enabled (bool): Whether the phases are being measured
+ * - totals ([Duration; 6]): The total time spent in each phase
+ * - loop_start (Option<Instant>): When the measured loop started, if it has
+ * - loop_total (Duration): The total time spent in the measured loop
+ * - lap_start (Option<Instant>): When the phase being measured started, if it has
+ *
+ * The clock is only read while enabled, since some targets such as
+ * wasm have no clock to read.
+ */
+pub struct Profiler {
+    pub enabled: bool,
+    totals: [Duration; 6],
+    loop_start: Option<Instant>,
+    loop_total: Duration,
+    lap_start: Option<Instant>
+}
+
+/** Profiler type implementation
+ *
+ * The following functions are implemented for the Profiler type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_profiler: Profiler = Profiler::from(true);
+ * my_profiler.start();
+ * my_profiler.lap(Phase::Arrivals);
+ * my_profiler.stop();
+ */
+impl Profiler {
+    /** Profiler constructor function
+     *
+     * Initialize a profiler which measures the phases only if enabled
+     */
+    pub fn from(enabled: bool) -> Profiler {
+        Profiler {
+            enabled,
+            totals: [Duration::ZERO; 6],
+            loop_start: None,
+            loop_total: Duration::ZERO,
+            lap_start: None
+        }
+    }
+
+    /** start function
+     *
+     * Mark the start of the measured loop, and of its first phase
+     */
+    pub fn start(&mut self) {
+        if self.enabled {
+            self.loop_start = Some(Instant::now());
+            self.lap_start = self.loop_start;
+        }
+    }
+
+    /** lap function
+     *
+     * Add the time since the previous lap to the given phase's total,
+     * then start timing the next phase.  Callers check that the
+     * profiler is enabled before calling.
+     */
+    pub fn lap(&mut self, phase: Phase) {
+        let now: Instant = Instant::now();
+        if let Some(lap_start) = self.lap_start {
+            self.totals[phase as usize] += now - lap_start;
+        }
+        self.lap_start = Some(now);
+    }
+
+    /** stop function
+     *
+     * Mark the end of the measured loop
+     */
+    pub fn stop(&mut self) {
+        if let Some(loop_start) = self.loop_start {
+            self.loop_total = loop_start.elapsed();
+        }
+    }
+
+    /** get_total function
+     *
+     * Return the total time spent in the given phase
+     */
+    pub fn get_total(&self, phase: Phase) -> Duration {
+        self.totals[phase as usize]
+    }
+
+    /** get_loop_total function
+     *
+     * Return the total time spent in the measured loop
+     */
+    pub fn get_loop_total(&self) -> Duration {
+        self.loop_total
+    }
+
+    /** render_table function
+     *
+     * Return a table breaking down the time spent in each phase, and
+     * its share of the time spent in the measured loop.
+     */
+    pub fn render_table(&self) -> String {
+        let loop_ms: f64 = self.loop_total.as_secs_f64() * 1000_f64;
+        let mut table: String = format!("{:<12}{:>12}{:>8}\n", "Phase", "Total (ms)", "Share");
+        for phase in PHASES.iter() {
+            let phase_ms: f64 = self.get_total(*phase).as_secs_f64() * 1000_f64;
+            let share: f64 = if loop_ms > 0_f64 { phase_ms / loop_ms * 100_f64 } else { 0_f64 };
+            let _ = writeln!(table, "{:<12}{:>12.2}{:>7.1}%", phase.get_name(), phase_ms, share);
+        }
+        let _ = write!(table, "{:<12}{:>12.2}{:>7.1}%", "total", loop_ms, 100_f64);
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::controller::{ElevatorController, NearestController};
+    use crate::simulation::step;
+
+    #[test]
+    fn phase_totals_add_up_to_the_loop_total() {
+        //Profile a loop of time steps, each rendered and followed by a short sleep, as the terminal loop does
+        let mut controller: NearestController = NearestController::from(
+            Building::from(8_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap()
+        );
+        let mut profiler: Profiler = Profiler::from(true);
+        profiler.start();
+        for i in 0_i32..20_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            let _ = controller.get_building().to_string();
+            profiler.lap(Phase::Render);
+            std::thread::sleep(Duration::from_millis(1_u64));
+            profiler.lap(Phase::Sleep);
+        }
+        profiler.stop();
+
+        //The sleep was measured for at least as long as it slept
+        assert!(profiler.get_total(Phase::Sleep) >= Duration::from_millis(20_u64));
+
+        //The phases cover the loop, less only the time between the last lap and the stop
+        let phase_total: Duration = PHASES.iter().map(|x| profiler.get_total(*x)).sum();
+        let loop_total: Duration = profiler.get_loop_total();
+        assert!(phase_total <= loop_total, "{:?} > {:?}", phase_total, loop_total);
+        assert!(loop_total - phase_total < loop_total / 20_u32, "{:?} of {:?} unaccounted for", loop_total - phase_total, loop_total);
+
+        //The table ends with the loop total
+        let table: String = profiler.render_table();
+        assert!(table.starts_with("Phase"));
+        assert_eq!(table.lines().count(), PHASES.len() + 2_usize);
+        assert!(table.ends_with("100.0%"), "{}", table);
+    }
+
+    #[test]
+    fn disabled_profiler_measures_nothing() {
+        let mut profiler: Profiler = Profiler::from(false);
+        profiler.start();
+        profiler.stop();
+        assert_eq!(profiler.get_loop_total(), Duration::ZERO);
+        assert!(PHASES.iter().all(|x| profiler.get_total(*x) == Duration::ZERO));
+    }
+}
@@ -0,0 +1,153 @@
+//Import external/standard modules
+use std::fmt::Write as FmtWrite;
+use std::io::{self, Write, IsTerminal};
+use std::time;
+use crossterm::{terminal, cursor, Command};
+
+//Constant representing the number of cells in the bar
+const BAR_WIDTH: usize = 30_usize;
+
+//Constant representing the least time between redraws of the bar, so that drawing does not slow the run
+const REDRAW_INTERVAL: time::Duration = time::Duration::from_millis(100_u64);
+
+/** ProgressBar struct schema
+ *
+ * A ProgressBar is a single line redrawn in place on stdout, with a
+ * bar, the percent complete, the estimated time remaining and a status
+ * given by the caller.  It is only drawn when stdout is a terminal, so
+ * that redirected output is left clean, and must be cleared before
+ * anything else is printed.
+ *
+ * A ProgressBar has the following properties
+ * - enabled (bool): Whether the bar is drawn
+ * - start (Instant): When the work the bar follows started
+ * - last_drawn (Option<Instant>): When the bar was last drawn, if it is on screen
+ * - line (String): The buffer each line of the bar is drawn into
+ */
+pub struct ProgressBar {
+    enabled: bool,
+    start: time::Instant,
+    last_drawn: Option<time::Instant>,
+    line: String
+}
+
+/** ProgressBar type implementation
+ *
+ * The following functions are implemented for the ProgressBar type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_bar: ProgressBar = ProgressBar::from(true);
+ * if my_bar.is_due(0.5_f64) {
+ *     my_bar.draw(0.5_f64, "step 500/1000");
+ * }
+ * my_bar.clear();
+ */
+impl ProgressBar {
+    /** ProgressBar constructor function
+     *
+     * Initialize a bar starting now, which is drawn if shown is true and
+     * stdout is a terminal.
+     */
+    pub fn from(shown: bool) -> ProgressBar {
+        ProgressBar {
+            enabled: shown && io::stdout().is_terminal(),
+            start: time::Instant::now(),
+            last_drawn: None,
+            line: String::new()
+        }
+    }
+
+    /** is_enabled function
+     *
+     * Return whether the bar is drawn.
+     */
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /** is_due function
+     *
+     * Return whether the bar should be redrawn at the given fraction
+     * complete, which it is unless it is not drawn, or it was redrawn
+     * less than REDRAW_INTERVAL ago and the work is not complete.  The
+     * status need only be computed when it is due.
+     */
+    pub fn is_due(&self, fraction_done: f64) -> bool {
+        self.enabled && (fraction_done >= 1_f64 || self.last_drawn.is_none_or(|x| x.elapsed() >= REDRAW_INTERVAL))
+    }
+
+    /** draw function
+     *
+     * Redraw the bar at the given fraction complete with the given
+     * status, if it is drawn at all.  The line is cut to the width of
+     * the terminal, since a wrapped line cannot be redrawn in place.
+     */
+    pub fn draw(&mut self, fraction_done: f64, status: &str) {
+        if !self.enabled {
+            return;
+        }
+        let fraction_done: f64 = fraction_done.clamp(0_f64, 1_f64);
+
+        //Estimate the time remaining from the rate of the work so far
+        let elapsed: f64 = self.start.elapsed().as_secs_f64();
+        let eta: String = if fraction_done > 0_f64 {
+            format_seconds(elapsed * (1_f64 - fraction_done) / fraction_done)
+        } else {
+            String::from("--")
+        };
+
+        //Draw the bar over the start of the line, clearing what was there
+        let num_filled: usize = (fraction_done * BAR_WIDTH as f64) as usize;
+        self.line.clear();
+        let _ = cursor::MoveToColumn(0).write_ansi(&mut self.line);
+        let _ = terminal::Clear(terminal::ClearType::CurrentLine).write_ansi(&mut self.line);
+        let prefix_len: usize = self.line.len();
+        let _ = write!(
+            self.line, "[{}{}] {:>3.0}% ETA {}, {}",
+            "#".repeat(num_filled), ".".repeat(BAR_WIDTH - num_filled), fraction_done * 100_f64, eta, status
+        );
+        if let Ok((cols, _)) = terminal::size() {
+            if let Some((i, _)) = self.line[prefix_len..].char_indices().nth((cols as usize).saturating_sub(1_usize)) {
+                self.line.truncate(prefix_len + i);
+            }
+        }
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(self.line.as_bytes()).and_then(|_| stdout.flush());
+        self.last_drawn = Some(time::Instant::now());
+    }
+
+    /** clear function
+     *
+     * Erase the bar if it is on screen, leaving the cursor at the start
+     * of its line for whatever is printed next.  It is drawn again on
+     * the next call to draw.
+     */
+    pub fn clear(&mut self) {
+        if self.last_drawn.is_none() {
+            return;
+        }
+        self.line.clear();
+        let _ = cursor::MoveToColumn(0).write_ansi(&mut self.line);
+        let _ = terminal::Clear(terminal::ClearType::CurrentLine).write_ansi(&mut self.line);
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(self.line.as_bytes()).and_then(|_| stdout.flush());
+        self.last_drawn = None;
+    }
+}
+
+/** format_seconds function
+ *
+ * Format a number of seconds as minutes and seconds, or hours and
+ * minutes if it is an hour or more.
+ */
+fn format_seconds(seconds: f64) -> String {
+    let seconds: u64 = seconds.round() as u64;
+    if seconds >= 3600_u64 {
+        format!("{}h{:02}m", seconds / 3600_u64, seconds % 3600_u64 / 60_u64)
+    } else if seconds >= 60_u64 {
+        format!("{}m{:02}s", seconds / 60_u64, seconds % 60_u64)
+    } else {
+        format!("{}s", seconds)
+    }
+}
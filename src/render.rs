@@ -0,0 +1,1374 @@
+//Import source modules
+use crate::building::Building;
+use crate::people::People;
+use crate::elevator::{DoorState, ElevatorProfile};
+use crate::elevators::get_elevator_label;
+
+//Import external/standard modules
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+#[cfg(feature = "cli")]
+use crossterm::style::{self, Color};
+
+/** Color enum
+ *
+ * The colors the building is drawn with.  Without the cli feature
+ * there is no terminal to color, so the building is drawn plainly.
+ */
+#[cfg(not(feature = "cli"))]
+#[derive(Clone, Copy)]
+pub enum Color {
+    Green,
+    DarkGreen,
+    Yellow,
+    DarkYellow,
+    Red
+}
+
+//Constant representing the number of lines rendered beneath the floors, the
+//floors shown, the elevator statuses, and the three average times and energy
+pub const FOOTER_LINES: usize = 6_usize;
+
+//Constants describing the building block and the active key bindings
+const LEGEND: [&str; 6] = [
+    "Legend:",
+    "  ##.. 0.25\tprobability the floor is a destination next time step, green to red",
+    "  ||w/n||\tnumber of people waiting for an elevator, of the n people on the floor",
+    "  |n|\t\televator carrying n people, [n] if its doors are open, >n< if closing",
+    "  |=|\t\troof of a freight elevator",
+    "  yellow\t\tpeople on the floor are waiting for an elevator"
+];
+const KEY_HINTS: &str = "Keys: h legend, t ticker, up/down pan, 1-9 follow elevator, b follow busiest floor, q quit";
+
+/** Style enum
+ *
+ * The characters the building is drawn with, either plain ASCII for
+ * compatibility with dumb terminals, or unicode box-drawing characters.
+ */
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Clone, Copy)]
+pub enum Style {
+    Ascii,
+    Unicode
+}
+
+/** GlyphSet struct schema
+ *
+ * A GlyphSet has the following properties
+ * - floor_roof (&str): The line drawn above each floor
+ * - floor_wall (&str): The walls on either side of each floor's people
+ * - cab_roof (&str): The line drawn above each elevator
+ * - freight_roof (&str): The line drawn above each freight elevator
+ * - cab_wall (&str): The walls on either side of each elevator's people
+ * - cab_open ((&str, &str)): The walls of an elevator whose doors are open
+ * - cab_closing ((&str, &str)): The walls of an elevator whose doors are closing
+ * - cab_empty (&str): Drawn before the number of people in an empty elevator
+ * - cab_full (&str): Drawn before the number of people in an occupied elevator
+ * - cab_space (&str): The space taken by an elevator not on the floor
+ * - arrow (&str): Points from an elevator's floor to its destination floor
+ * - bar_full (char): A filled cell of a floor's dest_prob bar
+ * - bar_empty (char): An empty cell of a floor's dest_prob bar
+ */
+pub struct GlyphSet {
+    pub floor_roof: &'static str,
+    pub floor_wall: &'static str,
+    pub cab_roof: &'static str,
+    pub freight_roof: &'static str,
+    pub cab_wall: &'static str,
+    pub cab_open: (&'static str, &'static str),
+    pub cab_closing: (&'static str, &'static str),
+    pub cab_empty: &'static str,
+    pub cab_full: &'static str,
+    pub cab_space: &'static str,
+    pub arrow: &'static str,
+    pub bar_full: char,
+    pub bar_empty: char
+}
+
+//Constants defining the glyphs of each style
+pub const ASCII_GLYPHS: GlyphSet = GlyphSet {
+    floor_roof: "----\t||---\t||",
+    floor_wall: "||",
+    cab_roof: "|-\t|",
+    freight_roof: "|=\t|",
+    cab_wall: "|",
+    cab_open: ("[", "]"),
+    cab_closing: (">", "<"),
+    cab_empty: "",
+    cab_full: "",
+    cab_space: "   \t ",
+    arrow: "->",
+    bar_full: '#',
+    bar_empty: '.'
+};
+pub const UNICODE_GLYPHS: GlyphSet = GlyphSet {
+    floor_roof: "\u{2500}\u{2500}\u{2500}\u{2500}\t\u{251c}\u{2500}\u{2500}\u{2500}\t\u{2524}",
+    floor_wall: "\u{2502}",
+    cab_roof: "\u{250c}\u{2500}\t\u{2510}",
+    freight_roof: "\u{250f}\u{2501}\t\u{2513}",
+    cab_wall: "\u{2502}",
+    cab_open: ("[", "]"),
+    cab_closing: ("\u{25b6}", "\u{25c0}"),
+    cab_empty: "\u{25a1}",
+    cab_full: "\u{25a0}",
+    cab_space: "   \t ",
+    arrow: "\u{2192}",
+    bar_full: '\u{2588}',
+    bar_empty: '\u{2591}'
+};
+
+/** Style type implementation
+ *
+ * The following functions are implemented for the Style type,
+ * and are callable via
+ *
+ * //Example
+ * let my_glyphs: &GlyphSet = Style::Unicode.get_glyphs();
+ */
+impl Style {
+    /** get_glyphs function
+     *
+     * Return the glyph set the style is drawn with.
+     */
+    pub fn get_glyphs(&self) -> &'static GlyphSet {
+        match self {
+            Style::Ascii => &ASCII_GLYPHS,
+            Style::Unicode => &UNICODE_GLYPHS
+        }
+    }
+}
+
+//Constants representing the dest_prob bar, colored from green to red by bucket
+pub const PROB_BAR_WIDTH: usize = 4_usize;
+const PROB_COLORS: [Color; PROB_BAR_WIDTH + 1] = [
+    Color::Green, Color::DarkGreen, Color::Yellow, Color::DarkYellow, Color::Red
+];
+
+/** get_prob_bucket function
+ *
+ * Bucket a probability into one of five equal-width buckets from 0
+ * to 4, with 1.0 in the highest bucket.  Probabilities outside of
+ * [0, 1], or NaN, are placed in the nearest bucket.
+ */
+pub fn get_prob_bucket(prob: f64) -> usize {
+    //Place NaN and non-positive probabilities in the lowest bucket
+    if prob.is_nan() || prob <= 0_f64 {
+        return 0_usize;
+    }
+
+    //Scale the probability by the number of buckets and clamp
+    let bucket: usize = (prob * (PROB_BAR_WIDTH + 1_usize) as f64) as usize;
+    if bucket > PROB_BAR_WIDTH { PROB_BAR_WIDTH } else { bucket }
+}
+
+/** ProbBar struct schema
+ *
+ * A ProbBar has the following properties
+ * - bucket (usize): The bucket of the probability, which is the number of filled cells
+ * - glyphs (&GlyphSet): The glyphs the bar is drawn with
+ */
+struct ProbBar<'a> {
+    bucket: usize,
+    glyphs: &'a GlyphSet
+}
+
+//Display trait implementation for a probability bar
+impl fmt::Display for ProbBar<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0_usize..PROB_BAR_WIDTH {
+            f.write_char(if i < self.bucket { self.glyphs.bar_full } else { self.glyphs.bar_empty })?;
+        }
+        Ok(())
+    }
+}
+
+/** render_prob_bar function
+ *
+ * Write a probability as a bar with one filled cell per bucket, in
+ * the bucket's color if colored.
+ */
+pub fn render_prob_bar(buf: &mut impl fmt::Write, prob: f64, glyphs: &GlyphSet, colored: bool) -> fmt::Result {
+    let bucket: usize = get_prob_bucket(prob);
+    let color: Option<Color> = if colored { Some(PROB_COLORS[bucket]) } else { None };
+    write_colored(buf, format_args!("{}", ProbBar { bucket, glyphs }), color)
+}
+
+/** write_colored function
+ *
+ * Write the formatted arguments in the given color, if any.
+ */
+#[cfg(feature = "cli")]
+fn write_colored(buf: &mut impl fmt::Write, args: fmt::Arguments, color: Option<Color>) -> fmt::Result {
+    match color {
+        Some(x) => write!(buf, "{}", style::Stylize::with(style::style(args), x)),
+        None => buf.write_fmt(args)
+    }
+}
+
+/** write_colored function
+ *
+ * Write the formatted arguments plainly, without a terminal to color.
+ */
+#[cfg(not(feature = "cli"))]
+fn write_colored(buf: &mut impl fmt::Write, args: fmt::Arguments, _color: Option<Color>) -> fmt::Result {
+    buf.write_fmt(args)
+}
+
+//Constant representing the number of events shown in the ticker
+pub const TICKER_LINES: usize = 2_usize;
+
+/** get_frame_lines function
+ *
+ * Calculate the number of lines in a rendered building given its
+ * number of floors and whether it is rendered in compact mode, in
+ * which case each floor takes one line rather than two.
+ */
+pub fn get_frame_lines(num_floors: usize, compact: bool) -> usize {
+    let lines_per_floor: usize = if compact { 1_usize } else { 2_usize };
+    (num_floors * lines_per_floor) + FOOTER_LINES
+}
+
+/** needs_compact function
+ *
+ * Determine whether a building with the given number of floors is
+ * too tall to render in full on a terminal of the given height.
+ */
+pub fn needs_compact(num_floors: usize, term_height: usize) -> bool {
+    get_frame_lines(num_floors, false) > term_height
+}
+
+//Constants representing the columns taken by a floor and by each elevator beside it
+pub const FLOOR_WIDTH: usize = 18_usize;
+pub const ELEVATOR_WIDTH: usize = 8_usize;
+
+/** get_frame_width function
+ *
+ * Calculate the number of columns in a rendered floor given the
+ * number of elevators beside it, each rendered as its own cab.
+ */
+pub fn get_frame_width(num_elevators: usize) -> usize {
+    FLOOR_WIDTH + (num_elevators * ELEVATOR_WIDTH)
+}
+
+/** needs_condensed function
+ *
+ * Determine whether a building with the given number of elevators is
+ * too wide to render its cabs in full on a terminal of the given width.
+ */
+pub fn needs_condensed(num_elevators: usize, term_width: usize) -> bool {
+    get_frame_width(num_elevators) > term_width
+}
+
+/** get_viewport_rows function
+ *
+ * Calculate the number of floors which fit on a terminal of the given
+ * height in compact mode, at most the number of floors in the building.
+ * At least one floor is shown however short the terminal, in which
+ * case the frame is taller than the terminal and printed sequentially.
+ */
+pub fn get_viewport_rows(num_floors: usize, term_height: usize) -> usize {
+    let available_rows: usize = term_height.saturating_sub(FOOTER_LINES).max(1_usize);
+    if available_rows < num_floors { available_rows } else { num_floors }
+}
+
+/** clamp_viewport function
+ *
+ * Given the floor the viewport should be centered on, the number of
+ * floors in the viewport, and the number of floors in the building,
+ * return the lowest floor shown such that the viewport remains within
+ * the bounds of the building.
+ */
+pub fn clamp_viewport(center_floor: usize, num_rows: usize, num_floors: usize) -> usize {
+    let first_floor: usize = center_floor.saturating_sub(num_rows / 2_usize);
+    let max_first_floor: usize = num_floors.saturating_sub(num_rows);
+    if first_floor > max_first_floor { max_first_floor } else { first_floor }
+}
+
+/** get_legend_lines function
+ *
+ * Calculate the number of lines in the legend, depending on whether
+ * it is shown and whether it includes the key bindings.
+ */
+pub fn get_legend_lines(legend: bool, keys: bool) -> usize {
+    if !legend {
+        return 0_usize;
+    }
+    if keys { LEGEND.len() + 1_usize } else { LEGEND.len() }
+}
+
+/** render_legend function
+ *
+ * Write the lines of the legend, each preceded by a newline, including
+ * the key bindings if keys are being read, and coloring the sample of
+ * the waiting floor color if colored.
+ */
+pub fn render_legend(buf: &mut impl fmt::Write, keys: bool, colored: bool) -> fmt::Result {
+    for (i, legend_line) in LEGEND.iter().enumerate() {
+        //Color the sample of the waiting floor color yellow
+        buf.write_char('\n')?;
+        if i == LEGEND.len() - 1_usize {
+            let (before, after): (&str, &str) = legend_line.split_once("yellow").unwrap_or((legend_line, ""));
+            buf.write_str(before)?;
+            write_colored(buf, format_args!("yellow"), if colored { Some(Color::Yellow) } else { None })?;
+            buf.write_str(after)?;
+        } else {
+            buf.write_str(legend_line)?;
+        }
+    }
+    if keys {
+        buf.write_char('\n')?;
+        buf.write_str(KEY_HINTS)?;
+    }
+    Ok(())
+}
+
+/** RenderOptions struct schema
+ *
+ * A RenderOptions has the following properties
+ * - compact (bool): If true, compact mode is forced, else it is used only if the building does not fit
+ * - legend (bool): If true, the legend is rendered beneath the building
+ * - ticker (bool): If true, the latest events are rendered beneath the building
+ * - verbose (bool): If true, each floor's dest_prob is rendered as a number rather than a bar
+ * - keys (bool): If true, key presses are being read and the legend includes their bindings
+ * - style (Style): The characters the building is drawn with
+ * - colored (bool): If true, the building is drawn in color where the terminal allows, else as plain text
+ */
+#[derive(Clone, Copy)]
+pub struct RenderOptions {
+    pub compact: bool,
+    pub legend: bool,
+    pub ticker: bool,
+    pub verbose: bool,
+    pub keys: bool,
+    pub style: Style,
+    pub colored: bool
+}
+
+/** RenderOptions type implementation
+ *
+ * The following functions are implemented for the RenderOptions type,
+ * and are callable via
+ *
+ * //Example
+ * let my_options: RenderOptions = RenderOptions::new();
+ */
+impl RenderOptions {
+    /** RenderOptions constructor function
+     *
+     * Initialize the default render options, drawing the building in
+     * full as plain ASCII text with numeric destination probabilities
+     * and nothing beneath it.
+     */
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> RenderOptions {
+        RenderOptions {
+            compact: false,
+            legend: false,
+            ticker: false,
+            verbose: true,
+            keys: false,
+            style: Style::Ascii,
+            colored: false
+        }
+    }
+}
+
+/** View struct schema
+ *
+ * A View has the following properties
+ * - compact (bool): If true, each floor is rendered on one line, else two
+ * - condensed (bool): If true, each elevator is rendered as one character, else as a cab
+ * - first_floor (usize): The lowest floor shown
+ * - num_rows (usize): The number of floors shown
+ * - options (RenderOptions): The options the view is rendered with
+ * - cab_floors (Vec<f64>): The position each cab is shown at, if between floors, else empty to show each cab on its floor
+ */
+pub struct View {
+    pub compact: bool,
+    pub condensed: bool,
+    pub first_floor: usize,
+    pub num_rows: usize,
+    pub options: RenderOptions,
+    pub cab_floors: Vec<f64>
+}
+
+/** View type implementation
+ *
+ * The following functions are implemented for the View type,
+ * and are callable via
+ *
+ * //Example
+ * let my_view: View = View::full(my_building.floors.len());
+ * render_building(&mut building_str, &my_building, &my_view).unwrap();
+ */
+impl View {
+    /** View constructor function
+     *
+     * Initialize a view showing every floor of a building with the
+     * given number of floors in full, with the default options.
+     */
+    pub fn full(num_floors: usize) -> View {
+        View {
+            compact: false,
+            condensed: false,
+            first_floor: 0_usize,
+            num_rows: num_floors,
+            options: RenderOptions::new(),
+            cab_floors: Vec::new()
+        }
+    }
+
+    /** get_cab_level function
+     *
+     * Return the line the body of the elevator at the given index is
+     * drawn on, counted in half floors from the ground floor's body,
+     * its roof being drawn on the line above.  A cab between floors is
+     * drawn on the nearest line, or the nearest floor if the floors
+     * are rendered on one line or the cabs as one character.
+     */
+    pub fn get_cab_level(&self, building: &Building, elevator: usize) -> usize {
+        let cab_floor: f64 = match self.cab_floors.get(elevator) {
+            Some(x) => *x,
+            None => building.elevators[elevator].floor_on as f64
+        };
+        if self.compact || self.condensed {
+            2_usize * cab_floor.round() as usize
+        } else {
+            (2_f64 * cab_floor).round() as usize
+        }
+    }
+}
+
+/** Focus enum
+ *
+ * The floor a scrolling viewport is centered on, either the floor
+ * with the most people waiting, the floor a selected elevator is on,
+ * or a floor the user panned to.
+ */
+pub enum Focus {
+    Busiest,
+    Elevator(usize),
+    Floor(usize)
+}
+
+/** Viewport struct schema
+ *
+ * A Viewport has the following properties
+ * - term_width (usize): The width of the terminal in columns
+ * - term_height (usize): The height of the terminal in lines
+ * - focus (Focus): The floor the viewport is centered on when the building does not fit
+ * - options (RenderOptions): The options the building is rendered with
+ */
+pub struct Viewport {
+    pub term_width: usize,
+    pub term_height: usize,
+    pub focus: Focus,
+    pub options: RenderOptions
+}
+
+/** Viewport type implementation
+ *
+ * The following functions are implemented for the Viewport type,
+ * and are callable via
+ *
+ * //Example
+ * let my_viewport: Viewport = Viewport::from(80_usize, 24_usize, RenderOptions::new());
+ * let my_view: View = my_viewport.get_view(&my_building);
+ */
+impl Viewport {
+    /** Viewport constructor function
+     *
+     * Initialize a viewport given the width and height of the terminal
+     * and the options to render with.
+     */
+    pub fn from(term_width: usize, term_height: usize, options: RenderOptions) -> Viewport {
+        Viewport {
+            term_width,
+            term_height,
+            focus: Focus::Busiest,
+            options
+        }
+    }
+
+    /** get_view function
+     *
+     * Determine the floors shown for the given building based on the
+     * terminal height and the viewport's focus.  Compact mode is enabled
+     * automatically if the building, ticker, and legend do not fit, and
+     * the elevators are condensed if their cabs are too wide to fit.
+     */
+    pub fn get_view(&self, building: &Building) -> View {
+        //Condense the elevators if their cabs do not fit beside the floors
+        let condensed: bool = needs_condensed(building.elevators.len(), self.term_width);
+
+        //Calculate the height available to the building beside the ticker and legend
+        let num_floors: usize = building.floors.len();
+        let ticker_lines: usize = if self.options.ticker { TICKER_LINES } else { 0_usize };
+        let building_height: usize = self.term_height.saturating_sub(
+            ticker_lines + get_legend_lines(self.options.legend, self.options.keys)
+        );
+
+        //If not in compact mode, then show the full building
+        if !self.options.compact && !needs_compact(num_floors, building_height) {
+            return View {
+                compact: false,
+                condensed,
+                first_floor: 0_usize,
+                num_rows: num_floors,
+                options: self.options,
+                cab_floors: Vec::new()
+            };
+        }
+
+        //Calculate the number of rows and the floor to center on
+        let num_rows: usize = get_viewport_rows(num_floors, building_height);
+        let center_floor: usize = match self.focus {
+            Focus::Busiest => get_busiest_floor(building),
+            Focus::Elevator(i) => match building.elevators.get(i) {
+                Some(elevator) => elevator.floor_on,
+                None => get_busiest_floor(building)
+            },
+            Focus::Floor(i) => i
+        };
+
+        //Return the clamped compact view
+        View {
+            compact: true,
+            condensed,
+            first_floor: clamp_viewport(center_floor, num_rows, num_floors),
+            num_rows,
+            options: self.options,
+            cab_floors: Vec::new()
+        }
+    }
+
+    /** resize function
+     *
+     * Update the size of the terminal, which determines on the next view
+     * whether compact mode is needed, how many floors fit, and whether
+     * the elevators are condensed.
+     */
+    pub fn resize(&mut self, term_width: usize, term_height: usize) {
+        self.term_width = term_width;
+        self.term_height = term_height;
+    }
+
+    /** pan function
+     *
+     * Pan the viewport up or down one floor from its current center.
+     */
+    pub fn pan(&mut self, building: &Building, up: bool) {
+        //Get the current view and the highest floor it may start on
+        let view: View = self.get_view(building);
+        let max_first_floor: usize = building.floors.len().saturating_sub(view.num_rows);
+
+        //Move the first floor shown, clamping to the building, and center on it
+        let first_floor: usize = if up {
+            if view.first_floor < max_first_floor { view.first_floor + 1_usize } else { max_first_floor }
+        } else {
+            view.first_floor.saturating_sub(1_usize)
+        };
+        self.focus = Focus::Floor(first_floor + (view.num_rows / 2_usize));
+    }
+}
+
+/** get_busiest_floor function
+ *
+ * Return the floor with the most people waiting, preferring the
+ * lowest such floor.
+ */
+pub fn get_busiest_floor(building: &Building) -> usize {
+    let mut busiest_floor: usize = 0_usize;
+    let mut max_waiting: usize = 0_usize;
+    for i in 0_usize..building.floors.len() {
+        let num_waiting: usize = building.get_num_people_waiting_on_floor(i);
+        if num_waiting > max_waiting {
+            max_waiting = num_waiting;
+            busiest_floor = i;
+        }
+    }
+    busiest_floor
+}
+
+/** render_status_strip function
+ *
+ * Write a line listing each elevator's current floor and the floor
+ * it is headed to, or idle if it has no destination floor.  If the
+ * elevators are condensed, then the statuses are shortened so that
+ * more of them fit on the line.  If the building has no elevators,
+ * then say so instead.
+ */
+pub fn render_status_strip(buf: &mut impl fmt::Write, building: &Building, glyphs: &GlyphSet,
+                           condensed: bool) -> fmt::Result {
+    if building.elevators.is_empty() {
+        return buf.write_str("No elevators, nobody is served");
+    }
+    for (i, elevator) in building.elevators.iter().enumerate() {
+        if i > 0_usize {
+            buf.write_str(if condensed { " " } else { "  " })?;
+        }
+        if condensed {
+            write!(buf, "{}@{}", get_elevator_label(i), elevator.floor_on)?;
+            if let Some(floor_to) = elevator.floor_to {
+                write!(buf, "{}{}", glyphs.arrow, floor_to)?;
+            }
+            continue;
+        }
+        match elevator.floor_to {
+            Some(floor_to) => write!(buf, "{}: @{} {}{}", get_elevator_label(i), elevator.floor_on, glyphs.arrow, floor_to)?,
+            None => write!(buf, "{}: @{} idle", get_elevator_label(i), elevator.floor_on)?
+        }
+    }
+    Ok(())
+}
+
+/** render_elevators function
+ *
+ * Write the parts of the elevators drawn on either the roof line or
+ * the body line of the given floor, each displayed as many spaces
+ * away from the building as its index.  A cab halfway between floors
+ * has its roof on the body line of the floor above and its body on
+ * the roof line of the floor below.
+ */
+fn render_elevators(buf: &mut impl fmt::Write, building: &Building, view: &View, floor_index: usize,
+                    glyphs: &GlyphSet, roof: bool) -> fmt::Result {
+    //Loop through the elevators to check if any are drawn on this line
+    let line_level: usize = 2_usize * floor_index + if roof { 1_usize } else { 0_usize };
+    let mut last_elevator_on_floor: usize = 0_usize;
+    for (j, elevator) in building.elevators.iter().enumerate() {
+        let cab_level: usize = view.get_cab_level(building, j);
+        let is_roof: bool = cab_level + 1_usize == line_level;
+        if cab_level != line_level && !is_roof {
+            continue;
+        }
+
+        //If the elevator is on this line, then display it j spaces away from the building
+        for _ in last_elevator_on_floor..j {
+            buf.write_str(glyphs.cab_space)?;
+        }
+        if is_roof && elevator.profile == ElevatorProfile::Freight {
+            buf.write_str(glyphs.freight_roof)?;
+        } else if is_roof {
+            buf.write_str(glyphs.cab_roof)?;
+        } else {
+            let num_people: usize = elevator.get_num_people();
+            let occupancy: &str = if num_people > 0_usize { glyphs.cab_full } else { glyphs.cab_empty };
+            let (left_wall, right_wall): (&str, &str) = match elevator.door_state {
+                DoorState::Closed => (glyphs.cab_wall, glyphs.cab_wall),
+                DoorState::Open => glyphs.cab_open,
+                DoorState::Closing => glyphs.cab_closing
+            };
+            write!(buf, "{}{}{}\t{}", left_wall, occupancy, num_people, right_wall)?;
+        }
+
+        //Increment the counter for num elevators on this floor
+        last_elevator_on_floor = j + 1_usize;
+    }
+    Ok(())
+}
+
+/** render_condensed_elevators function
+ *
+ * Write one character per elevator beside the given floor, the
+ * elevator's label if it is on the floor and a space otherwise, so
+ * that each elevator keeps its own column however many there are.
+ * The floor each elevator is on is also shown in the status strip.
+ */
+fn render_condensed_elevators(buf: &mut impl fmt::Write, building: &Building, view: &View,
+                              floor_index: usize) -> fmt::Result {
+    buf.write_char('\t')?;
+    for j in 0_usize..building.elevators.len() {
+        if view.get_cab_level(building, j) == 2_usize * floor_index {
+            buf.write_char(get_elevator_label(j).chars().next().unwrap_or(' '))?;
+        } else {
+            buf.write_char(' ')?;
+        }
+    }
+    Ok(())
+}
+
+/** render_building function
+ *
+ * Write the floors of the building within the given view, top floor
+ * first, along with the number of people waiting on and the number of
+ * people on each floor, and the elevators on each floor.  Then write the
+ * status of each elevator, and the average energy and wait times
+ * beneath the floors.  Lines are separated by newlines, with no
+ * newline following the last line.
+ */
+pub fn render_building(buf: &mut impl fmt::Write, building: &Building, view: &View) -> fmt::Result {
+    let glyphs: &GlyphSet = view.options.style.get_glyphs();
+    for i in (view.first_floor..(view.first_floor + view.num_rows)).rev() {
+        //Separate this floor from the floor above it
+        if i + 1_usize < view.first_floor + view.num_rows {
+            buf.write_char('\n')?;
+        }
+
+        //If this floor has people waiting, then color it yellow
+        let floor = &building.floors[i];
+        let num_waiting: usize = building.get_num_people_waiting_on_floor(i);
+        let floor_color: Option<Color> = if view.options.colored && num_waiting > 0_usize {
+            Some(Color::Yellow)
+        } else {
+            None
+        };
+
+        //Write the floor's roof and the elevators' roofs, omitting them in compact mode
+        if !view.compact {
+            write_colored(buf, format_args!("{}", glyphs.floor_roof), floor_color)?;
+            if !view.condensed {
+                render_elevators(buf, building, view, i, glyphs, true)?;
+            }
+            buf.write_char('\n')?;
+        }
+
+        //Write the dest_prob, as a number if verbose or else as a bar, followed by the floor's body
+        if view.options.verbose {
+            write_colored(buf, format_args!("{:.2}", floor.dest_prob), floor_color)?;
+        } else {
+            render_prob_bar(buf, floor.dest_prob, glyphs, view.options.colored)?;
+        }
+        write_colored(
+            buf, format_args!(
+                "\t{}{}/{}\t{}", glyphs.floor_wall, num_waiting, floor.get_num_people(), glyphs.floor_wall
+            ), floor_color
+        )?;
+        if view.condensed {
+            render_condensed_elevators(buf, building, view, i)?;
+        } else {
+            render_elevators(buf, building, view, i, glyphs, false)?;
+        }
+    }
+    if view.num_rows > 0_usize {
+        buf.write_char('\n')?;
+    }
+
+    //If only part of the building is shown, then say which floors in place of the blank line
+    let num_floors: usize = building.floors.len();
+    if view.num_rows > 0_usize && view.num_rows < num_floors {
+        write!(buf, "Floors {}-{} of {} (up/down to pan)", view.first_floor, view.first_floor + view.num_rows - 1_usize, num_floors)?;
+    }
+
+    //Write the elevator statuses, and the average energy and the wait times, split into waiting and riding
+    buf.write_char('\n')?;
+    render_status_strip(buf, building, glyphs, view.condensed)?;
+    write!(buf, "\nAverage floor wait:\t{:.2}", building.floor_wait_times.get_mean())?;
+    write!(buf, "\nAverage ride time:\t{:.2}", building.get_average_ride_time())?;
+    write!(buf, "\nAverage time in system:\t{:.2}", building.wait_times.get_mean())?;
+    write!(buf, "\nAverage energy spent:\t{:.2}", building.energy.get_mean())
+}
+
+/** get_interpolated_floor function
+ *
+ * Return the position a cab moving from one floor to another during a
+ * time step is shown at on the given frame of the time step.  The
+ * frames are numbered from 1 to the number of frames, the last of
+ * which shows the cab on the floor it moved to.
+ */
+pub fn get_interpolated_floor(floor_from: usize, floor_to: usize, frame: usize, num_frames: usize) -> f64 {
+    if frame >= num_frames {
+        return floor_to as f64;
+    }
+    let fraction: f64 = frame as f64 / num_frames as f64;
+    floor_from as f64 + (floor_to as f64 - floor_from as f64) * fraction
+}
+
+/** get_frame_floors function
+ *
+ * Return the position each elevator of the building is shown at on
+ * the given frame of the time step, given the floors they were on
+ * before the time step.  Elevators without a floor before the time
+ * step are shown on their floor.
+ */
+pub fn get_frame_floors(floors_from: &[usize], building: &Building, frame: usize, num_frames: usize) -> Vec<f64> {
+    building.elevators.iter().enumerate().map(|(i, elevator)| {
+        let floor_from: usize = floors_from.get(i).copied().unwrap_or(elevator.floor_on);
+        get_interpolated_floor(floor_from, elevator.floor_on, frame, num_frames)
+    }).collect()
+}
+
+/** render_frame function
+ *
+ * Write the building within the given view, followed by the ticker
+ * and the legend if they are shown.
+ */
+pub fn render_frame(buf: &mut impl fmt::Write, building: &Building, view: &View, ticker: &Ticker) -> fmt::Result {
+    sim_span!(_render_span, "render");
+    render_building(buf, building, view)?;
+    if view.options.ticker {
+        ticker.render(buf)?;
+    }
+    if view.options.legend {
+        render_legend(buf, view.options.keys, view.options.colored)?;
+    }
+    Ok(())
+}
+
+/** Ticker struct schema
+ *
+ * A Ticker has the following properties
+ * - events (VecDeque<String>): The descriptions of the latest events, oldest first
+ * - width (usize): The width of the terminal, which event descriptions are truncated to
+ */
+pub struct Ticker {
+    events: VecDeque<String>,
+    width: usize
+}
+
+/** Ticker type implementation
+ *
+ * The following functions are implemented for the Ticker type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_ticker: Ticker = Ticker::from(80_usize);
+ * my_ticker.push(my_event.describe());
+ */
+impl Ticker {
+    /** Ticker constructor function
+     *
+     * Initialize an empty ticker given the width of the terminal.
+     */
+    pub fn from(width: usize) -> Ticker {
+        Ticker {
+            events: VecDeque::with_capacity(TICKER_LINES),
+            width
+        }
+    }
+
+    /** resize function
+     *
+     * Update the width of the terminal, which event descriptions are
+     * truncated to.
+     */
+    pub fn resize(&mut self, width: usize) {
+        self.width = width;
+    }
+
+    /** push function
+     *
+     * Add an event description to the ticker, evicting the oldest
+     * description if the ticker is full.
+     */
+    pub fn push(&mut self, event_str: String) {
+        if self.events.len() == TICKER_LINES {
+            self.events.pop_front();
+        }
+        self.events.push_back(event_str);
+    }
+
+    /** render function
+     *
+     * Write a line for each event description in the ticker, each
+     * preceded by a newline, padded with blank lines when the ticker is
+     * not full, and truncated to the width of the terminal.
+     */
+    pub fn render(&self, buf: &mut impl fmt::Write) -> fmt::Result {
+        for i in 0_usize..TICKER_LINES {
+            buf.write_char('\n')?;
+            if let Some(event_str) = self.events.get(i) {
+                for c in event_str.chars().take(self.width) {
+                    buf.write_char(c)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::get_empty_building;
+    use crate::person::{Person, PersonState};
+    use crate::controller::{ElevatorController, NearestController};
+    use crate::simulation::step;
+    use crate::profile::Profiler;
+
+    //Render the building within the view and return its lines
+    fn render_lines(building: &Building, view: &View) -> Vec<String> {
+        let mut building_str: String = String::new();
+        render_building(&mut building_str, building, view).unwrap();
+        building_str.split('\n').map(String::from).collect()
+    }
+
+    //Build a building of 4 floors and 2 elevators, the first headed from floor 1 to floor 3 and the second idle on the ground floor
+    fn get_strip_building() -> Building {
+        let mut building: Building = get_empty_building(4_usize, 2_usize);
+        building.elevators[0].floor_on = 1_usize;
+        building.elevators[0].floor_to = Some(3_usize);
+        building.elevators[1].floor_to = None;
+        building
+    }
+
+    //Render the status strip of the building and return it
+    fn render_strip(building: &Building, glyphs: &GlyphSet, condensed: bool) -> String {
+        let mut strip_str: String = String::new();
+        render_status_strip(&mut strip_str, building, glyphs, condensed).unwrap();
+        strip_str
+    }
+
+    #[test]
+    fn status_strip_shows_each_target_floor() {
+        //Each elevator's floor, and where it is headed or that it is idle
+        let building: Building = get_strip_building();
+        assert_eq!(render_strip(&building, &ASCII_GLYPHS, false), "A: @1 ->3  B: @0 idle");
+        assert_eq!(render_strip(&building, &UNICODE_GLYPHS, false), "A: @1 \u{2192}3  B: @0 idle");
+
+        //Shortened when condensed, dropping the idle marker
+        assert_eq!(render_strip(&building, &ASCII_GLYPHS, true), "A@1->3 B@0");
+
+        //A building without elevators says so
+        let building: Building = get_empty_building(4_usize, 0_usize);
+        assert_eq!(render_strip(&building, &ASCII_GLYPHS, false), "No elevators, nobody is served");
+
+        //The strip is the first line of the footer
+        let building: Building = get_strip_building();
+        let lines: Vec<String> = render_lines(&building, &View::full(4_usize));
+        assert_eq!(lines[lines.len() - FOOTER_LINES + 1_usize], "A: @1 ->3  B: @0 idle");
+    }
+
+    //Build a building of 3 floors, with someone waiting on the top floor, where the second of 2 elevators is
+    fn get_styled_building() -> Building {
+        let mut building: Building = get_empty_building(3_usize, 2_usize);
+        building.place_person(Person::from_dest(0_f64, 2_usize, 0_usize).unwrap(), None);
+        building.elevators[1].floor_on = 2_usize;
+        building.floors[2].dest_prob = 0.5_f64;
+        building
+    }
+
+    //Render the floors of the building in the given style, with dest_prob as a bar
+    fn render_styled_floors(building: &Building, style: Style) -> Vec<String> {
+        let options: RenderOptions = RenderOptions { style, verbose: false, ..RenderOptions::new() };
+        let lines: Vec<String> = render_lines(building, &View { options, ..View::full(3_usize) });
+        lines[..6_usize].to_vec()
+    }
+
+    #[test]
+    fn ascii_style_snapshot() {
+        assert_eq!(render_styled_floors(&get_styled_building(), Style::Ascii), vec![
+            "----\t||---\t||   \t |-\t|",
+            "##..\t||1/1\t||   \t |0\t|",
+            "----\t||---\t||",
+            "....\t||0/0\t||",
+            "----\t||---\t|||-\t|",
+            "....\t||0/0\t|||0\t|"
+        ]);
+    }
+
+    #[test]
+    fn unicode_style_snapshot() {
+        assert_eq!(render_styled_floors(&get_styled_building(), Style::Unicode), vec![
+            "\u{2500}\u{2500}\u{2500}\u{2500}\t\u{251c}\u{2500}\u{2500}\u{2500}\t\u{2524}   \t \u{250c}\u{2500}\t\u{2510}",
+            "\u{2588}\u{2588}\u{2591}\u{2591}\t\u{2502}1/1\t\u{2502}   \t \u{2502}\u{25a1}0\t\u{2502}",
+            "\u{2500}\u{2500}\u{2500}\u{2500}\t\u{251c}\u{2500}\u{2500}\u{2500}\t\u{2524}",
+            "\u{2591}\u{2591}\u{2591}\u{2591}\t\u{2502}0/0\t\u{2502}",
+            "\u{2500}\u{2500}\u{2500}\u{2500}\t\u{251c}\u{2500}\u{2500}\u{2500}\t\u{2524}\u{250c}\u{2500}\t\u{2510}",
+            "\u{2591}\u{2591}\u{2591}\u{2591}\t\u{2502}0/0\t\u{2502}\u{2502}\u{25a1}0\t\u{2502}"
+        ]);
+    }
+
+    #[test]
+    fn door_state_is_drawn_on_the_cab_walls() {
+        //A cab carrying two people on the ground floor of a 2 floor building
+        let mut building: Building = get_empty_building(2_usize, 1_usize);
+        for id in 0_usize..2_usize {
+            let mut pers: Person = Person::from_dest(0_f64, 0_usize, 1_usize).unwrap();
+            pers.id = id;
+            pers.state = PersonState::Riding;
+            building.place_person(pers, Some(0_usize));
+        }
+
+        //Closed doors are drawn as walls, open doors as brackets, and closing doors as arrows
+        let cases: [(DoorState, &str, &str); 3] = [
+            (DoorState::Closed, "0.00\t||0/0\t|||2\t|", "0.00\t\u{2502}0/0\t\u{2502}\u{2502}\u{25a0}2\t\u{2502}"),
+            (DoorState::Open, "0.00\t||0/0\t||[2\t]", "0.00\t\u{2502}0/0\t\u{2502}[\u{25a0}2\t]"),
+            (DoorState::Closing, "0.00\t||0/0\t||>2\t<", "0.00\t\u{2502}0/0\t\u{2502}\u{25b6}\u{25a0}2\t\u{25c0}")
+        ];
+        for (door_state, ascii_line, unicode_line) in cases {
+            building.elevators[0].door_state = door_state;
+            let lines: Vec<String> = render_lines(&building, &View::full(2_usize));
+            assert_eq!(lines[3], ascii_line);
+            let options: RenderOptions = RenderOptions { style: Style::Unicode, ..RenderOptions::new() };
+            let lines: Vec<String> = render_lines(&building, &View { options, ..View::full(2_usize) });
+            assert_eq!(lines[3], unicode_line);
+        }
+    }
+
+    //Render the ticker and return its lines, without the newline preceding the first
+    fn render_ticker(ticker: &Ticker) -> Vec<String> {
+        let mut ticker_str: String = String::new();
+        ticker.render(&mut ticker_str).unwrap();
+        ticker_str.split('\n').skip(1_usize).map(String::from).collect()
+    }
+
+    #[test]
+    fn ticker_evicts_the_oldest_events() {
+        //An empty ticker is padded with blank lines
+        let mut ticker: Ticker = Ticker::from(80_usize);
+        assert_eq!(render_ticker(&ticker), vec![""; TICKER_LINES]);
+
+        //Events are shown oldest first until the ticker is full, then the oldest is evicted
+        ticker.push(String::from("first"));
+        assert_eq!(render_ticker(&ticker), vec!["first", ""]);
+        ticker.push(String::from("second"));
+        assert_eq!(render_ticker(&ticker), vec!["first", "second"]);
+        ticker.push(String::from("third"));
+        assert_eq!(ticker.events.len(), TICKER_LINES);
+        assert_eq!(render_ticker(&ticker), vec!["second", "third"]);
+
+        //Descriptions are truncated to the width of the terminal
+        ticker.resize(4_usize);
+        assert_eq!(render_ticker(&ticker), vec!["seco", "thir"]);
+    }
+
+    #[test]
+    fn prob_buckets_have_equal_width() {
+        let cases: [(f64, usize); 12] = [
+            (0_f64, 0_usize), (0.19_f64, 0_usize), (0.2_f64, 1_usize), (0.39_f64, 1_usize),
+            (0.4_f64, 2_usize), (0.6_f64, 3_usize), (0.79_f64, 3_usize), (0.8_f64, 4_usize),
+            (1_f64, 4_usize), (1.5_f64, 4_usize), (-0.5_f64, 0_usize), (f64::NAN, 0_usize)
+        ];
+        for (prob, bucket) in cases {
+            assert_eq!(get_prob_bucket(prob), bucket, "probability {}", prob);
+        }
+    }
+
+    #[test]
+    fn prob_bar_fills_a_cell_per_bucket() {
+        for (prob, bar) in [(0_f64, "...."), (0.25_f64, "#..."), (0.5_f64, "##.."), (1_f64, "####")] {
+            let mut bar_str: String = String::new();
+            render_prob_bar(&mut bar_str, prob, &ASCII_GLYPHS, false).unwrap();
+            assert_eq!(bar_str, bar);
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn prob_bar_is_plain_under_color_never() {
+        //Rendered with the colors of --color never, the bar and the rest of the frame emit no escape codes
+        let colored: bool = crate::terminal::ColorMode::Never.is_enabled();
+        for prob in [0_f64, 0.3_f64, 0.5_f64, 0.7_f64, 1_f64] {
+            let mut bar_str: String = String::new();
+            render_prob_bar(&mut bar_str, prob, &UNICODE_GLYPHS, colored).unwrap();
+            assert!(!bar_str.contains('\x1b'), "{:?}", bar_str);
+            assert_eq!(bar_str.chars().count(), PROB_BAR_WIDTH);
+        }
+        let mut building: Building = get_empty_building(3_usize, 1_usize);
+        building.place_person(Person::from_dest(0_f64, 2_usize, 0_usize).unwrap(), None);
+        let options: RenderOptions = RenderOptions { legend: true, verbose: false, colored, ..RenderOptions::new() };
+        let mut frame_str: String = String::new();
+        render_frame(&mut frame_str, &building, &View { options, ..View::full(3_usize) }, &Ticker::from(80_usize)).unwrap();
+        assert!(!frame_str.contains('\x1b'), "{:?}", frame_str);
+
+        //Whereas --color always colors the bar by its bucket
+        let mut bar_str: String = String::new();
+        render_prob_bar(&mut bar_str, 1_f64, &ASCII_GLYPHS, crate::terminal::ColorMode::Always.is_enabled()).unwrap();
+        assert!(bar_str.contains('\x1b'), "{:?}", bar_str);
+    }
+
+    #[cfg(not(feature = "cli"))]
+    #[test]
+    fn core_build_renders_plain_text() {
+        //Without the cli feature, even a colored frame of a busy building emits no escape codes
+        let mut building: Building = get_empty_building(4_usize, 2_usize);
+        building.place_person(Person::from_dest(0_f64, 3_usize, 0_usize).unwrap(), None);
+        let mut rider: Person = Person::from_dest(0_f64, 0_usize, 2_usize).unwrap();
+        rider.id = 1_usize;
+        rider.state = PersonState::Riding;
+        building.place_person(rider, Some(0_usize));
+        let options: RenderOptions = RenderOptions { legend: true, ticker: true, colored: true, ..RenderOptions::new() };
+        let mut frame_str: String = String::new();
+        render_frame(&mut frame_str, &building, &View { options, ..View::full(4_usize) }, &Ticker::from(80_usize)).unwrap();
+        assert!(!frame_str.contains('\x1b'), "{:?}", frame_str);
+        assert!(frame_str.lines().count() >= get_frame_lines(4_usize, false));
+
+        //The building displays as its plain full render
+        let lines: Vec<String> = render_lines(&building, &View::full(4_usize));
+        assert_eq!(building.to_string(), lines.join("\n"));
+        assert!(lines.iter().all(|x| !x.contains('\x1b')));
+    }
+
+    #[test]
+    fn frame_matches_display_and_reuses_its_buffer() {
+        let mut controller: NearestController = NearestController::from(
+            Building::from(6_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 3_u64).unwrap()
+        );
+        let mut profiler: Profiler = Profiler::from(false);
+        let ticker: Ticker = Ticker::from(80_usize);
+        let mut frame_str: String = String::new();
+        let mut max_frame_len: usize = 0_usize;
+        for i in 0_i32..50_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+
+            //The frame of the full view is what the building displays as
+            frame_str.clear();
+            render_frame(&mut frame_str, building, &View::full(6_usize), &ticker).unwrap();
+            assert_eq!(frame_str, building.to_string(), "step {}", i);
+
+            //The buffer grows only as far as the longest frame needs
+            max_frame_len = max_frame_len.max(frame_str.len());
+            assert!(frame_str.capacity() <= 2_usize * max_frame_len, "capacity {} at step {}", frame_str.capacity(), i);
+        }
+
+        //Rendering the same frame again into the buffer does not reallocate it
+        let (capacity, ptr): (usize, *const u8) = (frame_str.capacity(), frame_str.as_ptr());
+        for _ in 0_usize..10_usize {
+            frame_str.clear();
+            render_frame(&mut frame_str, controller.get_building(), &View::full(6_usize), &ticker).unwrap();
+            assert_eq!((frame_str.capacity(), frame_str.as_ptr()), (capacity, ptr));
+        }
+    }
+
+    #[test]
+    fn frame_lines_match_render() {
+        let building: Building = Building::from(12_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap();
+        let full_view: View = View::full(12_usize);
+        assert_eq!(render_lines(&building, &full_view).len(), get_frame_lines(12_usize, false));
+        let compact_view: View = View { compact: true, ..View::full(12_usize) };
+        assert_eq!(render_lines(&building, &compact_view).len(), get_frame_lines(12_usize, true));
+    }
+
+    #[test]
+    fn floors_show_waiting_and_total() {
+        let mut building: Building = get_empty_building(3_usize, 1_usize);
+        for (i, floor_to) in [0_usize, 2_usize].into_iter().enumerate() {
+            let mut pers: Person = Person::from_dest(0_f64, 2_usize, floor_to).unwrap();
+            pers.id = i;
+            building.place_person(pers, None);
+        }
+        let lines: Vec<String> = render_lines(&building, &View::full(3_usize));
+        assert!(lines[1].contains("||1/2\t||"), "{}", lines[1]);
+        assert!(lines[5].contains("||0/0\t||"), "{}", lines[5]);
+    }
+
+    #[test]
+    fn legend_toggles_without_disturbing_building() {
+        let building: Building = Building::from(5_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap();
+        let ticker: Ticker = Ticker::from(80_usize);
+        for keys in [false, true] {
+            //Render the frame with and without the legend
+            let hidden_view: View = View::full(5_usize);
+            let mut shown_view: View = View::full(5_usize);
+            shown_view.options.legend = true;
+            shown_view.options.keys = keys;
+            let mut hidden_str: String = String::new();
+            let mut shown_str: String = String::new();
+            render_frame(&mut hidden_str, &building, &hidden_view, &ticker).unwrap();
+            render_frame(&mut shown_str, &building, &shown_view, &ticker).unwrap();
+
+            //The legend is appended beneath the unchanged building block
+            assert!(shown_str.starts_with(&hidden_str));
+            let legend_str: &str = &shown_str[hidden_str.len()..];
+            assert_eq!(legend_str.split('\n').count() - 1_usize, get_legend_lines(true, keys));
+            assert!(legend_str.starts_with("\nLegend:"));
+            assert_eq!(legend_str.contains(KEY_HINTS), keys);
+        }
+    }
+
+    #[test]
+    fn viewport_shows_at_least_one_floor() {
+        assert_eq!(get_viewport_rows(12_usize, 0_usize), 1_usize);
+        assert_eq!(get_viewport_rows(12_usize, FOOTER_LINES), 1_usize);
+        assert_eq!(get_viewport_rows(12_usize, FOOTER_LINES + 5_usize), 5_usize);
+        assert_eq!(get_viewport_rows(12_usize, 100_usize), 12_usize);
+    }
+
+    #[test]
+    fn viewport_clamps_to_building() {
+        //Centered in the middle of the building
+        assert_eq!(clamp_viewport(50_usize, 10_usize, 100_usize), 45_usize);
+
+        //Centered near the ground floor or the top floor
+        assert_eq!(clamp_viewport(2_usize, 10_usize, 100_usize), 0_usize);
+        assert_eq!(clamp_viewport(99_usize, 10_usize, 100_usize), 90_usize);
+
+        //Centered beyond the top floor, or with more rows than floors
+        assert_eq!(clamp_viewport(500_usize, 10_usize, 100_usize), 90_usize);
+        assert_eq!(clamp_viewport(5_usize, 20_usize, 10_usize), 0_usize);
+    }
+
+    #[test]
+    fn short_terminal_views_show_a_floor() {
+        let building: Building = Building::from(12_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap();
+        let options: RenderOptions = RenderOptions { ticker: true, legend: true, keys: true, ..RenderOptions::new() };
+        let max_height: usize = FOOTER_LINES + TICKER_LINES + get_legend_lines(true, true);
+        for term_height in 0_usize..=max_height {
+            //Every view of a terminal too short for the ticker and legend still shows a floor
+            let viewport: Viewport = Viewport::from(80_usize, term_height, options);
+            let view: View = viewport.get_view(&building);
+            assert!(view.compact);
+            assert_eq!(view.num_rows, 1_usize);
+
+            //And renders its floor without panicking
+            let mut frame_str: String = String::new();
+            render_frame(&mut frame_str, &building, &view, &Ticker::from(80_usize)).unwrap();
+            assert_eq!(frame_str.split('\n').count(), max_height + 1_usize);
+        }
+    }
+
+    #[test]
+    fn resize_relayouts_the_view() {
+        //A building of 12 floors and 4 elevators fits in full on a large terminal
+        let building: Building = get_empty_building(12_usize, 4_usize);
+        let mut viewport: Viewport = Viewport::from(120_usize, 60_usize, RenderOptions::new());
+        let view: View = viewport.get_view(&building);
+        assert!(!view.compact && !view.condensed);
+        assert_eq!((view.first_floor, view.num_rows), (0_usize, 12_usize));
+
+        //Shrinking the terminal below the full height compacts the view to the floors which fit
+        viewport.resize(120_usize, get_frame_lines(12_usize, false) - 1_usize);
+        let view: View = viewport.get_view(&building);
+        assert!(view.compact && !view.condensed);
+        assert_eq!(view.num_rows, 12_usize);
+        viewport.resize(120_usize, FOOTER_LINES + 5_usize);
+        let view: View = viewport.get_view(&building);
+        assert!(view.compact);
+        assert_eq!(view.num_rows, 5_usize);
+
+        //Narrowing the terminal below the width of the cabs condenses them
+        viewport.resize(get_frame_width(4_usize) - 1_usize, FOOTER_LINES + 5_usize);
+        let view: View = viewport.get_view(&building);
+        assert!(view.compact && view.condensed);
+
+        //Growing the terminal back restores the full view
+        viewport.resize(get_frame_width(4_usize), get_frame_lines(12_usize, false));
+        let view: View = viewport.get_view(&building);
+        assert!(!view.compact && !view.condensed);
+        assert_eq!(view.num_rows, 12_usize);
+    }
+
+    #[test]
+    fn wide_buildings_are_condensed() {
+        //Whether the cabs fit beside the floors depends on the number of elevators and the width of the terminal
+        let cases: [(usize, usize, bool); 8] = [
+            (0_usize, 0_usize, true),
+            (0_usize, FLOOR_WIDTH, false),
+            (1_usize, 80_usize, false),
+            (8_usize, 80_usize, true),
+            (7_usize, 74_usize, false),
+            (7_usize, 73_usize, true),
+            (30_usize, 200_usize, true),
+            (30_usize, usize::MAX, false)
+        ];
+        for (num_elevators, term_width, condensed) in cases {
+            assert_eq!(needs_condensed(num_elevators, term_width), condensed, "{} elevators in {} columns", num_elevators, term_width);
+            let building: Building = get_empty_building(4_usize, num_elevators);
+            let view: View = Viewport::from(term_width, 100_usize, RenderOptions::new()).get_view(&building);
+            assert_eq!(view.condensed, condensed, "{} elevators in {} columns", num_elevators, term_width);
+        }
+    }
+
+    #[test]
+    fn condensed_elevators_keep_their_columns() {
+        //Of 28 elevators, the first is on the ground floor, the second on floor 2, and the last on floor 3
+        let mut building: Building = get_empty_building(4_usize, 28_usize);
+        for elevator in building.elevators.iter_mut() {
+            elevator.floor_on = 1_usize;
+        }
+        building.elevators[0].floor_on = 0_usize;
+        building.elevators[1].floor_on = 2_usize;
+        building.elevators[27].floor_on = 3_usize;
+        let view: View = Viewport::from(80_usize, 100_usize, RenderOptions::new()).get_view(&building);
+        assert!(view.condensed && !view.compact);
+
+        //Each floor's body has one column per elevator, lettered where the elevator is, with no roofs
+        let lines: Vec<String> = render_lines(&building, &view);
+        let columns: Vec<&str> = lines.iter().take(8_usize).map(|x| x.rsplit('\t').next().unwrap()).collect();
+        assert_eq!(lines[0], "----\t||---\t||");
+        assert_eq!(columns[1], format!("{}#", " ".repeat(27_usize)));
+        assert_eq!(columns[3], format!(" B{}", " ".repeat(26_usize)));
+        assert_eq!(columns[5], format!("  {} ", "CDEFGHIJKLMNOPQRSTUVWXYZ#"));
+        assert_eq!(columns[7], format!("A{}", " ".repeat(27_usize)));
+        assert!(lines[1..8].iter().step_by(2_usize).all(|x| x.len() == lines[1].len()));
+    }
+
+    #[test]
+    fn compact_render_shows_range() {
+        let building: Building = Building::from(12_usize, 2_usize, 0.5_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap();
+        let view: View = View { compact: true, first_floor: 8_usize, num_rows: 4_usize, ..View::full(12_usize) };
+        let lines: Vec<String> = render_lines(&building, &view);
+        assert_eq!(lines.len(), get_frame_lines(4_usize, true));
+        assert_eq!(lines[4], "Floors 8-11 of 12 (up/down to pan)");
+
+        //A view with no floors renders only the footer, without a range
+        let view: View = View { compact: true, num_rows: 0_usize, ..View::full(12_usize) };
+        let lines: Vec<String> = render_lines(&building, &view);
+        assert_eq!(lines.len(), FOOTER_LINES);
+        assert_eq!(lines[0], "");
+    }
+
+    #[test]
+    fn interpolated_floor_moves_evenly_through_the_frames() {
+        let cases: [(usize, usize, usize, usize, f64); 9] = [
+            (1_usize, 2_usize, 1_usize, 2_usize, 1.5_f64),
+            (1_usize, 2_usize, 2_usize, 2_usize, 2_f64),
+            (3_usize, 2_usize, 1_usize, 2_usize, 2.5_f64),
+            (0_usize, 4_usize, 1_usize, 4_usize, 1_f64),
+            (0_usize, 4_usize, 3_usize, 4_usize, 3_f64),
+            (5_usize, 5_usize, 2_usize, 3_usize, 5_f64),
+            (2_usize, 7_usize, 1_usize, 1_usize, 7_f64),
+            (2_usize, 7_usize, 0_usize, 5_usize, 2_f64),
+            (2_usize, 7_usize, 9_usize, 5_usize, 7_f64)
+        ];
+        for (floor_from, floor_to, frame, num_frames, expected) in cases {
+            assert_eq!(
+                get_interpolated_floor(floor_from, floor_to, frame, num_frames), expected,
+                "from {} to {} on frame {} of {}", floor_from, floor_to, frame, num_frames
+            );
+        }
+    }
+
+    #[test]
+    fn frame_floors_cover_each_frame_of_the_step() {
+        //Of 3 elevators, the first moved up from floor 1, the second down from floor 3, and the third has no floor before
+        let mut building: Building = get_empty_building(6_usize, 3_usize);
+        building.elevators[0].floor_on = 2_usize;
+        building.elevators[1].floor_on = 2_usize;
+        building.elevators[2].floor_on = 4_usize;
+        let floors_from: [usize; 2] = [1_usize, 3_usize];
+        for num_frames in [1_usize, 2_usize, 4_usize] {
+            for frame in 1_usize..=num_frames {
+                let fraction: f64 = frame as f64 / num_frames as f64;
+                let cab_floors: Vec<f64> = get_frame_floors(&floors_from, &building, frame, num_frames);
+                assert_eq!(cab_floors, vec![1_f64 + fraction, 3_f64 - fraction, 4_f64], "frame {} of {}", frame, num_frames);
+            }
+
+            //The last frame of the step shows each cab on its floor
+            let cab_floors: Vec<f64> = get_frame_floors(&floors_from, &building, num_frames, num_frames);
+            assert!(cab_floors.iter().zip(building.elevators.iter()).all(|(x, y)| *x == y.floor_on as f64));
+        }
+    }
+
+    #[test]
+    fn halfway_cabs_are_drawn_between_floors() {
+        //An elevator moved from floor 1 to floor 2 of 3, drawn over 2 frames
+        let mut building: Building = get_empty_building(3_usize, 1_usize);
+        building.elevators[0].floor_on = 2_usize;
+        let mut views: Vec<View> = Vec::new();
+        for frame in 1_usize..=2_usize {
+            let mut view: View = View::full(3_usize);
+            view.cab_floors = get_frame_floors(&[1_usize], &building, frame, 2_usize);
+            views.push(view);
+        }
+
+        //Halfway, the roof is on floor 2's body line and the body on its roof line
+        assert_eq!(views[0].get_cab_level(&building, 0_usize), 3_usize);
+        let lines: Vec<String> = render_lines(&building, &views[0]);
+        assert_eq!(lines[0], "----\t||---\t||");
+        assert_eq!(lines[1], "0.00\t||0/0\t|||-\t|");
+        assert_eq!(lines[2], "----\t||---\t|||0\t|");
+        assert_eq!(lines[3], "0.00\t||0/0\t||");
+
+        //On the last frame, it is drawn on its floor as without interpolation
+        assert_eq!(views[1].get_cab_level(&building, 0_usize), 4_usize);
+        assert_eq!(render_lines(&building, &views[1]), render_lines(&building, &View::full(3_usize)));
+
+        //Compact and condensed views draw it on the nearest floor
+        let view: View = View { compact: true, cab_floors: vec![1.25_f64], ..View::full(3_usize) };
+        assert_eq!(view.get_cab_level(&building, 0_usize), 2_usize);
+        let view: View = View { condensed: true, cab_floors: vec![1.5_f64], ..View::full(3_usize) };
+        assert_eq!(view.get_cab_level(&building, 0_usize), 4_usize);
+    }
+}
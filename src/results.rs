@@ -0,0 +1,476 @@
+//Import source modules
+use crate::simulation::SimulationResult;
+
+//Import external/standard modules
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//Constant representing the header line of a results log
+const RESULTS_HEADER: &str = "timestamp,config_hash,config,seed,controller,avg_wait_time,avg_energy";
+
+//Constant representing the header line of a metrics log
+const METRICS_HEADER: &str = "floors,elevators,expected_arrivals,controller,seed,avg_wait_time,avg_energy,num_delivered,time_steps";
+
+/** ResultsRow struct schema
+ *
+ * A ResultsRow has the following properties
+ * - timestamp (u64): The time the run was logged, in seconds since the unix epoch
+ * - config_hash (String): The hash of the config the run was simulated with
+ * - config (String): The config the run was simulated with, as JSON
+ * - seed (u64): The seed the run was simulated with
+ * - controller (String): The name of the controller the run was simulated with
+ * - avg_wait_time (f64): The average wait time at the end of the run
+ * - avg_energy (f64): The average energy spent at the end of the run
+ */
+pub struct ResultsRow {
+    pub timestamp: u64,
+    pub config_hash: String,
+    pub config: String,
+    pub seed: u64,
+    pub controller: String,
+    pub avg_wait_time: f64,
+    pub avg_energy: f64
+}
+
+//Implement display for ResultsRow
+impl fmt::Display for ResultsRow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "Config {} seed {} ({}): average wait time {:.2}, average energy spent {:.2}",
+            self.config_hash, self.seed, self.controller, self.avg_wait_time, self.avg_energy
+        )
+    }
+}
+
+/** ResultsMetric enum
+ *
+ * A ResultsMetric is a metric which logged runs can be ranked by,
+ * where lower is better.
+ * - AvgWait: The average wait time
+ * - AvgEnergy: The average energy spent
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ResultsMetric {
+    #[cfg_attr(feature = "cli", value(name = "avg_wait"))]
+    AvgWait,
+    #[cfg_attr(feature = "cli", value(name = "avg_energy"))]
+    AvgEnergy
+}
+
+/** ResultsMetric type implementation
+ *
+ * The following functions are implemented for the ResultsMetric
+ * type, and are callable via
+ *
+ * //Example
+ * let my_value: f64 = ResultsMetric::AvgWait.get_value(&my_row);
+ * let my_best: Option<&ResultsRow> = ResultsMetric::AvgWait.get_best(&my_rows);
+ */
+impl ResultsMetric {
+    /** get_value function
+     *
+     * Return the value of the metric for the given row
+     */
+    pub fn get_value(&self, row: &ResultsRow) -> f64 {
+        match self {
+            ResultsMetric::AvgWait => row.avg_wait_time,
+            ResultsMetric::AvgEnergy => row.avg_energy
+        }
+    }
+
+    /** get_best function
+     *
+     * Return the row with the lowest value of the metric, if any
+     */
+    pub fn get_best<'a>(&self, rows: &'a [ResultsRow]) -> Option<&'a ResultsRow> {
+        rows.iter().min_by(|a, b| self.get_value(a).total_cmp(&self.get_value(b)))
+    }
+}
+
+/** ResultsAxis enum
+ *
+ * A ResultsAxis is a property of logged runs which they may be grouped
+ * by to compare the groups.
+ * - Controller: The controller, comparing controllers across the buildings they ran
+ * - Building: The config hash, comparing buildings such as those of a campus across the controllers they ran with
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ResultsAxis {
+    #[cfg_attr(feature = "cli", value(name = "controller"))]
+    Controller,
+    #[cfg_attr(feature = "cli", value(name = "building"))]
+    Building
+}
+
+/** ResultsAxis type implementation
+ *
+ * The following functions are implemented for the ResultsAxis type,
+ * and are callable via
+ *
+ * //Example
+ * let my_key: &str = ResultsAxis::Building.get_key(&my_row);
+ * let my_groups: Vec<ResultsGroup> = ResultsAxis::Building.compare(&my_rows, ResultsMetric::AvgWait);
+ */
+impl ResultsAxis {
+    /** get_key function
+     *
+     * Return the key of the group the given row belongs to
+     */
+    pub fn get_key<'a>(&self, row: &'a ResultsRow) -> &'a str {
+        match self {
+            ResultsAxis::Controller => &row.controller,
+            ResultsAxis::Building => &row.config_hash
+        }
+    }
+
+    /** compare function
+     *
+     * Group the given rows by the axis and return the mean value of
+     * the metric over each group, in the order each group was first
+     * logged
+     */
+    pub fn compare(&self, rows: &[ResultsRow], metric: ResultsMetric) -> Vec<ResultsGroup> {
+        let mut groups: Vec<ResultsGroup> = Vec::new();
+        for row in rows.iter() {
+            let key: &str = self.get_key(row);
+            let value: f64 = metric.get_value(row);
+            match groups.iter_mut().find(|x| x.key == key) {
+                Some(group) => {
+                    group.mean += (value - group.mean) / (group.num_runs + 1_usize) as f64;
+                    group.num_runs += 1_usize;
+                },
+                None => groups.push(ResultsGroup {
+                    key: String::from(key),
+                    num_runs: 1_usize,
+                    mean: value
+                })
+            }
+        }
+        groups
+    }
+}
+
+/** ResultsGroup struct schema
+ *
+ * A ResultsGroup has the following properties
+ * - key (String): The controller or config hash the group's runs share
+ * - num_runs (usize): The number of runs in the group
+ * - mean (f64): The mean value of the compared metric over the group's runs
+ */
+pub struct ResultsGroup {
+    pub key: String,
+    pub num_runs: usize,
+    pub mean: f64
+}
+
+//Implement display for ResultsGroup
+impl fmt::Display for ResultsGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {:.2} over {} runs", self.key, self.mean, self.num_runs)
+    }
+}
+
+/** ResultsLog struct schema
+ *
+ * A ResultsLog has the following properties
+ * - path (String): The path of the CSV file the results are appended to
+ */
+pub struct ResultsLog {
+    pub path: String
+}
+
+/** ResultsLog type implementation
+ *
+ * The following functions are implemented for the ResultsLog type,
+ * and are callable via
+ *
+ * //Example
+ * let my_log: ResultsLog = ResultsLog::from("results.csv");
+ * my_log.append(&my_result)?;
+ * let my_rows: Vec<ResultsRow> = my_log.read()?;
+ */
+impl ResultsLog {
+    /** ResultsLog constructor function
+     *
+     * Initialize a results log at the given path, which is created
+     * when the first result is appended.
+     */
+    pub fn from(path: &str) -> ResultsLog {
+        ResultsLog {
+            path: String::from(path)
+        }
+    }
+
+    /** append function
+     *
+     * Append a row for the given run to the log, writing the header
+     * first if the log is empty.  The file is locked while appending
+     * so that runs logging in parallel do not interleave their rows.
+     */
+    pub fn append(&self, result: &SimulationResult) -> io::Result<()> {
+        //Format the row before taking the lock
+        let config_json: String = serde_json::to_string(&result.config)?;
+        let timestamp: u64 = SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0_u64);
+        let row: String = format!(
+            "{},{},{},{},{},{},{}\n",
+            timestamp, get_config_hash(&config_json), quote_csv_field(&config_json),
+            result.seed, quote_csv_field(&result.controller), result.wait.mean, result.energy.avg_per_step
+        );
+
+        append_row(&self.path, RESULTS_HEADER, &row)
+    }
+
+    /** read function
+     *
+     * Read every row of the log, in the order they were appended
+     */
+    pub fn read(&self) -> io::Result<Vec<ResultsRow>> {
+        let file: File = File::open(&self.path)?;
+        file.lock_shared()?;
+        let mut rows: Vec<ResultsRow> = Vec::new();
+        for (i, line) in BufReader::new(&file).lines().enumerate() {
+            let line: String = line?;
+            if i == 0_usize || line.is_empty() {
+                continue;
+            }
+            rows.push(parse_row(&line).ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: malformed results row", self.path, i + 1_usize)
+            ))?);
+        }
+        file.unlock()?;
+        Ok(rows)
+    }
+}
+
+/** MetricsLog struct schema
+ *
+ * A MetricsLog is a flat CSV dataset of runs, one row per run with the
+ * parameters of its building and its headline metrics, meant to be
+ * loaded as is by a spreadsheet or a dataframe library.
+ *
+ * A MetricsLog has the following properties
+ * - path (String): The path of the CSV file the rows are appended to
+ */
+pub struct MetricsLog {
+    pub path: String
+}
+
+/** MetricsLog type implementation
+ *
+ * The following functions are implemented for the MetricsLog type,
+ * and are callable via
+ *
+ * //Example
+ * let my_log: MetricsLog = MetricsLog::from("metrics.csv");
+ * my_log.check()?;
+ * my_log.append(&my_result)?;
+ * my_log.append_aggregate(&my_results)?;
+ */
+impl MetricsLog {
+    /** MetricsLog constructor function
+     *
+     * Initialize a metrics log at the given path, whose header is
+     * written when the first row is appended.
+     */
+    pub fn from(path: &str) -> MetricsLog {
+        MetricsLog {
+            path: String::from(path)
+        }
+    }
+
+    /** check function
+     *
+     * Check that the log may be appended to, creating it if needed, so
+     * that an unwritable log is reported before a run rather than
+     * after it.
+     */
+    pub fn check(&self) -> io::Result<()> {
+        OpenOptions::new().create(true).append(true).open(&self.path)?;
+        Ok(())
+    }
+
+    /** append function
+     *
+     * Append a row for the given run to the log, writing the header
+     * first if the log is empty.  The file is locked while appending
+     * so that runs logging in parallel do not interleave their rows.
+     */
+    pub fn append(&self, result: &SimulationResult) -> io::Result<()> {
+        let row: String = format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            result.config.num_floors, result.config.num_elevators, result.config.expected_arrivals,
+            quote_csv_field(&result.controller), result.seed, result.wait.mean, result.energy.avg_per_step,
+            result.wait.count, result.steps
+        );
+        append_row(&self.path, METRICS_HEADER, &row)
+    }
+
+    /** append_aggregate function
+     *
+     * Append a row aggregating the given runs of the same config, whose
+     * metrics are their means and whose seed is left empty to tell it
+     * apart from the rows of the runs themselves.  Nothing is appended
+     * if there are no runs.
+     */
+    pub fn append_aggregate(&self, results: &[SimulationResult]) -> io::Result<()> {
+        if let Some(first) = results.first() {
+            let num_runs: f64 = results.len() as f64;
+            let row: String = format!(
+                "{},{},{},{},,{},{},{},{}\n",
+                first.config.num_floors, first.config.num_elevators, first.config.expected_arrivals,
+                quote_csv_field(&first.controller),
+                results.iter().map(|x| x.wait.mean).sum::<f64>() / num_runs,
+                results.iter().map(|x| x.energy.avg_per_step.0).sum::<f64>() / num_runs,
+                results.iter().map(|x| x.wait.count as f64).sum::<f64>() / num_runs,
+                results.iter().map(|x| x.steps as f64).sum::<f64>() / num_runs
+            );
+            append_row(&self.path, METRICS_HEADER, &row)?;
+        }
+        Ok(())
+    }
+}
+
+/** append_row function
+ *
+ * Append a row to the CSV file at the given path, creating it if
+ * needed.  The file is locked, then the header is written first if it
+ * is empty, along with the row in one write.
+ */
+fn append_row(path: &str, header: &str, row: &str) -> io::Result<()> {
+    let mut file: File = OpenOptions::new().create(true).append(true).open(path)?;
+    file.lock()?;
+    let is_empty: bool = file.seek(SeekFrom::End(0_i64))? == 0_u64;
+    let file_str: String = if is_empty { format!("{}\n{}", header, row) } else { String::from(row) };
+    let write_result: io::Result<()> = file.write_all(file_str.as_bytes()).and_then(|_| file.flush());
+    file.unlock()?;
+    write_result
+}
+
+/** get_config_hash function
+ *
+ * Hash the JSON of a config, so that rows simulated with the same
+ * config share a key.
+ */
+pub fn get_config_hash(config_json: &str) -> String {
+    get_stable_hash(config_json)
+}
+
+/** get_stable_hash function
+ *
+ * Hash the given string with 64-bit FNV-1a, which unlike the standard
+ * library's hasher is stable across builds, and return it as hex.
+ */
+pub fn get_stable_hash(data: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325_u64;
+    for byte in data.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3_u64);
+    }
+    format!("{:016x}", hash)
+}
+
+/** quote_csv_field function
+ *
+ * Quote a CSV field, doubling any quotes within it
+ */
+fn quote_csv_field(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+/** split_csv_line function
+ *
+ * Split a CSV line into its fields, unquoting any quoted fields
+ */
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut field: String = String::new();
+    let mut in_quotes: bool = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c)
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/** parse_row function
+ *
+ * Parse a line of the log into a row, if it is well formed
+ */
+fn parse_row(line: &str) -> Option<ResultsRow> {
+    let fields: Vec<String> = split_csv_line(line);
+    if fields.len() != 7_usize {
+        return None;
+    }
+    Some(ResultsRow {
+        timestamp: fields[0].parse::<u64>().ok()?,
+        config_hash: fields[1].clone(),
+        config: fields[2].clone(),
+        seed: fields[3].parse::<u64>().ok()?,
+        controller: fields[4].clone(),
+        avg_wait_time: fields[5].parse::<f64>().ok()?,
+        avg_energy: fields[6].parse::<f64>().ok()?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::ControllerKind;
+    use crate::params::ControllerParams;
+    use crate::simulation::{SimulationConfig, run_simulation};
+
+    #[test]
+    fn logged_runs_are_read_back() {
+        let path: String = std::env::temp_dir()
+            .join(format!("elevator-results-{}.csv", std::process::id()))
+            .display().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        //Log two seeded runs of the same building with different controllers
+        let mut config: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.5_f64);
+        config.time_steps = 200_i32;
+        let results: Vec<SimulationResult> = [(ControllerKind::Random, 1_u64), (ControllerKind::Nearest, 2_u64)].iter()
+            .map(|(kind, seed)| run_simulation(&config, *kind, &ControllerParams::default(), *seed).unwrap())
+            .collect();
+        let log: ResultsLog = ResultsLog::from(&path);
+        for result in results.iter() {
+            log.append(result).unwrap();
+        }
+        let rows: Result<Vec<ResultsRow>, io::Error> = log.read();
+        let _ = std::fs::remove_file(&path);
+        let rows: Vec<ResultsRow> = rows.unwrap();
+
+        //Each row holds its run, under the hash of the config they share
+        assert_eq!(rows.len(), 2_usize);
+        for (row, result) in rows.iter().zip(results.iter()) {
+            assert_eq!(row.seed, result.seed);
+            assert_eq!(row.controller, result.controller);
+            assert_eq!(row.avg_wait_time, result.wait.mean);
+            assert_eq!(row.avg_energy, result.energy.avg_per_step.0);
+            assert_eq!(row.config, serde_json::to_string(&config).unwrap());
+        }
+        assert_eq!(rows[0].config_hash, rows[1].config_hash);
+
+        //The rows are queried back by metric and by controller
+        let best: &ResultsRow = ResultsMetric::AvgWait.get_best(&rows).unwrap();
+        let best_wait: f64 = results.iter().map(|x| x.wait.mean).fold(f64::INFINITY, f64::min);
+        assert_eq!(best.avg_wait_time, best_wait);
+        let groups: Vec<ResultsGroup> = ResultsAxis::Controller.compare(&rows, ResultsMetric::AvgWait);
+        assert_eq!(groups.iter().map(|x| x.key.as_str()).collect::<Vec<&str>>(), vec!["random", "nearest"]);
+        assert_eq!(ResultsAxis::Building.compare(&rows, ResultsMetric::AvgWait).len(), 1_usize);
+    }
+}
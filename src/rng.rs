@@ -0,0 +1,91 @@
+//Import external modules
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/** RngStream enum
+ *
+ * An RngStream is a purpose which randomness is drawn for during a
+ * simulation.  Each purpose draws from its own RNG, derived from the
+ * simulation's seed and the purpose alone, so that drawing more or
+ * fewer samples for one purpose never changes the samples drawn for
+ * another.  For a given seed, the arrivals are the same whichever
+ * controller is run, and a controller's choices are the same however
+ * the people behave.
+ *
+ * The purpose ids and the derivation are part of this contract, so
+ * they must not be changed, and new purposes must be given new ids.
+ * - Arrivals: The number of people arriving each time step
+ * - Destinations: The destination floor of each person arriving
+ * - Behavior: The decisions of the people in the building, such as leaving
+ * - Rerouting: The destination changes of the people on the elevators
+ * - Crowding: The crowding tolerances of the people arriving
+ * - Mobility: Whether the people arriving are slow to board
+ * - Exchange: The order the elevators exchange people in, if shuffled
+ * - Entry: The floor each person arriving enters the building on
+ * - Meetings: When and where meetings are called, and who goes to and leaves them
+ * - Controller: The choices of the controller with the given index
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RngStream {
+    Arrivals,
+    Destinations,
+    Behavior,
+    Rerouting,
+    Crowding,
+    Mobility,
+    Exchange,
+    Entry,
+    Meetings,
+    Controller(usize)
+}
+
+/** RngStream type implementation
+ *
+ * The following functions are implemented for the RngStream type,
+ * and are callable via
+ *
+ * //Example
+ * let my_rng: StdRng = RngStream::Arrivals.get_rng(seed);
+ */
+impl RngStream {
+    /** get_purpose_id function
+     *
+     * Return the id of the stream's purpose, which is mixed into the
+     * seed to derive its RNG.
+     */
+    pub fn get_purpose_id(&self) -> u64 {
+        match self {
+            RngStream::Arrivals => 1_u64,
+            RngStream::Destinations => 2_u64,
+            RngStream::Behavior => 3_u64,
+            RngStream::Rerouting => 4_u64,
+            RngStream::Crowding => 5_u64,
+            RngStream::Mobility => 6_u64,
+            RngStream::Exchange => 7_u64,
+            RngStream::Entry => 8_u64,
+            RngStream::Meetings => 9_u64,
+            RngStream::Controller(i) => 0x1000_u64 + *i as u64
+        }
+    }
+
+    /** get_rng function
+     *
+     * Derive the stream's RNG from the simulation's seed, by seeding
+     * it with the SplitMix64 mix of the seed and the purpose id.
+     */
+    pub fn get_rng(&self, seed: u64) -> StdRng {
+        StdRng::seed_from_u64(split_mix64(seed ^ self.get_purpose_id()))
+    }
+}
+
+/** split_mix64 function
+ *
+ * Mix the bits of the given value with the SplitMix64 finalizer, so
+ * that nearby seeds and purpose ids give unrelated RNGs.
+ */
+pub fn split_mix64(x: u64) -> u64 {
+    let mut z: u64 = x.wrapping_add(0x9e3779b97f4a7c15_u64);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9_u64);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb_u64);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,205 @@
+//Import source modules
+use crate::building::Building;
+use crate::simulation::SimulationResult;
+use crate::units::{Energy, Ticks};
+
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+/** Objective struct schema
+ *
+ * An Objective has the following properties
+ * - wait_weight (f64): The weight of the wait time
+ * - energy_weight (f64): The weight of the energy spent
+ * - fairness_weight (f64): The weight of the longest wait time
+ *
+ * An objective scores how bad a run or a time step was as a single
+ * value, lower being better, so that the tuner and any other consumer
+ * ranking runs weigh the same metrics the same way.  The default
+ * weighs the wait time alone.
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Objective {
+    pub wait_weight: f64,
+    pub energy_weight: f64,
+    pub fairness_weight: f64
+}
+
+//Default trait implementation for an objective, which weighs the wait time alone
+impl Default for Objective {
+    fn default() -> Objective {
+        Objective::from(1_f64, 0_f64, 0_f64)
+    }
+}
+
+/** TickReport struct schema
+ *
+ * A TickReport has the following properties
+ * - num_waiting (usize): The number of people waiting at the end of the time step, each of whom waited for it
+ * - energy_spent (Energy): The energy spent by the elevators during the time step
+ * - longest_wait (Ticks): The longest wait time of anyone in the building at the end of the time step
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+pub struct TickReport {
+    pub num_waiting: usize,
+    pub energy_spent: Energy,
+    pub longest_wait: Ticks
+}
+
+/** TickReport type implementation
+ *
+ * The following functions are implemented for the TickReport type,
+ * and are callable via
+ *
+ * //Example
+ * let my_report: TickReport = TickReport::from(&my_building, energy_spent);
+ */
+impl TickReport {
+    /** TickReport constructor function
+     *
+     * Initialize a report of the given building after a time step, in
+     * which its elevators spent the given energy.
+     */
+    pub fn from(building: &Building, energy_spent: Energy) -> TickReport {
+        let elevator_people = building.elevators.iter().flat_map(|x| x.people.iter());
+        let floor_people = building.floors.iter().flat_map(|x| x.get_people().iter());
+        let waiting_people: Vec<Ticks> = elevator_people.chain(floor_people)
+            .filter(|pers| pers.is_travelling())
+            .map(|pers| pers.wait_time)
+            .collect();
+        TickReport {
+            num_waiting: waiting_people.len(),
+            energy_spent,
+            longest_wait: waiting_people.iter().copied().max().unwrap_or(Ticks(0_u64))
+        }
+    }
+}
+
+/** Objective type implementation
+ *
+ * The following functions are implemented for the Objective type,
+ * and are callable via
+ *
+ * //Example
+ * let my_objective: Objective = Objective::from(0.7_f64, 0.3_f64, 0_f64);
+ * my_objective.validate()?;
+ * let my_score: f64 = my_objective.score(&my_result);
+ */
+impl Objective {
+    /** Objective constructor function
+     *
+     * Initialize an objective given the weights of the wait time, the
+     * energy spent and the longest wait time.
+     */
+    pub fn from(wait_weight: f64, energy_weight: f64, fairness_weight: f64) -> Objective {
+        Objective {
+            wait_weight,
+            energy_weight,
+            fairness_weight
+        }
+    }
+
+    /** validate function
+     *
+     * Check that every weight is a finite nonnegative number, and that
+     * at least one of them is positive.
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        let weights: [(&str, f64); 3] = [
+            ("wait weight", self.wait_weight),
+            ("energy weight", self.energy_weight),
+            ("fairness weight", self.fairness_weight)
+        ];
+        for (name, value) in weights.iter() {
+            if !(value.is_finite() && *value >= 0_f64) {
+                return Err(format!("{} {} must be a nonnegative number", name, value));
+            }
+        }
+        if weights.iter().all(|(_, value)| *value == 0_f64) {
+            return Err(String::from("at least one objective weight must be positive"));
+        }
+        Ok(())
+    }
+
+    /** score function
+     *
+     * Return the weighted sum of the average wait time, the average
+     * energy spent and the longest wait time of a run.
+     */
+    pub fn score(&self, result: &SimulationResult) -> f64 {
+        (self.wait_weight * result.wait.mean)
+            + (self.energy_weight * result.energy.avg_per_step.0)
+            + (self.fairness_weight * result.wait.max.as_f64())
+    }
+
+    /** step_score function
+     *
+     * Return the weighted sum of the wait time accrued, the energy
+     * spent and the longest wait time of a time step.
+     */
+    pub fn step_score(&self, report: &TickReport) -> f64 {
+        (self.wait_weight * report.num_waiting as f64)
+            + (self.energy_weight * report.energy_spent.0)
+            + (self.fairness_weight * report.longest_wait.as_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::ControllerKind;
+    use crate::params::ControllerParams;
+    use crate::simulation::{SimulationConfig, run_simulation};
+
+    //Run a short seeded simulation of a small building
+    fn get_result() -> SimulationResult {
+        let mut config: SimulationConfig = SimulationConfig::from(6_usize, 1_usize, 0.5_f64);
+        config.time_steps = 200_i32;
+        run_simulation(&config, ControllerKind::Nearest, &ControllerParams::default(), 4_u64).unwrap()
+    }
+
+    #[test]
+    fn score_is_the_weighted_sum_of_the_metrics() {
+        let result: SimulationResult = get_result();
+        let (wait, energy, longest): (f64, f64, f64) = (result.wait.mean, result.energy.avg_per_step.0, result.wait.max.as_f64());
+        assert!(wait > 0_f64 && energy > 0_f64 && longest > wait);
+
+        //Each weight scales its metric alone
+        let expected: f64 = (2_f64 * wait) + (0.5_f64 * energy) + (0.25_f64 * longest);
+        assert!((Objective::from(2_f64, 0.5_f64, 0.25_f64).score(&result) - expected).abs() < 1e-9_f64);
+
+        //Extreme weights reproduce a single metric
+        let cases: [(Objective, f64, &str); 4] = [
+            (Objective::default(), wait, "default"),
+            (Objective::from(1_f64, 0_f64, 0_f64), wait, "wait only"),
+            (Objective::from(0_f64, 1_f64, 0_f64), energy, "energy only"),
+            (Objective::from(0_f64, 0_f64, 1_f64), longest, "fairness only")
+        ];
+        for (objective, expected, name) in cases {
+            assert_eq!(objective.score(&result), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn step_score_weighs_the_tick_report() {
+        let report: TickReport = TickReport { num_waiting: 3_usize, energy_spent: Energy(2.5_f64), longest_wait: Ticks(7_u64) };
+        assert_eq!(Objective::from(2_f64, 4_f64, 0.5_f64).step_score(&report), 19.5_f64);
+        assert_eq!(Objective::from(0_f64, 1_f64, 0_f64).step_score(&report), 2.5_f64);
+        assert_eq!(Objective::default().step_score(&report), 3_f64);
+    }
+
+    #[test]
+    fn validate_rejects_bad_weights() {
+        let cases: [(Objective, bool); 5] = [
+            (Objective::from(0.7_f64, 0.3_f64, 0_f64), true),
+            (Objective::from(0_f64, 0_f64, 1_f64), true),
+            (Objective::from(0_f64, 0_f64, 0_f64), false),
+            (Objective::from(-1_f64, 1_f64, 0_f64), false),
+            (Objective::from(1_f64, f64::NAN, 0_f64), false)
+        ];
+        for (objective, is_valid) in cases {
+            assert_eq!(objective.validate().is_ok(), is_valid, "{:?}", objective);
+        }
+    }
+}
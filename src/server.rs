@@ -0,0 +1,84 @@
+//Import source modules
+use crate::controller::{ElevatorController, CommandController, parse_commands};
+use crate::simulation::{SimulationConfig, SimulationResult, step};
+use crate::snapshot::BuildingSnapshot;
+use crate::profile::Profiler;
+use crate::error::ElevatorError;
+
+//Import external/standard modules
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+//Constant representing the name of the controller in the results of served simulations
+pub const SERVER_CONTROLLER: &str = "server";
+
+/** run_server function
+ *
+ * Listen on the given port on the loopback interface and wait for a
+ * client to connect.  Each time step, send the client a snapshot of
+ * the building as one line of JSON, then wait up to the timeout for
+ * a line assigning each elevator U, D or S, which is applied when
+ * the elevators are next updated.  If the client times out or sends
+ * an invalid line, a warning is logged and every elevator is
+ * stopped.  The run ends early if the client disconnects.  If the
+ * building cannot be built, the port cannot be listened on or a time
+ * step fails, then an error is returned.
+ */
+pub fn run_server(config: &SimulationConfig, port: u16, timeout: Duration, seed: u64) -> Result<SimulationResult, ElevatorError> {
+    //Wait for a client to connect
+    let listener: TcpListener = TcpListener::bind(("127.0.0.1", port))?;
+    eprintln!("Listening on {}", listener.local_addr()?);
+    let (mut stream, client_addr) = listener.accept()?;
+    eprintln!("Client connected from {}", client_addr);
+    stream.set_read_timeout(Some(timeout))?;
+    let mut reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+
+    //Initialize the controller, whose building derives its RNG streams from the seed
+    let mut controller: CommandController = CommandController::from(config.build(seed)?);
+    let num_elevators: usize = controller.building.elevators.len();
+    let mut profiler: Profiler = Profiler::from(false);
+
+    //Loop until the number of time steps are complete or the client disconnects
+    let mut line: String = String::new();
+    for i in 0..config.time_steps {
+        //Send the snapshot of the building, ending the run if the client is gone
+        let snapshot_json: String = BuildingSnapshot::from(&controller.building, i).to_json();
+        if let Err(e) = writeln!(stream, "{}", snapshot_json).and_then(|_| stream.flush()) {
+            eprintln!("Client disconnected: {}", e);
+            break;
+        }
+
+        //Wait for the commands, keeping any partial line read before a timeout
+        match reader.read_line(&mut line) {
+            Ok(0_usize) => {
+                eprintln!("Client disconnected");
+                break;
+            },
+            Ok(_) => {
+                match parse_commands(&line, num_elevators) {
+                    Ok(commands) => controller.commands = commands,
+                    Err(e) => {
+                        eprintln!("Warning: invalid commands at step {} ({}), stopping all elevators", i, e);
+                        controller.stop_all();
+                    }
+                }
+                line.clear();
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                eprintln!("Warning: no commands within the timeout at step {}, stopping all elevators", i);
+                controller.stop_all();
+            },
+            Err(e) => {
+                eprintln!("Client disconnected: {}", e);
+                break;
+            }
+        }
+
+        //Advance the simulation with the commands
+        step(&mut controller, i, &mut profiler)?;
+    }
+
+    //Return the resulting metrics
+    Ok(SimulationResult::from(controller.get_building(), config, SERVER_CONTROLLER, seed))
+}
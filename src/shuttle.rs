@@ -0,0 +1,148 @@
+//Import source modules
+use crate::elevator::Elevator;
+use crate::people::People;
+
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+//Constants representing the default load and dwell of a lobby shuttle
+pub const SHUTTLE_CAPACITY: usize = 8_usize;
+pub const SHUTTLE_MAX_DWELL_STEPS: usize = 5_usize;
+
+/** get_default_capacity function
+ *
+ * Return the number of passengers a shuttle departs with when none
+ * is given.
+ */
+fn get_default_capacity() -> usize {
+    SHUTTLE_CAPACITY
+}
+
+/** get_default_max_dwell_steps function
+ *
+ * Return the number of time steps a shuttle waits to fill up when
+ * none is given.
+ */
+fn get_default_max_dwell_steps() -> usize {
+    SHUTTLE_MAX_DWELL_STEPS
+}
+
+/** ShuttleAssignment struct schema
+ *
+ * A ShuttleAssignment has the following properties
+ * - elevator (usize): The index of the elevator pinned as the lobby shuttle
+ * - capacity (usize): The number of passengers the shuttle departs the lobby with
+ * - max_dwell_steps (usize): The number of time steps the shuttle waits to fill up once someone boards
+ * - dwell_steps (usize): The number of time steps the shuttle has waited with passengers at the lobby
+ *
+ * The shuttle ignores hall calls, which the remaining elevators
+ * cover.  It returns to the lobby whenever it is empty and departs as
+ * soon as it is full or its dwell timer expires, stopping only for
+ * its passengers' destination floors.
+ */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ShuttleAssignment {
+    pub elevator: usize,
+    #[serde(default = "get_default_capacity")]
+    pub capacity: usize,
+    #[serde(default = "get_default_max_dwell_steps")]
+    pub max_dwell_steps: usize,
+    #[serde(skip)]
+    pub dwell_steps: usize
+}
+
+/** ShuttleAssignment type implementation
+ *
+ * The following functions are implemented for the ShuttleAssignment
+ * type, and are callable via
+ *
+ * //Example
+ * let mut my_shuttle: ShuttleAssignment = ShuttleAssignment::from(0_usize);
+ * let (decision, floor_to): (i32, Option<usize>) = my_shuttle.get_decision(&my_elevator, num_floors);
+ */
+impl ShuttleAssignment {
+    /** ShuttleAssignment constructor function
+     *
+     * Pin the elevator at the given index as the lobby shuttle, with
+     * the default capacity and dwell.
+     */
+    pub fn from(elevator: usize) -> ShuttleAssignment {
+        ShuttleAssignment {
+            elevator,
+            capacity: SHUTTLE_CAPACITY,
+            max_dwell_steps: SHUTTLE_MAX_DWELL_STEPS,
+            dwell_steps: 0_usize
+        }
+    }
+
+    /** get_decision function
+     *
+     * Decide whether the shuttle moves up (1), down (-1), or stays
+     * stopped (0), and the floor it is headed to, given the shuttle
+     * elevator and the number of floors in the building.
+     */
+    pub fn get_decision(&mut self, elevator: &Elevator, num_floors: usize) -> (i32, Option<usize>) {
+        //If moving, then stop for passengers getting off and at either end of the building
+        if !elevator.stopped {
+            let floor_to: Option<usize> = match elevator.floor_to {
+                Some(x) if x == elevator.floor_on => None,
+                floor_to => floor_to
+            };
+            if elevator.are_people_going_to_floor(elevator.floor_on)
+                || (!elevator.moving_up && elevator.floor_on == 0_usize)
+                || (elevator.moving_up && elevator.floor_on + 1_usize >= num_floors) {
+                return (0_i32, floor_to);
+            }
+            return (if elevator.moving_up { 1_i32 } else { -1_i32 }, floor_to);
+        }
+
+        //If stopped, then let passengers off, else go back to the lobby once empty
+        let nearest_dest_floor: usize = match elevator.get_nearest_dest_floor() {
+            Some((_, 0_usize)) => return (0_i32, None),
+            Some((floor, _)) => floor,
+            None => {
+                self.dwell_steps = 0_usize;
+                return if elevator.floor_on == 0_usize { (0_i32, None) } else { (-1_i32, Some(0_usize)) };
+            }
+        };
+
+        //If at the lobby, then wait to fill up until the dwell timer expires
+        if elevator.floor_on == 0_usize && elevator.people.len() < self.capacity
+            && self.dwell_steps < self.max_dwell_steps {
+            self.dwell_steps += 1_usize;
+            return (0_i32, None);
+        }
+
+        //Depart toward the nearest destination floor
+        self.dwell_steps = 0_usize;
+        if nearest_dest_floor > elevator.floor_on {
+            (1_i32, Some(nearest_dest_floor))
+        } else {
+            (-1_i32, Some(nearest_dest_floor))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::ControllerKind;
+    use crate::simulation::{SimulationConfig, SimulationResult, run_simulation};
+    use crate::params::ControllerParams;
+
+    #[test]
+    fn shuttle_shortens_lobby_waits() {
+        //Everyone arrives at the lobby of an 8 floor building with 2 elevators and few people leave, as during an up-peak
+        let mut config: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.8_f64);
+        config.entry_floors = vec![(0_usize, 1_f64)];
+        config.p_out = 0.005_f64;
+        config.time_steps = 500_i32;
+
+        //Pinning the first elevator as the lobby shuttle lowers the mean wait on the same seed
+        let nearest: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &ControllerParams::new(), 1_u64).unwrap();
+        let mut params: ControllerParams = ControllerParams::new();
+        params.set("shuttle", 0_f64);
+        let shuttle: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &params, 1_u64).unwrap();
+        assert!(shuttle.wait.mean < nearest.wait.mean, "shuttle {} >= nearest {}", shuttle.wait.mean, nearest.wait.mean);
+        assert!(shuttle.wait.count > 500_usize && nearest.wait.count > 500_usize);
+    }
+}
@@ -0,0 +1,1218 @@
+//Import source modules
+use crate::building::{Building, ExchangeOrder, P_OUT, SLOW_BOARDING_TICKS, validate_entry_floors};
+use crate::floors::Floors;
+use crate::controller::{ElevatorController, ControllerKind, RandomController, NearestController, MaxWaitController};
+use crate::params::ControllerParams;
+use crate::profile::{Profiler, Phase};
+use crate::format::ScenarioFile;
+use crate::rng::RngStream;
+use crate::snapshot::BuildingSnapshot;
+use crate::zone::ZoneMap;
+use crate::demand::DEMAND_SMOOTHING;
+use crate::score::Objective;
+use crate::bank::{Bank, BankSummary, validate_banks};
+use crate::elevator::{ElevatorProfile, ElevatorHome};
+use crate::maintenance::MaintenanceWindow;
+use crate::error::ElevatorError;
+use crate::destination::DestinationModel;
+use crate::units::Energy;
+use crate::energy::OverloadPenalty;
+use crate::meeting::MeetingModel;
+use crate::arrival::ArrivalProfile;
+use crate::observe::ObservabilityLevel;
+use crate::stats::{StatsSummary, EnergyBreakdown, FloorStats, ElevatorStats, get_mean_and_std_dev};
+use crate::results::get_config_hash;
+
+//Import libraries
+use std::fmt;
+use std::thread;
+use std::time;
+use std::sync::atomic::{AtomicUsize, AtomicU64, Ordering};
+use serde::{Serialize, Deserialize};
+
+/** SimulationConfig struct schema
+ *
+ * A SimulationConfig has the following properties
+ * - num_floors (usize): The number of floors in the building
+ * - num_elevators (usize): The number of elevators in the building
+ * - expected_arrivals (f64): The expected number of people arriving per time step
+ * - energy_up (f64): The base energy spent moving an elevator up
+ * - energy_down (f64): The base energy spent moving an elevator down
+ * - energy_coef (f64): The coefficient for energy spent by moving N people
+ * - time_steps (i32): The number of time steps to simulate
+ * - demand_smoothing (f64): The weight of the latest time step in the building's demand estimates
+ * - p_change_dest (f64): The probability each person on an elevator changes their destination each time step
+ * - no_reversal (bool): Whether an occupied elevator is kept from moving away from all its passengers' destinations
+ * - objective (Objective): The weights runs of the building are scored with, such as when tuning
+ * - capacity (Option<usize>): The number of passengers each elevator may carry, if limited
+ * - min_crowding_tolerance (f64): The lowest fraction of an elevator's capacity people arriving are willing to ride at
+ * - p_slow_boarding (f64): The probability a person arriving is slow to get on and off elevators
+ * - slow_boarding_ticks (usize): The number of time steps a person slow to board takes to get on or off
+ * - banks (Vec<Bank>): The banks the elevators are grouped into, each serving its own floors
+ * - elevator_profiles (Vec<ElevatorProfile>): The profile of each elevator, all passenger elevators if empty
+ * - freight_serves_passengers (bool): Whether freight elevators always answer calls and carry people
+ * - freight_surge_waiting (Option<usize>): The number of people waiting above which freight elevators carry people
+ * - homes (Vec<ElevatorHome>): The floors elevators return to after idling, if any
+ * - maintenance (Vec<MaintenanceWindow>): The recurring windows in which elevators are out of service, if any
+ * - exchange_order (ExchangeOrder): The order the elevators exchange people in each time step
+ * - entry_floors (Vec<(usize, f64)>): The floors people arrive on and their weights, the first floor alone if empty
+ * - destinations (DestinationModel): The way the destination floors of the people arriving are drawn
+ * - overload (Option<OverloadPenalty>): The extra energy elevators spend carrying more than a nominal load, if any
+ * - meetings (Option<MeetingModel>): The meetings drawing people to a floor and then sending them down, if any
+ * - max_population (Option<usize>): The most people allowed in the building at once, if limited
+ * - observability (ObservabilityLevel): How much of the building the controller sees
+ * - strict_stops (bool): Whether an elevator about to pass a floor some of its passengers are headed to is stopped there
+ * - p_out (f64): The probability each person arriving leaves each time step, P_OUT by default
+ * - arrival_profile (ArrivalProfile): The schedule scaling the expected arrivals over the time steps, constant by default
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    pub num_floors: usize,
+    pub num_elevators: usize,
+    pub expected_arrivals: f64,
+    pub energy_up: f64,
+    pub energy_down: f64,
+    pub energy_coef: f64,
+    pub time_steps: i32,
+    #[serde(default = "get_default_demand_smoothing")]
+    pub demand_smoothing: f64,
+    #[serde(default)]
+    pub p_change_dest: f64,
+    #[serde(default)]
+    pub no_reversal: bool,
+    #[serde(default)]
+    pub objective: Objective,
+    #[serde(default)]
+    pub capacity: Option<usize>,
+    #[serde(default = "get_default_crowding_tolerance")]
+    pub min_crowding_tolerance: f64,
+    #[serde(default)]
+    pub p_slow_boarding: f64,
+    #[serde(default = "get_default_slow_boarding_ticks")]
+    pub slow_boarding_ticks: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banks: Vec<Bank>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elevator_profiles: Vec<ElevatorProfile>,
+    #[serde(default)]
+    pub freight_serves_passengers: bool,
+    #[serde(default)]
+    pub freight_surge_waiting: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub homes: Vec<ElevatorHome>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintenance: Vec<MaintenanceWindow>,
+    #[serde(default)]
+    pub exchange_order: ExchangeOrder,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entry_floors: Vec<(usize, f64)>,
+    #[serde(default, skip_serializing_if = "DestinationModel::is_uniform")]
+    pub destinations: DestinationModel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overload: Option<OverloadPenalty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meetings: Option<MeetingModel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_population: Option<usize>,
+    #[serde(default, skip_serializing_if = "ObservabilityLevel::is_full")]
+    pub observability: ObservabilityLevel,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_stops: bool,
+    #[serde(default = "get_default_p_out", skip_serializing_if = "is_default_p_out")]
+    pub p_out: f64,
+    #[serde(default, skip_serializing_if = "ArrivalProfile::is_constant")]
+    pub arrival_profile: ArrivalProfile
+}
+
+/** get_default_p_out function
+ *
+ * Return the probability of leaving each time step when a config does
+ * not give one.
+ */
+fn get_default_p_out() -> f64 {
+    P_OUT
+}
+
+/** is_default_p_out function
+ *
+ * Return whether a probability of leaving is the default, which is
+ * left out of the config so that the hashes of older configs hold.
+ */
+fn is_default_p_out(p_out: &f64) -> bool {
+    *p_out == P_OUT
+}
+
+/** get_default_demand_smoothing function
+ *
+ * Return the weight of the latest time step in the demand estimates
+ * when a config does not give one.
+ */
+fn get_default_demand_smoothing() -> f64 {
+    DEMAND_SMOOTHING
+}
+
+/** get_default_crowding_tolerance function
+ *
+ * Return the lowest crowding tolerance when a config does not give
+ * one, with which nobody refuses an elevator with room.
+ */
+fn get_default_crowding_tolerance() -> f64 {
+    1_f64
+}
+
+/** get_default_slow_boarding_ticks function
+ *
+ * Return the number of time steps a person slow to board takes to get
+ * on or off when a config does not give one.
+ */
+fn get_default_slow_boarding_ticks() -> usize {
+    SLOW_BOARDING_TICKS
+}
+
+/** SimulationConfig type implementation
+ *
+ * The following functions are implemented for the SimulationConfig
+ * type, and are callable via
+ *
+ * //Example
+ * let my_config: SimulationConfig = SimulationConfig::from(4_usize, 2_usize, 0.2_f64);
+ * let my_building: Building = my_config.build(seed)?;
+ */
+impl SimulationConfig {
+    /** SimulationConfig constructor function
+     *
+     * Initialize a config for a building with the given floors,
+     * elevators and arrival rate, with the default energy parameters
+     * and number of time steps.
+     */
+    pub fn from(num_floors: usize, num_elevators: usize, expected_arrivals: f64) -> SimulationConfig {
+        SimulationConfig {
+            num_floors,
+            num_elevators,
+            expected_arrivals,
+            energy_up: 5.0_f64,   //Base energy spent moving elevator up
+            energy_down: 2.5_f64, //Base energy spent moving elevator down
+            energy_coef: 0.5_f64, //Coefficient for energy spent by moving N people
+            time_steps: 1000_i32,
+            demand_smoothing: DEMAND_SMOOTHING,
+            p_change_dest: 0_f64,
+            no_reversal: false,
+            objective: Objective::default(),
+            capacity: None,
+            min_crowding_tolerance: 1_f64,
+            p_slow_boarding: 0_f64,
+            slow_boarding_ticks: SLOW_BOARDING_TICKS,
+            banks: Vec::new(),
+            elevator_profiles: Vec::new(),
+            freight_serves_passengers: false,
+            freight_surge_waiting: None,
+            homes: Vec::new(),
+            maintenance: Vec::new(),
+            exchange_order: ExchangeOrder::default(),
+            entry_floors: Vec::new(),
+            destinations: DestinationModel::Uniform,
+            overload: None,
+            meetings: None,
+            max_population: None,
+            observability: ObservabilityLevel::Full,
+            strict_stops: false,
+            p_out: P_OUT,
+            arrival_profile: ArrivalProfile::Constant
+        }
+    }
+
+    /** validate function
+     *
+     * Check that a building can be simulated with the config.  It must
+     * have at least one floor, a finite nonnegative arrival rate and
+     * energy parameters, a nonnegative number of time steps, and a
+     * demand smoothing factor above 0 and at most 1, a destination
+     * change probability between 0 and 1, a valid objective,
+     * a positive elevator capacity if limited, a minimum crowding
+     * tolerance above 0 and at most 1, a slow boarding probability
+     * between 0 and 1 and positive slow boarding ticks, valid banks, if
+     * any, and
+     * either no elevator profiles or one per elevator, and at most one
+     * home per elevator, on a floor of the building, valid
+     * maintenance windows, valid entry floors, if any, a valid
+     * destination model, a valid overload penalty and meeting model,
+     * if any, a positive population limit, if limited, and a probability
+     * of leaving above 0 and at most 1.  Any number of elevators,
+     * including none, may be simulated.
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_floors == 0_usize {
+            return Err(String::from("the building must have at least one floor"));
+        }
+        if !(self.expected_arrivals.is_finite() && self.expected_arrivals >= 0_f64) {
+            return Err(format!("expected arrivals {} must be in [0, inf)", self.expected_arrivals));
+        }
+        for (name, value) in [("energy up", self.energy_up), ("energy down", self.energy_down), ("energy coefficient", self.energy_coef)] {
+            if !(value.is_finite() && value >= 0_f64) {
+                return Err(format!("{} {} must be a nonnegative number", name, value));
+            }
+        }
+        if self.time_steps < 0_i32 {
+            return Err(format!("time steps {} must not be negative", self.time_steps));
+        }
+        if !(self.demand_smoothing > 0_f64 && self.demand_smoothing <= 1_f64) {
+            return Err(format!("demand smoothing {} must be above 0 and at most 1", self.demand_smoothing));
+        }
+        if !(self.p_out > 0_f64 && self.p_out <= 1_f64) {
+            return Err(format!("probability of leaving {} must be above 0 and at most 1", self.p_out));
+        }
+        if !(self.p_change_dest >= 0_f64 && self.p_change_dest <= 1_f64) {
+            return Err(format!("destination change probability {} must be between 0 and 1", self.p_change_dest));
+        }
+        self.objective.validate()?;
+        if self.capacity == Some(0_usize) {
+            return Err(String::from("the elevator capacity must be positive"));
+        }
+        if !(self.min_crowding_tolerance > 0_f64 && self.min_crowding_tolerance <= 1_f64) {
+            return Err(format!("minimum crowding tolerance {} must be above 0 and at most 1", self.min_crowding_tolerance));
+        }
+        if !(self.p_slow_boarding >= 0_f64 && self.p_slow_boarding <= 1_f64) {
+            return Err(format!("slow boarding probability {} must be between 0 and 1", self.p_slow_boarding));
+        }
+        if self.slow_boarding_ticks == 0_usize {
+            return Err(String::from("the slow boarding ticks must be positive"));
+        }
+        validate_banks(&self.banks, self.num_floors, self.num_elevators)?;
+        validate_entry_floors(&self.entry_floors, self.num_floors)?;
+        self.destinations.validate(self.num_floors)?;
+        if let Some(overload) = &self.overload {
+            overload.validate()?;
+        }
+        if let Some(meetings) = &self.meetings {
+            meetings.validate()?;
+        }
+        if self.max_population == Some(0_usize) {
+            return Err(String::from("max population must be positive if limited"));
+        }
+        if !self.elevator_profiles.is_empty() && self.elevator_profiles.len() != self.num_elevators {
+            return Err(format!(
+                "{} elevator profiles were given for {} elevators", self.elevator_profiles.len(), self.num_elevators
+            ));
+        }
+        for (i, home) in self.homes.iter().enumerate() {
+            if home.elevator >= self.num_elevators {
+                return Err(format!(
+                    "home elevator {} is out of range for a building with {} elevators", home.elevator, self.num_elevators
+                ));
+            }
+            if home.floor >= self.num_floors {
+                return Err(format!(
+                    "home floor {} is out of range for a building with {} floors", home.floor, self.num_floors
+                ));
+            }
+            if self.homes[..i].iter().any(|x| x.elevator == home.elevator) {
+                return Err(format!("elevator {} is given more than one home", home.elevator));
+            }
+        }
+        for window in self.maintenance.iter() {
+            window.validate(self.num_elevators)?;
+        }
+        Ok(())
+    }
+
+    /** build function
+     *
+     * Initialize a building with the configured floors, elevators,
+     * arrival rate, energy parameters, demand smoothing and policies, whose RNG
+     * streams are derived from the given seed.  The config is validated
+     * first, and if it is invalid, then an error is returned.
+     */
+    pub fn build(&self, seed: u64) -> Result<Building, ElevatorError> {
+        self.validate().map_err(ElevatorError::Config)?;
+        let mut building: Building = Building::from(
+            self.num_floors,
+            self.num_elevators,
+            self.expected_arrivals,
+            self.energy_up,
+            self.energy_down,
+            self.energy_coef,
+            seed
+        )?;
+        building.set_demand_smoothing(self.demand_smoothing);
+        building.p_change_dest = self.p_change_dest;
+        building.set_no_reversal(self.no_reversal);
+        building.set_profiles(&self.elevator_profiles);
+        building.set_capacity(self.capacity);
+        building.set_overload(self.overload);
+        building.min_crowding_tolerance = self.min_crowding_tolerance;
+        building.p_slow_boarding = self.p_slow_boarding;
+        building.slow_boarding_ticks = self.slow_boarding_ticks;
+        building.freight_serves_passengers = self.freight_serves_passengers;
+        building.freight_surge_waiting = self.freight_surge_waiting;
+        building.set_banks(self.banks.clone());
+        building.set_homes(&self.homes);
+        building.maintenance = self.maintenance.clone();
+        building.exchange_order = self.exchange_order;
+        building.set_entry_floors(self.entry_floors.clone())?;
+        building.set_destination_model(self.destinations.clone())?;
+        building.meetings = self.meetings;
+        building.max_population = self.max_population;
+        building.strict_stops = self.strict_stops;
+        building.set_p_out(self.p_out)?;
+        building.set_arrival_profile(self.arrival_profile, self.time_steps.max(0_i32) as u64)?;
+        Ok(building)
+    }
+}
+
+//Constant representing the header of the CSV rows of simulation results
+pub const RESULT_CSV_HEADER: &str = "config_hash,seed,controller,steps,num_delivered,avg_wait_time,max_wait_time,\
+avg_ride_time,max_ride_time,total_energy,avg_energy,throughput,abandonments";
+
+/** SimulationResult struct schema
+ *
+ * A SimulationResult has the following properties
+ * - config (SimulationConfig): The config the simulation was run with
+ * - seed (u64): The seed the simulation was run with
+ * - controller (String): The name of the controller the simulation was run with
+ * - steps (u64): The number of time steps the metrics cover
+ * - wait (StatsSummary): The wait times of the people who reached their destination, from arriving to getting off
+ * - floor_wait (StatsSummary): The time those people spent waiting on a floor, before getting on an elevator
+ * - ride (StatsSummary): The time those people spent on an elevator
+ * - energy (EnergyBreakdown): The energy spent by the elevators
+ * - throughput (f64): The number of people who reached their destination per time step
+ * - abandonments (usize): The number of trips given up on, which is always 0 since nobody gives up in this model
+ * - arrivals (usize): The number of people who arrived during the time steps the metrics cover
+ * - rejected_arrivals (usize): The number of people turned away on arriving because the building was full
+ * - missed_stops (usize): The number of times an elevator left a floor some of its passengers were headed to
+ * - per_floor (Vec<FloorStats>): The people getting on, off and waiting at each floor
+ * - per_elevator (Vec<ElevatorStats>): The people delivered, energy spent and downtime of each elevator
+ * - banks (Vec<BankSummary>): The people delivered and their average wait time per bank, if any
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub config: SimulationConfig,
+    pub seed: u64,
+    pub controller: String,
+    pub steps: u64,
+    pub wait: StatsSummary,
+    #[serde(default)]
+    pub floor_wait: StatsSummary,
+    pub ride: StatsSummary,
+    pub energy: EnergyBreakdown,
+    pub throughput: f64,
+    pub abandonments: usize,
+    #[serde(default)]
+    pub arrivals: usize,
+    #[serde(default)]
+    pub rejected_arrivals: usize,
+    #[serde(default)]
+    pub missed_stops: usize,
+    pub per_floor: Vec<FloorStats>,
+    pub per_elevator: Vec<ElevatorStats>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banks: Vec<BankSummary>
+}
+
+/** SimulationResult type implementation
+ *
+ * The following functions are implemented for the SimulationResult
+ * type, and are callable via
+ *
+ * //Example
+ * let my_result: SimulationResult = SimulationResult::from(&my_building, &my_config, "random", seed);
+ * let my_row: String = my_result.to_csv_row();
+ */
+impl SimulationResult {
+    /** SimulationResult constructor function
+     *
+     * Initialize the result of a simulation from the building at the
+     * end of it, the config and seed it was built from, and the name of
+     * the controller which ran it.
+     */
+    pub fn from(building: &Building, config: &SimulationConfig, controller: &str, seed: u64) -> SimulationResult {
+        let num_delivered: usize = building.get_num_delivered();
+        let moving_up: Energy = building.elevators.iter().map(|x| x.energy_meter.get_spent_up()).sum();
+        let moving_down: Energy = building.elevators.iter().map(|x| x.energy_meter.get_spent_down()).sum();
+        SimulationResult {
+            config: config.clone(),
+            seed,
+            controller: String::from(controller),
+            steps: building.measured_steps,
+            wait: building.wait_times.to_summary(),
+            floor_wait: building.floor_wait_times.to_summary(),
+            ride: StatsSummary::from_total(num_delivered, building.total_ride_time, building.max_ride_time),
+            energy: EnergyBreakdown {
+                total: moving_up + moving_down,
+                avg_per_step: Energy(building.energy.get_mean()),
+                moving_up,
+                moving_down
+            },
+            throughput: if building.measured_steps == 0_u64 {
+                0_f64
+            } else {
+                num_delivered as f64 / building.measured_steps as f64
+            },
+            abandonments: 0_usize,
+            arrivals: building.num_arrivals,
+            rejected_arrivals: building.rejected_arrivals,
+            missed_stops: building.missed_stops,
+            per_floor: building.floors.iter().enumerate().map(|(i, x)| FloorStats::from(i, x)).collect(),
+            per_elevator: building.elevators.iter().enumerate().map(|(i, x)| ElevatorStats::from(i, x)).collect(),
+            banks: building.get_bank_summaries()
+        }
+    }
+
+    /** to_csv_row function
+     *
+     * Return the headline metrics of the result as a CSV row, without
+     * a trailing newline, whose columns are given by RESULT_CSV_HEADER.
+     * The config is keyed by its hash.
+     */
+    pub fn to_csv_row(&self) -> String {
+        let config_json: String = serde_json::to_string(&self.config).unwrap_or_default();
+        format!(
+            "{},{},\"{}\",{},{},{},{},{},{},{},{},{},{}",
+            get_config_hash(&config_json), self.seed, self.controller.replace('"', "\"\""), self.steps,
+            self.wait.count, self.wait.mean, self.wait.max, self.ride.mean, self.ride.max,
+            self.energy.total, self.energy.avg_per_step, self.throughput, self.abandonments
+        )
+    }
+}
+
+//Implement display for SimulationResult, as a summary of its headline metrics and banks
+impl fmt::Display for SimulationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "seed {} ({}, {} steps): average floor wait {:.2}, average ride time {:.2}, average time in system {:.2}, \
+            max time in system {}, average energy spent {:.2}, {:.3} delivered per step",
+            self.seed, self.controller, self.steps, self.floor_wait.mean, self.ride.mean, self.wait.mean, self.wait.max,
+            self.energy.avg_per_step, self.throughput
+        )?;
+        if !self.config.arrival_profile.is_constant() {
+            write!(f, ", {} arrived under the {} profile", self.arrivals, self.config.arrival_profile.get_name())?;
+        }
+        if self.rejected_arrivals > 0_usize {
+            write!(f, ", {} arrivals turned away", self.rejected_arrivals)?;
+        }
+        if self.missed_stops > 0_usize {
+            write!(f, ", {} missed stops", self.missed_stops)?;
+        }
+        for bank in self.banks.iter() {
+            write!(f, "\n  Bank {}: {} delivered, average wait time {:.2}", bank.name, bank.num_delivered, bank.avg_wait_time)?;
+        }
+        Ok(())
+    }
+}
+
+//Constant representing the version of the summary schema
+pub const SUMMARY_VERSION: u32 = 2_u32;
+
+/** SimulationSummary struct schema
+ *
+ * A SimulationSummary is the final report of a run for scripts to read,
+ * with the parameters it was run with and its headline metrics.  Unlike
+ * a SimulationResult, which carries the whole config, its fields only
+ * change along with its version.
+ *
+ * A SimulationSummary has the following properties
+ * - version (u32): The version of the summary schema
+ * - num_floors (usize): The number of floors in the building
+ * - num_elevators (usize): The number of elevators in the building
+ * - expected_arrivals (f64): The expected number of people arriving each time step, before the arrival profile scales it
+ * - arrival_profile (ArrivalProfile): The schedule scaling the expected arrivals over the run
+ * - arrivals (usize): The number of people who arrived during the time steps the metrics cover
+ * - controller (String): The name of the controller the run was simulated with
+ * - seed (u64): The seed the run was simulated with
+ * - time_steps (u64): The number of time steps the metrics cover
+ * - avg_wait_time (f64): The average wait time of the people who reached their destination
+ * - wait_time_denom (usize): The number of people the average wait time is taken over
+ * - avg_energy (Energy): The average energy spent per time step
+ * - total_energy (Energy): The energy spent over the run
+ * - per_elevator (Vec<ElevatorStats>): The people delivered, energy spent and downtime of each elevator
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SimulationSummary {
+    pub version: u32,
+    pub num_floors: usize,
+    pub num_elevators: usize,
+    pub expected_arrivals: f64,
+    pub arrival_profile: ArrivalProfile,
+    pub arrivals: usize,
+    pub controller: String,
+    pub seed: u64,
+    pub time_steps: u64,
+    pub avg_wait_time: f64,
+    pub wait_time_denom: usize,
+    pub avg_energy: Energy,
+    pub total_energy: Energy,
+    pub per_elevator: Vec<ElevatorStats>
+}
+
+/** SimulationSummary type implementation
+ *
+ * The following functions are implemented for the SimulationSummary
+ * type, and are callable via
+ *
+ * //Example
+ * let my_summary: SimulationSummary = SimulationSummary::from(&my_result);
+ * let my_json: String = my_summary.to_json()?;
+ * let my_parsed: SimulationSummary = SimulationSummary::from_json(&my_json)?;
+ */
+impl SimulationSummary {
+    /** SimulationSummary constructor function
+     *
+     * Initialize the summary of a run from its result.
+     */
+    pub fn from(result: &SimulationResult) -> SimulationSummary {
+        SimulationSummary {
+            version: SUMMARY_VERSION,
+            num_floors: result.config.num_floors,
+            num_elevators: result.config.num_elevators,
+            expected_arrivals: result.config.expected_arrivals,
+            arrival_profile: result.config.arrival_profile,
+            arrivals: result.arrivals,
+            controller: result.controller.clone(),
+            seed: result.seed,
+            time_steps: result.steps,
+            avg_wait_time: result.wait.mean,
+            wait_time_denom: result.wait.count,
+            avg_energy: result.energy.avg_per_step,
+            total_energy: result.energy.total,
+            per_elevator: result.per_elevator.clone()
+        }
+    }
+
+    /** to_json function
+     *
+     * Return the summary as a single line of JSON.
+     */
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    /** from_json function
+     *
+     * Parse a summary from JSON, failing if its version is not the
+     * current one.
+     */
+    pub fn from_json(json: &str) -> Result<SimulationSummary, String> {
+        let summary: SimulationSummary = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if summary.version != SUMMARY_VERSION {
+            return Err(format!("unsupported version {}, expected {}", summary.version, SUMMARY_VERSION));
+        }
+        Ok(summary)
+    }
+}
+
+/** ReplicationSummary struct schema
+ *
+ * A ReplicationSummary aggregates the headline metrics of repeated
+ * runs of the same config from different seeds, so that the noise of a
+ * single run can be told apart from a real difference.
+ *
+ * A ReplicationSummary has the following properties
+ * - num_runs (usize): The number of runs aggregated
+ * - avg_wait_time (f64): The mean over the runs of their average wait time
+ * - std_wait_time (f64): The standard deviation over the runs of their average wait time
+ * - avg_energy (f64): The mean over the runs of their average energy spent per time step
+ * - std_energy (f64): The standard deviation over the runs of their average energy spent per time step
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ReplicationSummary {
+    pub num_runs: usize,
+    pub avg_wait_time: f64,
+    pub std_wait_time: f64,
+    pub avg_energy: f64,
+    pub std_energy: f64
+}
+
+/** ReplicationSummary type implementation
+ *
+ * The following functions are implemented for the ReplicationSummary
+ * type, and are callable via
+ *
+ * //Example
+ * let my_summary: ReplicationSummary = ReplicationSummary::from(&my_results);
+ */
+impl ReplicationSummary {
+    /** ReplicationSummary constructor function
+     *
+     * Aggregate the results of the given runs.
+     */
+    pub fn from(results: &[SimulationResult]) -> ReplicationSummary {
+        let wait_times: Vec<f64> = results.iter().map(|x| x.wait.mean).collect();
+        let energies: Vec<f64> = results.iter().map(|x| x.energy.avg_per_step.0).collect();
+        let (avg_wait_time, std_wait_time): (f64, f64) = get_mean_and_std_dev(&wait_times);
+        let (avg_energy, std_energy): (f64, f64) = get_mean_and_std_dev(&energies);
+        ReplicationSummary {
+            num_runs: results.len(),
+            avg_wait_time,
+            std_wait_time,
+            avg_energy,
+            std_energy
+        }
+    }
+}
+
+//Implement display for ReplicationSummary, as the mean and standard deviation of each metric
+impl fmt::Display for ReplicationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "Across {} runs: average wait time {:.2} (std dev {:.2}), average energy spent {:.2} (std dev {:.2})",
+            self.num_runs, self.avg_wait_time, self.std_wait_time, self.avg_energy, self.std_energy
+        )
+    }
+}
+
+/** step function
+ *
+ * Advance the simulation by one time step, generating people,
+ * moving them on and off the elevators, updating the elevators,
+ * and accumulating the metrics.  If the profiler is enabled, then
+ * the duration of each phase of the time step is measured.  If the
+ * people arriving cannot be generated, then an error is returned.
+ */
+pub fn step<C: ElevatorController + ?Sized>(controller: &mut C, time_step: i32, profiler: &mut Profiler) -> Result<(), ElevatorError> {
+    step_with(controller, time_step, profiler, |building: &mut Building| building.gen_people())
+}
+
+/** step_scenario function
+ *
+ * Advance the simulation by one time step as in step, first applying
+ * the scenario's events and arrivals for the time step, if any.
+ */
+pub fn step_scenario<C>(controller: &mut C, time_step: i32, profiler: &mut Profiler,
+                        scenario: Option<&ScenarioFile>) -> Result<(), ElevatorError> where C: ElevatorController + ?Sized {
+    step_with(controller, time_step, profiler, |building: &mut Building| {
+        if let Some(x) = scenario {
+            x.apply(building, time_step);
+        }
+        building.gen_people()
+    })
+}
+
+/** step_with function
+ *
+ * Advance the simulation by one time step, using the given function
+ * to generate the people arriving and leaving, such as from a trace
+ * rather than an RNG.  If the function returns an error, then the
+ * time step is abandoned and the error is returned.
+ */
+pub fn step_with<C, F>(controller: &mut C, time_step: i32, profiler: &mut Profiler, gen_people: F) -> Result<(), ElevatorError>
+    where C: ElevatorController + ?Sized, F: FnOnce(&mut Building) -> Result<(), ElevatorError> {
+    //Check whether to profile once per time step, so the unprofiled step has no timers
+    if profiler.enabled {
+        step_phases::<C, F, true>(controller, time_step, profiler, gen_people)
+    } else {
+        step_phases::<C, F, false>(controller, time_step, profiler, gen_people)
+    }
+}
+
+/** step_phases function
+ *
+ * Advance the simulation by one time step, measuring the duration
+ * of each phase only if PROFILE is true.
+ */
+fn step_phases<C, F, const PROFILE: bool>(controller: &mut C, time_step: i32, profiler: &mut Profiler, gen_people: F)
+    -> Result<(), ElevatorError> where C: ElevatorController + ?Sized, F: FnOnce(&mut Building) -> Result<(), ElevatorError> {
+    //Clear the previous time step's events
+    sim_span!(_step_span, "step", time_step);
+    let building: &mut Building = controller.get_building_mut();
+    building.events.clear();
+
+    //Take elevators in and out of service for maintenance
+    building.update_maintenance();
+
+    //Generate people arriving and leaving, then fold them into the demand estimates
+    sim_span!(arrivals_span, "arrivals");
+    gen_people(building)?;
+    building.demand.update();
+    building.flush_first_floor();
+    sim_exit!(arrivals_span);
+    if PROFILE {
+        profiler.lap(Phase::Arrivals);
+    }
+
+    //Move people on and off the elevators, then cache the waiting and destination floors
+    sim_span!(exchange_span, "exchange");
+    building.exchange_people_on_elevator();
+    building.update_cache();
+    sim_exit!(exchange_span);
+    if PROFILE {
+        profiler.lap(Phase::Exchange);
+    }
+
+    //Update the elevators
+    sim_span!(controller_span, "controller");
+    controller.update_elevators();
+    sim_exit!(controller_span);
+    if PROFILE {
+        profiler.lap(Phase::Controller);
+    }
+
+    //Increment the wait times, update average energy, update dest probabilities
+    sim_span!(metrics_span, "metrics");
+    let building: &mut Building = controller.get_building_mut();
+    let energy_spent: Energy = building.get_energy_spent();
+    building.increment_wait_times();
+    building.tick(energy_spent);
+    building.update_dest_probabilities();
+    sim_exit!(metrics_span);
+    if PROFILE {
+        profiler.lap(Phase::Metrics);
+    }
+
+    //Check the invariants every time step in debug builds
+    if cfg!(debug_assertions) {
+        enforce_invariants(controller.get_building(), time_step);
+    }
+    Ok(())
+}
+
+/** enforce_invariants function
+ *
+ * Check the invariants of the building after the given time step.  If
+ * any is violated, then panic with the violation and a snapshot of the
+ * building, so that the panic unwinds through any test or thread.
+ */
+pub fn enforce_invariants(building: &Building, time_step: i32) {
+    if let Err(violation) = building.check_invariants() {
+        sim_event!(error, time_step, %violation, "invariant violated");
+        panic!(
+            "Invariant violated at step {}: {}\n{}",
+            time_step, violation, BuildingSnapshot::from(building, time_step).to_json()
+        );
+    }
+}
+
+/** run_controller function
+ *
+ * Run the configured number of time steps with the given controller,
+ * of the given kind, counting each on the given counter, then return
+ * the resulting metrics, or the error of any time step which failed.
+ */
+fn run_controller(mut controller: Box<dyn ElevatorController>, config: &SimulationConfig, controller_kind: ControllerKind,
+                  seed: u64, steps_run: &AtomicU64) -> Result<SimulationResult, ElevatorError> {
+    let mut profiler: Profiler = Profiler::from(false);
+    for i in 0..config.time_steps {
+        step(controller.as_mut(), i, &mut profiler)?;
+        steps_run.fetch_add(1_u64, Ordering::Relaxed);
+    }
+    Ok(SimulationResult::from(controller.get_building(), config, controller_kind.get_name(), seed))
+}
+
+/** build_controller function
+ *
+ * Build the configured building and a controller of the given kind
+ * for it, tuned with the given validated parameters and seeing as
+ * much of the building as configured.  The building and
+ * the controller each own their RNG streams, which are derived from
+ * the given seed.  If the building cannot be built from the config,
+ * then an error is returned.
+ */
+pub fn build_controller(config: &SimulationConfig, controller_kind: ControllerKind, params: &ControllerParams,
+                        seed: u64) -> Result<Box<dyn ElevatorController>, ElevatorError> {
+    let building: Building = config.build(seed)?;
+    Ok(match controller_kind {
+        ControllerKind::Random => Box::new(RandomController::from(building, RngStream::Controller(0_usize).get_rng(seed))),
+        ControllerKind::Nearest => {
+            let mut controller: NearestController = NearestController::from(building);
+            controller.apply_params(params);
+            controller.observability = config.observability;
+            Box::new(controller)
+        },
+        ControllerKind::OddEven => {
+            let mut controller: NearestController = NearestController::from_zones(
+                building, ZoneMap::odd_even(config.num_floors, config.num_elevators)
+            );
+            controller.apply_params(params);
+            controller.observability = config.observability;
+            Box::new(controller)
+        },
+        ControllerKind::MaxWait => {
+            let mut controller: MaxWaitController = MaxWaitController::from(building);
+            controller.observability = config.observability;
+            Box::new(controller)
+        }
+    })
+}
+
+/** run_simulation function
+ *
+ * Run a full simulation of the configured building with the given
+ * kind of controller, tuned with the given validated parameters.  The
+ * simulation owns all of its RNGs, which are derived from the given
+ * seed, so that the same seed always produces the same result
+ * regardless of which thread it is run on.  If the building cannot be
+ * built from the config, or a time step fails, then an error is
+ * returned.
+ */
+pub fn run_simulation(config: &SimulationConfig, controller_kind: ControllerKind, params: &ControllerParams,
+                      seed: u64) -> Result<SimulationResult, ElevatorError> {
+    run_counted_simulation(config, controller_kind, params, seed, &AtomicU64::new(0_u64))
+}
+
+/** run_counted_simulation function
+ *
+ * Run a full simulation as run_simulation does, counting each time
+ * step run on the given counter, which may be shared between threads.
+ */
+fn run_counted_simulation(config: &SimulationConfig, controller_kind: ControllerKind, params: &ControllerParams,
+                          seed: u64, steps_run: &AtomicU64) -> Result<SimulationResult, ElevatorError> {
+    let controller: Box<dyn ElevatorController> = build_controller(config, controller_kind, params, seed)?;
+    run_controller(controller, config, controller_kind, seed, steps_run)
+}
+
+//Constant representing how often the progress of replications is reported
+const PROGRESS_INTERVAL: time::Duration = time::Duration::from_millis(100_u64);
+
+/** ReplicationProgress struct schema
+ *
+ * A ReplicationProgress has the following properties
+ * - num_runs_done (usize): The number of runs completed
+ * - num_runs (usize): The number of runs in all
+ * - num_steps_done (u64): The number of time steps run so far, across all the runs
+ * - num_steps (u64): The number of time steps in all the runs
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReplicationProgress {
+    pub num_runs_done: usize,
+    pub num_runs: usize,
+    pub num_steps_done: u64,
+    pub num_steps: u64
+}
+
+/** ReplicationProgress type implementation
+ *
+ * The following functions are implemented for the ReplicationProgress
+ * type, and are callable via
+ *
+ * //Example
+ * let my_fraction: f64 = my_progress.get_fraction_done();
+ */
+impl ReplicationProgress {
+    /** get_fraction_done function
+     *
+     * Return the fraction of the time steps of all the runs which have
+     * been run, 1 if there are none.
+     */
+    pub fn get_fraction_done(&self) -> f64 {
+        if self.num_steps == 0_u64 {
+            1_f64
+        } else {
+            self.num_steps_done as f64 / self.num_steps as f64
+        }
+    }
+}
+
+//Type representing the result of a replication, along with the index of its seed
+type IndexedResult = (usize, Result<SimulationResult, ElevatorError>);
+
+/** run_replications function
+ *
+ * Run a simulation for each of the given seeds with the given kind of
+ * controller and parameters, fanning the runs out across one thread per
+ * available core.  The results are returned in the order of the seeds,
+ * or the error of the first run to fail, if any.
+ */
+pub fn run_replications(config: &SimulationConfig, controller_kind: ControllerKind, params: &ControllerParams,
+                        seeds: &[u64]) -> Result<Vec<SimulationResult>, ElevatorError> {
+    fan_out_replications(config, controller_kind, params, seeds, None)
+}
+
+/** run_replications_with_progress function
+ *
+ * Run a simulation for each of the given seeds as run_replications
+ * does, passing the progress across all the runs to the given callback
+ * on the calling thread every PROGRESS_INTERVAL while they run, and
+ * once more when they are all complete.  The callback is never called
+ * from the threads running the simulations, so it may print freely.
+ */
+pub fn run_replications_with_progress<F>(config: &SimulationConfig, controller_kind: ControllerKind, params: &ControllerParams,
+                                         seeds: &[u64], mut on_progress: F) -> Result<Vec<SimulationResult>, ElevatorError>
+    where F: FnMut(&ReplicationProgress) {
+    fan_out_replications(config, controller_kind, params, seeds, Some(&mut on_progress))
+}
+
+/** fan_out_replications function
+ *
+ * Run a simulation for each of the given seeds across one thread per
+ * available core, polling the progress of the runs from the calling
+ * thread if a callback is given.
+ */
+fn fan_out_replications(config: &SimulationConfig, controller_kind: ControllerKind, params: &ControllerParams, seeds: &[u64],
+                        mut on_progress: Option<&mut dyn FnMut(&ReplicationProgress)>)
+                        -> Result<Vec<SimulationResult>, ElevatorError> {
+    //Get the number of threads to run the replications across
+    let num_runs: usize = seeds.len();
+    let num_threads: usize = match thread::available_parallelism() {
+        Ok(x) => x.get().min(num_runs).max(1_usize),
+        Err(_) => 1_usize
+    };
+
+    //Spawn the threads, each taking the next unclaimed run until none remain
+    let next_run: AtomicUsize = AtomicUsize::new(0_usize);
+    let num_completed: AtomicUsize = AtomicUsize::new(0_usize);
+    let steps_run: AtomicU64 = AtomicU64::new(0_u64);
+    let get_progress = || ReplicationProgress {
+        num_runs_done: num_completed.load(Ordering::Relaxed),
+        num_runs,
+        num_steps_done: steps_run.load(Ordering::Relaxed),
+        num_steps: config.time_steps.max(0_i32) as u64 * num_runs as u64
+    };
+    let mut indexed_results: Vec<IndexedResult> = thread::scope(|scope| {
+        let handles: Vec<thread::ScopedJoinHandle<Vec<IndexedResult>>> = (0..num_threads).map(|_| {
+            scope.spawn(|| {
+                let mut thread_results: Vec<IndexedResult> = Vec::new();
+                loop {
+                    let i: usize = next_run.fetch_add(1_usize, Ordering::Relaxed);
+                    if i >= num_runs {
+                        break;
+                    }
+                    thread_results.push((i, run_counted_simulation(config, controller_kind, params, seeds[i], &steps_run)));
+                    num_completed.fetch_add(1_usize, Ordering::Relaxed);
+                }
+                thread_results
+            })
+        }).collect();
+
+        //Report the progress of the runs until they are all complete, if requested
+        if let Some(on_progress) = on_progress.as_mut() {
+            while !handles.iter().all(|handle| handle.is_finished()) {
+                on_progress(&get_progress());
+                thread::sleep(PROGRESS_INTERVAL);
+            }
+            on_progress(&get_progress());
+        }
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    });
+
+    //Put the results back in the order of the seeds
+    indexed_results.sort_by_key(|(i, _)| *i);
+    indexed_results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::SimEvent;
+
+    #[test]
+    #[should_panic(expected = "Invariant violated at step 3: elevator 0 is on floor 9, outside a building with 5 floors")]
+    fn enforce_invariants_panics_with_violation() {
+        let mut building: Building = SimulationConfig::from(5_usize, 1_usize, 0.5_f64).build(0_u64).unwrap();
+        enforce_invariants(&building, 2_i32);
+        building.elevators[0].floor_on = 9_usize;
+        enforce_invariants(&building, 3_i32);
+    }
+
+    #[test]
+    fn controller_does_not_change_arrivals() {
+        let mut config: SimulationConfig = SimulationConfig::from(10_usize, 3_usize, 0.8_f64);
+        config.entry_floors = vec![(0_usize, 3_f64), (5_usize, 1_f64)];
+        config.destinations = DestinationModel::Zipf { s: 1_f64 };
+        let mut arrival_sequences: Vec<Vec<String>> = Vec::new();
+        for controller_kind in [ControllerKind::Random, ControllerKind::Nearest, ControllerKind::OddEven, ControllerKind::MaxWait] {
+            //Record every arrival of 300 time steps with the same seed
+            let mut controller: Box<dyn ElevatorController> = build_controller(
+                &config, controller_kind, &ControllerParams::default(), 11_u64
+            ).unwrap();
+            let mut profiler: Profiler = Profiler::from(false);
+            let mut arrivals: Vec<String> = Vec::new();
+            for i in 0_i32..300_i32 {
+                step(controller.as_mut(), i, &mut profiler).unwrap();
+                for event in controller.get_building().events.iter() {
+                    if matches!(event, SimEvent::PersonArrived { .. }) {
+                        arrivals.push(format!("{} {:?}", i, event));
+                    }
+                }
+            }
+            arrival_sequences.push(arrivals);
+        }
+        assert!(arrival_sequences[0].len() > 100_usize);
+        for arrivals in arrival_sequences.iter().skip(1_usize) {
+            assert_eq!(arrivals, &arrival_sequences[0]);
+        }
+    }
+
+    #[test]
+    fn parallel_replications_match_sequential_runs() {
+        let mut config: SimulationConfig = SimulationConfig::from(10_usize, 3_usize, 0.5_f64);
+        config.time_steps = 200_i32;
+        let seeds: Vec<u64> = (100_u64..108_u64).collect();
+        for controller_kind in [ControllerKind::Random, ControllerKind::Nearest] {
+            let params: ControllerParams = ControllerParams::default();
+            let parallel: Vec<SimulationResult> = run_replications(&config, controller_kind, &params, &seeds).unwrap();
+            assert_eq!(parallel.len(), seeds.len());
+            for (seed, parallel_result) in seeds.iter().zip(parallel.iter()) {
+                let sequential_result: SimulationResult = run_simulation(&config, controller_kind, &params, *seed).unwrap();
+                assert_eq!(parallel_result.seed, *seed);
+                assert_eq!(serde_json::to_string(parallel_result).unwrap(), serde_json::to_string(&sequential_result).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn replication_progress_completes() {
+        let mut config: SimulationConfig = SimulationConfig::from(5_usize, 1_usize, 0.5_f64);
+        config.time_steps = 50_i32;
+        let seeds: Vec<u64> = (0_u64..4_u64).collect();
+        let mut last_progress: Option<ReplicationProgress> = None;
+        run_replications_with_progress(&config, ControllerKind::Nearest, &ControllerParams::default(), &seeds, |x| {
+            last_progress = Some(*x);
+        }).unwrap();
+        let last_progress: ReplicationProgress = last_progress.unwrap();
+        assert_eq!((last_progress.num_runs_done, last_progress.num_runs), (4_usize, 4_usize));
+        assert_eq!((last_progress.num_steps_done, last_progress.num_steps), (200_u64, 200_u64));
+        assert_eq!(last_progress.get_fraction_done(), 1_f64);
+    }
+
+    #[test]
+    fn seeded_result_round_trips_through_json() {
+        let mut config: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.6_f64);
+        config.time_steps = 300_i32;
+        let result: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &ControllerParams::default(), 5_u64).unwrap();
+        assert_eq!((result.seed, result.steps, result.controller.as_str()), (5_u64, 300_u64, "nearest"));
+        assert_eq!((result.per_floor.len(), result.per_elevator.len()), (8_usize, 2_usize));
+        assert!(result.wait.count > 0_usize);
+
+        //Every field, down to the last bit of each float, survives the round trip
+        let json: String = serde_json::to_string(&result).unwrap();
+        let read_back: SimulationResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(read_back, result);
+        assert_eq!(read_back.to_csv_row(), result.to_csv_row());
+    }
+
+    #[test]
+    fn summary_round_trips_and_pins_its_version() {
+        let mut config: SimulationConfig = SimulationConfig::from(8_usize, 2_usize, 0.6_f64);
+        config.time_steps = 200_i32;
+        let result: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &ControllerParams::default(), 9_u64).unwrap();
+        let summary: SimulationSummary = SimulationSummary::from(&result);
+
+        //The summary is written at version 2, on a single line, and reads back whole
+        let json: String = summary.to_json().unwrap();
+        assert_eq!(SUMMARY_VERSION, 2_u32);
+        assert!(json.starts_with("{\"version\":2,") && !json.contains('\n'), "{}", json);
+        let read_back: SimulationSummary = SimulationSummary::from_json(&json).unwrap();
+        assert_eq!(read_back, summary);
+        assert_eq!((read_back.seed, read_back.time_steps, read_back.controller.as_str()), (9_u64, 200_u64, "nearest"));
+        assert_eq!((read_back.avg_wait_time, read_back.wait_time_denom), (result.wait.mean, result.wait.count));
+        assert_eq!((read_back.total_energy, read_back.per_elevator.len()), (result.energy.total, 2_usize));
+
+        //A summary of another version is rejected
+        let other_json: String = json.replacen("\"version\":2", "\"version\":1", 1_usize);
+        assert_eq!(SimulationSummary::from_json(&other_json), Err(String::from("unsupported version 1, expected 2")));
+    }
+
+    #[test]
+    fn random_misses_stops_and_nearest_does_not() {
+        let mut config: SimulationConfig = SimulationConfig::from(10_usize, 2_usize, 0.5_f64);
+        config.time_steps = 500_i32;
+        let params: ControllerParams = ControllerParams::default();
+        let random_result: SimulationResult = run_simulation(&config, ControllerKind::Random, &params, 3_u64).unwrap();
+        let nearest_result: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &params, 3_u64).unwrap();
+        assert!(random_result.missed_stops > 0_usize);
+        assert_eq!(nearest_result.missed_stops, 0_usize);
+        assert!(random_result.to_string().contains(&format!("{} missed stops", random_result.missed_stops)));
+
+        //With strict stops, each missed stop is logged as forced and the elevator stays put
+        config.strict_stops = true;
+        let mut controller: Box<dyn ElevatorController> = build_controller(&config, ControllerKind::Random, &params, 3_u64).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut num_forced: usize = 0_usize;
+        for i in 0_i32..config.time_steps {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+            for event in building.events.iter() {
+                if let SimEvent::StopMissed { elevator, floor, forced, .. } = event {
+                    assert!(*forced, "unforced missed stop at step {}", i);
+                    assert!(building.elevators[*elevator].stopped && building.elevators[*elevator].floor_on == *floor, "left at step {}", i);
+                    num_forced += 1_usize;
+                }
+            }
+        }
+        assert!(num_forced > 0_usize);
+        assert_eq!(controller.get_building().missed_stops, num_forced);
+    }
+
+    #[test]
+    fn high_p_out_drains_the_building_faster() {
+        //Fill a building of short visits and one of long stays with the same arrivals, then stop the arrivals
+        let mut populations: Vec<(usize, usize)> = Vec::new();
+        for p_out in [0.5_f64, 0.01_f64] {
+            let mut config: SimulationConfig = SimulationConfig::from(10_usize, 3_usize, 2_f64);
+            config.p_out = p_out;
+            let mut controller: Box<dyn ElevatorController> = build_controller(
+                &config, ControllerKind::Nearest, &ControllerParams::default(), 21_u64
+            ).unwrap();
+            let mut profiler: Profiler = Profiler::from(false);
+            for i in 0_i32..30_i32 {
+                step(controller.as_mut(), i, &mut profiler).unwrap();
+            }
+            let num_arrivals: usize = controller.get_building().num_arrivals;
+            controller.get_building_mut().set_expected_arrivals(0_f64).unwrap();
+
+            //Let the building drain for 100 time steps
+            for i in 30_i32..130_i32 {
+                step(controller.as_mut(), i, &mut profiler).unwrap();
+            }
+            let building: &Building = controller.get_building();
+            assert_eq!(building.num_arrivals, num_arrivals, "arrivals after stopping them");
+            populations.push((num_arrivals, building.get_population()));
+        }
+
+        //Both saw the same arrivals, but far fewer remain where people leave quickly
+        let ((high_arrivals, high_population), (low_arrivals, low_population)) = (populations[0], populations[1]);
+        assert_eq!(high_arrivals, low_arrivals);
+        assert!(low_arrivals > 30_usize);
+        assert!(high_population * 4_usize < low_population, "{} people remain with a high p_out, {} with a low one", high_population, low_population);
+    }
+
+    #[test]
+    fn overload_penalty_costs_energy_under_heavy_traffic() {
+        //Run the same heavy traffic with and without a penalty beyond 4 people
+        let mut config: SimulationConfig = SimulationConfig::from(12_usize, 2_usize, 2.5_f64);
+        config.time_steps = 300_i32;
+        let without: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &ControllerParams::default(), 5_u64).unwrap();
+        config.overload = Some(OverloadPenalty::from(4_usize, 0.5_f64));
+        let with: SimulationResult = run_simulation(&config, ControllerKind::Nearest, &ControllerParams::default(), 5_u64).unwrap();
+
+        //The penalty changes nothing but the energy, which it raises in both directions
+        assert_eq!((with.arrivals, with.throughput), (without.arrivals, without.throughput));
+        assert_eq!((with.wait.count, with.wait.max, with.ride.max), (without.wait.count, without.wait.max, without.ride.max));
+        assert!(with.energy.total > without.energy.total, "{} <= {}", with.energy.total, without.energy.total);
+        assert!(with.energy.moving_up > without.energy.moving_up);
+        assert!(with.energy.moving_down > without.energy.moving_down);
+    }
+
+    //Get the difference between the energy each elevator of a building has spent and that of the same elevator of another
+    fn get_energy_differences(building: &Building, other: &Building) -> Vec<f64> {
+        building.elevators.iter().zip(other.elevators.iter()).map(|(x, y)| x.energy_total().0 - y.energy_total().0).collect()
+    }
+
+    #[test]
+    fn overload_penalty_is_spent_by_overloaded_moving_cabs() {
+        //Step the same heavy traffic in lockstep with and without a penalty beyond 4 people
+        let penalty: OverloadPenalty = OverloadPenalty::from(4_usize, 0.5_f64);
+        let mut config: SimulationConfig = SimulationConfig::from(12_usize, 2_usize, 2.5_f64);
+        let mut without: Box<dyn ElevatorController> = build_controller(&config, ControllerKind::Nearest, &ControllerParams::default(), 8_u64).unwrap();
+        config.overload = Some(penalty);
+        let mut with: Box<dyn ElevatorController> = build_controller(&config, ControllerKind::Nearest, &ControllerParams::default(), 8_u64).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut num_penalized: usize = 0_usize;
+        for i in 0_i32..200_i32 {
+            let extras: Vec<f64> = get_energy_differences(with.get_building(), without.get_building());
+            step(without.as_mut(), i, &mut profiler).unwrap();
+            step(with.as_mut(), i, &mut profiler).unwrap();
+
+            //Each moving cab spends exactly the penalty for the load it moved on top of the energy spent without it
+            let building: &Building = with.get_building();
+            for (j, (elevator, other)) in building.elevators.iter().zip(without.get_building().elevators.iter()).enumerate() {
+                assert_eq!((elevator.floor_on, elevator.people.len()), (other.floor_on, other.people.len()), "elevator {} at step {}", j, i);
+                let extra: f64 = get_energy_differences(building, without.get_building())[j] - extras[j];
+                let expected: f64 = if elevator.stopped { 0_f64 } else { penalty.get_energy(elevator.people.len()) };
+                assert!((extra - expected).abs() < 1e-9_f64, "elevator {} at step {}: {} != {}", j, i, extra, expected);
+                if expected > 0_f64 {
+                    num_penalized += 1_usize;
+                }
+            }
+        }
+        assert!(num_penalized > 10_usize, "only {} penalized cab steps", num_penalized);
+    }
+}
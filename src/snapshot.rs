@@ -0,0 +1,536 @@
+//Import source modules
+use crate::building::Building;
+use crate::elevator::DoorState;
+use crate::elevators::get_elevator_label;
+use crate::event::SimEvent;
+use crate::people::People;
+use crate::person::{Person, PersonState};
+use crate::units::Ticks;
+
+//Import external modules
+use std::fmt::Write as FmtWrite;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+
+/** PersonSnapshot struct schema
+ *
+ * A PersonSnapshot has the following properties
+ * - id (usize): The id of the person
+ * - floor_to (usize): The floor the person is headed to
+ * - state (PersonState): The stage of a trip the person is at
+ * - wait_time (Ticks): The number of time steps of the person's current trip
+ * - ride_time (Ticks): The number of those time steps spent on an elevator
+ */
+#[derive(Serialize, Deserialize)]
+pub struct PersonSnapshot {
+    pub id: usize,
+    pub floor_to: usize,
+    pub state: PersonState,
+    pub wait_time: Ticks,
+    pub ride_time: Ticks
+}
+
+/** PersonSnapshot type implementation
+ *
+ * The following functions are implemented for the PersonSnapshot type,
+ * and are callable via
+ *
+ * //Example
+ * let my_snapshot: PersonSnapshot = PersonSnapshot::from(&my_person);
+ */
+impl PersonSnapshot {
+    /** PersonSnapshot constructor function
+     *
+     * Capture the state of the given person
+     */
+    pub fn from(pers: &Person) -> PersonSnapshot {
+        PersonSnapshot {
+            id: pers.id,
+            floor_to: pers.floor_to,
+            state: pers.state,
+            wait_time: pers.wait_time,
+            ride_time: pers.ride_time
+        }
+    }
+}
+
+/** FloorSnapshot struct schema
+ *
+ * A FloorSnapshot has the following properties
+ * - num_people (usize): The number of people on the floor
+ * - num_waiting (usize): The number of people on the floor waiting for an elevator
+ * - dest_prob (f64): The probability the floor becomes a destination next time step
+ * - people (Vec<PersonSnapshot>): The people on the floor, only captured by a detailed snapshot
+ */
+#[derive(Serialize, Deserialize)]
+pub struct FloorSnapshot {
+    pub num_people: usize,
+    pub num_waiting: usize,
+    pub dest_prob: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<PersonSnapshot>
+}
+
+/** ElevatorSnapshot struct schema
+ *
+ * An ElevatorSnapshot has the following properties
+ * - floor_on (usize): The floor the elevator is on
+ * - floor_to (Option<usize>): The floor the elevator is headed to, if any
+ * - num_people (usize): The number of people on the elevator
+ * - stopped (bool): Whether the elevator is stopped
+ * - moving_up (bool): Whether the elevator is moving up
+ * - door_state (DoorState): The state of the elevator's doors
+ * - in_service (bool): Whether the elevator is in service, rather than out for maintenance
+ * - downtime (usize): The number of time steps the elevator has spent out of service
+ * - people (Vec<PersonSnapshot>): The people on the elevator, only captured by a detailed snapshot
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ElevatorSnapshot {
+    pub floor_on: usize,
+    pub floor_to: Option<usize>,
+    pub num_people: usize,
+    pub stopped: bool,
+    pub moving_up: bool,
+    pub door_state: DoorState,
+    pub in_service: bool,
+    pub downtime: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<PersonSnapshot>
+}
+
+/** BuildingSnapshot struct schema
+ *
+ * A BuildingSnapshot has the following properties
+ * - time_step (i32): The time step the snapshot was taken after
+ * - avg_wait_time (f64): The average wait time throughout the building
+ * - avg_energy (f64): The average energy spent by the elevators
+ * - floors (Vec<FloorSnapshot>): The state of each floor, bottom floor first
+ * - elevators (Vec<ElevatorSnapshot>): The state of each elevator
+ */
+#[derive(Serialize, Deserialize)]
+pub struct BuildingSnapshot {
+    pub time_step: i32,
+    pub avg_wait_time: f64,
+    pub avg_energy: f64,
+    pub floors: Vec<FloorSnapshot>,
+    pub elevators: Vec<ElevatorSnapshot>
+}
+
+/** BuildingSnapshot type implementation
+ *
+ * The following functions are implemented for the BuildingSnapshot
+ * type, and are callable via
+ *
+ * //Example
+ * let my_snapshot: BuildingSnapshot = BuildingSnapshot::from(&my_building, 0_i32);
+ * let snapshot_json: String = my_snapshot.to_json();
+ * let my_dump: String = BuildingSnapshot::from_detailed(&my_building, 0_i32).get_dump(&my_building.events, true);
+ */
+impl BuildingSnapshot {
+    /** BuildingSnapshot constructor function
+     *
+     * Capture the state of the building after the given time step
+     */
+    pub fn from(building: &Building, time_step: i32) -> BuildingSnapshot {
+        BuildingSnapshot {
+            time_step,
+            avg_wait_time: building.wait_times.get_mean(),
+            avg_energy: building.energy.get_mean(),
+            floors: building.floors.iter().map(|floor| FloorSnapshot {
+                num_people: floor.get_num_people(),
+                num_waiting: floor.get_num_people_waiting(),
+                dest_prob: floor.dest_prob,
+                people: Vec::new()
+            }).collect(),
+            elevators: building.elevators.iter().map(|elevator| ElevatorSnapshot {
+                floor_on: elevator.floor_on,
+                floor_to: elevator.floor_to,
+                num_people: elevator.get_num_people(),
+                stopped: elevator.stopped,
+                moving_up: elevator.moving_up,
+                door_state: elevator.door_state,
+                in_service: elevator.in_service,
+                downtime: elevator.downtime,
+                people: Vec::new()
+            }).collect()
+        }
+    }
+
+    /** BuildingSnapshot constructor function
+     *
+     * Capture the state of the building after the given time step,
+     * along with everyone on each floor and elevator
+     */
+    pub fn from_detailed(building: &Building, time_step: i32) -> BuildingSnapshot {
+        let mut snapshot: BuildingSnapshot = BuildingSnapshot::from(building, time_step);
+        for (floor_snapshot, floor) in snapshot.floors.iter_mut().zip(building.floors.iter()) {
+            floor_snapshot.people = floor.get_people().iter().map(PersonSnapshot::from).collect();
+        }
+        for (elevator_snapshot, elevator) in snapshot.elevators.iter_mut().zip(building.elevators.iter()) {
+            elevator_snapshot.people = elevator.people.iter().map(PersonSnapshot::from).collect();
+        }
+        snapshot
+    }
+
+    /** to_json function
+     *
+     * Serialize the snapshot as a single line of JSON
+     */
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /** from_json function
+     *
+     * Parse a snapshot from its JSON, such as a line of a snapshot
+     * stream, returning an error if it is not a valid snapshot.
+     */
+    pub fn from_json(json: &str) -> Result<BuildingSnapshot, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /** get_dump function
+     *
+     * Return a plain text dump of the snapshot for stepping through a
+     * run, with the state of each elevator and the floor it is headed
+     * to, followed by the events of the time step, which record the
+     * controller's decisions and their reasons, such as the stops it
+     * inserted, the trips it dropped and the elevators it sent home.
+     * If verbose, then everyone on each elevator and floor is listed
+     * with their destination and wait time, the floors top floor first.
+     */
+    pub fn get_dump(&self, events: &[SimEvent], verbose: bool) -> String {
+        //Write the time step and the running averages
+        let mut dump: String = String::new();
+        let _ = writeln!(
+            dump, "Step {}: average wait time {:.2}, average energy spent {:.2}",
+            self.time_step + 1_i32, self.avg_wait_time, self.avg_energy
+        );
+
+        //Write the state of each elevator, and its passengers if verbose
+        let _ = writeln!(dump, "Elevators:");
+        for (i, elevator) in self.elevators.iter().enumerate() {
+            let motion: &str = if !elevator.in_service {
+                "out of service"
+            } else if elevator.stopped {
+                "stopped"
+            } else if elevator.moving_up {
+                "moving up"
+            } else {
+                "moving down"
+            };
+            let doors: &str = match elevator.door_state {
+                DoorState::Closed => "closed",
+                DoorState::Open => "open",
+                DoorState::Closing => "closing"
+            };
+            let floor_to: String = elevator.floor_to.map_or(String::from("idle"), |x| format!("to {}", x));
+            let _ = writeln!(
+                dump, "  {} on {}, {}, {}, doors {}, {} aboard",
+                get_elevator_label(i), elevator.floor_on, floor_to, motion, doors, elevator.num_people
+            );
+            if verbose {
+                write_people(&mut dump, &elevator.people);
+            }
+        }
+
+        //Write the people on each floor if verbose, top floor first, skipping the empty floors
+        if verbose {
+            let _ = writeln!(dump, "Floors:");
+            for (i, floor) in self.floors.iter().enumerate().rev() {
+                if floor.num_people == 0_usize {
+                    continue;
+                }
+                let _ = writeln!(
+                    dump, "  {}: {} waiting of {}, destination probability {:.3}",
+                    i, floor.num_waiting, floor.num_people, floor.dest_prob
+                );
+                write_people(&mut dump, &floor.people);
+            }
+        }
+
+        //Write the events of the time step
+        let _ = writeln!(dump, "Events:");
+        if events.is_empty() {
+            let _ = writeln!(dump, "  none");
+        }
+        for event in events.iter() {
+            let _ = writeln!(dump, "  {}", event.describe());
+        }
+        dump
+    }
+}
+
+/** write_people function
+ *
+ * Write a line to the dump for each of the given people, with their
+ * stage of the trip, destination and wait and ride times.
+ */
+fn write_people(dump: &mut String, people: &[PersonSnapshot]) {
+    for pers in people.iter() {
+        let _ = writeln!(
+            dump, "    P#{} {:?} to {}, waited {}, rode {}",
+            pers.id, pers.state, pers.floor_to, pers.wait_time, pers.ride_time
+        );
+    }
+}
+
+/** SnapshotSink enum
+ *
+ * Where a snapshot log writes its snapshots.
+ * - Stream: A single JSON-Lines file, one snapshot per line
+ * - Directory: A directory of JSON files, one per snapshot, numbered by time step
+ */
+enum SnapshotSink {
+    Stream(BufWriter<File>),
+    Directory(String)
+}
+
+/** SnapshotLog struct schema
+ *
+ * A SnapshotLog has the following properties
+ * - sink (SnapshotSink): Where the snapshots are written
+ * - interval (i32): The number of time steps between snapshots
+ * - num_written (usize): The number of snapshots written so far
+ *
+ * A SnapshotLog writes the full state of a building every interval
+ * time steps, starting from the first, for tools which visualize a run
+ * from outside.  The snapshot is only captured on the time steps it is
+ * written, so that sparse sampling of a long run costs next to nothing.
+ */
+pub struct SnapshotLog {
+    sink: SnapshotSink,
+    interval: i32,
+    num_written: usize
+}
+
+/** SnapshotLog type implementation
+ *
+ * The following functions are implemented for the SnapshotLog type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_log: SnapshotLog = SnapshotLog::create("snapshots.jsonl", 100_i32)?;
+ * my_log.record_step(&my_building, time_step)?;
+ * my_log.finish()?;
+ */
+impl SnapshotLog {
+    /** create function
+     *
+     * Create the snapshot log at the given path, writing every interval
+     * time steps.  A path with the jsonl extension is created as a
+     * JSON-Lines file, replacing any there, and any other path as a
+     * directory of numbered JSON files, created if missing.  An interval
+     * of 0 is taken as 1.
+     */
+    pub fn create(path: &str, interval: i32) -> io::Result<SnapshotLog> {
+        let sink: SnapshotSink = if Path::new(path).extension().is_some_and(|x| x == "jsonl") {
+            SnapshotSink::Stream(BufWriter::new(File::create(path)?))
+        } else {
+            fs::create_dir_all(path)?;
+            SnapshotSink::Directory(String::from(path))
+        };
+        Ok(SnapshotLog {
+            sink,
+            interval: interval.max(1_i32),
+            num_written: 0_usize
+        })
+    }
+
+    /** record_step function
+     *
+     * Write a snapshot of the building after the given time step, if
+     * it is one of the time steps sampled.
+     */
+    pub fn record_step(&mut self, building: &Building, time_step: i32) -> io::Result<()> {
+        if time_step % self.interval != 0_i32 {
+            return Ok(());
+        }
+        let snapshot: BuildingSnapshot = BuildingSnapshot::from(building, time_step);
+        match &mut self.sink {
+            SnapshotSink::Stream(writer) => {
+                serde_json::to_writer(&mut *writer, &snapshot)?;
+                writeln!(writer)?;
+            },
+            SnapshotSink::Directory(dir) => {
+                let file_path: PathBuf = Path::new(dir).join(get_snapshot_file_name(time_step));
+                let mut writer: BufWriter<File> = BufWriter::new(File::create(file_path)?);
+                serde_json::to_writer(&mut writer, &snapshot)?;
+                writer.flush()?;
+            }
+        }
+        self.num_written += 1_usize;
+        Ok(())
+    }
+
+    /** get_num_written function
+     *
+     * Return the number of snapshots written so far.
+     */
+    pub fn get_num_written(&self) -> usize {
+        self.num_written
+    }
+
+    /** finish function
+     *
+     * Flush the remainder of the snapshot stream, if any
+     */
+    pub fn finish(&mut self) -> io::Result<()> {
+        match &mut self.sink {
+            SnapshotSink::Stream(writer) => writer.flush(),
+            SnapshotSink::Directory(_) => Ok(())
+        }
+    }
+}
+
+/** get_snapshot_file_name function
+ *
+ * Return the name of the file a directory of snapshots holds the
+ * snapshot after the given time step in, zero padded so that the
+ * files sort by time step.
+ */
+pub fn get_snapshot_file_name(time_step: i32) -> String {
+    format!("snapshot_{:08}.json", time_step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::get_empty_building;
+    use crate::controller::{ElevatorController, NearestController};
+    use crate::simulation::step;
+    use crate::profile::Profiler;
+
+    //Build a building of 4 floors, the first elevator carrying someone from floor 1 to floor 3 and the second idle on
+    //the ground floor, with someone waiting on floor 2 to go down
+    fn get_fixture_building() -> Building {
+        let mut building: Building = get_empty_building(4_usize, 2_usize);
+        building.elevators[0].floor_on = 1_usize;
+        building.elevators[0].floor_to = Some(3_usize);
+        building.elevators[0].stopped = false;
+        building.elevators[0].moving_up = true;
+        let mut rider: Person = Person::from_dest(0_f64, 1_usize, 3_usize).unwrap();
+        rider.wait_time = Ticks(2_u64);
+        rider.ride_time = Ticks(1_u64);
+        rider.board();
+        building.place_person(rider, Some(0_usize));
+        let mut caller: Person = Person::from_dest(0_f64, 2_usize, 0_usize).unwrap();
+        caller.id = 1_usize;
+        caller.wait_time = Ticks(4_u64);
+        building.place_person(caller, None);
+        building.floors[2].dest_prob = 0.25_f64;
+        building
+    }
+
+    #[test]
+    fn verbose_dump_lists_everyone() {
+        let building: Building = get_fixture_building();
+        let events: [SimEvent; 1] = [SimEvent::StopInserted { elevator: 0_usize, floor: 2_usize, floor_to: 3_usize }];
+        let dump: String = BuildingSnapshot::from_detailed(&building, 4_i32).get_dump(&events, true);
+        assert_eq!(dump, concat!(
+            "Step 5: average wait time 0.00, average energy spent 0.00\n",
+            "Elevators:\n",
+            "  A on 1, to 3, moving up, doors closed, 1 aboard\n",
+            "    P#0 Riding to 3, waited 2, rode 1\n",
+            "  B on 0, idle, stopped, doors closed, 0 aboard\n",
+            "Floors:\n",
+            "  2: 1 waiting of 1, destination probability 0.250\n",
+            "    P#1 WaitingDown to 0, waited 4, rode 0\n",
+            "Events:\n",
+            "  A stopped at 2 on its way to 3\n"
+        ));
+    }
+
+    #[test]
+    fn plain_dump_lists_only_the_elevators_and_events() {
+        let mut building: Building = get_fixture_building();
+        building.elevators[1].in_service = false;
+        let dump: String = BuildingSnapshot::from_detailed(&building, 0_i32).get_dump(&[], false);
+        assert_eq!(dump, concat!(
+            "Step 1: average wait time 0.00, average energy spent 0.00\n",
+            "Elevators:\n",
+            "  A on 1, to 3, moving up, doors closed, 1 aboard\n",
+            "  B on 0, idle, out of service, doors closed, 0 aboard\n",
+            "Events:\n",
+            "  none\n"
+        ));
+
+        //Without the people captured, a verbose dump lists the floors they are on, but not them
+        let dump: String = BuildingSnapshot::from(&building, 0_i32).get_dump(&[], true);
+        assert!(dump.contains("Floors:\n  2: 1 waiting of 1, destination probability 0.250\nEvents:\n"), "{}", dump);
+        assert!(!dump.contains("P#"), "{}", dump);
+    }
+
+    //Get a path in the temporary directory unique to this test run
+    fn get_temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("elevator-snapshots-{}-{}", std::process::id(), name))
+            .display().to_string()
+    }
+
+    //Run a building of 8 floors for 30 time steps, logging snapshots every 4 to the given path, and return the
+    //positions of the elevators after each time step
+    fn run_logged(path: &str) -> Vec<Vec<usize>> {
+        let mut log: SnapshotLog = SnapshotLog::create(path, 4_i32).unwrap();
+        let mut controller: NearestController = NearestController::from(
+            Building::from(8_usize, 2_usize, 0.8_f64, 5_f64, 2.5_f64, 0.5_f64, 1_u64).unwrap()
+        );
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut floors_on: Vec<Vec<usize>> = Vec::new();
+        for i in 0_i32..30_i32 {
+            step(&mut controller, i, &mut profiler).unwrap();
+            log.record_step(controller.get_building(), i).unwrap();
+            floors_on.push(controller.get_building().elevators.iter().map(|x| x.floor_on).collect());
+        }
+        log.finish().unwrap();
+        assert_eq!(log.get_num_written(), 8_usize);
+        floors_on
+    }
+
+    //Check that the snapshots were taken every 4 time steps in order, each capturing the elevators as they were
+    fn check_snapshots(snapshots: &[BuildingSnapshot], floors_on: &[Vec<usize>]) {
+        let time_steps: Vec<i32> = snapshots.iter().map(|x| x.time_step).collect();
+        assert_eq!(time_steps, (0_i32..30_i32).step_by(4_usize).collect::<Vec<i32>>());
+        for snapshot in snapshots.iter() {
+            let snapshot_floors: Vec<usize> = snapshot.elevators.iter().map(|x| x.floor_on).collect();
+            assert_eq!(snapshot_floors, floors_on[snapshot.time_step as usize], "step {}", snapshot.time_step);
+            assert_eq!(snapshot.floors.len(), 8_usize);
+            assert!(snapshot.floors.iter().all(|x| x.people.is_empty()) && snapshot.elevators.iter().all(|x| x.people.is_empty()));
+        }
+    }
+
+    #[test]
+    fn stream_holds_a_snapshot_per_line() {
+        let path: String = format!("{}.jsonl", get_temp_path("stream"));
+        let floors_on: Vec<Vec<usize>> = run_logged(&path);
+        let stream: String = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        //Each of the 8 lines parses to a snapshot
+        let snapshots: Vec<BuildingSnapshot> = stream.lines().map(|x| BuildingSnapshot::from_json(x).unwrap()).collect();
+        assert_eq!(snapshots.len(), 8_usize);
+        check_snapshots(&snapshots, &floors_on);
+    }
+
+    #[test]
+    fn directory_holds_a_snapshot_per_file() {
+        let path: String = get_temp_path("dir");
+        let floors_on: Vec<Vec<usize>> = run_logged(&path);
+        let mut file_names: Vec<String> = std::fs::read_dir(&path).unwrap()
+            .map(|x| x.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        file_names.sort();
+
+        //The files are named by their time step, sorting in order, and each parses to a snapshot
+        let expected: Vec<String> = (0_i32..30_i32).step_by(4_usize).map(get_snapshot_file_name).collect();
+        assert_eq!(file_names, expected);
+        assert_eq!(file_names[7], "snapshot_00000028.json");
+        let snapshots: Vec<BuildingSnapshot> = file_names.iter()
+            .map(|x| BuildingSnapshot::from_json(&std::fs::read_to_string(Path::new(&path).join(x)).unwrap()).unwrap())
+            .collect();
+        let _ = std::fs::remove_dir_all(&path);
+        check_snapshots(&snapshots, &floors_on);
+    }
+}
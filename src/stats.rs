@@ -0,0 +1,358 @@
+//Import source modules
+use crate::elevator::{Elevator, ElevatorProfile};
+use crate::floor::Floor;
+use crate::people::People;
+use crate::units::{Energy, Ticks};
+
+//Import external modules
+use serde::{Serialize, Deserialize};
+
+/** StatsSummary struct schema
+ *
+ * A StatsSummary has the following properties
+ * - count (usize): The number of samples summarized
+ * - mean (f64): The mean of the samples, 0 if there are none
+ * - max (Ticks): The largest sample, 0 if there are none
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct StatsSummary {
+    pub count: usize,
+    pub mean: f64,
+    pub max: Ticks
+}
+
+/** StatsSummary type implementation
+ *
+ * The following functions are implemented for the StatsSummary type,
+ * and are callable via
+ *
+ * //Example
+ * let my_summary: StatsSummary = StatsSummary::from_total(4_usize, Ticks(10_u64), Ticks(5_u64));
+ * let my_combined: StatsSummary = StatsSummary::combine(&[my_summary, my_other_summary]);
+ */
+impl StatsSummary {
+    /** StatsSummary constructor function
+     *
+     * Initialize a summary of the given number of samples from their
+     * total and the largest of them.
+     */
+    pub fn from_total(count: usize, total: Ticks, max: Ticks) -> StatsSummary {
+        StatsSummary {
+            count,
+            mean: if count == 0_usize { 0_f64 } else { total.as_f64() / count as f64 },
+            max
+        }
+    }
+
+    /** combine function
+     *
+     * Initialize a summary of the samples of all the given summaries,
+     * weighting the mean of each by its number of samples.
+     */
+    pub fn combine(summaries: &[StatsSummary]) -> StatsSummary {
+        let count: usize = summaries.iter().map(|x| x.count).sum();
+        let total: f64 = summaries.iter().map(|x| x.mean * x.count as f64).sum();
+        StatsSummary {
+            count,
+            mean: if count == 0_usize { 0_f64 } else { total / count as f64 },
+            max: summaries.iter().map(|x| x.max).max().unwrap_or(Ticks(0_u64))
+        }
+    }
+}
+
+/** StreamingStats struct schema
+ *
+ * A StreamingStats folds samples into their mean one at a time using
+ * Welford's update, so that the mean neither overflows nor drifts as
+ * the samples accumulate over very long runs.
+ *
+ * A StreamingStats has the following properties
+ * - count (u64): The number of samples folded in
+ * - mean (f64): The running mean of the samples, 0 if there are none
+ * - max (u64): The largest whole sample folded in, 0 if there are none
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct StreamingStats {
+    count: u64,
+    mean: f64,
+    max: u64
+}
+
+/** StreamingStats type implementation
+ *
+ * The following functions are implemented for the StreamingStats
+ * type, and are callable via
+ *
+ * //Example
+ * let mut my_stats: StreamingStats = StreamingStats::new();
+ * my_stats.push(5_u64);
+ * let my_mean: f64 = my_stats.get_mean();
+ */
+impl StreamingStats {
+    /** StreamingStats constructor function
+     *
+     * Initialize the stats of no samples.
+     */
+    pub fn new() -> StreamingStats {
+        StreamingStats {
+            count: 0_u64,
+            mean: 0_f64,
+            max: 0_u64
+        }
+    }
+
+    /** push function
+     *
+     * Fold a whole sample, such as a wait time, into the mean and max.
+     */
+    pub fn push(&mut self, sample: u64) {
+        self.push_f64(sample as f64);
+        self.max = self.max.max(sample);
+    }
+
+    /** push_f64 function
+     *
+     * Fold a real sample, such as the energy spent during a time step,
+     * into the mean.  The max only covers whole samples.
+     */
+    pub fn push_f64(&mut self, sample: f64) {
+        self.count = self.count.saturating_add(1_u64);
+        self.mean += (sample - self.mean) / self.count as f64;
+    }
+
+    /** get_count function
+     *
+     * Return the number of samples folded in.
+     */
+    pub fn get_count(&self) -> u64 {
+        self.count
+    }
+
+    /** get_mean function
+     *
+     * Return the mean of the samples, 0 if there are none.
+     */
+    pub fn get_mean(&self) -> f64 {
+        self.mean
+    }
+
+    /** get_max function
+     *
+     * Return the largest whole sample, 0 if there are none.
+     */
+    pub fn get_max(&self) -> u64 {
+        self.max
+    }
+
+    /** to_summary function
+     *
+     * Return a summary of the samples folded in.
+     */
+    pub fn to_summary(&self) -> StatsSummary {
+        StatsSummary {
+            count: self.count as usize,
+            mean: self.mean,
+            max: Ticks(self.max)
+        }
+    }
+}
+
+/** EnergyBreakdown struct schema
+ *
+ * An EnergyBreakdown has the following properties
+ * - total (Energy): The energy spent by every elevator over the measured time steps
+ * - avg_per_step (Energy): The average energy spent per measured time step
+ * - moving_up (Energy): The part of the total spent moving up
+ * - moving_down (Energy): The part of the total spent moving down
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct EnergyBreakdown {
+    pub total: Energy,
+    pub avg_per_step: Energy,
+    pub moving_up: Energy,
+    pub moving_down: Energy
+}
+
+/** EnergyBreakdown type implementation
+ *
+ * The following functions are implemented for the EnergyBreakdown
+ * type, and are callable via
+ *
+ * //Example
+ * let my_combined: EnergyBreakdown = EnergyBreakdown::combine(&[my_energy, my_other_energy]);
+ */
+impl EnergyBreakdown {
+    /** combine function
+     *
+     * Initialize the breakdown of the energy spent by all the given
+     * breakdowns' elevators together, such that the average per step
+     * is the energy spent per step by all of them.
+     */
+    pub fn combine(breakdowns: &[EnergyBreakdown]) -> EnergyBreakdown {
+        EnergyBreakdown {
+            total: breakdowns.iter().map(|x| x.total).sum(),
+            avg_per_step: breakdowns.iter().map(|x| x.avg_per_step).sum(),
+            moving_up: breakdowns.iter().map(|x| x.moving_up).sum(),
+            moving_down: breakdowns.iter().map(|x| x.moving_down).sum()
+        }
+    }
+}
+
+/** FloorStats struct schema
+ *
+ * A FloorStats has the following properties
+ * - floor (usize): The index of the floor
+ * - num_boarded (usize): The number of people who got on an elevator at the floor
+ * - num_alighted (usize): The number of people who got off an elevator at the floor
+ * - num_waiting (usize): The number of people waiting on the floor at the end of the run
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FloorStats {
+    pub floor: usize,
+    pub num_boarded: usize,
+    pub num_alighted: usize,
+    pub num_waiting: usize
+}
+
+/** FloorStats type implementation
+ *
+ * The following functions are implemented for the FloorStats type,
+ * and are callable via
+ *
+ * //Example
+ * let my_stats: FloorStats = FloorStats::from(0_usize, &my_building.floors[0]);
+ */
+impl FloorStats {
+    /** FloorStats constructor function
+     *
+     * Initialize the stats of the floor with the given index from the
+     * floor's counters.
+     */
+    pub fn from(floor_index: usize, floor: &Floor) -> FloorStats {
+        FloorStats {
+            floor: floor_index,
+            num_boarded: floor.num_boarded,
+            num_alighted: floor.num_alighted,
+            num_waiting: floor.get_num_people_waiting()
+        }
+    }
+}
+
+/** ElevatorStats struct schema
+ *
+ * An ElevatorStats has the following properties
+ * - elevator (usize): The index of the elevator
+ * - profile (ElevatorProfile): The kind of elevator
+ * - num_delivered (usize): The number of people the elevator carried to their destinations
+ * - avg_wait_time (f64): The average wait time of those people
+ * - energy_spent (Energy): The energy the elevator spent over the measured time steps
+ * - downtime (usize): The number of time steps the elevator spent out of service
+ */
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ElevatorStats {
+    pub elevator: usize,
+    pub profile: ElevatorProfile,
+    pub num_delivered: usize,
+    pub avg_wait_time: f64,
+    pub energy_spent: Energy,
+    pub downtime: usize
+}
+
+/** ElevatorStats type implementation
+ *
+ * The following functions are implemented for the ElevatorStats type,
+ * and are callable via
+ *
+ * //Example
+ * let my_stats: ElevatorStats = ElevatorStats::from(0_usize, &my_building.elevators[0]);
+ */
+impl ElevatorStats {
+    /** ElevatorStats constructor function
+     *
+     * Initialize the stats of the elevator with the given index from
+     * the elevator's counters.
+     */
+    pub fn from(elevator_index: usize, elevator: &Elevator) -> ElevatorStats {
+        ElevatorStats {
+            elevator: elevator_index,
+            profile: elevator.profile,
+            num_delivered: elevator.num_delivered,
+            avg_wait_time: if elevator.num_delivered == 0_usize { 0_f64 } else { elevator.total_wait_time.as_f64() / elevator.num_delivered as f64 },
+            energy_spent: elevator.energy_total(),
+            downtime: elevator.downtime
+        }
+    }
+}
+
+/** get_mean_and_std_dev function
+ *
+ * Return the mean of the given samples and their sample standard
+ * deviation, 0 for either if there are too few samples to tell.
+ */
+pub fn get_mean_and_std_dev(samples: &[f64]) -> (f64, f64) {
+    if samples.is_empty() {
+        return (0_f64, 0_f64);
+    }
+    let mean: f64 = samples.iter().sum::<f64>() / samples.len() as f64;
+    if samples.len() < 2_usize {
+        return (mean, 0_f64);
+    }
+    let variance: f64 = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (samples.len() - 1_usize) as f64;
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    //Constant representing the number of samples folded into the long-run stats
+    const NUM_SAMPLES: u64 = 10_000_000_u64;
+
+    #[test]
+    #[ignore = "slow in debug builds, run with cargo test --release -- --ignored"]
+    fn long_run_mean_matches_batch_mean() {
+        //Fold in wait times, including long waits of people served late, summing them exactly too
+        let mut rng: StdRng = StdRng::seed_from_u64(0_u64);
+        let mut stats: StreamingStats = StreamingStats::new();
+        let mut sum: u128 = 0_u128;
+        let mut max: u64 = 0_u64;
+        for _ in 0_u64..NUM_SAMPLES {
+            let sample: u64 = if rng.gen_bool(0.001_f64) { rng.gen_range(0_u64..1_000_000_u64) } else { rng.gen_range(0_u64..100_u64) };
+            stats.push(sample);
+            sum += sample as u128;
+            max = max.max(sample);
+        }
+        assert_eq!(stats.get_count(), NUM_SAMPLES);
+        assert_eq!(stats.get_max(), max);
+        assert!((stats.get_mean() - sum as f64 / NUM_SAMPLES as f64).abs() < 1e-6_f64);
+    }
+
+    #[test]
+    #[ignore = "slow in debug builds, run with cargo test --release -- --ignored"]
+    fn long_run_energy_matches_batch_mean() {
+        //Fold in energies which are exact in binary, so that the batch sum is exact
+        let mut rng: StdRng = StdRng::seed_from_u64(1_u64);
+        let mut stats: StreamingStats = StreamingStats::new();
+        let mut sum: f64 = 0_f64;
+        for _ in 0_u64..NUM_SAMPLES {
+            let sample: f64 = rng.gen_range(0_u32..40_u32) as f64 * 0.25_f64;
+            stats.push_f64(sample);
+            sum += sample;
+        }
+        assert!((stats.get_mean() - sum / NUM_SAMPLES as f64).abs() < 1e-6_f64);
+    }
+
+    #[test]
+    fn huge_samples_do_not_overflow() {
+        let mut stats: StreamingStats = StreamingStats::new();
+        for _ in 0_u64..1000_u64 {
+            stats.push(u64::MAX);
+        }
+        assert_eq!(stats.get_max(), u64::MAX);
+        assert_eq!(stats.get_mean(), u64::MAX as f64);
+        assert!(stats.to_summary().mean.is_finite());
+    }
+}
@@ -0,0 +1,156 @@
+//Import source modules
+use crate::building::Building;
+use crate::simulation::{SimulationConfig, SimulationResult};
+use crate::snapshot::BuildingSnapshot;
+
+//Import external/standard modules
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+//Constant representing how long a status request may take to arrive
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5_u64);
+
+/** StatusState struct schema
+ *
+ * A StatusState has the following properties
+ * - snapshot (Option<BuildingSnapshot>): The snapshot of the building after the latest time step, if any
+ * - summary (Option<SimulationResult>): The result of the simulation so far, if any time step has run
+ */
+struct StatusState {
+    snapshot: Option<BuildingSnapshot>,
+    summary: Option<SimulationResult>
+}
+
+/** StatusServer struct schema
+ *
+ * A StatusServer has the following properties
+ * - state (Arc<Mutex<StatusState>>): The latest state, shared with the thread serving requests
+ * - config (SimulationConfig): The config of the simulation, reported in its result
+ * - controller (String): The name of the controller, reported in the result
+ * - seed (u64): The seed of the simulation, reported in the result
+ */
+pub struct StatusServer {
+    state: Arc<Mutex<StatusState>>,
+    config: SimulationConfig,
+    controller: String,
+    seed: u64
+}
+
+/** StatusServer type implementation
+ *
+ * The following functions are implemented for the StatusServer type,
+ * and are callable via
+ *
+ * //Example
+ * let my_server: StatusServer = StatusServer::from(8080_u16, &my_config, "random", seed)?;
+ * my_server.update(&my_building, time_step);
+ */
+impl StatusServer {
+    /** StatusServer constructor function
+     *
+     * Listen on the given port on the loopback interface, and serve
+     * read-only status requests from a background thread.  GET /state
+     * returns the latest snapshot of the building, and GET /summary
+     * returns the result of the simulation so far.
+     */
+    pub fn from(port: u16, config: &SimulationConfig, controller: &str, seed: u64) -> io::Result<StatusServer> {
+        //Initialize the shared state
+        let state: Arc<Mutex<StatusState>> = Arc::new(Mutex::new(StatusState {
+            snapshot: None,
+            summary: None
+        }));
+
+        //Serve each request in turn from a background thread
+        let listener: TcpListener = TcpListener::bind(("127.0.0.1", port))?;
+        let thread_state: Arc<Mutex<StatusState>> = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = handle_request(stream, &thread_state);
+            }
+        });
+        Ok(StatusServer {
+            state,
+            config: config.clone(),
+            controller: String::from(controller),
+            seed
+        })
+    }
+
+    /** update function
+     *
+     * Update the served state with the building after the given time
+     * step.  The state is overwritten whole, so it is updated even if
+     * the thread serving requests panicked while holding it.
+     */
+    pub fn update(&self, building: &Building, time_step: i32) {
+        let snapshot: BuildingSnapshot = BuildingSnapshot::from(building, time_step);
+        let summary: SimulationResult = SimulationResult::from(building, &self.config, &self.controller, self.seed);
+        let mut state: MutexGuard<StatusState> = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.summary = Some(summary);
+        state.snapshot = Some(snapshot);
+    }
+}
+
+/** handle_request function
+ *
+ * Read a request from the stream and write the response, closing the
+ * connection afterward.
+ */
+fn handle_request(mut stream: TcpStream, state: &Mutex<StatusState>) -> io::Result<()> {
+    //Read the request line, then the headers up to the blank line
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+    let mut request_line: String = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header_line: String = String::new();
+    while reader.read_line(&mut header_line)? > 2_usize {
+        header_line.clear();
+    }
+
+    //Route the request by its method and path
+    let mut request_parts = request_line.split_whitespace();
+    let (status, body): (&str, String) = match (request_parts.next(), request_parts.next()) {
+        (Some("GET"), Some("/state")) => match state.lock() {
+            Ok(state) => get_json_response(serde_json::to_string(&state.snapshot)),
+            Err(_) => get_error_response("the state is unavailable")
+        },
+        (Some("GET"), Some("/summary")) => match state.lock() {
+            Ok(state) => get_json_response(serde_json::to_string(&state.summary)),
+            Err(_) => get_error_response("the summary is unavailable")
+        },
+        (Some("GET"), _) => ("404 Not Found", String::from("{\"error\":\"not found\"}")),
+        _ => ("405 Method Not Allowed", String::from("{\"error\":\"method not allowed\"}"))
+    };
+
+    //Write the response
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, body.len(), body
+    )?;
+    stream.flush()
+}
+
+/** get_json_response function
+ *
+ * Return the status and body of a response with the given serialized
+ * JSON, or of an internal server error if it failed to serialize.
+ */
+fn get_json_response(json: serde_json::Result<String>) -> (&'static str, String) {
+    match json {
+        Ok(x) => ("200 OK", x),
+        Err(_) => get_error_response("the response could not be serialized")
+    }
+}
+
+/** get_error_response function
+ *
+ * Return the status and body of an internal server error with the
+ * given message.
+ */
+fn get_error_response(message: &str) -> (&'static str, String) {
+    ("500 Internal Server Error", format!("{{\"error\":\"{}\"}}", message))
+}
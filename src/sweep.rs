@@ -0,0 +1,243 @@
+//Import source modules
+use crate::controller::ControllerKind;
+use crate::params::ControllerParams;
+use crate::simulation::{SimulationConfig, SimulationResult, ReplicationSummary, run_replications};
+use crate::error::ElevatorError;
+
+//Import external/standard modules
+use std::fmt;
+
+//Constant representing the most values a sweep axis may take, so that a typo cannot start a sweep which never ends
+pub const MAX_SWEEP_VALUES: usize = 1000_usize;
+
+//Type representing the start, end and step, if any, of a range given as start:end[:step]
+type RangeParts<'a> = (&'a str, &'a str, Option<&'a str>);
+
+//Constant representing the header line of the table of sweep points
+pub const SWEEP_HEADER: &str = "floors elevators  arrivals   avg wait    std dev avg energy    std dev";
+
+/** SweepAxis struct schema
+ *
+ * A SweepAxis is the values a parameter of the building takes over a
+ * sweep, given on the command line as a single value, a comma
+ * separated list, or an inclusive range start:end[:step].
+ *
+ * A SweepAxis has the following properties
+ * - values (Vec<T>): The values the parameter takes, in order
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct SweepAxis<T> {
+    pub values: Vec<T>
+}
+
+/** SweepAxis type implementation
+ *
+ * The following functions are implemented for the SweepAxis type for
+ * counts, and are callable via
+ *
+ * //Example
+ * let my_axis: SweepAxis<usize> = SweepAxis::parse_counts("2:10:2", 2_usize)?;
+ */
+impl SweepAxis<usize> {
+    /** parse_counts function
+     *
+     * Parse an axis of counts, each of which must be at least the given
+     * minimum.  A range steps by 1 unless a step is given.
+     */
+    pub fn parse_counts(axis_str: &str, min: usize) -> Result<SweepAxis<usize>, String> {
+        let values: Vec<usize> = match parse_range(axis_str)? {
+            Some((start, end, step)) => {
+                let (start, end, step): (usize, usize, usize) = (
+                    parse_count(start)?, parse_count(end)?, step.map_or(Ok(1_usize), parse_count)?
+                );
+                if step == 0_usize {
+                    return Err(String::from("the step must be at least 1"));
+                }
+                if end < start {
+                    return Err(format!("the end {} is below the start {}", end, start));
+                }
+                (start..=end).step_by(step).take(MAX_SWEEP_VALUES + 1_usize).collect()
+            },
+            None => axis_str.split(',').map(parse_count).collect::<Result<Vec<usize>, String>>()?
+        };
+        if let Some(x) = values.iter().find(|x| **x < min) {
+            return Err(format!("{} must be at least {}", x, min));
+        }
+        SweepAxis::from(values)
+    }
+}
+
+/** SweepAxis type implementation
+ *
+ * The following functions are implemented for the SweepAxis type for
+ * rates, and are callable via
+ *
+ * //Example
+ * let my_axis: SweepAxis<f64> = SweepAxis::parse_rates("0.1:0.5:0.1")?;
+ */
+impl SweepAxis<f64> {
+    /** parse_rates function
+     *
+     * Parse an axis of rates, each of which must be finite and above 0.
+     * A range must give its step.
+     */
+    pub fn parse_rates(axis_str: &str) -> Result<SweepAxis<f64>, String> {
+        let values: Vec<f64> = match parse_range(axis_str)? {
+            Some((_, _, None)) => return Err(String::from("a range of rates must give its step, as start:end:step")),
+            Some((start, end, Some(step))) => {
+                let (start, end, step): (f64, f64, f64) = (parse_rate(start)?, parse_rate(end)?, parse_rate(step)?);
+                if step <= 0_f64 {
+                    return Err(String::from("the step must be above 0"));
+                }
+                if end < start {
+                    return Err(format!("the end {} is below the start {}", end, start));
+                }
+                //Step by index rather than by summing, so that rounding does not drop the end of the range
+                let num_steps: f64 = ((end - start) / step + 1e-9_f64).floor();
+                if num_steps >= MAX_SWEEP_VALUES as f64 {
+                    return Err(format!("a sweep axis may take at most {} values", MAX_SWEEP_VALUES));
+                }
+                (0_usize..=num_steps as usize).map(|i| start + i as f64 * step).collect()
+            },
+            None => axis_str.split(',').map(parse_rate).collect::<Result<Vec<f64>, String>>()?
+        };
+        if let Some(x) = values.iter().find(|x| **x <= 0_f64) {
+            return Err(format!("{} must be above 0", x));
+        }
+        SweepAxis::from(values)
+    }
+}
+
+/** SweepAxis type implementation
+ *
+ * The following functions are implemented for the SweepAxis type for
+ * any value, and are callable via
+ *
+ * //Example
+ * let my_axis: SweepAxis<usize> = SweepAxis::from(vec![2_usize, 4_usize])?;
+ */
+impl<T> SweepAxis<T> {
+    /** SweepAxis constructor function
+     *
+     * Initialize an axis taking the given values, which must number at
+     * least one and at most MAX_SWEEP_VALUES.
+     */
+    pub fn from(values: Vec<T>) -> Result<SweepAxis<T>, String> {
+        if values.is_empty() {
+            return Err(String::from("a sweep axis must take at least one value"));
+        }
+        if values.len() > MAX_SWEEP_VALUES {
+            return Err(format!("a sweep axis may take at most {} values", MAX_SWEEP_VALUES));
+        }
+        Ok(SweepAxis {
+            values
+        })
+    }
+}
+
+/** SweepPoint struct schema
+ *
+ * A SweepPoint has the following properties
+ * - num_floors (usize): The number of floors of the building simulated
+ * - num_elevators (usize): The number of elevators of the building simulated
+ * - expected_arrivals (f64): The expected number of people arriving per time step
+ * - results (Vec<SimulationResult>): The result of each run at the point
+ * - summary (ReplicationSummary): The mean and standard deviation of the metrics over the runs
+ */
+pub struct SweepPoint {
+    pub num_floors: usize,
+    pub num_elevators: usize,
+    pub expected_arrivals: f64,
+    pub results: Vec<SimulationResult>,
+    pub summary: ReplicationSummary
+}
+
+//Implement display for SweepPoint, as a row of the table under SWEEP_HEADER
+impl fmt::Display for SweepPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{:>6} {:>9} {:>9.3} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+            self.num_floors, self.num_elevators, self.expected_arrivals, self.summary.avg_wait_time,
+            self.summary.std_wait_time, self.summary.avg_energy, self.summary.std_energy
+        )
+    }
+}
+
+/** run_sweep function
+ *
+ * Run the controller on every combination of the given floors,
+ * elevators and arrival rates, in that order of nesting, each on the
+ * given seeds, starting from the base config.  Each point is passed to
+ * the given callback as soon as it is run, so that progress can be
+ * shown, and the sweep stops at the first error it returns.  The
+ * config of every point is validated before any is run.
+ */
+pub fn run_sweep<F>(base_config: &SimulationConfig, floors: &[usize], elevators: &[usize], arrivals: &[f64],
+                    controller_kind: ControllerKind, seeds: &[u64], mut on_point: F) -> Result<(), ElevatorError>
+    where F: FnMut(&SweepPoint) -> Result<(), ElevatorError> {
+    //Build and validate the config of every point
+    let mut configs: Vec<SimulationConfig> = Vec::new();
+    for num_floors in floors.iter() {
+        for num_elevators in elevators.iter() {
+            for expected_arrivals in arrivals.iter() {
+                let mut config: SimulationConfig = base_config.clone();
+                config.num_floors = *num_floors;
+                config.num_elevators = *num_elevators;
+                config.expected_arrivals = *expected_arrivals;
+                config.validate().map_err(|e| ElevatorError::Config(format!(
+                    "{} floors, {} elevators, {} arrivals: {}", num_floors, num_elevators, expected_arrivals, e
+                )))?;
+                configs.push(config);
+            }
+        }
+    }
+
+    //Run each point on every seed
+    for config in configs.iter() {
+        let results: Vec<SimulationResult> = run_replications(config, controller_kind, &ControllerParams::default(), seeds)?;
+        let summary: ReplicationSummary = ReplicationSummary::from(&results);
+        on_point(&SweepPoint {
+            num_floors: config.num_floors,
+            num_elevators: config.num_elevators,
+            expected_arrivals: config.expected_arrivals,
+            results,
+            summary
+        })?;
+    }
+    Ok(())
+}
+
+/** parse_range function
+ *
+ * Split an inclusive range start:end[:step] into its parts, if the
+ * given string is a range rather than a list.
+ */
+fn parse_range(range_str: &str) -> Result<Option<RangeParts<'_>>, String> {
+    let parts: Vec<&str> = range_str.split(':').collect();
+    match parts.as_slice() {
+        [_] => Ok(None),
+        [start, end] => Ok(Some((start, end, None))),
+        [start, end, step] => Ok(Some((start, end, Some(step)))),
+        _ => Err(format!("{} is not a range, which is given as start:end[:step]", range_str))
+    }
+}
+
+/** parse_count function
+ *
+ * Parse a count of a sweep axis.
+ */
+fn parse_count(count_str: &str) -> Result<usize, String> {
+    count_str.trim().parse::<usize>().map_err(|e| format!("{}: {}", count_str, e))
+}
+
+/** parse_rate function
+ *
+ * Parse a rate of a sweep axis, rejecting a non-finite value.
+ */
+fn parse_rate(rate_str: &str) -> Result<f64, String> {
+    match rate_str.trim().parse::<f64>() {
+        Ok(x) if x.is_finite() => Ok(x),
+        Ok(x) => Err(format!("{} is not a finite number", x)),
+        Err(e) => Err(format!("{}: {}", rate_str, e))
+    }
+}
@@ -0,0 +1,483 @@
+//Import external/standard modules
+use std::io::{self, Write, IsTerminal};
+use crossterm::{terminal, cursor, Command};
+use crossterm::style;
+
+/** ColorMode enum
+ *
+ * When the building is drawn in color, either always, never, or only
+ * when writing to a terminal and the NO_COLOR variable is not set.
+ */
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never
+}
+
+/** ColorMode type implementation
+ *
+ * The following functions are implemented for the ColorMode type,
+ * and are callable via
+ *
+ * //Example
+ * ColorMode::Never.apply();
+ */
+impl ColorMode {
+    /** is_enabled function
+     *
+     * Determine whether the building is drawn in color.
+     */
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ColorMode::Auto => {
+                let no_color: bool = std::env::var("NO_COLOR").map(|x| !x.is_empty()).unwrap_or(false);
+                std::io::stdout().is_terminal() && !no_color
+            },
+            ColorMode::Always => true,
+            ColorMode::Never => false
+        }
+    }
+
+    /** apply function
+     *
+     * Enable or disable colored output for the rest of the run.
+     */
+    pub fn apply(&self) {
+        style::force_color_output(self.is_enabled());
+    }
+}
+
+//Constant dividing frames which are printed sequentially
+const FRAME_DIVIDER: &str = "========================================";
+
+/** FrameMode enum
+ *
+ * How frames are drawn, either updated in place by moving the cursor
+ * back over the previous frame, or printed one after another when the
+ * frame is too tall for the cursor to move back over it.
+ */
+#[derive(PartialEq, Clone, Copy)]
+pub enum FrameMode {
+    InPlace,
+    Sequential
+}
+
+/** get_frame_mode function
+ *
+ * Decide how a frame with the given number of lines is drawn on a
+ * terminal of the given height.
+ */
+pub fn get_frame_mode(frame_lines: usize, term_height: usize) -> FrameMode {
+    if frame_lines > term_height { FrameMode::Sequential } else { FrameMode::InPlace }
+}
+
+/** Renderer struct schema
+ *
+ * A Renderer has the following properties
+ * - frame_buf (String): A buffer the frame is composed into, reused across frames
+ * - prev_lines (Vec<String>): The lines of the previously drawn frame
+ * - cursor_line (usize): The line of the previous frame the cursor is currently on
+ * - term_height (usize): The height of the terminal in lines
+ * - mode (FrameMode): How the previous frame was drawn
+ * - invalidated (bool): If true, the screen is cleared and the next frame is drawn from scratch
+ */
+pub struct Renderer {
+    frame_buf: String,
+    prev_lines: Vec<String>,
+    cursor_line: usize,
+    term_height: usize,
+    mode: FrameMode,
+    invalidated: bool
+}
+
+/** Renderer type implementation
+ *
+ * The following functions are implemented for the Renderer type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_renderer: Renderer = Renderer::from(24_usize);
+ * my_renderer.draw(&mut stdout, &building_str).unwrap();
+ */
+impl Renderer {
+    /** Renderer constructor function
+     *
+     * Initialize a renderer with an empty frame cache given the height
+     * of the terminal it draws to.
+     */
+    pub fn from(term_height: usize) -> Renderer {
+        Renderer {
+            frame_buf: String::new(),
+            prev_lines: Vec::new(),
+            cursor_line: 0_usize,
+            term_height,
+            mode: FrameMode::InPlace,
+            invalidated: false
+        }
+    }
+
+    /** invalidate function
+     *
+     * Discard the previous frame so that the next frame clears the
+     * screen and is drawn from scratch, for instance once the previous
+     * frame was reflowed by a terminal resize.
+     */
+    pub fn invalidate(&mut self) {
+        self.prev_lines.clear();
+        self.cursor_line = 0_usize;
+        self.invalidated = true;
+    }
+
+    /** resize function
+     *
+     * Update the height of the terminal and invalidate the previous
+     * frame, since its position on the screen is no longer known.
+     */
+    pub fn resize(&mut self, term_height: usize) {
+        self.term_height = term_height;
+        self.invalidate();
+    }
+
+    /** draw function
+     *
+     * Compose the given frame into the frame buffer and write it to
+     * the output with a single write call.  If the previous frame has
+     * the same number of lines, then only the changed lines are
+     * rewritten, otherwise the previous frame is cleared and the
+     * full frame is redrawn.  If the frame is taller than the terminal,
+     * then it is printed beneath the previous frame instead.
+     */
+    pub fn draw(&mut self, out: &mut impl Write, frame_str: &str) -> io::Result<()> {
+        //Clear the frame buffer, retaining its allocation
+        self.frame_buf.clear();
+
+        //If invalidated, then clear the screen before drawing
+        if self.invalidated {
+            let _ = cursor::MoveTo(0, 0).write_ansi(&mut self.frame_buf);
+            let _ = terminal::Clear(terminal::ClearType::All).write_ansi(&mut self.frame_buf);
+            self.invalidated = false;
+        }
+
+        //If the frame is too tall to update in place, then print it sequentially
+        let num_lines: usize = frame_str.split('\n').count();
+        if get_frame_mode(num_lines, self.term_height) == FrameMode::Sequential {
+            self.draw_sequential(frame_str);
+            out.write_all(self.frame_buf.as_bytes())?;
+            return out.flush();
+        }
+        self.mode = FrameMode::InPlace;
+
+        //Compose either a partial or a full redraw into the frame buffer
+        if num_lines == self.prev_lines.len() {
+            self.cursor_line = diff_frame(&self.prev_lines, frame_str, self.cursor_line, &mut self.frame_buf);
+        } else {
+            self.cursor_line = full_frame(&self.prev_lines, frame_str, self.cursor_line, &mut self.frame_buf);
+        }
+
+        //Write the frame in one call if anything changed
+        if !self.frame_buf.is_empty() {
+            out.write_all(self.frame_buf.as_bytes())?;
+            out.flush()?;
+        }
+
+        //Cache the lines of this frame for the next draw
+        self.prev_lines.truncate(num_lines);
+        for (i, line) in frame_str.split('\n').enumerate() {
+            if i < self.prev_lines.len() {
+                if self.prev_lines[i] != line {
+                    self.prev_lines[i].clear();
+                    self.prev_lines[i].push_str(line);
+                }
+            } else {
+                self.prev_lines.push(String::from(line));
+            }
+        }
+        Ok(())
+    }
+
+    /** draw_sequential function
+     *
+     * Compose a frame printed beneath the previous frame followed by a
+     * divider into the frame buffer.  When switching from updating in
+     * place, note the switch once, since it persists while the frame
+     * remains too tall.
+     */
+    fn draw_sequential(&mut self, frame_str: &str) {
+        //If switching from updating in place, then move beneath the previous frame and note the switch
+        if self.mode != FrameMode::Sequential {
+            if !self.prev_lines.is_empty() {
+                move_to_line(self.cursor_line, self.prev_lines.len() - 1_usize, &mut self.frame_buf);
+                self.frame_buf.push_str("\r\n");
+            }
+            self.frame_buf.push_str(&format!(
+                "Frame is taller than the terminal ({} lines), printing frames sequentially\r\n",
+                self.term_height
+            ));
+            self.mode = FrameMode::Sequential;
+        }
+
+        //Append the frame and the divider, leaving the cursor on a fresh line
+        full_frame(&[], frame_str, 0_usize, &mut self.frame_buf);
+        self.frame_buf.push_str("\r\n");
+        self.frame_buf.push_str(FRAME_DIVIDER);
+        self.frame_buf.push_str("\r\n");
+
+        //Clear the frame cache since the next in-place frame is drawn from scratch
+        self.prev_lines.clear();
+        self.cursor_line = 0_usize;
+    }
+}
+
+/** move_to_line function
+ *
+ * Append the cursor commands moving the cursor from one line of
+ * the frame to another, and to the first column, into the buffer.
+ */
+fn move_to_line(from_line: usize, to_line: usize, buf: &mut String) {
+    //Move vertically only if needed, since a move of 0 lines moves 1 line on some terminals
+    if to_line < from_line {
+        let _ = cursor::MoveUp((from_line - to_line) as u16).write_ansi(buf);
+    } else if to_line > from_line {
+        let _ = cursor::MoveDown((to_line - from_line) as u16).write_ansi(buf);
+    }
+    let _ = cursor::MoveToColumn(0).write_ansi(buf);
+}
+
+/** full_frame function
+ *
+ * Append the commands clearing the previous frame, if any, followed
+ * by the entire new frame into the buffer.  Return the line the
+ * cursor is left on.
+ */
+pub fn full_frame(prev_lines: &[String], frame_str: &str, cursor_line: usize, buf: &mut String) -> usize {
+    //If a previous frame was drawn, then move to its first line and clear it
+    if !prev_lines.is_empty() {
+        move_to_line(cursor_line, 0_usize, buf);
+        let _ = terminal::Clear(terminal::ClearType::FromCursorDown).write_ansi(buf);
+    }
+
+    //Append the frame line by line, returning the carriage explicitly
+    //since newlines do not do so while the terminal is in raw mode
+    let mut num_lines: usize = 0_usize;
+    for (i, line) in frame_str.split('\n').enumerate() {
+        if i > 0_usize {
+            buf.push_str("\r\n");
+        }
+        buf.push_str(line);
+        num_lines += 1_usize;
+    }
+
+    //Return the last line, which the cursor is left on
+    num_lines - 1_usize
+}
+
+/** diff_frame function
+ *
+ * Compare the new frame against the previous frame line by line, and
+ * append a positioning command, a line clear, and the new contents
+ * into the buffer for each line which changed.  The frames must have
+ * the same number of lines.  Return the line the cursor is left on.
+ */
+pub fn diff_frame(prev_lines: &[String], frame_str: &str, cursor_line: usize, buf: &mut String) -> usize {
+    //Loop through the lines of the new frame and rewrite those which changed
+    let mut current_line: usize = cursor_line;
+    for (i, line) in frame_str.split('\n').enumerate() {
+        if prev_lines[i] == line {
+            continue;
+        }
+
+        //Move to the line, clear it, and write the new contents
+        move_to_line(current_line, i, buf);
+        let _ = terminal::Clear(terminal::ClearType::CurrentLine).write_ansi(buf);
+        buf.push_str(line);
+        current_line = i;
+    }
+
+    //Return the line the cursor was left on
+    current_line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //A writer recording the bytes written and the number of write calls
+    struct CountingWriter {
+        bytes: Vec<u8>,
+        num_writes: usize
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.bytes.extend_from_slice(buf);
+            self.num_writes += 1_usize;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CountingWriter {
+        fn new() -> CountingWriter {
+            CountingWriter { bytes: Vec::new(), num_writes: 0_usize }
+        }
+
+        //Take the bytes written since the last call along with the number of writes
+        fn take(&mut self) -> (String, usize) {
+            let written: String = String::from_utf8(std::mem::take(&mut self.bytes)).unwrap();
+            let num_writes: usize = self.num_writes;
+            self.num_writes = 0_usize;
+            (written, num_writes)
+        }
+    }
+
+    #[test]
+    fn frame_mode_follows_height() {
+        let cases: [(usize, usize, FrameMode); 6] = [
+            (1_usize, 0_usize, FrameMode::Sequential),
+            (10_usize, 0_usize, FrameMode::Sequential),
+            (25_usize, 24_usize, FrameMode::Sequential),
+            (24_usize, 24_usize, FrameMode::InPlace),
+            (1_usize, 24_usize, FrameMode::InPlace),
+            (0_usize, 0_usize, FrameMode::InPlace)
+        ];
+        for (frame_lines, term_height, mode) in cases {
+            assert!(get_frame_mode(frame_lines, term_height) == mode, "{} lines on {} line terminal", frame_lines, term_height);
+        }
+    }
+
+    //Get the lines of a frame as they are cached after it is drawn
+    fn get_lines(frame_str: &str) -> Vec<String> {
+        frame_str.split('\n').map(String::from).collect()
+    }
+
+    #[test]
+    fn diff_frame_rewrites_only_changed_lines() {
+        let prev_lines: Vec<String> = get_lines("roof\nfloor 3\nfloor 2\nfloor 1");
+
+        //A single changed line is moved to from the bottom, cleared and rewritten, and nothing else
+        let mut buf: String = String::new();
+        let cursor_line: usize = diff_frame(&prev_lines, "roof\nfloor 3\nfloor 2 |1|\nfloor 1", 3_usize, &mut buf);
+        let mut expected: String = String::new();
+        let _ = cursor::MoveUp(1_u16).write_ansi(&mut expected);
+        let _ = cursor::MoveToColumn(0).write_ansi(&mut expected);
+        let _ = terminal::Clear(terminal::ClearType::CurrentLine).write_ansi(&mut expected);
+        expected.push_str("floor 2 |1|");
+        assert_eq!(buf, expected);
+        assert_eq!(cursor_line, 2_usize);
+
+        //Changed lines further down are moved to from the last line rewritten
+        let mut buf: String = String::new();
+        let cursor_line: usize = diff_frame(&prev_lines, "roof |2|\nfloor 3\nfloor 2\nfloor 1 |1|", 3_usize, &mut buf);
+        let mut expected: String = String::new();
+        let _ = cursor::MoveUp(3_u16).write_ansi(&mut expected);
+        let _ = cursor::MoveToColumn(0).write_ansi(&mut expected);
+        let _ = terminal::Clear(terminal::ClearType::CurrentLine).write_ansi(&mut expected);
+        expected.push_str("roof |2|");
+        let _ = cursor::MoveDown(3_u16).write_ansi(&mut expected);
+        let _ = cursor::MoveToColumn(0).write_ansi(&mut expected);
+        let _ = terminal::Clear(terminal::ClearType::CurrentLine).write_ansi(&mut expected);
+        expected.push_str("floor 1 |1|");
+        assert_eq!(buf, expected);
+        assert_eq!(cursor_line, 3_usize);
+
+        //An unchanged frame emits nothing and leaves the cursor where it was
+        let mut buf: String = String::new();
+        assert_eq!(diff_frame(&prev_lines, "roof\nfloor 3\nfloor 2\nfloor 1", 1_usize, &mut buf), 1_usize);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn full_frame_clears_the_previous_frame() {
+        //A frame of a new size clears the previous frame from its first line before it is written
+        let mut buf: String = String::new();
+        let cursor_line: usize = full_frame(&get_lines("roof\nfloor 1"), "roof\nfloor 2\nfloor 1", 1_usize, &mut buf);
+        let mut expected: String = String::new();
+        let _ = cursor::MoveUp(1_u16).write_ansi(&mut expected);
+        let _ = cursor::MoveToColumn(0).write_ansi(&mut expected);
+        let _ = terminal::Clear(terminal::ClearType::FromCursorDown).write_ansi(&mut expected);
+        expected.push_str("roof\r\nfloor 2\r\nfloor 1");
+        assert_eq!(buf, expected);
+        assert_eq!(cursor_line, 2_usize);
+    }
+
+    #[test]
+    fn draw_writes_each_frame_once() {
+        let mut out: CountingWriter = CountingWriter::new();
+        let mut renderer: Renderer = Renderer::from(24_usize);
+
+        //The first frame is written in full, in one call
+        renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+        let (written, num_writes) = out.take();
+        assert_eq!(num_writes, 1_usize);
+        assert_eq!(written, "roof\r\nfloor 2\r\nfloor 1");
+
+        //A changed frame is written in one call
+        renderer.draw(&mut out, "roof\nfloor 2 |1|\nfloor 1").unwrap();
+        let (written, num_writes) = out.take();
+        assert_eq!(num_writes, 1_usize);
+        assert!(written.ends_with("floor 2 |1|"));
+
+        //An unchanged frame is not written at all
+        renderer.draw(&mut out, "roof\nfloor 2 |1|\nfloor 1").unwrap();
+        assert_eq!(out.take(), (String::new(), 0_usize));
+
+        //A frame of a different height is redrawn in full, in one call
+        renderer.draw(&mut out, "roof\nfloor 3\nfloor 2\nfloor 1").unwrap();
+        let (written, num_writes) = out.take();
+        assert_eq!(num_writes, 1_usize);
+        assert!(written.ends_with("roof\r\nfloor 3\r\nfloor 2\r\nfloor 1"));
+    }
+
+    #[test]
+    fn resize_redraws_from_scratch() {
+        let mut out: CountingWriter = CountingWriter::new();
+        let mut renderer: Renderer = Renderer::from(24_usize);
+        renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+        out.take();
+
+        //After a resize, even an unchanged frame clears the screen and is drawn in full
+        renderer.resize(30_usize);
+        renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+        let mut expected: String = String::new();
+        let _ = cursor::MoveTo(0, 0).write_ansi(&mut expected);
+        let _ = terminal::Clear(terminal::ClearType::All).write_ansi(&mut expected);
+        expected.push_str("roof\r\nfloor 2\r\nfloor 1");
+        assert_eq!(out.take(), (expected, 1_usize));
+
+        //The screen is cleared only once
+        renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+        assert_eq!(out.take(), (String::new(), 0_usize));
+
+        //Shrinking the terminal below the frame prints frames sequentially
+        renderer.resize(2_usize);
+        renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+        let (written, _) = out.take();
+        assert!(written.contains("Frame is taller than the terminal (2 lines)"), "{:?}", written);
+
+        //And growing it back draws in place again
+        renderer.resize(24_usize);
+        renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+        let (written, _) = out.take();
+        assert!(!written.contains(FRAME_DIVIDER));
+        assert!(written.ends_with("roof\r\nfloor 2\r\nfloor 1"));
+    }
+
+    #[test]
+    fn draw_writes_tall_frames_once() {
+        let mut out: CountingWriter = CountingWriter::new();
+        let mut renderer: Renderer = Renderer::from(2_usize);
+
+        //Each frame taller than the terminal is written in full beneath the last, in one call
+        for _ in 0_usize..3_usize {
+            renderer.draw(&mut out, "roof\nfloor 2\nfloor 1").unwrap();
+            let (written, num_writes) = out.take();
+            assert_eq!(num_writes, 1_usize);
+            assert!(written.contains("roof\r\nfloor 2\r\nfloor 1\r\n"));
+            assert!(written.ends_with(&format!("{}\r\n", FRAME_DIVIDER)));
+        }
+    }
+}
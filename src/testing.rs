@@ -0,0 +1,36 @@
+//Import source modules
+use crate::building::Building;
+use crate::person::Person;
+use crate::units::Ticks;
+
+/** get_empty_building function
+ *
+ * Build an empty building with the given number of floors and
+ * elevators, in which nobody arrives, with the default energy
+ * parameters and seed 0.
+ */
+pub fn get_empty_building(num_floors: usize, num_elevators: usize) -> Building {
+    Building::from(num_floors, num_elevators, 0_f64, 5_f64, 2.5_f64, 0.5_f64, 0_u64).unwrap()
+}
+
+/** get_person function
+ *
+ * Build a person with the given id headed from one floor to another,
+ * who never leaves.
+ */
+pub fn get_person(id: usize, floor_on: usize, floor_to: usize) -> Person {
+    let mut pers: Person = Person::from_dest(0_f64, floor_on, floor_to).unwrap();
+    pers.id = id;
+    pers
+}
+
+/** get_caller function
+ *
+ * Build a person with the given id waiting on a floor for another, as
+ * get_person does, having waited the given time.
+ */
+pub fn get_caller(id: usize, floor_on: usize, floor_to: usize, wait_time: u64) -> Person {
+    let mut pers: Person = get_person(id, floor_on, floor_to);
+    pers.wait_time = Ticks(wait_time);
+    pers
+}
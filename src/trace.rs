@@ -0,0 +1,232 @@
+//Import source modules
+use crate::building::Building;
+use crate::person::Person;
+use crate::event::SimEvent;
+use crate::controller::{ElevatorController, CommandController, parse_commands};
+use crate::simulation::{SimulationConfig, SimulationResult, step_with};
+use crate::profile::Profiler;
+use crate::format::{FormatError, read_versioned, write_json};
+use crate::error::ElevatorError;
+use crate::zone::ZoneMap;
+
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+//Constant representing the version of the trace format written
+pub const TRACE_VERSION: u32 = 1_u32;
+
+//Constant representing the name of the controller in the results of replayed simulations
+pub const REPLAY_CONTROLLER: &str = "replay";
+
+/** TraceArrival struct schema
+ *
+ * A TraceArrival has the following properties
+ * - floor_on (usize): The floor the person arrived on, the first floor by default
+ * - floor_to (usize): The destination floor of the arriving person
+ * - p_out (f64): The probability the arriving person leaves each time step
+ * - crowding_tolerance (f64): The fraction of an elevator's capacity the arriving person rides at, at most
+ * - boarding_ticks (usize): The number of time steps the arriving person takes to get on or off an elevator
+ */
+#[derive(Serialize, Deserialize)]
+pub struct TraceArrival {
+    #[serde(default)]
+    pub floor_on: usize,
+    pub floor_to: usize,
+    pub p_out: f64,
+    #[serde(default = "get_default_crowding_tolerance")]
+    pub crowding_tolerance: f64,
+    #[serde(default = "get_default_boarding_ticks")]
+    pub boarding_ticks: usize
+}
+
+/** get_default_crowding_tolerance function
+ *
+ * Return the crowding tolerance of an arrival recorded without one,
+ * which rides any elevator with room.
+ */
+fn get_default_crowding_tolerance() -> f64 {
+    1_f64
+}
+
+/** get_default_boarding_ticks function
+ *
+ * Return the boarding ticks of an arrival recorded without them, who
+ * gets on and off in a single time step.
+ */
+fn get_default_boarding_ticks() -> usize {
+    1_usize
+}
+
+/** TraceReroute struct schema
+ *
+ * A TraceReroute has the following properties
+ * - person_id (usize): The id of the person on an elevator who changed their destination
+ * - floor_to (usize): The person's new destination floor
+ */
+#[derive(Serialize, Deserialize)]
+pub struct TraceReroute {
+    pub person_id: usize,
+    pub floor_to: usize
+}
+
+/** TraceStep struct schema
+ *
+ * A TraceStep has the following properties
+ * - arrivals (Vec<TraceArrival>): The people who arrived during the time step
+ * - leaving (Vec<usize>): The ids of the people who decided to leave during the time step
+ * - rerouted (Vec<TraceReroute>): The destination changes of people on the elevators during the time step
+ * - commands (String): The movement of each elevator during the time step, as U, D or S
+ */
+#[derive(Serialize, Deserialize)]
+pub struct TraceStep {
+    pub arrivals: Vec<TraceArrival>,
+    pub leaving: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rerouted: Vec<TraceReroute>,
+    pub commands: String
+}
+
+/** Trace struct schema
+ *
+ * A Trace has the following properties
+ * - version (u32): The version of the trace format
+ * - seed (u64): The seed of the recorded simulation
+ * - config (SimulationConfig): The config of the recorded simulation
+ * - zones (Option<ZoneMap>): The floors served by each elevator, if the controller zoned them
+ * - steps (Vec<TraceStep>): Everything nondeterministic which happened during each time step
+ */
+#[derive(Serialize, Deserialize)]
+pub struct Trace {
+    pub version: u32,
+    pub seed: u64,
+    pub config: SimulationConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zones: Option<ZoneMap>,
+    pub steps: Vec<TraceStep>
+}
+
+/** Trace type implementation
+ *
+ * The following functions are implemented for the Trace type,
+ * and are callable via
+ *
+ * //Example
+ * let mut my_trace: Trace = Trace::from(seed, &my_config);
+ * my_trace.record_step(&my_building);
+ * my_trace.write("run.trace.json")?;
+ * let my_result: SimulationResult = Trace::read("run.trace.json")?.replay()?;
+ */
+impl Trace {
+    /** Trace constructor function
+     *
+     * Initialize an empty trace of a simulation with the given seed
+     * and config.
+     */
+    pub fn from(seed: u64, config: &SimulationConfig) -> Trace {
+        Trace {
+            version: TRACE_VERSION,
+            seed,
+            config: config.clone(),
+            zones: None,
+            steps: Vec::new()
+        }
+    }
+
+    /** record_step function
+     *
+     * Record the arrivals, leave decisions and destination changes
+     * from the building's events, and the movement of each elevator,
+     * after a time step.  The zones of the elevators are recorded with
+     * the first time step, since people only board an elevator which
+     * serves their destination floor.
+     */
+    pub fn record_step(&mut self, building: &Building) {
+        if self.steps.is_empty() {
+            self.zones = building.zones.clone();
+        }
+        let mut trace_step: TraceStep = TraceStep {
+            arrivals: Vec::new(),
+            leaving: Vec::new(),
+            rerouted: Vec::new(),
+            commands: String::new()
+        };
+        for event in building.events.iter() {
+            match event {
+                SimEvent::PersonArrived { floor, floor_to, p_out, crowding_tolerance, boarding_ticks, .. } => {
+                    trace_step.arrivals.push(TraceArrival {
+                        floor_on: *floor,
+                        floor_to: *floor_to,
+                        p_out: *p_out,
+                        crowding_tolerance: *crowding_tolerance,
+                        boarding_ticks: *boarding_ticks
+                    });
+                },
+                SimEvent::PersonLeaving { person_id, .. } => trace_step.leaving.push(*person_id),
+                SimEvent::DestinationChanged { person_id, floor_to, .. } => trace_step.rerouted.push(TraceReroute {
+                    person_id: *person_id,
+                    floor_to: *floor_to
+                }),
+                _ => {}
+            }
+        }
+        for elevator in building.elevators.iter() {
+            trace_step.commands.push(if elevator.stopped { 'S' } else if elevator.moving_up { 'U' } else { 'D' });
+        }
+        self.steps.push(trace_step);
+    }
+
+    /** read function
+     *
+     * Read a trace from the JSON file at the given path
+     */
+    pub fn read(path: &str) -> Result<Trace, FormatError> {
+        read_versioned(path, TRACE_VERSION)
+    }
+
+    /** write function
+     *
+     * Write the trace as JSON to the file at the given path
+     */
+    pub fn write(&self, path: &str) -> Result<(), FormatError> {
+        write_json(path, self)
+    }
+
+    /** replay function
+     *
+     * Rerun the recorded simulation, taking the arrivals, leave
+     * decisions, destination changes and elevator movements from the trace rather than
+     * from an RNG and a controller, within the recorded zones, and return the resulting metrics.
+     * These are identical to those of the recorded simulation.  If the
+     * trace holds an invalid config, commands or arrival, then an error
+     * is returned.
+     */
+    pub fn replay(&self) -> Result<SimulationResult, ElevatorError> {
+        let mut controller: CommandController = CommandController::from(self.config.build(self.seed)?);
+        if self.zones.is_some() {
+            controller.building.set_zones(self.zones.clone());
+        }
+        let num_elevators: usize = controller.building.elevators.len();
+        let mut profiler: Profiler = Profiler::from(false);
+        for (i, trace_step) in self.steps.iter().enumerate() {
+            let context: String = format!("steps[{}]", i);
+            controller.commands = parse_commands(&trace_step.commands, num_elevators)
+                .map_err(|e| FormatError::from("", None, &context, &format!("invalid commands: {}", e)))?;
+            let arrivals: Vec<Person> = trace_step.arrivals.iter()
+                .map(|x| {
+                    let mut new_person: Person = Person::from_dest(x.p_out, x.floor_on, x.floor_to)?;
+                    new_person.crowding_tolerance = x.crowding_tolerance;
+                    new_person.boarding_ticks = x.boarding_ticks;
+                    Ok(new_person)
+                })
+                .collect::<Result<Vec<Person>, ElevatorError>>()?;
+            step_with(&mut controller, i as i32, &mut profiler, |building: &mut Building| {
+                building.add_arrivals(arrivals);
+                building.set_people_leaving(&trace_step.leaving);
+                let changes: Vec<(usize, usize)> = trace_step.rerouted.iter().map(|x| (x.person_id, x.floor_to)).collect();
+                building.change_destinations(&changes);
+                Ok(())
+            })?;
+        }
+        Ok(SimulationResult::from(controller.get_building(), &self.config, REPLAY_CONTROLLER, self.seed))
+    }
+}
@@ -0,0 +1,267 @@
+//Import source modules
+use crate::controller::ControllerKind;
+use crate::params::{ParamSpec, ControllerParams};
+use crate::simulation::{SimulationConfig, SimulationResult, run_replications};
+use crate::format::FormatError;
+use crate::error::ElevatorError;
+use crate::score::Objective;
+use crate::rng::split_mix64;
+
+//Import external/standard modules
+use std::fs;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::Serialize;
+
+//Constant representing the number of proposals tried before giving up on finding a valid one
+const MAX_PROPOSAL_ATTEMPTS: usize = 32_usize;
+
+//Constant representing the initial temperature, as a fraction of the initial objective
+const INITIAL_TEMPERATURE: f64 = 0.05_f64;
+
+/** TuneOptions struct schema
+ *
+ * A TuneOptions has the following properties
+ * - controller_kind (ControllerKind): The controller whose parameters are tuned
+ * - objective (Objective): The score minimized
+ * - budget (usize): The number of evaluations, including that of the defaults
+ * - replications (usize): The number of seeded simulations per evaluation
+ * - seed (u64): The seed the replications' seeds and the proposals are derived from
+ */
+pub struct TuneOptions {
+    pub controller_kind: ControllerKind,
+    pub objective: Objective,
+    pub budget: usize,
+    pub replications: usize,
+    pub seed: u64
+}
+
+/** TuneStep struct schema
+ *
+ * A TuneStep has the following properties
+ * - evaluation (usize): The index of the evaluation, from 0 for the defaults
+ * - params (ControllerParams): The parameters evaluated
+ * - value (f64): The objective of the parameters
+ * - accepted (bool): Whether the parameters became the current state
+ */
+pub struct TuneStep {
+    pub evaluation: usize,
+    pub params: ControllerParams,
+    pub value: f64,
+    pub accepted: bool
+}
+
+/** TunedParams struct schema
+ *
+ * A TunedParams has the following properties
+ * - controller (ControllerKind): The controller the parameters were tuned for
+ * - objective (Objective): The score the parameters minimize
+ * - value (f64): The objective of the parameters
+ * - params (ControllerParams): The best parameters found
+ */
+#[derive(Serialize)]
+pub struct TunedParams {
+    pub controller: ControllerKind,
+    pub objective: Objective,
+    pub value: f64,
+    pub params: ControllerParams
+}
+
+/** TunedParams type implementation
+ *
+ * The following functions are implemented for the TunedParams type,
+ * and are callable via
+ *
+ * //Example
+ * let my_tuned: TunedParams = tune(&my_config, &my_options, |_| {})?;
+ * my_tuned.write("tuned.json")?;
+ */
+impl TunedParams {
+    /** write function
+     *
+     * Write the tuned parameters as pretty JSON to the file at the
+     * given path, as a snippet to keep alongside a config.
+     */
+    pub fn write(&self, path: &str) -> Result<(), FormatError> {
+        let file_str: String = serde_json::to_string_pretty(self)
+            .map_err(|e| FormatError::from(path, None, "", &e.to_string()))?;
+        fs::write(path, file_str + "\n").map_err(|e| FormatError::from(path, None, "", &e.to_string()))
+    }
+}
+
+/** evaluate function
+ *
+ * Run the given parameters once per seed and return the mean of the
+ * objective's score over the runs, or the error of a run which failed.
+ */
+pub fn evaluate(config: &SimulationConfig, options: &TuneOptions, params: &ControllerParams,
+                seeds: &[u64]) -> Result<f64, ElevatorError> {
+    let results: Vec<SimulationResult> = run_replications(config, options.controller_kind, params, seeds)?;
+    let total: f64 = results.iter().map(|x| options.objective.score(x)).sum();
+    Ok(total / results.len().max(1_usize) as f64)
+}
+
+/** propose function
+ *
+ * Perturb one parameter of the current state by its step, up or down,
+ * retrying until the proposal is valid for the building.  Return None
+ * if no valid proposal was found.
+ */
+fn propose(current: &ControllerParams, options: &TuneOptions, num_elevators: usize, rng: &mut StdRng) -> Option<ControllerParams> {
+    let specs: &[ParamSpec] = options.controller_kind.get_param_specs();
+    for _ in 0_usize..MAX_PROPOSAL_ATTEMPTS {
+        let spec: &ParamSpec = &specs[rng.gen_range(0_usize..specs.len())];
+        let direction: f64 = if rng.gen::<bool>() { 1_f64 } else { -1_f64 };
+        let mut proposal: ControllerParams = current.clone();
+        proposal.set(spec.name, current.get(spec) + (direction * spec.step));
+        if options.controller_kind.validate_params(&proposal, num_elevators).is_ok() {
+            return Some(proposal);
+        }
+    }
+    None
+}
+
+/** tune function
+ *
+ * Minimize the objective over the controller's parameters with
+ * simulated annealing.  Starting from the defaults, each evaluation
+ * perturbs one parameter of the current state and evaluates it on the
+ * same seeds, accepting it if it is better, or if it is worse with a
+ * probability which falls as the temperature cools linearly to zero
+ * over the budget.  The given function is called after each evaluation,
+ * and the best parameters found are returned, or the error of a run
+ * which failed.
+ */
+pub fn tune(config: &SimulationConfig, options: &TuneOptions, mut on_step: impl FnMut(&TuneStep)) -> Result<TunedParams, ElevatorError> {
+    //Check that the controller can be tuned
+    let specs: &[ParamSpec] = options.controller_kind.get_param_specs();
+    if specs.is_empty() {
+        return Err(ElevatorError::Config(format!(
+            "the {} controller has no parameters to tune", options.controller_kind.get_name()
+        )));
+    }
+    if options.budget == 0_usize || options.replications == 0_usize {
+        return Err(ElevatorError::Config(String::from("the budget and the number of replications must be positive")));
+    }
+
+    //Derive the seeds every evaluation runs on, and the RNG of the proposals
+    let seeds: Vec<u64> = (0..options.replications as u64).map(|i| options.seed.wrapping_add(i)).collect();
+    let mut rng: StdRng = StdRng::seed_from_u64(split_mix64(options.seed));
+
+    //Evaluate the defaults, given explicitly so that every parameter is listed
+    let mut current: ControllerParams = ControllerParams::new();
+    for spec in specs.iter() {
+        current.set(spec.name, spec.default);
+    }
+    let mut current_value: f64 = evaluate(config, options, &current, &seeds)?;
+    on_step(&TuneStep { evaluation: 0_usize, params: current.clone(), value: current_value, accepted: true });
+    let mut best: ControllerParams = current.clone();
+    let mut best_value: f64 = current_value;
+
+    //Anneal over the remaining evaluations
+    let initial_temperature: f64 = (current_value.abs() * INITIAL_TEMPERATURE).max(f64::EPSILON);
+    for evaluation in 1_usize..options.budget {
+        let proposal: ControllerParams = match propose(&current, options, config.num_elevators, &mut rng) {
+            Some(x) => x,
+            None => break
+        };
+        let value: f64 = evaluate(config, options, &proposal, &seeds)?;
+
+        //Accept improvements, and worse states with a probability falling with the temperature
+        let temperature: f64 = initial_temperature * (1_f64 - (evaluation as f64 / options.budget as f64));
+        let accepted: bool = value <= current_value || rng.gen::<f64>() < (-(value - current_value) / temperature).exp();
+        if value < best_value {
+            best = proposal.clone();
+            best_value = value;
+        }
+        on_step(&TuneStep { evaluation, params: proposal.clone(), value, accepted });
+        if accepted {
+            current = proposal;
+            current_value = value;
+        }
+    }
+
+    //Return the best parameters found
+    Ok(TunedParams {
+        controller: options.controller_kind,
+        objective: options.objective,
+        value: best_value,
+        params: best
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Get the options of a tuning run of the nearest controller with the given budget and replications
+    fn get_options(budget: usize, replications: usize) -> TuneOptions {
+        TuneOptions {
+            controller_kind: ControllerKind::Nearest,
+            objective: Objective::default(),
+            budget,
+            replications,
+            seed: 7_u64
+        }
+    }
+
+    //Get the config of a tiny building run for few time steps
+    fn get_tiny_config() -> SimulationConfig {
+        let mut config: SimulationConfig = SimulationConfig::from(4_usize, 2_usize, 0.5_f64);
+        config.time_steps = 60_i32;
+        config
+    }
+
+    #[test]
+    fn accepted_improvements_never_raise_the_objective() {
+        //Tune a tiny building, recording every step
+        let config: SimulationConfig = get_tiny_config();
+        let options: TuneOptions = get_options(12_usize, 2_usize);
+        let mut steps: Vec<(usize, f64, bool)> = Vec::new();
+        let tuned: TunedParams = tune(&config, &options, |x| steps.push((x.evaluation, x.value, x.accepted))).unwrap();
+
+        //The defaults are evaluated and accepted first, and each evaluation is reported once
+        assert!(!steps.is_empty() && steps.len() <= options.budget);
+        assert_eq!((steps[0].0, steps[0].2), (0_usize, true));
+        assert!(steps.iter().enumerate().all(|(i, x)| x.0 == i), "{:?}", steps);
+
+        //Only states worse than the current one are ever rejected
+        let mut current: f64 = steps[0].1;
+        for (evaluation, value, accepted) in steps.iter().skip(1_usize) {
+            if *value <= current {
+                assert!(*accepted, "an improvement was rejected at evaluation {}", evaluation);
+            }
+            if *accepted {
+                current = *value;
+            }
+        }
+
+        //The objective returned is the best evaluated, and is no worse than that of the defaults
+        let best: f64 = steps.iter().map(|x| x.1).fold(f64::INFINITY, f64::min);
+        assert_eq!(tuned.value, best);
+        assert!(tuned.value <= steps[0].1);
+        assert_eq!(tuned.controller, ControllerKind::Nearest);
+
+        //The tuned parameters reproduce their objective
+        let seeds: Vec<u64> = (0_u64..2_u64).map(|x| options.seed + x).collect();
+        assert_eq!(evaluate(&config, &options, &tuned.params, &seeds).unwrap(), tuned.value);
+    }
+
+    #[test]
+    fn zero_budget_or_replications_is_rejected() {
+        let config: SimulationConfig = get_tiny_config();
+        let cases: [(usize, usize); 3] = [(0_usize, 2_usize), (4_usize, 0_usize), (0_usize, 0_usize)];
+        for (budget, replications) in cases {
+            let mut num_steps: usize = 0_usize;
+            let result: Result<TunedParams, ElevatorError> = tune(&config, &get_options(budget, replications), |_| num_steps += 1_usize);
+            assert!(matches!(result, Err(ElevatorError::Config(_))), "budget {}, replications {}", budget, replications);
+            assert_eq!(num_steps, 0_usize, "budget {}, replications {} evaluated", budget, replications);
+        }
+    }
+
+    #[test]
+    fn controller_without_parameters_is_rejected() {
+        let options: TuneOptions = TuneOptions { controller_kind: ControllerKind::Random, ..get_options(4_usize, 1_usize) };
+        assert!(matches!(tune(&get_tiny_config(), &options, |_| {}), Err(ElevatorError::Config(_))));
+    }
+}
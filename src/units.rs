@@ -0,0 +1,213 @@
+//Import external/standard modules
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Div};
+use serde::{Serialize, Deserialize};
+
+/** Energy struct schema
+ *
+ * An Energy is an amount of energy spent by elevators, kept apart from
+ * bare numbers so that it cannot be mixed up with other metrics.  It
+ * serializes, displays and debugs as the bare number.
+ */
+#[derive(Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Energy(pub f64);
+
+//Add trait implementation for energy
+impl Add for Energy {
+    type Output = Energy;
+
+    fn add(self, other: Energy) -> Energy {
+        Energy(self.0 + other.0)
+    }
+}
+
+//AddAssign trait implementation for energy
+impl AddAssign for Energy {
+    fn add_assign(&mut self, other: Energy) {
+        self.0 += other.0;
+    }
+}
+
+//Mul trait implementation for scaling energy
+impl Mul<f64> for Energy {
+    type Output = Energy;
+
+    fn mul(self, factor: f64) -> Energy {
+        Energy(self.0 * factor)
+    }
+}
+
+//Div trait implementation for spreading energy
+impl Div<f64> for Energy {
+    type Output = Energy;
+
+    fn div(self, divisor: f64) -> Energy {
+        Energy(self.0 / divisor)
+    }
+}
+
+//Sum trait implementation for energy
+impl Sum for Energy {
+    fn sum<I: Iterator<Item=Energy>>(iter: I) -> Energy {
+        iter.fold(Energy(0_f64), |acc, x| acc + x)
+    }
+}
+
+//Display trait implementation for energy, as the bare number
+impl fmt::Display for Energy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+//Debug trait implementation for energy, as the bare number
+impl fmt::Debug for Energy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/** Ticks struct schema
+ *
+ * A Ticks is a number of time steps, such as a wait or ride time,
+ * kept apart from bare numbers so that it cannot be mixed up with
+ * counts of people or floors.  It serializes, displays and debugs as
+ * the bare number.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ticks(pub u64);
+
+/** Ticks type implementation
+ *
+ * The following functions are implemented for the Ticks type, and are
+ * callable via
+ *
+ * //Example
+ * let my_ticks: Ticks = Ticks(3_u64).saturating_add(Ticks(1_u64));
+ */
+impl Ticks {
+    /** saturating_add function
+     *
+     * Return the sum of the ticks, saturating rather than overflowing.
+     */
+    pub fn saturating_add(self, other: Ticks) -> Ticks {
+        Ticks(self.0.saturating_add(other.0))
+    }
+
+    /** as_f64 function
+     *
+     * Return the number of time steps as a float, such as for averaging.
+     */
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+}
+
+//Add trait implementation for ticks
+impl Add for Ticks {
+    type Output = Ticks;
+
+    fn add(self, other: Ticks) -> Ticks {
+        Ticks(self.0 + other.0)
+    }
+}
+
+//AddAssign trait implementation for ticks
+impl AddAssign for Ticks {
+    fn add_assign(&mut self, other: Ticks) {
+        self.0 += other.0;
+    }
+}
+
+//Mul trait implementation for scaling ticks, such as into a duration
+impl Mul<f64> for Ticks {
+    type Output = f64;
+
+    fn mul(self, factor: f64) -> f64 {
+        self.0 as f64 * factor
+    }
+}
+
+//Sum trait implementation for ticks, saturating rather than overflowing
+impl Sum for Ticks {
+    fn sum<I: Iterator<Item=Ticks>>(iter: I) -> Ticks {
+        iter.fold(Ticks(0_u64), |acc, x| acc.saturating_add(x))
+    }
+}
+
+//Display trait implementation for ticks, as the bare number
+impl fmt::Display for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+//Debug trait implementation for ticks, as the bare number
+impl fmt::Debug for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_serialize_as_bare_numbers() {
+        //Each unit serializes as the number it wraps, and deserializes back from it
+        assert_eq!(serde_json::to_string(&Energy(12.5_f64)).unwrap(), "12.5");
+        assert_eq!(serde_json::to_string(&Ticks(42_u64)).unwrap(), "42");
+        assert_eq!(serde_json::from_str::<Energy>("12.5").unwrap(), Energy(12.5_f64));
+        assert_eq!(serde_json::from_str::<Ticks>("42").unwrap(), Ticks(42_u64));
+        assert!(serde_json::from_str::<Ticks>("{\"0\": 42}").is_err());
+
+        //Nested in other values, they are still bare numbers
+        let value: serde_json::Value = serde_json::json!({ "energy": Energy(3_f64), "waits": [Ticks(1_u64), Ticks(2_u64)] });
+        assert_eq!(value, serde_json::json!({ "energy": 3_f64, "waits": [1_u64, 2_u64] }));
+
+        //They display and debug as bare numbers too
+        assert_eq!((format!("{}", Energy(2.5_f64)), format!("{:?}", Energy(2.5_f64))), (String::from("2.5"), String::from("2.5")));
+        assert_eq!((format!("{}", Ticks(7_u64)), format!("{:?}", Ticks(7_u64))), (String::from("7"), String::from("7")));
+        assert_eq!(format!("{:.2}", Energy(1_f64 / 3_f64)), "0.33");
+    }
+
+    #[test]
+    fn energy_arithmetic_and_comparisons() {
+        let mut energy: Energy = Energy(1.5_f64) + Energy(2_f64);
+        assert_eq!(energy, Energy(3.5_f64));
+        energy += Energy(0.5_f64);
+        assert_eq!(energy, Energy(4_f64));
+        assert_eq!(energy * 2.5_f64, Energy(10_f64));
+        assert_eq!(energy / 8_f64, Energy(0.5_f64));
+        assert_eq!([Energy(1_f64), Energy(2_f64), Energy(3_f64)].into_iter().sum::<Energy>(), Energy(6_f64));
+        assert_eq!(std::iter::empty::<Energy>().sum::<Energy>(), Energy::default());
+        assert!(Energy(1_f64) < Energy(1.5_f64) && Energy(2_f64) >= Energy(2_f64) && Energy(3_f64) > Energy(-3_f64));
+        assert_eq!(Energy(f64::NAN).partial_cmp(&Energy(0_f64)), None);
+    }
+
+    #[test]
+    fn ticks_arithmetic_and_comparisons() {
+        let mut ticks: Ticks = Ticks(3_u64) + Ticks(4_u64);
+        assert_eq!(ticks, Ticks(7_u64));
+        ticks += Ticks(1_u64);
+        assert_eq!(ticks, Ticks(8_u64));
+        assert_eq!(ticks * 0.25_f64, 2_f64);
+        assert_eq!(ticks.as_f64(), 8_f64);
+
+        //Sums saturate rather than overflowing
+        assert_eq!(Ticks(u64::MAX - 1_u64).saturating_add(Ticks(5_u64)), Ticks(u64::MAX));
+        assert_eq!([Ticks(u64::MAX), Ticks(1_u64)].into_iter().sum::<Ticks>(), Ticks(u64::MAX));
+        assert_eq!([Ticks(1_u64), Ticks(2_u64)].into_iter().sum::<Ticks>(), Ticks(3_u64));
+
+        //Ticks are totally ordered
+        let mut waits: Vec<Ticks> = vec![Ticks(5_u64), Ticks(0_u64), Ticks(3_u64)];
+        waits.sort();
+        assert_eq!(waits, vec![Ticks(0_u64), Ticks(3_u64), Ticks(5_u64)]);
+        assert_eq!(waits.iter().max(), Some(&Ticks(5_u64)));
+        assert!(Ticks(2_u64) < Ticks(3_u64) && Ticks(3_u64) <= Ticks(3_u64));
+    }
+}
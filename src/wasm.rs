@@ -0,0 +1,146 @@
+//Import source modules
+use crate::building::Building;
+use crate::controller::{ElevatorController, ControllerKind, RandomController, NearestController, MaxWaitController};
+use crate::simulation::{SimulationConfig, step};
+use crate::snapshot::BuildingSnapshot;
+use crate::profile::Profiler;
+use crate::rng::RngStream;
+use crate::zone::ZoneMap;
+
+//Import external modules
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+/** WasmConfig struct schema
+ *
+ * A WasmConfig has the following properties
+ * - simulation (SimulationConfig): The building and energy parameters, flattened into the config
+ * - controller (ControllerKind): The kind of controller to run, random by default
+ * - seed (u64): The seed for the simulation's RNGs, since there is no OS entropy on wasm
+ */
+#[derive(Deserialize)]
+struct WasmConfig {
+    #[serde(flatten)]
+    simulation: SimulationConfig,
+    #[serde(default = "get_default_controller")]
+    controller: ControllerKind,
+    #[serde(default)]
+    seed: u64
+}
+
+/** get_default_controller function
+ *
+ * Return the controller a WasmConfig uses when none is given
+ */
+fn get_default_controller() -> ControllerKind {
+    ControllerKind::Random
+}
+
+/** WasmSimulation struct schema
+ *
+ * A WasmSimulation has the following properties
+ * - controller (Box<dyn ElevatorController>): The controller, which owns the building
+ * - time_step (i32): The number of time steps simulated so far
+ * - profiler (Profiler): A disabled profiler, since wasm has no clock
+ */
+#[wasm_bindgen]
+pub struct WasmSimulation {
+    controller: Box<dyn ElevatorController>,
+    time_step: i32,
+    profiler: Profiler
+}
+
+/** WasmSimulation type implementation
+ *
+ * The following functions are implemented for the WasmSimulation
+ * type, and are callable from JavaScript via
+ *
+ * //Example
+ * const sim = new WasmSimulation('{"num_floors": 4, ..., "seed": 42}');
+ * const snapshot = JSON.parse(sim.step());
+ * const text = sim.render_text();
+ */
+#[wasm_bindgen]
+impl WasmSimulation {
+    /** WasmSimulation constructor function
+     *
+     * Initialize a simulation from a JSON config, which has the
+     * fields of a SimulationConfig along with an optional controller
+     * and seed.
+     */
+    #[wasm_bindgen(constructor)]
+    pub fn new(config_json: &str) -> Result<WasmSimulation, JsValue> {
+        //Parse the config
+        let config: WasmConfig = serde_json::from_str(config_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid config: {}", e)))?;
+
+        //Initialize the controller with the configured building, each with their own RNG streams
+        let building: Building = config.simulation.build(config.seed)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let controller: Box<dyn ElevatorController> = match config.controller {
+            ControllerKind::Random => Box::new(
+                RandomController::from(building, RngStream::Controller(0_usize).get_rng(config.seed))
+            ),
+            ControllerKind::Nearest => Box::new(NearestController::from(building)),
+            ControllerKind::OddEven => Box::new(NearestController::from_zones(
+                building, ZoneMap::odd_even(config.simulation.num_floors, config.simulation.num_elevators)
+            )),
+            ControllerKind::MaxWait => Box::new(MaxWaitController::from(building))
+        };
+        Ok(WasmSimulation {
+            controller,
+            time_step: 0_i32,
+            profiler: Profiler::from(false)
+        })
+    }
+
+    /** step function
+     *
+     * Advance the simulation by one time step and return a JSON
+     * snapshot of the building, or throw if the time step fails.
+     */
+    pub fn step(&mut self) -> Result<String, JsValue> {
+        step(self.controller.as_mut(), self.time_step, &mut self.profiler)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let snapshot: BuildingSnapshot = BuildingSnapshot::from(self.controller.get_building(), self.time_step);
+        self.time_step += 1_i32;
+        Ok(snapshot.to_json())
+    }
+
+    /** render_text function
+     *
+     * Render every floor of the building as plain text
+     */
+    pub fn render_text(&self) -> String {
+        let mut building_str: String = String::new();
+        let _ = self.controller.get_building().render_into(&mut building_str);
+        building_str
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    //Constant representing the config of a small building run by the nearest controller
+    const SMALL_CONFIG: &str = r#"{
+        "num_floors": 4, "num_elevators": 1, "expected_arrivals": 0.5,
+        "energy_up": 5.0, "energy_down": 2.5, "energy_coef": 0.5,
+        "time_steps": 20, "controller": "nearest", "seed": 42
+    }"#;
+
+    #[wasm_bindgen_test]
+    fn steps_a_small_building() {
+        //Each step returns a snapshot of the whole building after that time step
+        let mut sim: WasmSimulation = WasmSimulation::new(SMALL_CONFIG).unwrap();
+        for i in 0_i32..20_i32 {
+            let snapshot: BuildingSnapshot = BuildingSnapshot::from_json(&sim.step().unwrap()).unwrap();
+            assert_eq!(snapshot.time_step, i);
+            assert_eq!(snapshot.floors.len(), 4_usize);
+            assert_eq!(snapshot.elevators.len(), 1_usize);
+            assert!(snapshot.avg_energy.is_finite());
+        }
+        assert!(!sim.render_text().is_empty());
+    }
+}
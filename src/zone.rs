@@ -0,0 +1,164 @@
+//Import source modules
+use crate::floor::Floor;
+use crate::people::People;
+
+//Import external/standard modules
+use serde::{Serialize, Deserialize};
+
+/** ZoneMap struct schema
+ *
+ * A ZoneMap has the following properties
+ * - zones (Vec<Vec<usize>>): The floors served by each elevator, indexed by elevator
+ *
+ * An elevator only picks up people on the floors it serves whose
+ * destination floors it also serves.  The zones are arbitrary sets of
+ * floors rather than contiguous ranges, and may overlap, such as the
+ * lobby being served by every elevator.
+ */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ZoneMap {
+    pub zones: Vec<Vec<usize>>
+}
+
+/** ZoneMap type implementation
+ *
+ * The following functions are implemented for the ZoneMap type,
+ * and are callable via
+ *
+ * //Example
+ * let my_zones: ZoneMap = ZoneMap::odd_even(num_floors, num_elevators);
+ * my_zones.validate(num_floors, num_elevators)?;
+ * let serves_floor: bool = my_zones.serves(0_usize, 3_usize);
+ */
+impl ZoneMap {
+    /** ZoneMap constructor function
+     *
+     * Initialize a zone map given the floors served by each elevator
+     */
+    pub fn from(zones: Vec<Vec<usize>>) -> ZoneMap {
+        ZoneMap {
+            zones
+        }
+    }
+
+    /** odd_even function
+     *
+     * Initialize a zone map in which the even elevators serve the even
+     * floors and the odd elevators serve the odd floors, with every
+     * elevator serving the lobby.  A lone elevator serves every floor.
+     */
+    pub fn odd_even(num_floors: usize, num_elevators: usize) -> ZoneMap {
+        let zones: Vec<Vec<usize>> = (0_usize..num_elevators).map(|i| {
+            (0_usize..num_floors).filter(|floor| {
+                num_elevators < 2_usize || *floor == 0_usize || floor % 2_usize == i % 2_usize
+            }).collect()
+        }).collect();
+        ZoneMap::from(zones)
+    }
+
+    /** validate function
+     *
+     * Check that the zone map has one zone per elevator, that each
+     * zone only has floors of the building, and that every floor is
+     * served by at least one elevator.
+     */
+    pub fn validate(&self, num_floors: usize, num_elevators: usize) -> Result<(), String> {
+        if self.zones.len() != num_elevators {
+            return Err(format!("{} zones were given for {} elevators", self.zones.len(), num_elevators));
+        }
+        let mut served_floors: Vec<bool> = vec![false; num_floors];
+        for (i, zone) in self.zones.iter().enumerate() {
+            for floor in zone.iter() {
+                match served_floors.get_mut(*floor) {
+                    Some(x) => *x = true,
+                    None => return Err(format!(
+                        "zone {} has floor {}, out of range for a building with {} floors", i, floor, num_floors
+                    ))
+                }
+            }
+        }
+        let unserved_floors: Vec<String> = served_floors.iter().enumerate()
+            .filter(|(_, served)| !**served)
+            .map(|(floor, _)| floor.to_string())
+            .collect();
+        if !unserved_floors.is_empty() {
+            return Err(format!("no elevator serves floors {}", unserved_floors.join(", ")));
+        }
+        Ok(())
+    }
+
+    /** serves function
+     *
+     * Return whether the elevator at the given index serves the given
+     * floor.  An elevator without a zone serves every floor.
+     */
+    pub fn serves(&self, elevator: usize, floor: usize) -> bool {
+        match self.zones.get(elevator) {
+            Some(zone) => zone.contains(&floor),
+            None => true
+        }
+    }
+
+    /** get_hall_calls function
+     *
+     * Return whether each floor has someone waiting whom the elevator
+     * at the given index may pick up, being on a floor it serves and
+     * headed to a floor it serves.
+     */
+    pub fn get_hall_calls(&self, elevator: usize, floors: &[Floor]) -> Vec<bool> {
+        floors.iter().enumerate().map(|(i, floor)| {
+            self.serves(elevator, i) && floor.waiting().any(|pers| self.serves(elevator, pers.floor_to))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Building;
+    use crate::controller::{ElevatorController, ControllerKind};
+    use crate::simulation::{SimulationConfig, build_controller, step};
+    use crate::params::ControllerParams;
+    use crate::profile::Profiler;
+
+    #[test]
+    fn odd_even_zones_serve_the_lobby_and_their_floors() {
+        let zones: ZoneMap = ZoneMap::odd_even(6_usize, 3_usize);
+        assert_eq!(zones.zones, vec![vec![0_usize, 2_usize, 4_usize], vec![0_usize, 1_usize, 3_usize, 5_usize], vec![0_usize, 2_usize, 4_usize]]);
+        assert_eq!(zones.validate(6_usize, 3_usize), Ok(()));
+        assert_eq!(ZoneMap::odd_even(6_usize, 1_usize).zones, vec![(0_usize..6_usize).collect::<Vec<usize>>()]);
+    }
+
+    #[test]
+    fn validate_rejects_uncovered_floors() {
+        let cases: [(Vec<Vec<usize>>, &str); 4] = [
+            (vec![vec![0_usize, 2_usize], vec![0_usize, 1_usize]], "no elevator serves floors 3"),
+            (vec![vec![0_usize], vec![2_usize]], "no elevator serves floors 1, 3"),
+            (vec![vec![0_usize, 1_usize, 2_usize, 3_usize]], "1 zones were given for 2 elevators"),
+            (vec![vec![0_usize, 1_usize], vec![2_usize, 3_usize, 4_usize]], "zone 1 has floor 4, out of range for a building with 4 floors")
+        ];
+        for (zones, message) in cases {
+            assert_eq!(ZoneMap::from(zones).validate(4_usize, 2_usize), Err(String::from(message)));
+        }
+    }
+
+    #[test]
+    fn odd_floors_are_never_reached_by_the_even_cab() {
+        //Run the odd/even controller on 6 floors with 2 elevators
+        let config: SimulationConfig = SimulationConfig::from(6_usize, 2_usize, 0.8_f64);
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, ControllerKind::OddEven, &ControllerParams::default(), 5_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+
+        //Nobody going to floor 3 ever boards the even cab, while the odd cab carries them
+        let mut num_odd_riders: usize = 0_usize;
+        for i in 0_i32..500_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+            assert!(building.elevators[0].people.iter().all(|pers| pers.floor_to != 3_usize), "floor 3 rider on the even cab at step {}", i);
+            num_odd_riders += building.elevators[1].people.iter().filter(|pers| pers.floor_to == 3_usize).count();
+        }
+        assert!(num_odd_riders > 0_usize);
+    }
+}
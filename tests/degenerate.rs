@@ -0,0 +1,166 @@
+//Import source modules
+use elevator_optimization::building::Building;
+use elevator_optimization::controller::{ElevatorController, ControllerKind};
+use elevator_optimization::simulation::{SimulationConfig, SimulationResult, build_controller, step};
+use elevator_optimization::params::ControllerParams;
+use elevator_optimization::profile::Profiler;
+
+//Constant representing every kind of controller
+const CONTROLLER_KINDS: [ControllerKind; 4] = [
+    ControllerKind::Random, ControllerKind::Nearest, ControllerKind::OddEven, ControllerKind::MaxWait
+];
+
+//Constant representing the number of seeds each controller is run with, 100 runs per case in all
+const NUM_SEEDS: u64 = 25_u64;
+
+//Run the config for the given number of time steps with every controller and many seeds,
+//checking the invariants every time step and that the metrics are finite at the end
+fn check_config(config: &SimulationConfig, time_steps: i32) {
+    for controller_kind in CONTROLLER_KINDS {
+        for seed in 0_u64..NUM_SEEDS {
+            let mut controller: Box<dyn ElevatorController> = build_controller(
+                config, controller_kind, &ControllerParams::default(), seed
+            ).unwrap();
+            let mut profiler: Profiler = Profiler::from(false);
+            for i in 0_i32..time_steps {
+                step(controller.as_mut(), i, &mut profiler).unwrap();
+                if let Err(violation) = controller.get_building().check_invariants() {
+                    panic!("{} seed {} step {}: {}", controller_kind.get_name(), seed, i, violation);
+                }
+            }
+            let result: SimulationResult = SimulationResult::from(
+                controller.get_building(), config, controller_kind.get_name(), seed
+            );
+            let metrics: [f64; 8] = [
+                result.wait.mean, result.floor_wait.mean, result.ride.mean, result.energy.total.0,
+                result.energy.avg_per_step.0, result.energy.moving_up.0, result.energy.moving_down.0, result.throughput
+            ];
+            assert!(
+                metrics.iter().all(|x| x.is_finite()),
+                "{} seed {}: metrics {:?} are not all finite", controller_kind.get_name(), seed, metrics
+            );
+        }
+    }
+}
+
+#[test]
+fn no_elevators() {
+    check_config(&SimulationConfig::from(10_usize, 0_usize, 1_f64), 50_i32);
+}
+
+#[test]
+fn no_elevators_leave_everyone_waiting() {
+    //Nobody is served, so the number waiting never falls, nothing is spent and the building still renders
+    let config: SimulationConfig = SimulationConfig::from(10_usize, 0_usize, 1_f64);
+    for controller_kind in CONTROLLER_KINDS {
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, controller_kind, &ControllerParams::default(), 7_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        let mut num_waiting: usize = 0_usize;
+        for i in 0_i32..200_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+            let num_waiting_now: usize = (0_usize..building.floors.len()).map(|j| building.get_num_people_waiting_on_floor(j)).sum();
+            assert!(num_waiting_now >= num_waiting, "{} step {}: {} waiting after {}", controller_kind.get_name(), i, num_waiting_now, num_waiting);
+            assert_eq!(building.get_energy_spent().0, 0_f64);
+            num_waiting = num_waiting_now;
+        }
+        assert!(num_waiting > 0_usize, "{}: nobody arrived", controller_kind.get_name());
+        let mut building_str: String = String::new();
+        controller.get_building().render_into(&mut building_str).unwrap();
+        assert!(!building_str.is_empty());
+    }
+}
+
+#[test]
+fn one_elevator_many_floors() {
+    check_config(&SimulationConfig::from(50_usize, 1_usize, 0.5_f64), 50_i32);
+}
+
+#[test]
+fn two_floors() {
+    check_config(&SimulationConfig::from(2_usize, 2_usize, 0.5_f64), 50_i32);
+}
+
+#[test]
+fn one_floor() {
+    check_config(&SimulationConfig::from(1_usize, 1_usize, 0.5_f64), 50_i32);
+}
+
+#[test]
+fn one_floor_parks_the_elevators() {
+    //Everyone arrives with nowhere to go, so no elevator moves, nothing is spent and nobody waits
+    let config: SimulationConfig = SimulationConfig::from(1_usize, 2_usize, 0.5_f64);
+    for controller_kind in CONTROLLER_KINDS {
+        let mut controller: Box<dyn ElevatorController> = build_controller(
+            &config, controller_kind, &ControllerParams::default(), 3_u64
+        ).unwrap();
+        let mut profiler: Profiler = Profiler::from(false);
+        for i in 0_i32..200_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+            let building: &Building = controller.get_building();
+            assert_eq!(building.check_invariants(), Ok(()), "{} step {}", controller_kind.get_name(), i);
+            assert!(building.elevators.iter().all(|x| x.floor_on == 0_usize), "{} step {}", controller_kind.get_name(), i);
+            assert_eq!(building.get_num_people_waiting_on_floor(0_usize), 0_usize, "{} step {}", controller_kind.get_name(), i);
+        }
+        let result: SimulationResult = SimulationResult::from(
+            controller.get_building(), &config, controller_kind.get_name(), 3_u64
+        );
+        assert!(result.arrivals > 0_usize, "{}: nobody arrived", controller_kind.get_name());
+        assert_eq!((result.wait.mean, result.floor_wait.mean, result.ride.mean), (0_f64, 0_f64, 0_f64), "{}", controller_kind.get_name());
+        assert_eq!(result.energy.total.0, 0_f64, "{}", controller_kind.get_name());
+    }
+}
+
+#[test]
+fn two_floors_deliver_with_every_controller() {
+    //Everyone heads to the other floor, and every controller carries them there
+    let config: SimulationConfig = SimulationConfig::from(2_usize, 2_usize, 0.5_f64);
+    for controller_kind in CONTROLLER_KINDS {
+        for seed in 0_u64..5_u64 {
+            let mut controller: Box<dyn ElevatorController> = build_controller(
+                &config, controller_kind, &ControllerParams::default(), seed
+            ).unwrap();
+            let mut profiler: Profiler = Profiler::from(false);
+            for i in 0_i32..300_i32 {
+                step(controller.as_mut(), i, &mut profiler).unwrap();
+                if let Err(violation) = controller.get_building().check_invariants() {
+                    panic!("{} seed {} step {}: {}", controller_kind.get_name(), seed, i, violation);
+                }
+            }
+            let building: &Building = controller.get_building();
+            assert!(building.get_num_delivered() > 0_usize, "{} seed {}: nobody was delivered", controller_kind.get_name(), seed);
+            assert!(building.elevators.iter().all(|x| x.floor_on < 2_usize));
+        }
+    }
+}
+
+#[test]
+fn near_zero_arrivals() {
+    check_config(&SimulationConfig::from(10_usize, 2_usize, 1e-9_f64), 50_i32);
+    check_config(&SimulationConfig::from(10_usize, 2_usize, 0_f64), 50_i32);
+}
+
+#[test]
+fn many_arrivals() {
+    check_config(&SimulationConfig::from(10_usize, 2_usize, 100_f64), 20_i32);
+}
+
+#[test]
+fn capacity_of_one() {
+    let mut config: SimulationConfig = SimulationConfig::from(10_usize, 2_usize, 2_f64);
+    config.capacity = Some(1_usize);
+    check_config(&config, 50_i32);
+}
+
+#[test]
+fn capacity_of_zero_is_rejected() {
+    let mut config: SimulationConfig = SimulationConfig::from(10_usize, 2_usize, 0.5_f64);
+    config.capacity = Some(0_usize);
+    for controller_kind in CONTROLLER_KINDS {
+        let error: String = build_controller(&config, controller_kind, &ControllerParams::default(), 0_u64)
+            .err().unwrap().to_string();
+        assert!(error.contains("the elevator capacity must be positive"), "{}", error);
+    }
+}
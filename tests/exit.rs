@@ -0,0 +1,52 @@
+#![cfg(feature = "cli")]
+
+//Import external/standard modules
+use std::process::{Command, Output};
+
+//Run the binary with the given args, returning its exit status and output
+fn run_binary(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_elevator-optimization"))
+        .args(args)
+        .output()
+        .expect("the binary should start")
+}
+
+#[test]
+fn invalid_config_exits_with_a_clean_message() {
+    //A freight elevator the building does not have is rejected before any simulation starts
+    let output: Output = run_binary(&["--fast", "--elevators", "1", "--freight", "3"]);
+    let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert_eq!(output.status.code(), Some(2_i32));
+    assert!(stderr.starts_with("Error: invalid config: freight elevator 3 is out of range"), "unexpected stderr: {}", stderr);
+    assert_eq!(stderr.lines().count(), 1_usize, "unexpected stderr: {}", stderr);
+    assert!(!stderr.contains("panicked"));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn unwritable_output_exits_with_a_clean_message() {
+    //A log which cannot be opened fails the run before it starts, naming the path
+    let output: Output = run_binary(&["--fast", "--metrics-csv", "/nonexistent/metrics.csv"]);
+    let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert_eq!(output.status.code(), Some(1_i32));
+    assert!(stderr.starts_with("Error: i/o error: failed to open the metrics log /nonexistent/metrics.csv"), "unexpected stderr: {}", stderr);
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+fn floor_weights_of_the_wrong_length_exit_with_a_clean_message() {
+    //Weights for 3 floors are rejected for a building of 5 before any simulation starts, naming the file and the rows expected
+    let path: String = format!("{}/tests/fixtures/floor-weights/three-floors.csv", env!("CARGO_MANIFEST_DIR"));
+    let output: Output = run_binary(&["--fast", "--floors", "5", "--floor-weights", &path]);
+    let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert_eq!(output.status.code(), Some(1_i32));
+    assert!(stderr.starts_with(&format!("Error: invalid file: {}: ", path)), "unexpected stderr: {}", stderr);
+    assert!(stderr.contains("3 floors were given weights, expected 5"), "unexpected stderr: {}", stderr);
+    assert_eq!(stderr.lines().count(), 1_usize, "unexpected stderr: {}", stderr);
+    assert!(!stderr.contains("panicked"));
+    assert!(output.stdout.is_empty());
+
+    //The same weights are accepted for a building of 3
+    let output: Output = run_binary(&["--fast", "--floors", "3", "--time-steps", "10", "--floor-weights", &path]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,15 @@
+//Import source modules
+use elevator_optimization::golden::{GOLDEN_CASES, check_golden_files};
+
+//Compare every golden case against its file, or overwrite the files if UPDATE_GOLDEN=1
+#[test]
+fn golden_cases_match() {
+    let dir: String = format!("{}/golden", env!("CARGO_MANIFEST_DIR"));
+    let update: bool = std::env::var("UPDATE_GOLDEN").map(|x| x == "1").unwrap_or(false);
+    let mismatches: Vec<String> = check_golden_files(&dir, update).unwrap();
+    assert!(
+        mismatches.is_empty(),
+        "{}\n{} of {} golden cases differ, rerun with UPDATE_GOLDEN=1 if the change is intended",
+        mismatches.join("\n"), mismatches.len(), GOLDEN_CASES.len()
+    );
+}
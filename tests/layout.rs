@@ -0,0 +1,123 @@
+//Import source modules
+use elevator_optimization::building::Building;
+use elevator_optimization::format::FormatError;
+use elevator_optimization::layout::{LayoutFile, LayoutPerson, LAYOUT_VERSION};
+use elevator_optimization::person::{Person, PersonState};
+use elevator_optimization::units::Ticks;
+
+//Get the path of a layout fixture
+fn get_fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/layouts/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+//Find the person with the given id on the floors or elevators of the building, and whether they are on an elevator
+fn find_person(building: &Building, id: usize) -> (&Person, bool) {
+    let on_floor: Option<&Person> = building.floors.iter()
+        .flat_map(|floor| floor.get_people().iter())
+        .find(|pers| pers.id == id);
+    let on_elevator: Option<&Person> = building.elevators.iter()
+        .flat_map(|elevator| elevator.people.iter())
+        .find(|pers| pers.id == id);
+    match (on_floor, on_elevator) {
+        (Some(pers), None) => (pers, false),
+        (None, Some(pers)) => (pers, true),
+        _ => panic!("person {} is not in the building exactly once", id)
+    }
+}
+
+//Check that the person in the building matches the person in the layout
+fn check_person(building: &Building, expected: &LayoutPerson, on_elevator: bool) {
+    let (pers, is_on_elevator) = find_person(building, expected.id);
+    assert_eq!(is_on_elevator, on_elevator, "person {}", expected.id);
+    assert_eq!((pers.floor_on, pers.floor_to), (expected.floor_on, expected.floor_to), "person {}", expected.id);
+    assert_eq!((pers.wait_time, pers.ride_time), (expected.wait_time, expected.ride_time), "person {}", expected.id);
+    assert_eq!(pers.is_leaving, expected.is_leaving, "person {}", expected.id);
+    assert_eq!(pers.p_out, expected.p_out, "person {}", expected.id);
+    assert_eq!(pers.crowding_tolerance, expected.crowding_tolerance, "person {}", expected.id);
+    assert_eq!(pers.boarding_ticks, expected.boarding_ticks, "person {}", expected.id);
+}
+
+#[test]
+fn fixture_loads_field_by_field() {
+    let layout: LayoutFile = LayoutFile::load(&get_fixture_path("lobby-rush.json")).unwrap();
+    layout.validate().unwrap();
+    assert_eq!((layout.version, layout.seed), (LAYOUT_VERSION, Some(7_u64)));
+    let building: Building = layout.build().unwrap();
+    assert_eq!(building.check_invariants(), Ok(()));
+
+    //The floors hold the people the layout lists on them, and nobody else
+    assert_eq!(building.floors.len(), 6_usize);
+    let num_people: Vec<usize> = building.floors.iter().map(|x| x.get_people().len()).collect();
+    assert_eq!(num_people, vec![2_usize, 0_usize, 0_usize, 1_usize, 0_usize, 1_usize]);
+    for floor in layout.floors.iter() {
+        for pers in floor.people.iter() {
+            check_person(&building, pers, false);
+        }
+    }
+
+    //The people are at the stage of a trip their floors and destinations imply
+    let states: Vec<PersonState> = (0_usize..5_usize).map(|x| find_person(&building, x).0.state).collect();
+    assert_eq!(states, vec![
+        PersonState::WaitingUp,
+        PersonState::WaitingUp,
+        PersonState::WaitingDown,
+        PersonState::Idle,
+        PersonState::Riding
+    ]);
+    assert_eq!(find_person(&building, 1_usize).0.p_out, 0.1_f64);
+    assert_eq!(find_person(&building, 1_usize).0.boarding_ticks, 3_usize);
+    assert_eq!(find_person(&building, 3_usize).0.crowding_tolerance, 0.5_f64);
+
+    //The first elevator carries its passenger up from floor 2 to floor 4, and the second is stopped on the lobby
+    let elevator_states: Vec<(usize, Option<usize>, bool, bool, usize)> = building.elevators.iter()
+        .map(|x| (x.floor_on, x.floor_to, x.moving_up, x.stopped, x.people.len()))
+        .collect();
+    assert_eq!(elevator_states, vec![
+        (2_usize, Some(4_usize), true, false, 1_usize),
+        (0_usize, None, false, true, 0_usize)
+    ]);
+    check_person(&building, &layout.elevators[0].passengers[0], true);
+    assert_eq!(find_person(&building, 4_usize).0.ride_time, Ticks(2_u64));
+
+    //Everyone listed is counted in the population
+    assert_eq!(building.get_population(), 5_usize);
+}
+
+#[test]
+fn fixture_round_trips_through_its_file() {
+    let path: String = std::env::temp_dir()
+        .join(format!("elevator-layout-{}.json", std::process::id()))
+        .display().to_string();
+    let layout: LayoutFile = LayoutFile::load(&get_fixture_path("lobby-rush.json")).unwrap();
+    layout.write(&path).unwrap();
+    let loaded: LayoutFile = LayoutFile::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    //The layout loaded is the layout written, and is still valid
+    assert_eq!(loaded.path, path);
+    assert_eq!(loaded.config, layout.config);
+    assert_eq!(loaded.seed, layout.seed);
+    assert_eq!(loaded.floors, layout.floors);
+    assert_eq!(loaded.elevators, layout.elevators);
+    assert!(loaded.validate().is_ok());
+}
+
+#[test]
+fn inconsistent_layouts_report_their_error() {
+    let cases: [(&str, &str, &str); 2] = [
+        ("floor-on-mismatch.json", "floors[3].people[1]", "floor_on 2 does not match the floor 3 they are listed on"),
+        ("duplicate-id.json", "elevators[0].passengers[0]", "id 0 is already taken by floors[0].people[0]")
+    ];
+    for (name, context, message) in cases {
+        let layout: LayoutFile = LayoutFile::load(&get_fixture_path(name)).unwrap();
+        let err: FormatError = match layout.validate() {
+            Ok(_) => panic!("{} should be rejected", name),
+            Err(e) => e
+        };
+        assert_eq!(err.path, get_fixture_path(name));
+        assert_eq!(err.line, None, "{}", name);
+        assert_eq!(err.context, context, "{}", name);
+        assert_eq!(err.message, message, "{}", name);
+        assert_eq!(err.to_string(), format!("{}: {}: {}", get_fixture_path(name), context, message));
+    }
+}
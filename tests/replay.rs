@@ -0,0 +1,50 @@
+//Import source modules
+use elevator_optimization::controller::{ElevatorController, ControllerKind};
+use elevator_optimization::simulation::{SimulationConfig, SimulationResult, build_controller, step};
+use elevator_optimization::params::ControllerParams;
+use elevator_optimization::profile::Profiler;
+use elevator_optimization::trace::Trace;
+
+//Constant representing the number of time steps recorded
+const RECORD_TIME_STEPS: i32 = 500_i32;
+
+//Record a run with the given controller to a trace file, replay it, and compare the results
+fn check_replay(config: &SimulationConfig, controller_kind: ControllerKind, seed: u64) {
+    //Record the run, keeping its result
+    let mut controller: Box<dyn ElevatorController> = build_controller(
+        config, controller_kind, &ControllerParams::default(), seed
+    ).unwrap();
+    let mut trace: Trace = Trace::from(seed, config);
+    let mut profiler: Profiler = Profiler::from(false);
+    for i in 0_i32..RECORD_TIME_STEPS {
+        step(controller.as_mut(), i, &mut profiler).unwrap();
+        trace.record_step(controller.get_building());
+    }
+    let recorded: SimulationResult = SimulationResult::from(
+        controller.get_building(), config, controller_kind.get_name(), seed
+    );
+
+    //Write the trace, read it back and replay it
+    let path: String = std::env::temp_dir()
+        .join(format!("elevator-replay-{}-{}.json", std::process::id(), controller_kind.get_name()))
+        .display().to_string();
+    trace.write(&path).unwrap();
+    let read_trace: Result<Trace, _> = Trace::read(&path);
+    let _ = std::fs::remove_file(&path);
+    let mut replayed: SimulationResult = read_trace.unwrap().replay().unwrap();
+
+    //The replayed metrics are identical, bit for bit, other than the controller named
+    replayed.controller = recorded.controller.clone();
+    assert_eq!(serde_json::to_string(&replayed).unwrap(), serde_json::to_string(&recorded).unwrap());
+    assert!(recorded.arrivals > 0_usize);
+}
+
+#[test]
+fn replay_matches_recording() {
+    let mut config: SimulationConfig = SimulationConfig::from(12_usize, 3_usize, 0.6_f64);
+    config.time_steps = RECORD_TIME_STEPS;
+    config.p_change_dest = 0.01_f64;
+    for controller_kind in [ControllerKind::Random, ControllerKind::Nearest, ControllerKind::OddEven, ControllerKind::MaxWait] {
+        check_replay(&config, controller_kind, 7_u64);
+    }
+}
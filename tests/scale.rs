@@ -0,0 +1,59 @@
+//Import source modules
+use elevator_optimization::controller::{ElevatorController, ControllerKind};
+use elevator_optimization::simulation::{SimulationConfig, SimulationResult, build_controller, step};
+use elevator_optimization::params::ControllerParams;
+use elevator_optimization::profile::Profiler;
+
+//Import external/standard modules
+#[cfg(feature = "cli")]
+use std::process::{Command, Output};
+use std::time::{Duration, Instant};
+
+//Constant representing how long the large run may take, generous enough for an unoptimized build
+const TIME_BUDGET: Duration = Duration::from_secs(60_u64);
+
+#[test]
+fn tall_building_runs_within_budget() {
+    //Run 120 floors with 8 elevators for 10k time steps without rendering, checking the invariants each step
+    let config: SimulationConfig = SimulationConfig::from(120_usize, 8_usize, 3_f64);
+    let mut controller: Box<dyn ElevatorController> = build_controller(
+        &config, ControllerKind::Nearest, &ControllerParams::default(), 0_u64
+    ).unwrap();
+    let mut profiler: Profiler = Profiler::from(false);
+    let start: Instant = Instant::now();
+    for i in 0_i32..10_000_i32 {
+        step(controller.as_mut(), i, &mut profiler).unwrap();
+        if let Err(violation) = controller.get_building().check_invariants() {
+            panic!("step {}: {}", i, violation);
+        }
+    }
+    let elapsed: Duration = start.elapsed();
+    assert!(elapsed < TIME_BUDGET, "10k steps of 120 floors took {:?}", elapsed);
+
+    //The metrics stay finite at this scale
+    let result: SimulationResult = SimulationResult::from(controller.get_building(), &config, "nearest", 0_u64);
+    let metrics: [f64; 5] = [result.wait.mean, result.floor_wait.mean, result.ride.mean, result.energy.total.0, result.throughput];
+    assert!(metrics.iter().all(|x| x.is_finite()), "metrics {:?} are not all finite", metrics);
+    assert!(result.arrivals > 20_000_usize);
+    assert!(controller.get_building().get_num_delivered() > 0_usize);
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn fast_mode_runs_within_budget() {
+    //Run the binary in fast mode on 10 floors with 2 elevators for 100k time steps
+    let start: Instant = Instant::now();
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_elevator-optimization"))
+        .args(["--fast", "--floors", "10", "--elevators", "2", "--expected-arrivals", "0.5"])
+        .args(["--time-steps", "100000", "--controller", "nearest", "--seed", "1"])
+        .output()
+        .expect("the binary should start");
+    let elapsed: Duration = start.elapsed();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(elapsed < TIME_BUDGET, "100k fast steps of 10 floors took {:?}", elapsed);
+
+    //Every time step was run, and the speed of the run reported
+    let stdout: String = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Step 100000/100000"), "{}", stdout);
+    assert!(stdout.contains("Ran 100000 time steps of the nearest controller with seed 1"), "{}", stdout);
+}
@@ -0,0 +1,87 @@
+//Import source modules
+use elevator_optimization::format::{ScenarioFile, ScenarioArrival, ScenarioEvent, ScenarioAction, FormatError, SCENARIO_VERSION};
+use elevator_optimization::shuttle::ShuttleAssignment;
+
+//Get the path of a scenario fixture
+fn get_fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/scenarios/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+//Load and validate a scenario fixture against a building of 4 floors and 2 elevators, returning its error
+fn get_fixture_error(name: &str) -> FormatError {
+    match ScenarioFile::load(&get_fixture_path(name)).and_then(|x| x.validate(4_usize, 2_usize).map(|_| x)) {
+        Ok(_) => panic!("{} should be rejected", name),
+        Err(e) => e
+    }
+}
+
+#[test]
+fn invalid_scenarios_report_their_error() {
+    let cases: [(&str, &str, &str); 8] = [
+        ("bad-version.json", "version", "unsupported version 2, expected 1"),
+        ("negative-step.json", "arrivals[0]", "step -1 is negative"),
+        ("out-of-order.json", "arrivals[2]", "step 3 comes before the previous step 5, steps must be in order"),
+        ("floor-out-of-range.json", "arrivals[1]", "floor_to 4 is out of range for a building with 4 floors"),
+        ("bad-p-out.json", "arrivals[0]", "p_out 1.5 is not in [0, 1]"),
+        ("bad-arrival-rate.json", "events[0]", "expected_arrivals 0 is not a positive number"),
+        ("shuttle-out-of-range.json", "events[1]", "shuttle elevator 2 is out of range for a building with 2 elevators"),
+        ("shuttle-no-capacity.json", "events[0]", "shuttle capacity must be positive")
+    ];
+    for (name, context, message) in cases {
+        let err: FormatError = get_fixture_error(name);
+        assert_eq!(err.path, get_fixture_path(name));
+        assert_eq!(err.line, None, "{}", name);
+        assert_eq!(err.context, context, "{}", name);
+        assert_eq!(err.message, message, "{}", name);
+        assert_eq!(err.to_string(), format!("{}: {}: {}", get_fixture_path(name), context, message));
+    }
+}
+
+#[test]
+fn unparsable_scenarios_report_their_line() {
+    //Malformed JSON is reported on the line it was found on
+    let err: FormatError = get_fixture_error("not-json.json");
+    assert_eq!(err.line, Some(3_usize));
+    assert!(err.to_string().starts_with(&format!("{}:3: ", get_fixture_path("not-json.json"))), "{}", err);
+
+    //An action of an unknown type is named
+    let err: FormatError = get_fixture_error("unknown-action.json");
+    assert_eq!(err.line, Some(1_usize));
+    assert!(err.message.contains("unknown variant `set_fire_alarm`"), "{}", err);
+
+    //A missing file is reported against its path
+    let err: FormatError = get_fixture_error("missing.json");
+    assert_eq!(err.line, None);
+    assert_eq!(err.path, get_fixture_path("missing.json"));
+}
+
+#[test]
+fn scenario_round_trips_through_its_file() {
+    let path: String = std::env::temp_dir()
+        .join(format!("elevator-scenario-{}.json", std::process::id()))
+        .display().to_string();
+
+    //Write a scenario with every kind of arrival and event, then load it back
+    let mut scenario: ScenarioFile = ScenarioFile::new();
+    scenario.arrivals = vec![
+        ScenarioArrival { step: 0_i32, floor_to: 3_usize, p_out: 0.05_f64 },
+        ScenarioArrival { step: 0_i32, floor_to: 1_usize, p_out: 1_f64 },
+        ScenarioArrival { step: 12_i32, floor_to: 2_usize, p_out: 0_f64 }
+    ];
+    scenario.events = vec![
+        ScenarioEvent { step: 5_i32, action: ScenarioAction::SetArrivals { expected_arrivals: 1.5_f64 } },
+        ScenarioEvent { step: 8_i32, action: ScenarioAction::SetShuttle { shuttle: Some(ShuttleAssignment {
+            elevator: 1_usize, capacity: 6_usize, max_dwell_steps: 4_usize, dwell_steps: 0_usize
+        }) } },
+        ScenarioEvent { step: 30_i32, action: ScenarioAction::SetShuttle { shuttle: None } }
+    ];
+    scenario.write(&path).unwrap();
+    let loaded: ScenarioFile = ScenarioFile::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    //The scenario loaded is the scenario written, and is still valid
+    assert_eq!(loaded.version, SCENARIO_VERSION);
+    assert_eq!(loaded.path, path);
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), serde_json::to_string(&scenario).unwrap());
+    assert!(loaded.validate(4_usize, 2_usize).is_ok());
+}
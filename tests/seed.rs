@@ -0,0 +1,40 @@
+//Import external/standard modules
+use std::process::{Command, Output};
+
+//Run the binary with the given seed and controller, returning its progress lines and its JSON summary
+fn run_seeded(seed: &str, controller: &str, run: usize) -> (String, Vec<u8>) {
+    let path: String = std::env::temp_dir()
+        .join(format!("elevator-seed-{}-{}-{}-{}.json", std::process::id(), seed, controller, run))
+        .display().to_string();
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_elevator-optimization"))
+        .args(["--fast", "--floors", "8", "--elevators", "3", "--expected-arrivals", "0.5", "--time-steps", "400"])
+        .args(["--controller", controller, "--seed", seed, "--summary-json", &path])
+        .output()
+        .expect("the binary should start");
+    let summary: std::io::Result<Vec<u8>> = std::fs::read(&path);
+    let _ = std::fs::remove_file(&path);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    //Leave out the line with the wall time of the run
+    let stdout: String = String::from_utf8(output.stdout).unwrap()
+        .lines()
+        .filter(|x| !x.contains("steps per second"))
+        .collect::<Vec<&str>>()
+        .join("\n");
+    (stdout, summary.unwrap())
+}
+
+#[test]
+fn same_seed_gives_identical_statistics() {
+    for controller in ["random", "nearest"] {
+        let (first_stdout, first_summary) = run_seeded("42", controller, 0_usize);
+        let (second_stdout, second_summary) = run_seeded("42", controller, 1_usize);
+        assert!(first_stdout.contains("Step 400/400"), "{}", first_stdout);
+        assert_eq!(first_stdout, second_stdout, "{} progress differs", controller);
+        assert_eq!(first_summary, second_summary, "{} summary differs", controller);
+
+        //Another seed gives other arrivals, so other statistics
+        let (_, other_summary) = run_seeded("43", controller, 2_usize);
+        assert_ne!(first_summary, other_summary, "{} ignores the seed", controller);
+    }
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "cli")]
+
+//Import source modules
+use elevator_optimization::server::{run_server, SERVER_CONTROLLER};
+use elevator_optimization::simulation::{SimulationConfig, SimulationResult};
+use elevator_optimization::snapshot::BuildingSnapshot;
+
+//Import external/standard modules
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+//Constant representing how long the server waits for each line of commands
+const TIMEOUT: Duration = Duration::from_millis(500_u64);
+
+//Connect to the server on the given port, retrying while it starts listening
+fn connect(port: u16) -> TcpStream {
+    for _ in 0_usize..100_usize {
+        if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)) {
+            return stream;
+        }
+        thread::sleep(Duration::from_millis(20_u64));
+    }
+    panic!("the server never listened on port {}", port);
+}
+
+#[test]
+fn client_drives_the_elevators_over_loopback() {
+    //Serve a building of 6 floors with 1 elevator and no arrivals on a free port, for more steps than the client takes
+    let port: u16 = TcpListener::bind(("127.0.0.1", 0_u16)).unwrap().local_addr().unwrap().port();
+    let mut config: SimulationConfig = SimulationConfig::from(6_usize, 1_usize, 0_f64);
+    config.time_steps = 50_i32;
+    let server: JoinHandle<_> = thread::spawn(move || run_server(&config, port, TIMEOUT, 0_u64));
+    let stream: TcpStream = connect(port);
+    let mut reader: BufReader<TcpStream> = BufReader::new(stream.try_clone().unwrap());
+    let mut writer: TcpStream = stream;
+
+    //Act as the client for 20 steps, sending the elevator up, or down from the top floor, except on step 10, on which nothing is sent within one timeout
+    let mut floors: Vec<usize> = Vec::new();
+    for i in 0_i32..20_i32 {
+        //Each step begins with one line of JSON holding the snapshot of the building
+        let mut line: String = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'), "{:?}", line);
+        let snapshot: BuildingSnapshot = serde_json::from_str(&line).unwrap();
+        assert_eq!(snapshot.time_step, i);
+        assert_eq!(snapshot.floors.len(), 6_usize);
+        assert_eq!(snapshot.elevators.len(), 1_usize);
+        floors.push(snapshot.elevators[0].floor_on);
+        if i == 10_i32 {
+            thread::sleep(TIMEOUT * 3_u32 / 2_u32);
+        } else {
+            writeln!(writer, "{}", if snapshot.elevators[0].floor_on < 5_usize { "U" } else { "D" }).unwrap();
+        }
+    }
+
+    //The elevator climbed while commanded, but stood still once the client timed out
+    assert_eq!(&floors[..6], &[0_usize, 1_usize, 2_usize, 3_usize, 4_usize, 5_usize]);
+    assert_eq!(floors[11], floors[10], "moved at step 10 with no commands");
+    assert!(floors[12..].iter().any(|x| *x != floors[11]), "did not move again once commanded: {:?}", floors);
+
+    //Disconnecting ends the run cleanly after the steps the client took
+    drop(reader);
+    drop(writer);
+    let result: SimulationResult = server.join().unwrap().unwrap();
+    assert_eq!(result.controller, SERVER_CONTROLLER);
+    assert_eq!(result.steps, 20_u64);
+}
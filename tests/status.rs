@@ -0,0 +1,87 @@
+#![cfg(feature = "cli")]
+
+//Import source modules
+use elevator_optimization::simulation::SimulationResult;
+use elevator_optimization::snapshot::BuildingSnapshot;
+
+//Import external/standard modules
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+//A slow run of the binary, killed once dropped so that a failing test does not leave it running
+struct SlowRun {
+    child: Child
+}
+
+impl Drop for SlowRun {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+//Start a seeded run of 6 floors and 2 elevators which takes several seconds, serving its status on the given port
+fn start_slow_run(port: u16) -> SlowRun {
+    let child: Child = Command::new(env!("CARGO_BIN_EXE_elevator-optimization"))
+        .args(["--quiet", "--tick-ms", "20", "--time-steps", "500", "--floors", "6", "--elevators", "2", "--seed", "11"])
+        .args(["--http-status", &port.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("the binary should start");
+    SlowRun { child }
+}
+
+//Send a GET request for the given path, retrying while the server starts listening, returning the status line and body
+fn get(port: u16, path: &str) -> (String, String) {
+    for _ in 0_usize..100_usize {
+        if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+            write!(stream, "GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", path).unwrap();
+            let mut response: String = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            let (head, body): (&str, &str) = response.split_once("\r\n\r\n").unwrap();
+            return (String::from(head.lines().next().unwrap()), String::from(body));
+        }
+        thread::sleep(Duration::from_millis(20_u64));
+    }
+    panic!("the status server never listened on port {}", port);
+}
+
+//Send GET requests for the given path until a time step has run and its body is no longer null
+fn get_once_stepped(port: u16, path: &str) -> String {
+    for _ in 0_usize..100_usize {
+        let (status, body): (String, String) = get(port, path);
+        assert_eq!(status, "HTTP/1.1 200 OK", "{}", path);
+        if body != "null" {
+            return body;
+        }
+        thread::sleep(Duration::from_millis(20_u64));
+    }
+    panic!("{} was still null after 2 seconds", path);
+}
+
+#[test]
+fn status_is_served_during_a_slow_run() {
+    let port: u16 = TcpListener::bind(("127.0.0.1", 0_u16)).unwrap().local_addr().unwrap().port();
+    let _run: SlowRun = start_slow_run(port);
+
+    //The state parses into the snapshot of the building being run
+    let snapshot: BuildingSnapshot = serde_json::from_str(&get_once_stepped(port, "/state")).unwrap();
+    assert_eq!(snapshot.floors.len(), 6_usize);
+    assert_eq!(snapshot.elevators.len(), 2_usize);
+    assert!(snapshot.time_step >= 0_i32 && snapshot.time_step < 500_i32, "{}", snapshot.time_step);
+
+    //The summary parses into the result of the run so far, which goes on while it is served
+    let summary: SimulationResult = serde_json::from_str(&get_once_stepped(port, "/summary")).unwrap();
+    assert_eq!((summary.seed, summary.controller.as_str()), (11_u64, "random"));
+    assert_eq!((summary.config.num_floors, summary.config.num_elevators), (6_usize, 2_usize));
+    assert!(summary.steps < 500_u64, "the run was over before its summary was served");
+    let later: BuildingSnapshot = serde_json::from_str(&get_once_stepped(port, "/state")).unwrap();
+    assert!(later.time_step >= snapshot.time_step);
+
+    //Other paths are not found
+    assert_eq!(get(port, "/other").0, "HTTP/1.1 404 Not Found");
+}
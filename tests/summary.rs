@@ -0,0 +1,73 @@
+#![cfg(feature = "cli")]
+
+//Import source modules
+use elevator_optimization::simulation::{SimulationSummary, SUMMARY_VERSION};
+
+//Import external/standard modules
+use std::process::{Command, Output};
+
+//Run the binary with the given args after those of a short seeded run, returning its output
+fn run_binary(args: &[&str]) -> Output {
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_elevator-optimization"))
+        .args(["--fast", "--floors", "6", "--elevators", "2", "--time-steps", "100", "--seed", "3"])
+        .args(args)
+        .output()
+        .expect("the binary should start");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+//Parse each line of stdout as a summary, failing on anything else
+fn parse_summaries(output: &Output) -> Vec<SimulationSummary> {
+    let stdout: String = String::from_utf8(output.stdout.clone()).unwrap();
+    assert!(stdout.ends_with('\n'), "{:?}", stdout);
+    stdout.lines()
+        .map(|x| SimulationSummary::from_json(x).unwrap_or_else(|e| panic!("{:?} is not a summary: {}", x, e)))
+        .collect()
+}
+
+#[test]
+fn summary_to_stdout_is_only_json() {
+    for args in [&["--summary-json", "-"][..], &["--summary-json"][..]] {
+        let summaries: Vec<SimulationSummary> = parse_summaries(&run_binary(args));
+        assert_eq!(summaries.len(), 1_usize, "{:?}", args);
+        assert_eq!(summaries[0].version, SUMMARY_VERSION);
+        assert_eq!((summaries[0].seed, summaries[0].time_steps), (3_u64, 100_u64));
+    }
+
+    //Replications write a summary each, from consecutive seeds
+    let summaries: Vec<SimulationSummary> = parse_summaries(&run_binary(&["--replications", "3", "--summary-json", "-"]));
+    let seeds: Vec<u64> = summaries.iter().map(|x| x.seed).collect();
+    assert_eq!(seeds, vec![3_u64, 4_u64, 5_u64]);
+}
+
+#[test]
+fn summary_file_matches_stdout() {
+    //The summary written to a file is the one written to stdout, while stdout keeps the usual report
+    let path: String = std::env::temp_dir()
+        .join(format!("elevator-summary-{}.json", std::process::id()))
+        .display().to_string();
+    let file_output: Output = run_binary(&["--summary-json", &path]);
+    let summary_json: String = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(SimulationSummary::from_json(String::from_utf8_lossy(&file_output.stdout).lines().next().unwrap()).is_err());
+    let stdout_summaries: Vec<SimulationSummary> = parse_summaries(&run_binary(&["--summary-json", "-"]));
+    assert_eq!(SimulationSummary::from_json(summary_json.trim_end()).unwrap(), stdout_summaries[0]);
+}
+
+#[test]
+fn quiet_summary_to_stdout_sends_the_rest_to_stderr() {
+    //The quiet run prints its summary line and profile to stderr, leaving only the JSON on stdout
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_elevator-optimization"))
+        .args(["--quiet", "--tick-ms", "0", "--floors", "6", "--elevators", "2", "--time-steps", "50", "--seed", "3"])
+        .args(["--profile", "--summary-json", "-"])
+        .output()
+        .expect("the binary should start");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let summaries: Vec<SimulationSummary> = parse_summaries(&output);
+    assert_eq!(summaries.len(), 1_usize);
+    assert_eq!((summaries[0].seed, summaries[0].time_steps), (3_u64, 50_u64));
+    let stderr: String = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("50 steps, avg wait "), "{:?}", stderr);
+    assert!(stderr.contains("total"), "{:?}", stderr);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "tracing")]
+
+//Import source modules
+use elevator_optimization::controller::{ElevatorController, ControllerKind};
+use elevator_optimization::simulation::{SimulationConfig, build_controller, step};
+use elevator_optimization::params::ControllerParams;
+use elevator_optimization::profile::Profiler;
+
+//Import external/standard modules
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::Registry;
+
+/** CapturedSpan struct schema
+ *
+ * A CapturedSpan has the following properties
+ * - name (&'static str): The name of the span
+ * - time_step (Option<i64>): The time step recorded on the span, if any
+ */
+struct CapturedSpan {
+    name: &'static str,
+    time_step: Option<i64>
+}
+
+/** CapturingLayer struct schema
+ *
+ * A CapturingLayer has the following properties
+ * - spans (Arc<Mutex<Vec<CapturedSpan>>>): The spans created, in order
+ */
+struct CapturingLayer {
+    spans: Arc<Mutex<Vec<CapturedSpan>>>
+}
+
+//Layer trait implementation for the capturing layer, recording each span as it is created
+impl<S: Subscriber> Layer<S> for CapturingLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        let mut span: CapturedSpan = CapturedSpan { name: attrs.metadata().name(), time_step: None };
+        attrs.record(&mut span);
+        self.spans.lock().unwrap().push(span);
+    }
+}
+
+//Visit trait implementation for a captured span, keeping its time step field
+impl Visit for CapturedSpan {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == "time_step" {
+            self.time_step = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+#[test]
+fn steps_emit_step_and_phase_spans() {
+    //Capture the spans of a few time steps of a small building
+    let spans: Arc<Mutex<Vec<CapturedSpan>>> = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = Registry::default().with(CapturingLayer { spans: Arc::clone(&spans) });
+    let config: SimulationConfig = SimulationConfig::from(6_usize, 2_usize, 0.5_f64);
+    let mut controller: Box<dyn ElevatorController> = build_controller(
+        &config, ControllerKind::Nearest, &ControllerParams::default(), 0_u64
+    ).unwrap();
+    let mut profiler: Profiler = Profiler::from(false);
+    tracing::subscriber::with_default(subscriber, || {
+        for i in 0_i32..3_i32 {
+            step(controller.as_mut(), i, &mut profiler).unwrap();
+        }
+    });
+
+    //Each time step has a span with its number, followed by a span for each phase
+    let spans = spans.lock().unwrap();
+    let steps: Vec<Option<i64>> = spans.iter().filter(|x| x.name == "step").map(|x| x.time_step).collect();
+    assert_eq!(steps, vec![Some(0_i64), Some(1_i64), Some(2_i64)]);
+    for phase in ["arrivals", "exchange", "controller", "metrics"] {
+        assert_eq!(spans.iter().filter(|x| x.name == phase).count(), 3_usize, "{} spans", phase);
+    }
+    assert_eq!(spans[0].name, "step");
+}